@@ -0,0 +1,39 @@
+//! Helpers for the JSON documents, such as descriptors and manifests, which are written by the
+//! sub crates.
+
+/// Escapes the text, so it can be written as (the contents of) a JSON string.
+pub fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_json_quotes_and_backslashes() {
+        assert_eq!("say \\\"hi\\\" \\\\o/", escape_json("say \"hi\" \\o/"));
+    }
+
+    #[test]
+    fn escape_json_control_characters() {
+        assert_eq!(
+            "a\\\\b\\n\\t\\u0001\\u001f",
+            escape_json("a\\b\n\t\u{1}\u{1f}")
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Descriptors of the sheets of an atlas, in formats which can be read by common game engines and
+//! frameworks.
+
+use std::error::Error;
+
+use sic_core::image::GenericImageView;
+use sic_core::json::escape_json;
+
+use crate::atlas::{Frame, Sheet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Descriptor {
+    /// The JSON (hash) format of TexturePacker.
+    Json,
+    /// The XML format of Sparrow and Starling, which is also supported by TexturePacker.
+    Xml,
+}
+
+impl Default for Descriptor {
+    fn default() -> Self {
+        Descriptor::Json
+    }
+}
+
+impl Descriptor {
+    pub fn try_from_str(val: &str) -> Result<Descriptor, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "json" => Ok(Descriptor::Json),
+            "xml" => Ok(Descriptor::Xml),
+            fail => Err(format!("No such descriptor format: {}", fail).into()),
+        }
+    }
+
+    /// The file extension of the descriptor.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Descriptor::Json => "json",
+            Descriptor::Xml => "xml",
+        }
+    }
+
+    /// Describes the frames of a sheet, which is stored as the image with the given file name.
+    pub fn describe(self, sheet: &Sheet, image_name: &str) -> String {
+        match self {
+            Descriptor::Json => json(sheet, image_name),
+            Descriptor::Xml => xml(sheet, image_name),
+        }
+    }
+}
+
+fn json(sheet: &Sheet, image_name: &str) -> String {
+    let frames = sheet
+        .frames
+        .iter()
+        .map(json_frame)
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let (width, height) = sheet.image.dimensions();
+
+    format!(
+        r#"{{"frames": {{
+{}
+}},
+"meta": {{
+	"app": "sic",
+	"image": "{}",
+	"format": "RGBA8888",
+	"size": {{"w":{},"h":{}}},
+	"scale": "1"
+}}
+}}
+"#,
+        frames,
+        escape_json(image_name),
+        width,
+        height
+    )
+}
+
+fn json_frame(frame: &Frame) -> String {
+    format!(
+        r#""{}":
+{{
+	"frame": {{"x":{},"y":{},"w":{},"h":{}}},
+	"rotated": false,
+	"trimmed": {},
+	"spriteSourceSize": {{"x":{},"y":{},"w":{},"h":{}}},
+	"sourceSize": {{"w":{},"h":{}}}
+}}"#,
+        escape_json(&frame.name),
+        frame.frame.x,
+        frame.frame.y,
+        frame.frame.width,
+        frame.frame.height,
+        frame.trimmed(),
+        frame.source.x,
+        frame.source.y,
+        frame.source.width,
+        frame.source.height,
+        frame.source_size.0,
+        frame.source_size.1
+    )
+}
+
+fn xml(sheet: &Sheet, image_name: &str) -> String {
+    let frames = sheet
+        .frames
+        .iter()
+        .map(xml_frame)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <TextureAtlas imagePath=\"{}\">\n{}\n</TextureAtlas>\n",
+        escape_xml(image_name),
+        frames
+    )
+}
+
+fn xml_frame(frame: &Frame) -> String {
+    let mut element = format!(
+        "    <SubTexture name=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+        escape_xml(&frame.name),
+        frame.frame.x,
+        frame.frame.y,
+        frame.frame.width,
+        frame.frame.height
+    );
+
+    // the frame of a trimmed image is relative to the trimmed image
+    if frame.trimmed() {
+        element.push_str(&format!(
+            " frameX=\"-{}\" frameY=\"-{}\" frameWidth=\"{}\" frameHeight=\"{}\"",
+            frame.source.x, frame.source.y, frame.source_size.0, frame.source_size.1
+        ));
+    }
+
+    element.push_str("/>");
+    element
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atlas::Rect;
+    use sic_core::image::{DynamicImage, RgbaImage};
+
+    fn sheet() -> Sheet {
+        let rect = |x, y, width, height| Rect {
+            x,
+            y,
+            width,
+            height,
+        };
+
+        Sheet {
+            image: DynamicImage::ImageRgba8(RgbaImage::new(16, 8)),
+            frames: vec![
+                Frame {
+                    name: "hero.png".to_string(),
+                    frame: rect(0, 0, 8, 8),
+                    source: rect(0, 0, 8, 8),
+                    source_size: (8, 8),
+                },
+                Frame {
+                    name: "coin \"gold\".png".to_string(),
+                    frame: rect(8, 0, 3, 2),
+                    source: rect(1, 2, 3, 2),
+                    source_size: (5, 6),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn json_descriptor() {
+        let json = Descriptor::Json.describe(&sheet(), "sheet.png");
+
+        assert!(json.starts_with("{\"frames\": {\n\"hero.png\":\n{"));
+        assert!(json.contains("\t\"frame\": {\"x\":8,\"y\":0,\"w\":3,\"h\":2},"));
+        assert!(json.contains("\"coin \\\"gold\\\".png\":"));
+        assert!(json.contains("\t\"trimmed\": true,"));
+        assert!(json.contains("\t\"spriteSourceSize\": {\"x\":1,\"y\":2,\"w\":3,\"h\":2},"));
+        assert!(json.contains("\t\"sourceSize\": {\"w\":5,\"h\":6}"));
+        assert!(json.contains("\t\"image\": \"sheet.png\","));
+        assert!(json.contains("\t\"size\": {\"w\":16,\"h\":8},"));
+    }
+
+    #[test]
+    fn xml_descriptor() {
+        let xml = Descriptor::Xml.describe(&sheet(), "sheet & co.png");
+
+        assert!(xml.contains("<TextureAtlas imagePath=\"sheet &amp; co.png\">"));
+        assert!(xml
+            .contains("<SubTexture name=\"hero.png\" x=\"0\" y=\"0\" width=\"8\" height=\"8\"/>"));
+        assert!(xml.contains(
+            "<SubTexture name=\"coin &quot;gold&quot;.png\" x=\"8\" y=\"0\" width=\"3\" height=\"2\" \
+             frameX=\"-1\" frameY=\"-2\" frameWidth=\"5\" frameHeight=\"6\"/>"
+        ));
+        assert!(xml.ends_with("</TextureAtlas>\n"));
+    }
+
+    #[test]
+    fn descriptor_from_str() {
+        assert_eq!(Descriptor::Xml, Descriptor::try_from_str("XML").unwrap());
+        assert!(Descriptor::try_from_str("plist").is_err());
+    }
+}
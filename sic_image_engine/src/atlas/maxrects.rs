@@ -0,0 +1,155 @@
+use crate::atlas::Rect;
+
+/// Keeps track of all maximal free rectangles of the bin. A new rectangle is placed in the free
+/// rectangle which leaves the shortest side over (best short side fit).
+pub(crate) struct MaxRects {
+    free: Vec<Rect>,
+}
+
+impl MaxRects {
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        MaxRects {
+            free: vec![Rect {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            }],
+        }
+    }
+
+    pub(crate) fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let best = self
+            .free
+            .iter()
+            .filter(|free| free.width >= width && free.height >= height)
+            .min_by_key(|free| {
+                let (left_x, left_y) = (free.width - width, free.height - height);
+                (left_x.min(left_y), left_x.max(left_y), free.y, free.x)
+            })?;
+
+        let placed = Rect {
+            x: best.x,
+            y: best.y,
+            width,
+            height,
+        };
+
+        self.split_free_rects(&placed);
+        self.prune_free_rects();
+
+        Some((placed.x, placed.y))
+    }
+
+    /// Replaces each free rectangle which overlaps the placed rectangle by the (at most four)
+    /// maximal rectangles which remain free around it.
+    fn split_free_rects(&mut self, placed: &Rect) {
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+
+        for rect in &self.free {
+            if !rect.intersects(placed) {
+                free.push(*rect);
+                continue;
+            }
+
+            if placed.x > rect.x {
+                free.push(Rect {
+                    width: placed.x - rect.x,
+                    ..*rect
+                });
+            }
+
+            if placed.right() < rect.right() {
+                free.push(Rect {
+                    x: placed.right(),
+                    width: rect.right() - placed.right(),
+                    ..*rect
+                });
+            }
+
+            if placed.y > rect.y {
+                free.push(Rect {
+                    height: placed.y - rect.y,
+                    ..*rect
+                });
+            }
+
+            if placed.bottom() < rect.bottom() {
+                free.push(Rect {
+                    y: placed.bottom(),
+                    height: rect.bottom() - placed.bottom(),
+                    ..*rect
+                });
+            }
+        }
+
+        self.free = free;
+    }
+
+    /// Removes the free rectangles which are contained in another free rectangle.
+    fn prune_free_rects(&mut self) {
+        let mut index = 0;
+
+        while index < self.free.len() {
+            let rect = self.free[index];
+            let contained = self.free.iter().enumerate().any(|(other, outer)| {
+                other != index && outer.contains(&rect) && (*outer != rect || other < index)
+            });
+
+            if contained {
+                self.free.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_bin() {
+        let mut bin = MaxRects::new(4, 4);
+
+        assert_eq!(Some((0, 0)), bin.insert(2, 2));
+        assert!(bin.insert(2, 2).is_some());
+        assert!(bin.insert(2, 2).is_some());
+        assert!(bin.insert(2, 2).is_some());
+        assert_eq!(None, bin.insert(1, 1));
+    }
+
+    #[test]
+    fn does_not_overlap() {
+        let mut bin = MaxRects::new(10, 10);
+        let sizes = [(6, 3), (4, 7), (3, 3), (5, 2), (2, 5), (1, 1)];
+
+        let placed = sizes
+            .iter()
+            .map(|&(width, height)| {
+                let (x, y) = bin.insert(width, height).unwrap();
+                Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (i, a) in placed.iter().enumerate() {
+            assert!(a.right() <= 10 && a.bottom() <= 10);
+            for b in &placed[i + 1..] {
+                assert!(!a.intersects(b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn too_large() {
+        let mut bin = MaxRects::new(4, 4);
+
+        assert_eq!(None, bin.insert(5, 1));
+    }
+}
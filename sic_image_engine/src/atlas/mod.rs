@@ -0,0 +1,471 @@
+//! A texture atlas (or sprite sheet) packs many small images into one or more larger images, the
+//! sheets. A descriptor of each sheet records where each image can be found on it.
+
+use std::error::Error;
+
+use sic_core::image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+
+use crate::atlas::maxrects::MaxRects;
+use crate::atlas::skyline::Skyline;
+
+pub mod descriptor;
+mod maxrects;
+mod skyline;
+
+/// The algorithm which decides where the images are placed on a sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packer {
+    /// Places each image in the free area which fits it best; usually packs the tightest.
+    MaxRects,
+    /// Places each image as low, and then as far to the left, as possible on the skyline formed by
+    /// the images placed so far; faster, but usually leaves more space unused.
+    Skyline,
+}
+
+impl Packer {
+    pub fn try_from_str(val: &str) -> Result<Packer, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "maxrects" => Ok(Packer::MaxRects),
+            "skyline" => Ok(Packer::Skyline),
+            fail => Err(format!("No such packer: {}", fail).into()),
+        }
+    }
+}
+
+/// The layout of an atlas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Atlas {
+    /// The maximum width and height of a sheet; should be a power of two. The width and height of
+    /// each sheet are the smallest powers of two which fit its images.
+    pub max_size: u32,
+    /// The number of transparent pixels between the images on a sheet.
+    pub padding: u32,
+    /// Whether the transparent borders of the images are trimmed before they are packed.
+    pub trim: bool,
+    pub packer: Packer,
+}
+
+impl Default for Atlas {
+    fn default() -> Self {
+        Atlas {
+            max_size: 2048,
+            padding: 2,
+            trim: false,
+            packer: Packer::MaxRects,
+        }
+    }
+}
+
+/// A sheet and the images which were placed on it.
+pub struct Sheet {
+    pub image: DynamicImage,
+    /// The frames of the placed images, in the order in which the images were given.
+    pub frames: Vec<Frame>,
+}
+
+/// The location of an image on a sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub name: String,
+    /// The part of the sheet covered by the (possibly trimmed) image.
+    pub frame: Rect,
+    /// The part of the original image which remains after trimming; covers the whole image if it
+    /// wasn't trimmed.
+    pub source: Rect,
+    /// The (width, height) of the original image.
+    pub source_size: (u32, u32),
+}
+
+impl Frame {
+    pub fn trimmed(&self) -> bool {
+        (self.source.width, self.source.height) != self.source_size
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub(crate) fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    pub(crate) fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    pub(crate) fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    pub(crate) fn contains(&self, other: &Rect) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+}
+
+/// A sheet which is being packed.
+enum Bin {
+    MaxRects(MaxRects),
+    Skyline(Skyline),
+}
+
+impl Bin {
+    fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        match self {
+            Bin::MaxRects(bin) => bin.insert(width, height),
+            Bin::Skyline(bin) => bin.insert(width, height),
+        }
+    }
+}
+
+/// An image which is ready to be packed.
+struct Sprite<'a> {
+    index: usize,
+    name: &'a str,
+    image: &'a DynamicImage,
+    source: Rect,
+}
+
+impl Atlas {
+    /// Packs the named images onto as few sheets as possible.
+    pub fn pack(&self, images: &[(String, DynamicImage)]) -> Result<Vec<Sheet>, Box<dyn Error>> {
+        if images.is_empty() {
+            return Err("Atlas: At least one image is required.".into());
+        }
+
+        if !self.max_size.is_power_of_two() {
+            return Err("Atlas: The maximum sheet size should be a power of two.".into());
+        }
+
+        let mut sprites = images
+            .iter()
+            .enumerate()
+            .map(|(index, (name, image))| Sprite {
+                index,
+                name,
+                image,
+                source: if self.trim {
+                    opaque_bounds(image)
+                } else {
+                    let (width, height) = image.dimensions();
+                    Rect {
+                        x: 0,
+                        y: 0,
+                        width,
+                        height,
+                    }
+                },
+            })
+            .collect::<Vec<_>>();
+
+        // larger images first; they are the hardest to place
+        sprites.sort_by_key(|sprite| {
+            let Rect { width, height, .. } = sprite.source;
+            std::cmp::Reverse((width.max(height), width.min(height)))
+        });
+
+        let mut sheets = Vec::new();
+
+        while !sprites.is_empty() {
+            let (sheet, remaining) = self.pack_sheet(sprites)?;
+            sheets.push(sheet);
+            sprites = remaining;
+        }
+
+        Ok(sheets)
+    }
+
+    /// Packs as many of the sprites on a single sheet as possible; returns the sheet and the
+    /// sprites which didn't fit.
+    fn pack_sheet<'a>(
+        &self,
+        sprites: Vec<Sprite<'a>>,
+    ) -> Result<(Sheet, Vec<Sprite<'a>>), Box<dyn Error>> {
+        // the padding is added to the right and bottom of each sprite, so the bin is extended by
+        // the padding to allow sprites to touch the right and bottom edges of the sheet
+        let bin_size = self.max_size.saturating_add(self.padding);
+        let mut bin = match self.packer {
+            Packer::MaxRects => Bin::MaxRects(MaxRects::new(bin_size, bin_size)),
+            Packer::Skyline => Bin::Skyline(Skyline::new(bin_size, bin_size)),
+        };
+
+        let mut placed = Vec::new();
+        let mut remaining = Vec::new();
+
+        for sprite in sprites {
+            let Rect { width, height, .. } = sprite.source;
+            let position = bin.insert(
+                width.saturating_add(self.padding),
+                height.saturating_add(self.padding),
+            );
+
+            match position {
+                Some((x, y)) => placed.push((sprite, x, y)),
+                None => remaining.push(sprite),
+            }
+        }
+
+        if placed.is_empty() {
+            let sprite = &remaining[0];
+            return Err(format!(
+                "Atlas: The image '{}' ({}x{}) doesn't fit on a sheet of {}x{}.",
+                sprite.name,
+                sprite.source.width,
+                sprite.source.height,
+                self.max_size,
+                self.max_size
+            )
+            .into());
+        }
+
+        let (used_width, used_height) = placed.iter().fold((1, 1), |(w, h), (sprite, x, y)| {
+            (
+                w.max(x + sprite.source.width),
+                h.max(y + sprite.source.height),
+            )
+        });
+
+        let mut sheet = RgbaImage::new(
+            used_width.next_power_of_two(),
+            used_height.next_power_of_two(),
+        );
+
+        placed.sort_by_key(|(sprite, _, _)| sprite.index);
+
+        let frames = placed
+            .into_iter()
+            .map(|(sprite, x, y)| {
+                let Rect {
+                    x: source_x,
+                    y: source_y,
+                    width,
+                    height,
+                } = sprite.source;
+
+                let part = sprite.image.view(source_x, source_y, width, height);
+                imageops::replace(&mut sheet, &part.to_image(), x, y);
+
+                Frame {
+                    name: sprite.name.to_string(),
+                    frame: Rect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    },
+                    source: sprite.source,
+                    source_size: sprite.image.dimensions(),
+                }
+            })
+            .collect();
+
+        Ok((
+            Sheet {
+                image: DynamicImage::ImageRgba8(sheet),
+                frames,
+            },
+            remaining,
+        ))
+    }
+}
+
+/// The smallest part of the image which contains all pixels which aren't fully transparent. An
+/// image without such pixels is trimmed to its top left pixel.
+fn opaque_bounds(image: &DynamicImage) -> Rect {
+    let (width, height) = image.dimensions();
+    let opaque = |x: u32, y: u32| image.get_pixel(x, y).0[3] != 0;
+
+    let rows = (0..height).filter(|&y| (0..width).any(|x| opaque(x, y)));
+    let (top, bottom) = match min_max(rows) {
+        Some(bounds) => bounds,
+        None => {
+            return Rect {
+                x: 0,
+                y: 0,
+                width: 1.min(width),
+                height: 1.min(height),
+            }
+        }
+    };
+
+    let columns = (0..width).filter(|&x| (top..=bottom).any(|y| opaque(x, y)));
+    let (left, right) = min_max(columns).unwrap_or((0, width - 1));
+
+    Rect {
+        x: left,
+        y: top,
+        width: right - left + 1,
+        height: bottom - top + 1,
+    }
+}
+
+fn min_max(mut values: impl Iterator<Item = u32>) -> Option<(u32, u32)> {
+    let first = values.next()?;
+    Some(values.fold((first, first), |(min, max), value| {
+        (min.min(value), max.max(value))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::Rgba;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    fn image(name: &str, width: u32, height: u32) -> (String, DynamicImage) {
+        (
+            name.to_string(),
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, RED)),
+        )
+    }
+
+    fn atlas(max_size: u32, padding: u32, trim: bool, packer: Packer) -> Atlas {
+        Atlas {
+            max_size,
+            padding,
+            trim,
+            packer,
+        }
+    }
+
+    fn assert_no_overlap(sheet: &Sheet) {
+        for (i, a) in sheet.frames.iter().enumerate() {
+            assert!(a.frame.right() <= sheet.image.width());
+            assert!(a.frame.bottom() <= sheet.image.height());
+
+            for b in &sheet.frames[i + 1..] {
+                assert!(!a.frame.intersects(&b.frame));
+            }
+        }
+    }
+
+    #[test]
+    fn single_sheet() {
+        for &packer in &[Packer::MaxRects, Packer::Skyline] {
+            let images = vec![image("a", 10, 6), image("b", 3, 3), image("c", 5, 12)];
+            let sheets = atlas(64, 0, false, packer).pack(&images).unwrap();
+
+            assert_eq!(1, sheets.len());
+            assert_no_overlap(&sheets[0]);
+
+            let names = sheets[0]
+                .frames
+                .iter()
+                .map(|frame| frame.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(vec!["a", "b", "c"], names);
+
+            let (width, height) = sheets[0].image.dimensions();
+            assert!(width.is_power_of_two() && height.is_power_of_two());
+            assert!(width <= 32 && height <= 32);
+        }
+    }
+
+    #[test]
+    fn sheet_contains_images() {
+        let images = vec![image("a", 3, 2)];
+        let sheets = atlas(8, 0, false, Packer::MaxRects).pack(&images).unwrap();
+        let frame = &sheets[0].frames[0];
+
+        assert_eq!((4, 2), sheets[0].image.dimensions());
+        assert_eq!(RED, sheets[0].image.get_pixel(frame.frame.x, frame.frame.y));
+        assert!(!frame.trimmed());
+    }
+
+    #[test]
+    fn padding_between_images() {
+        for &packer in &[Packer::MaxRects, Packer::Skyline] {
+            let images = vec![image("a", 4, 4), image("b", 4, 4)];
+            let sheets = atlas(16, 2, false, packer).pack(&images).unwrap();
+            let (a, b) = (&sheets[0].frames[0].frame, &sheets[0].frames[1].frame);
+
+            let gap_x = b.x.max(a.x) - b.x.min(a.x);
+            let gap_y = b.y.max(a.y) - b.y.min(a.y);
+            assert!(gap_x >= 6 || gap_y >= 6);
+        }
+    }
+
+    #[test]
+    fn multiple_sheets() {
+        let images = vec![image("a", 8, 8), image("b", 8, 8), image("c", 4, 4)];
+        let sheets = atlas(8, 0, false, Packer::Skyline).pack(&images).unwrap();
+
+        assert_eq!(3, sheets.len());
+        assert_eq!(8, sheets[0].image.width());
+        assert_eq!((4, 4), sheets[2].image.dimensions());
+    }
+
+    #[test]
+    fn trim_transparent_borders() {
+        let mut buffer = RgbaImage::from_pixel(6, 5, TRANSPARENT);
+        buffer.put_pixel(2, 1, RED);
+        buffer.put_pixel(3, 3, RED);
+        let images = vec![("a".to_string(), DynamicImage::ImageRgba8(buffer))];
+
+        let sheets = atlas(8, 0, true, Packer::MaxRects).pack(&images).unwrap();
+        let frame = &sheets[0].frames[0];
+
+        assert!(frame.trimmed());
+        assert_eq!(
+            Rect {
+                x: 2,
+                y: 1,
+                width: 2,
+                height: 3
+            },
+            frame.source
+        );
+        assert_eq!((6, 5), frame.source_size);
+        assert_eq!((2, 4), sheets[0].image.dimensions());
+    }
+
+    #[test]
+    fn trim_fully_transparent() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 3, TRANSPARENT));
+
+        assert_eq!(
+            Rect {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1
+            },
+            opaque_bounds(&image)
+        );
+    }
+
+    #[test]
+    fn err_image_too_large() {
+        let images = vec![image("a", 9, 1)];
+
+        assert!(atlas(8, 0, false, Packer::MaxRects).pack(&images).is_err());
+    }
+
+    #[test]
+    fn err_max_size_not_power_of_two() {
+        let images = vec![image("a", 1, 1)];
+
+        assert!(atlas(100, 0, false, Packer::MaxRects)
+            .pack(&images)
+            .is_err());
+    }
+
+    #[test]
+    fn packer_from_str() {
+        assert_eq!(Packer::Skyline, Packer::try_from_str("Skyline").unwrap());
+        assert!(Packer::try_from_str("guillotine").is_err());
+    }
+}
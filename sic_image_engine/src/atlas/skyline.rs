@@ -0,0 +1,151 @@
+/// Keeps track of the top edge (the skyline) of the placed rectangles. A new rectangle is placed
+/// on the skyline as low as possible, and then as far to the left as possible (bottom left).
+pub(crate) struct Skyline {
+    width: u32,
+    height: u32,
+    /// Segments of (x, y, width), from left to right.
+    segments: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        Skyline {
+            width,
+            height,
+            segments: vec![(0, 0, width)],
+        }
+    }
+
+    pub(crate) fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (index, x, y) = (0..self.segments.len())
+            .filter_map(|index| {
+                self.fit(index, width, height)
+                    .map(|y| (index, self.segments[index].0, y))
+            })
+            .min_by_key(|&(_, x, y)| (y + height, x))?;
+
+        self.add(index, x, y + height, width);
+
+        Some((x, y))
+    }
+
+    /// The height at which a rectangle fits when its left side is placed at the start of the
+    /// segment at the index, if it fits at all. Sides are added as u64, so that (too) large
+    /// rectangles don't overflow.
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.segments[index].0;
+        if u64::from(x) + u64::from(width) > u64::from(self.width) {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut covered = 0;
+
+        for &(_, segment_y, segment_width) in &self.segments[index..] {
+            y = y.max(segment_y);
+            if u64::from(y) + u64::from(height) > u64::from(self.height) {
+                return None;
+            }
+
+            covered += segment_width;
+            if covered >= width {
+                return Some(y);
+            }
+        }
+
+        None
+    }
+
+    /// Raises the skyline to the given top, from x over the given width.
+    fn add(&mut self, index: usize, x: u32, top: u32, width: u32) {
+        self.segments.insert(index, (x, top, width));
+
+        // shrink or remove the segments which are now covered
+        let end = x + width;
+        let next = index + 1;
+        while next < self.segments.len() {
+            let (segment_x, segment_y, segment_width) = self.segments[next];
+            if segment_x >= end {
+                break;
+            }
+
+            let segment_end = segment_x + segment_width;
+            if segment_end <= end {
+                self.segments.remove(next);
+            } else {
+                self.segments[next] = (end, segment_y, segment_end - end);
+                break;
+            }
+        }
+
+        // merge neighbouring segments at the same height
+        let mut i = 0;
+        while i + 1 < self.segments.len() {
+            if self.segments[i].1 == self.segments[i + 1].1 {
+                self.segments[i].2 += self.segments[i + 1].2;
+                self.segments.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atlas::Rect;
+
+    #[test]
+    fn bottom_left() {
+        let mut bin = Skyline::new(4, 4);
+
+        assert_eq!(Some((0, 0)), bin.insert(2, 3));
+        assert_eq!(Some((2, 0)), bin.insert(2, 1));
+        assert_eq!(Some((2, 1)), bin.insert(2, 2));
+        assert_eq!(Some((0, 3)), bin.insert(4, 1));
+        assert_eq!(None, bin.insert(1, 1));
+    }
+
+    #[test]
+    fn does_not_overlap() {
+        let mut bin = Skyline::new(12, 12);
+        let sizes = [(6, 3), (4, 7), (3, 3), (5, 2), (2, 5), (1, 1)];
+
+        let placed = sizes
+            .iter()
+            .map(|&(width, height)| {
+                let (x, y) = bin.insert(width, height).unwrap();
+                Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for (i, a) in placed.iter().enumerate() {
+            assert!(a.right() <= 12 && a.bottom() <= 12);
+            for b in &placed[i + 1..] {
+                assert!(!a.intersects(b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn too_large() {
+        let mut bin = Skyline::new(4, 4);
+
+        assert_eq!(None, bin.insert(1, 5));
+    }
+
+    #[test]
+    fn too_large_without_overflow() {
+        let mut bin = Skyline::new(8, 8);
+
+        assert_eq!(Some((0, 0)), bin.insert(2, 2));
+        assert_eq!(None, bin.insert(std::u32::MAX, 1));
+        assert_eq!(None, bin.insert(1, std::u32::MAX));
+    }
+}
@@ -7,25 +7,110 @@ use std::hash::Hash;
 use sic_core::image::DynamicImage;
 use sic_core::image::FilterType;
 use sic_core::image::GenericImageView;
+use sic_core::image::Rgba;
 
+use crate::operations::draw::{self, Style};
+use crate::operations::font::Font;
+use crate::operations::text::{self, TextStyle};
+use crate::operations::{append, blend, color_transfer, effects, histogram, redact, tone};
+use crate::wrapper::alignment::Alignment;
 use crate::wrapper::filter_type::FilterTypeWrap;
+use crate::wrapper::gravity::Gravity;
+use crate::wrapper::match_mode::MatchMode;
+use crate::wrapper::region::Region;
 use crate::ImgOp;
 
+/// The sampling filter used to resize images, unless another filter is set in the environment.
+pub const DEFAULT_RESIZE_SAMPLING_FILTER: FilterType = FilterType::Gaussian;
+
 trait EnvironmentKey {
     fn key(&self) -> EnvironmentKind;
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumDiscriminants)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumDiscriminants)]
 #[strum_discriminants(name(EnvironmentKind), derive(Display, Hash))]
 pub enum EnvironmentItem {
+    AppendAlignment(Alignment),
+    AppendBackground(Rgba<u8>),
     CustomSamplingFilter(FilterTypeWrap),
+    DrawAntialias,
+    DrawFill(Rgba<u8>),
+    DrawStrokeWidth(u32),
+    MatchHistogramMode(MatchMode),
     PreserveAspectRatio,
+    TextColor(Rgba<u8>),
+    TextFont(String),
+    TextGravity(Gravity),
+    TextSize(u32),
 }
 
 impl EnvironmentItem {
-    pub fn resize_sampling_filter(self) -> Option<FilterTypeWrap> {
+    pub fn append_alignment(&self) -> Option<Alignment> {
+        match self {
+            EnvironmentItem::AppendAlignment(alignment) => Some(*alignment),
+            _ => None,
+        }
+    }
+
+    pub fn append_background(&self) -> Option<Rgba<u8>> {
+        match self {
+            EnvironmentItem::AppendBackground(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    pub fn resize_sampling_filter(&self) -> Option<FilterTypeWrap> {
+        match self {
+            EnvironmentItem::CustomSamplingFilter(k) => Some(*k),
+            _ => None,
+        }
+    }
+
+    pub fn draw_fill(&self) -> Option<Rgba<u8>> {
+        match self {
+            EnvironmentItem::DrawFill(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    pub fn draw_stroke_width(&self) -> Option<u32> {
+        match self {
+            EnvironmentItem::DrawStrokeWidth(width) => Some(*width),
+            _ => None,
+        }
+    }
+
+    pub fn match_histogram_mode(&self) -> Option<MatchMode> {
+        match self {
+            EnvironmentItem::MatchHistogramMode(mode) => Some(*mode),
+            _ => None,
+        }
+    }
+
+    pub fn text_color(&self) -> Option<Rgba<u8>> {
+        match self {
+            EnvironmentItem::TextColor(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    pub fn text_font(&self) -> Option<&str> {
+        match self {
+            EnvironmentItem::TextFont(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn text_gravity(&self) -> Option<Gravity> {
+        match self {
+            EnvironmentItem::TextGravity(gravity) => Some(*gravity),
+            _ => None,
+        }
+    }
+
+    pub fn text_size(&self) -> Option<u32> {
         match self {
-            EnvironmentItem::CustomSamplingFilter(k) => Some(k),
+            EnvironmentItem::TextSize(size) => Some(*size),
             _ => None,
         }
     }
@@ -34,8 +119,18 @@ impl EnvironmentItem {
 impl EnvironmentKey for EnvironmentItem {
     fn key(&self) -> EnvironmentKind {
         match self {
+            EnvironmentItem::AppendAlignment(_) => EnvironmentKind::AppendAlignment,
+            EnvironmentItem::AppendBackground(_) => EnvironmentKind::AppendBackground,
             EnvironmentItem::CustomSamplingFilter(_) => EnvironmentKind::CustomSamplingFilter,
+            EnvironmentItem::DrawAntialias => EnvironmentKind::DrawAntialias,
+            EnvironmentItem::DrawFill(_) => EnvironmentKind::DrawFill,
+            EnvironmentItem::DrawStrokeWidth(_) => EnvironmentKind::DrawStrokeWidth,
+            EnvironmentItem::MatchHistogramMode(_) => EnvironmentKind::MatchHistogramMode,
             EnvironmentItem::PreserveAspectRatio => EnvironmentKind::PreserveAspectRatio,
+            EnvironmentItem::TextColor(_) => EnvironmentKind::TextColor,
+            EnvironmentItem::TextFont(_) => EnvironmentKind::TextFont,
+            EnvironmentItem::TextGravity(_) => EnvironmentKind::TextGravity,
+            EnvironmentItem::TextSize(_) => EnvironmentKind::TextSize,
         }
     }
 }
@@ -55,9 +150,7 @@ impl Default for Environment {
 
 impl Environment {
     pub fn insert_or_update(&mut self, item: EnvironmentItem) {
-        let key = item.key();
-
-        *self.store.entry(key).or_insert(item) = item;
+        self.store.insert(item.key(), item);
     }
 
     pub fn remove(&mut self, key: EnvironmentKind) -> Option<()> {
@@ -69,11 +162,24 @@ impl Environment {
     }
 }
 
+/// The part of an image to which the instructions of a block are applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// The instructions are applied to the given region only.
+    Region(Region),
+    /// The instructions are applied to the whole image, after which the result is blended with the
+    /// original image, weighted by the grayscale mask image at the given path.
+    Mask(String),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Instruction {
     Operation(ImgOp),
     AddToEnv(EnvironmentItem),
     RemoveFromEnv(EnvironmentKind),
+    /// A nested list of instructions, which is applied within the given scope.
+    /// Changes to the environment made within the block, do not affect the instructions after it.
+    Block(Scope, Vec<Instruction>),
 }
 
 #[derive(Clone)]
@@ -107,21 +213,124 @@ impl ImageEngine {
     fn process_instruction(&mut self, instruction: &Instruction) -> Result<(), Box<dyn Error>> {
         match instruction {
             Instruction::Operation(op) => self.process_operation(op),
-            Instruction::AddToEnv(item) => self.insert_env(*item),
+            Instruction::AddToEnv(item) => self.insert_env(item.clone()),
             Instruction::RemoveFromEnv(key) => self.remove_env(*key),
+            Instruction::Block(scope, instructions) => self.process_block(scope, instructions),
+        }
+    }
+
+    fn process_block(
+        &mut self,
+        scope: &Scope,
+        instructions: &[Instruction],
+    ) -> Result<(), Box<dyn Error>> {
+        match scope {
+            Scope::Region(region) => {
+                let (width, height) = self.image.dimensions();
+
+                // a region outside of the image leaves nothing to apply the instructions to
+                let region = match region.within(width, height) {
+                    Some(region) => region,
+                    None => return Ok(()),
+                };
+
+                let part = self
+                    .image
+                    .crop(region.x, region.y, region.width, region.height);
+                let processed = self.process_nested(part, instructions)?;
+
+                *self.image = blend::replace_region(&self.image, &processed, region)?;
+                Ok(())
+            }
+            Scope::Mask(mask_path) => {
+                let mask = sic_core::image::open(mask_path).map_err(|err| {
+                    format!(
+                        "Block: mask -- Unable to open the mask image '{}': {}",
+                        mask_path, err
+                    )
+                })?;
+
+                let processed = self.process_nested((*self.image).clone(), instructions)?;
+
+                *self.image = blend::blend_with_mask(&self.image, &processed, &mask.to_luma())?;
+                Ok(())
+            }
         }
     }
 
+    // Applies the instructions to the given image with a separate engine, which starts out with a
+    // copy of the current environment.
+    fn process_nested(
+        &self,
+        image: DynamicImage,
+        instructions: &[Instruction],
+    ) -> Result<DynamicImage, Box<dyn Error>> {
+        let mut engine = ImageEngine {
+            environment: self.environment.clone(),
+            image: Box::from(image),
+        };
+
+        engine.ignite(instructions)?;
+
+        Ok(*engine.image)
+    }
+
     fn process_operation(&mut self, operation: &ImgOp) -> Result<(), Box<dyn Error>> {
         match operation {
+            ImgOp::Append((axis, paths)) => {
+                const DEFAULT_BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+                let others = paths
+                    .iter()
+                    .map(|path| {
+                        sic_core::image::open(path).map_err(|err| {
+                            format!(
+                                "Operation: append -- Unable to open the image '{}': {}",
+                                path, err
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let alignment = self
+                    .environment
+                    .get(EnvironmentKind::AppendAlignment)
+                    .and_then(|item| item.append_alignment())
+                    .unwrap_or_default();
+
+                let background = self
+                    .environment
+                    .get(EnvironmentKind::AppendBackground)
+                    .and_then(|item| item.append_background())
+                    .unwrap_or(DEFAULT_BACKGROUND);
+
+                *self.image = append::append(&self.image, &others, *axis, alignment, background)?;
+                Ok(())
+            }
+            ImgOp::AutoLevels((black_clip, white_clip)) => {
+                *self.image = histogram::auto_levels(&self.image, *black_clip, *white_clip)?;
+                Ok(())
+            }
             ImgOp::Blur(sigma) => {
                 *self.image = self.image.blur(*sigma);
                 Ok(())
             }
+            ImgOp::BlurRegions((sigma, regions)) => {
+                *self.image = redact::blur(&self.image, *sigma, regions)?;
+                Ok(())
+            }
             ImgOp::Brighten(amount) => {
                 *self.image = self.image.brighten(*amount);
                 Ok(())
             }
+            ImgOp::CircleCrop => {
+                *self.image = effects::circle_crop(&self.image);
+                Ok(())
+            }
+            ImgOp::Clahe((tiles, clip_limit)) => {
+                *self.image = histogram::clahe(&self.image, *tiles, *clip_limit)?;
+                Ok(())
+            }
             ImgOp::Contrast(c) => {
                 *self.image = self.image.adjust_contrast(*c);
                 Ok(())
@@ -138,6 +347,34 @@ impl ImageEngine {
                         *self.image = self.image.crop(*lx, *ly, rx - lx, ry - ly);
                     })
             }
+            ImgOp::Curves((channel, points)) => {
+                *self.image = tone::curves(&self.image, *channel, points)?;
+                Ok(())
+            }
+            ImgOp::DrawEllipse((x, y, radius_x, radius_y, color)) => {
+                let style = self.draw_style(*color);
+                *self.image = draw::ellipse(&self.image, (*x, *y), *radius_x, *radius_y, &style)?;
+                Ok(())
+            }
+            ImgOp::DrawLine((x1, y1, x2, y2, color)) => {
+                let style = self.draw_style(*color);
+                *self.image = draw::line(&self.image, (*x1, *y1), (*x2, *y2), &style);
+                Ok(())
+            }
+            ImgOp::DrawPolygon((color, points)) => {
+                let style = self.draw_style(*color);
+                *self.image = draw::polygon(&self.image, points, &style)?;
+                Ok(())
+            }
+            ImgOp::DrawRect((x, y, width, height, color)) => {
+                let style = self.draw_style(*color);
+                *self.image = draw::rect(&self.image, *x, *y, *width, *height, &style);
+                Ok(())
+            }
+            ImgOp::Equalize => {
+                *self.image = histogram::equalize(&self.image);
+                Ok(())
+            }
             // We need to ensure here that Filter3x3's `it` (&[f32]) has length 9.
             // Otherwise it will panic, see: https://docs.rs/image/0.19.0/src/image/dynimage.rs.html#349
             // This check already happens within the `parse` module.
@@ -165,15 +402,58 @@ impl ImageEngine {
                 self.image.invert();
                 Ok(())
             }
-            ImgOp::Resize((new_x, new_y)) => {
-                const DEFAULT_RESIZE_FILTER: FilterType = FilterType::Gaussian;
+            ImgOp::Levels((in_black, in_white, gamma, out_black, out_white)) => {
+                *self.image = tone::levels(
+                    &self.image,
+                    *in_black,
+                    *in_white,
+                    *gamma,
+                    *out_black,
+                    *out_white,
+                )?;
+                Ok(())
+            }
+            ImgOp::MatchHistogram(reference_path) => {
+                let reference = sic_core::image::open(reference_path).map_err(|err| {
+                    format!(
+                        "Operation: matchhistogram -- Unable to open the reference image '{}': {}",
+                        reference_path, err
+                    )
+                })?;
+
+                let mode = self
+                    .environment
+                    .get(EnvironmentKind::MatchHistogramMode)
+                    .and_then(|item| item.match_histogram_mode())
+                    .unwrap_or_default();
+
+                *self.image = match mode {
+                    MatchMode::Histogram => {
+                        color_transfer::match_histogram(&self.image, &reference)
+                    }
+                    MatchMode::Reinhard => color_transfer::reinhard(&self.image, &reference),
+                };
+
+                Ok(())
+            }
+            ImgOp::Pixelate(block) => {
+                let (width, height) = self.image.dimensions();
+                let whole = Region::new(0, 0, width, height);
 
+                *self.image = redact::pixelate(&self.image, *block, &[whole])?;
+                Ok(())
+            }
+            ImgOp::PixelateRegions((block, regions)) => {
+                *self.image = redact::pixelate(&self.image, *block, regions)?;
+                Ok(())
+            }
+            ImgOp::Resize((new_x, new_y)) => {
                 let filter = self
                     .environment
                     .get(EnvironmentKind::CustomSamplingFilter)
                     .and_then(|item| item.resize_sampling_filter())
                     .map(FilterType::from)
-                    .unwrap_or(DEFAULT_RESIZE_FILTER);
+                    .unwrap_or(DEFAULT_RESIZE_SAMPLING_FILTER);
 
                 *self.image = if self
                     .environment
@@ -199,10 +479,104 @@ impl ImageEngine {
                 *self.image = self.image.rotate270();
                 Ok(())
             }
+            ImgOp::RoundCorners(radius) => {
+                *self.image = effects::round_corners(&self.image, *radius);
+                Ok(())
+            }
+            ImgOp::Shadow((offset_x, offset_y, blur, color)) => {
+                *self.image = effects::shadow(&self.image, *offset_x, *offset_y, *blur, *color)?;
+                Ok(())
+            }
+            ImgOp::Text((text, x, y)) => {
+                let font = match self
+                    .environment
+                    .get(EnvironmentKind::TextFont)
+                    .and_then(|item| item.text_font())
+                {
+                    Some(path) => Font::from_file(path).map_err(|err| {
+                        format!(
+                            "Operation: text -- Unable to load the font '{}': {}",
+                            path, err
+                        )
+                    })?,
+                    None => Font::Builtin,
+                };
+
+                let style = self.text_style(&font);
+                *self.image = text::text(&self.image, text, *x, *y, &style)?;
+                Ok(())
+            }
             ImgOp::Unsharpen((sigma, threshold)) => {
                 *self.image = self.image.unsharpen(*sigma, *threshold);
                 Ok(())
             }
+            ImgOp::Vignette((strength, radius)) => {
+                *self.image = effects::vignette(&self.image, *strength, *radius)?;
+                Ok(())
+            }
+        }
+    }
+
+    // The style of the drawing operations, with the given stroke color. The stroke width (default:
+    // 1 pixel), fill color (default: none) and anti-aliasing (default: off) can be changed by the
+    // user, by setting the draw modifiers.
+    fn draw_style(&mut self, stroke: Rgba<u8>) -> Style {
+        const DEFAULT_STROKE_WIDTH: u32 = 1;
+
+        let stroke_width = self
+            .environment
+            .get(EnvironmentKind::DrawStrokeWidth)
+            .and_then(|item| item.draw_stroke_width())
+            .unwrap_or(DEFAULT_STROKE_WIDTH);
+
+        let fill = self
+            .environment
+            .get(EnvironmentKind::DrawFill)
+            .and_then(|item| item.draw_fill());
+
+        let antialias = self
+            .environment
+            .get(EnvironmentKind::DrawAntialias)
+            .is_some();
+
+        Style {
+            stroke,
+            stroke_width,
+            fill,
+            antialias,
+        }
+    }
+
+    // The style of the text operation, drawn with the given font. The size (default: 16 pixels),
+    // color (default: black) and gravity (default: north west) can be changed by the user, by
+    // setting the text modifiers.
+    fn text_style<'font>(&mut self, font: &'font Font) -> TextStyle<'font> {
+        const DEFAULT_SIZE: u32 = 16;
+        const DEFAULT_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+        let size = self
+            .environment
+            .get(EnvironmentKind::TextSize)
+            .and_then(|item| item.text_size())
+            .unwrap_or(DEFAULT_SIZE);
+
+        let color = self
+            .environment
+            .get(EnvironmentKind::TextColor)
+            .and_then(|item| item.text_color())
+            .unwrap_or(DEFAULT_COLOR);
+
+        let gravity = self
+            .environment
+            .get(EnvironmentKind::TextGravity)
+            .and_then(|item| item.text_gravity())
+            .unwrap_or_default();
+
+        TextStyle {
+            font,
+            size,
+            color,
+            gravity,
         }
     }
 
@@ -373,6 +747,9 @@ mod tests {
     use sic_core::image::GenericImageView;
     use sic_core::image::Rgba;
 
+    use crate::wrapper::axis::Axis;
+    use crate::wrapper::channel::Channel;
+    use crate::wrapper::region::Region;
     use sic_testing::{in_, out_};
 
     // output images during tests to verify the results visually
@@ -497,42 +874,79 @@ mod tests {
     }
 
     #[test]
-    fn test_blur() {
+    fn test_append() {
         let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::Blur(10.0);
+
+        let operation = ImgOp::Append((
+            Axis::X,
+            vec![
+                in_!("rainbow_8x6.bmp").to_string(),
+                in_!("palette_4x4.png").to_string(),
+            ],
+        ));
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
 
         assert!(done.is_ok());
 
-        output_test_image_for_manual_inspection(&done.unwrap(), out_!("test_blur.png"));
+        let result_img = done.unwrap();
+
+        assert_eq!((217 + 8 + 4, 447), result_img.dimensions());
+        // the default background is transparent
+        assert_eq!(Rgba([0, 0, 0, 0]), result_img.get_pixel(217, 6));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_append.png"));
     }
 
     #[test]
-    fn test_brighten_pos() {
+    fn test_append_with_alignment_and_background() {
         let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Brighten(25);
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::AppendAlignment(Alignment::End)),
+            Instruction::AddToEnv(EnvironmentItem::AppendBackground(Rgba([255, 0, 255, 255]))),
+            Instruction::Operation(ImgOp::Append((
+                Axis::Y,
+                vec![in_!("rainbow_8x6.bmp").to_string()],
+            ))),
+        ];
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&operations);
 
         assert!(done.is_ok());
 
         let result_img = done.unwrap();
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!((217, 447 + 6), result_img.dimensions());
+        assert_eq!(Rgba([255, 0, 255, 255]), result_img.get_pixel(0, 447));
+        assert_ne!(Rgba([255, 0, 255, 255]), result_img.get_pixel(216, 447));
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_pos_25.png"));
+        output_test_image_for_manual_inspection(
+            &result_img,
+            out_!("test_append_with_alignment_and_background.png"),
+        );
     }
 
     #[test]
-    fn test_brighten_zero() {
+    fn test_append_err_image_not_found() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Append((Axis::X, vec![in_!("does_not_exist.png").to_string()]));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_auto_levels() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::Brighten(0);
+
+        let operation = ImgOp::AutoLevels((0.5, 0.5));
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -541,17 +955,46 @@ mod tests {
 
         let result_img = done.unwrap();
 
-        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.color(), result_img.color());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_zero.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_auto_levels.png"));
     }
 
     #[test]
-    fn test_brighten_neg() {
+    fn test_auto_levels_err_invalid_clip() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::AutoLevels((60.0, 40.0));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_blur() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::Blur(10.0);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        output_test_image_for_manual_inspection(&done.unwrap(), out_!("test_blur.png"));
+    }
+
+    #[test]
+    fn test_blur_regions() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Brighten(-25);
+        let operation = ImgOp::BlurRegions((
+            5.0,
+            vec![Region::new(10, 10, 80, 60), Region::new(100, 200, 60, 60)],
+        ));
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -561,35 +1004,44 @@ mod tests {
         let result_img = done.unwrap();
 
         assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.get_pixel(0, 0), result_img.get_pixel(0, 0));
+        assert_eq!(cmp.color(), result_img.color());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_neg_25.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_blur_regions.png"));
     }
 
     #[test]
-    fn test_contrast_pos() {
+    fn test_blur_regions_none() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Contrast(150.9);
+        let operation = ImgOp::BlurRegions((5.0, vec![]));
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
 
         assert!(done.is_ok());
+        assert_eq!(cmp.raw_pixels(), done.unwrap().raw_pixels());
+    }
 
-        let result_img = done.unwrap();
+    #[test]
+    fn test_blur_regions_err_zero_sigma() {
+        let img: DynamicImage = setup_default_test_image();
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        let operation = ImgOp::BlurRegions((0.0, vec![Region::new(10, 10, 80, 60)]));
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_contrast_neg() {
+    fn test_brighten_pos() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Contrast(-150.9);
+        let operation = ImgOp::Brighten(25);
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -600,15 +1052,14 @@ mod tests {
 
         assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_pos_25.png"));
     }
 
     #[test]
-    fn test_crop_ok_no_change() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-
-        let operation = ImgOp::Crop((0, 0, 2, 2));
+    fn test_brighten_zero() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::Brighten(0);
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -619,15 +1070,15 @@ mod tests {
 
         assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_crop_no_change.bmp"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_zero.png"));
     }
 
     #[test]
-    fn test_crop_ok_to_one_pixel() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_brighten_neg() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Crop((0, 0, 1, 1));
+        let operation = ImgOp::Brighten(-25);
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -638,24 +1089,15 @@ mod tests {
 
         assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
 
-        let result_dim = result_img.dimensions();
-        assert_eq!(1, result_dim.0);
-        assert_eq!(1, result_dim.1);
-
-        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
-
-        output_test_image_for_manual_inspection(
-            &result_img,
-            out_!("test_crop_ok_to_one_pixel.bmp"),
-        );
+        output_test_image_for_manual_inspection(&result_img, out_!("test_brighten_neg_25.png"));
     }
 
     #[test]
-    fn test_crop_ok_to_half_horizontal() {
-        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
-        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+    fn test_circle_crop() {
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
 
-        let operation = ImgOp::Crop((0, 0, 2, 1));
+        let operation = ImgOp::CircleCrop;
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -663,20 +1105,159 @@ mod tests {
         assert!(done.is_ok());
 
         let result_img = done.unwrap();
+        let side = width.min(height);
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
-
-        let result_dim = result_img.dimensions();
-        assert_eq!(2, result_dim.0);
-        assert_eq!(1, result_dim.1);
-
-        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
-        assert_eq!(Rgba([255, 255, 255, 255]), result_img.get_pixel(1, 0));
+        assert_eq!((side, side), result_img.dimensions());
+        assert_eq!(0, result_img.get_pixel(0, 0).0[3]);
+        assert_eq!(255, result_img.get_pixel(side / 2, side / 2).0[3]);
 
-        output_test_image_for_manual_inspection(
-            &result_img,
-            out_!("test_crop_ok_to_half_horizontal.bmp"),
-        );
+        output_test_image_for_manual_inspection(&result_img, out_!("test_circle_crop.png"));
+    }
+
+    #[test]
+    fn test_clahe() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Clahe((8, 2.0));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_clahe_8_2.png"));
+    }
+
+    #[test]
+    fn test_clahe_err_zero_tiles() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Clahe((0, 2.0));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_contrast_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Contrast(150.9);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+    }
+
+    #[test]
+    fn test_contrast_neg() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Contrast(-150.9);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_contrast_pos_15_9.png"));
+    }
+
+    #[test]
+    fn test_crop_ok_no_change() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 2, 2));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_crop_no_change.bmp"));
+    }
+
+    #[test]
+    fn test_crop_ok_to_one_pixel() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 1, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        let result_dim = result_img.dimensions();
+        assert_eq!(1, result_dim.0);
+        assert_eq!(1, result_dim.1);
+
+        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
+
+        output_test_image_for_manual_inspection(
+            &result_img,
+            out_!("test_crop_ok_to_one_pixel.bmp"),
+        );
+    }
+
+    #[test]
+    fn test_crop_ok_to_half_horizontal() {
+        let img: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+        let cmp: DynamicImage = sic_testing::open_test_image(in_!("blackwhite_2x2.bmp"));
+
+        let operation = ImgOp::Crop((0, 0, 2, 1));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        let result_dim = result_img.dimensions();
+        assert_eq!(2, result_dim.0);
+        assert_eq!(1, result_dim.1);
+
+        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(0, 0));
+        assert_eq!(Rgba([255, 255, 255, 255]), result_img.get_pixel(1, 0));
+
+        output_test_image_for_manual_inspection(
+            &result_img,
+            out_!("test_crop_ok_to_half_horizontal.bmp"),
+        );
     }
 
     #[test]
@@ -750,18 +1331,490 @@ mod tests {
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
 
-        assert!(done.is_err());
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_curves() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Curves((Channel::Rgb, vec![(0, 0), (64, 100), (255, 255)]));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.color(), result_img.color());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_curves.png"));
+    }
+
+    #[test]
+    fn test_curves_per_channel() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::Operation(ImgOp::Curves((Channel::Red, vec![(0, 20), (255, 235)]))),
+            Instruction::Operation(ImgOp::Curves((
+                Channel::Blue,
+                vec![(0, 0), (128, 90), (255, 255)],
+            ))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_ok());
+
+        output_test_image_for_manual_inspection(
+            &done.unwrap(),
+            out_!("test_curves_per_channel.png"),
+        );
+    }
+
+    #[test]
+    fn test_curves_err_single_point() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Curves((Channel::Rgb, vec![(0, 0)]));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_draw_ellipse() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::DrawFill(Rgba([0, 0, 255, 255]))),
+            Instruction::Operation(ImgOp::DrawEllipse((
+                100,
+                200,
+                50,
+                30,
+                Rgba([255, 0, 0, 255]),
+            ))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
+        assert_eq!(Rgba([0, 0, 255, 255]), result_img.get_pixel(100, 200));
+        assert_eq!(Rgba([255, 0, 0, 255]), result_img.get_pixel(50, 200));
+        assert_eq!(cmp.get_pixel(0, 0), result_img.get_pixel(0, 0));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_draw_ellipse.png"));
+    }
+
+    #[test]
+    fn test_draw_ellipse_err_zero_radius() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::DrawEllipse((100, 200, 0, 30, Rgba([255, 0, 0, 255])));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_draw_line() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::DrawStrokeWidth(5)),
+            Instruction::AddToEnv(EnvironmentItem::DrawAntialias),
+            Instruction::Operation(ImgOp::DrawLine((
+                10,
+                10,
+                200,
+                400,
+                Rgba([255, 255, 0, 255]),
+            ))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(Rgba([255, 255, 0, 255]), result_img.get_pixel(10, 10));
+        assert_eq!(Rgba([255, 255, 0, 255]), result_img.get_pixel(12, 10));
+        assert_eq!(cmp.get_pixel(200, 10), result_img.get_pixel(200, 10));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_draw_line.png"));
+    }
+
+    #[test]
+    fn test_draw_polygon() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::DrawFill(Rgba([0, 255, 0, 128]))),
+            Instruction::AddToEnv(EnvironmentItem::DrawStrokeWidth(0)),
+            Instruction::Operation(ImgOp::DrawPolygon((
+                Rgba([255, 0, 0, 255]),
+                vec![(20, 20), (180, 40), (100, 300)],
+            ))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.get_pixel(100, 100), result_img.get_pixel(100, 100));
+        assert_eq!(cmp.get_pixel(10, 400), result_img.get_pixel(10, 400));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_draw_polygon.png"));
+    }
+
+    #[test]
+    fn test_draw_polygon_err_too_few_points() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::DrawPolygon((Rgba([255, 0, 0, 255]), vec![(20, 20), (180, 40)]));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_draw_rect() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::DrawRect((10, 20, 100, 50, Rgba([255, 0, 0, 255])));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(Rgba([255, 0, 0, 255]), result_img.get_pixel(10, 20));
+        assert_eq!(Rgba([255, 0, 0, 255]), result_img.get_pixel(109, 69));
+        assert_eq!(cmp.get_pixel(50, 40), result_img.get_pixel(50, 40));
+        assert_eq!(cmp.get_pixel(110, 70), result_img.get_pixel(110, 70));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_draw_rect.png"));
+    }
+
+    #[test]
+    fn test_equalize() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Equalize;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.color(), result_img.color());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_equalize.png"));
+    }
+
+    #[test]
+    fn test_filter3x3() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Filter3x3([1.0, 0.5, 0.0, 1.0, 0.5, 0.0, 1.0, 0.5, 0.0]);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_filter3x3.png"))
+    }
+
+    #[test]
+    fn test_flip_h() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::FlipHorizontal;
+
+        let (xa, ya) = img.dimensions();
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+        let (xb, yb) = img_result.dimensions();
+
+        assert_eq!(xa, xb);
+        assert_eq!(ya, yb);
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_fliph.png"));
+    }
+
+    #[test]
+    fn test_flip_v() {
+        let img: DynamicImage = setup_default_test_image();
+        let operation = ImgOp::FlipVertical;
+
+        let (xa, ya) = img.dimensions();
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+        let (xb, yb) = img_result.dimensions();
+
+        assert_eq!(xa, xb);
+        assert_eq!(ya, yb);
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_flipv.png"));
+    }
+
+    #[test]
+    fn test_gray_scale() {
+        use sic_core::image::Pixel;
+
+        let img: DynamicImage = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
+        let operation = ImgOp::GrayScale;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let img_result = done.unwrap();
+
+        // The color type isn't actually changed to luma, so instead of checking color type,
+        // here pixels are checked to have equal (r, g, b) components.
+        for i in 0..8 {
+            for j in 0..6 {
+                let pixel = img_result.get_pixel(i, j);
+                let channels_result = pixel.channels();
+                let r_component = channels_result[0];
+                let g_component = channels_result[1];
+                let b_component = channels_result[2];
+
+                assert_eq!(r_component, g_component);
+                assert_eq!(g_component, b_component);
+            }
+        }
+
+        output_test_image_for_manual_inspection(&img_result, out_!("test_gray_scale.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_neg() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(-100);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_neg_100.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(100);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_100.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_zero() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(0);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_0.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_360() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(360);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        // https://docs.rs/image/0.19.0/image/enum.DynamicImage.html#method.huerotate
+        // huerotate(0) should be huerotate(360), but this doesn't seem the case
+        assert_eq!(cmp.huerotate(360).raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_360.png"));
+    }
+
+    #[test]
+    fn test_hue_rotate_over_rotate_pos() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::HueRotate(460);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.huerotate(100).raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_460.png"));
+    }
+
+    #[test]
+    fn test_invert() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Invert;
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_invert.png"));
+    }
+
+    #[test]
+    fn test_levels() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Levels((20, 230, 1.4, 10, 245));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.color(), result_img.color());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_levels.png"));
+    }
+
+    #[test]
+    fn test_levels_err_input_range() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Levels((230, 20, 1.0, 0, 255));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_match_histogram() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::MatchHistogram(in_!("rainbow_8x6.bmp").to_string());
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_match_histogram.png"));
     }
 
     #[test]
-    fn test_filter3x3() {
+    fn test_match_histogram_reinhard() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Filter3x3([1.0, 0.5, 0.0, 1.0, 0.5, 0.0, 1.0, 0.5, 0.0]);
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::MatchHistogramMode(MatchMode::Reinhard)),
+            Instruction::Operation(ImgOp::MatchHistogram(in_!("rainbow_8x6.bmp").to_string())),
+        ];
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&operations);
 
         assert!(done.is_ok());
 
@@ -769,87 +1822,86 @@ mod tests {
 
         assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_filter3x3.png"))
+        output_test_image_for_manual_inspection(
+            &result_img,
+            out_!("test_match_histogram_reinhard.png"),
+        );
     }
 
     #[test]
-    fn test_flip_h() {
+    fn test_match_histogram_modes_differ() {
+        let reference = in_!("rainbow_8x6.bmp").to_string();
+
+        let mut histogram = ImageEngine::new(setup_default_test_image());
+        let histogram = histogram
+            .ignite(&[Instruction::Operation(ImgOp::MatchHistogram(
+                reference.clone(),
+            ))])
+            .unwrap()
+            .clone();
+
+        let mut reinhard = ImageEngine::new(setup_default_test_image());
+        let reinhard = reinhard
+            .ignite(&[
+                Instruction::AddToEnv(EnvironmentItem::MatchHistogramMode(MatchMode::Reinhard)),
+                Instruction::Operation(ImgOp::MatchHistogram(reference)),
+            ])
+            .unwrap();
+
+        assert_ne!(histogram.raw_pixels(), reinhard.raw_pixels());
+    }
+
+    #[test]
+    fn test_match_histogram_err_reference_not_found() {
         let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::FlipHorizontal;
 
-        let (xa, ya) = img.dimensions();
+        let operation = ImgOp::MatchHistogram(in_!("does_not_exist.png").to_string());
+
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
 
-        assert!(done.is_ok());
-
-        let img_result = done.unwrap();
-        let (xb, yb) = img_result.dimensions();
-
-        assert_eq!(xa, xb);
-        assert_eq!(ya, yb);
-
-        output_test_image_for_manual_inspection(&img_result, out_!("test_fliph.png"));
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_flip_v() {
+    fn test_pixelate() {
         let img: DynamicImage = setup_default_test_image();
-        let operation = ImgOp::FlipVertical;
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Pixelate(16);
 
-        let (xa, ya) = img.dimensions();
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
 
         assert!(done.is_ok());
 
-        let img_result = done.unwrap();
-        let (xb, yb) = img_result.dimensions();
+        let result_img = done.unwrap();
 
-        assert_eq!(xa, xb);
-        assert_eq!(ya, yb);
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
+        assert_eq!(result_img.get_pixel(0, 0), result_img.get_pixel(15, 15));
 
-        output_test_image_for_manual_inspection(&img_result, out_!("test_flipv.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_pixelate.png"));
     }
 
     #[test]
-    fn test_gray_scale() {
-        use sic_core::image::Pixel;
+    fn test_pixelate_err_zero_block() {
+        let img: DynamicImage = setup_default_test_image();
 
-        let img: DynamicImage = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
-        let operation = ImgOp::GrayScale;
+        let operation = ImgOp::Pixelate(0);
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
 
-        assert!(done.is_ok());
-
-        let img_result = done.unwrap();
-
-        // The color type isn't actually changed to luma, so instead of checking color type,
-        // here pixels are checked to have equal (r, g, b) components.
-        for i in 0..8 {
-            for j in 0..6 {
-                let pixel = img_result.get_pixel(i, j);
-                let channels_result = pixel.channels();
-                let r_component = channels_result[0];
-                let g_component = channels_result[1];
-                let b_component = channels_result[2];
-
-                assert_eq!(r_component, g_component);
-                assert_eq!(g_component, b_component);
-            }
-        }
-
-        output_test_image_for_manual_inspection(&img_result, out_!("test_gray_scale.png"));
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_hue_rotate_neg() {
+    fn test_pixelate_regions() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(-100);
+        let operation = ImgOp::PixelateRegions((10, vec![Region::new(50, 100, 100, 100)]));
 
         let mut operator = ImageEngine::new(img);
         let done = operator.ignite(&vec![Instruction::Operation(operation)]);
@@ -859,105 +1911,183 @@ mod tests {
         let result_img = done.unwrap();
 
         assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.get_pixel(0, 0), result_img.get_pixel(0, 0));
+        assert_eq!(result_img.get_pixel(50, 100), result_img.get_pixel(59, 109));
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_neg_100.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_pixelate_regions.png"));
     }
 
     #[test]
-    fn test_hue_rotate_pos() {
+    fn test_block_region() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(100);
+        let block = Instruction::Block(
+            Scope::Region(Region::new(50, 100, 100, 100)),
+            vec![
+                Instruction::Operation(ImgOp::Blur(3.0)),
+                Instruction::Operation(ImgOp::Invert),
+            ],
+        );
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&[block]);
 
         assert!(done.is_ok());
 
         let result_img = done.unwrap();
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.dimensions(), result_img.dimensions());
+        assert_eq!(cmp.get_pixel(0, 0), result_img.get_pixel(0, 0));
+        assert_eq!(cmp.get_pixel(150, 200), result_img.get_pixel(150, 200));
+        assert_ne!(cmp.get_pixel(100, 150), result_img.get_pixel(100, 150));
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_100.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_block_region.png"));
     }
 
     #[test]
-    fn test_hue_rotate_zero() {
+    fn test_block_region_nested() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(0);
+        let block = Instruction::Block(
+            Scope::Region(Region::new(50, 100, 100, 100)),
+            vec![Instruction::Block(
+                Scope::Region(Region::new(0, 0, 10, 10)),
+                vec![Instruction::Operation(ImgOp::Invert)],
+            )],
+        );
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&[block]);
 
         assert!(done.is_ok());
 
         let result_img = done.unwrap();
 
-        assert_eq!(cmp.raw_pixels(), result_img.raw_pixels());
+        let inverted = |pixel: Rgba<u8>| {
+            let [r, g, b, a] = pixel.0;
+            Rgba([255 - r, 255 - g, 255 - b, a])
+        };
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_0.png"));
+        assert_eq!(
+            inverted(cmp.get_pixel(50, 100)),
+            result_img.get_pixel(50, 100)
+        );
+        assert_eq!(
+            inverted(cmp.get_pixel(59, 109)),
+            result_img.get_pixel(59, 109)
+        );
+        assert_eq!(cmp.get_pixel(60, 110), result_img.get_pixel(60, 110));
     }
 
     #[test]
-    fn test_hue_rotate_360() {
+    fn test_block_region_outside() {
         let img: DynamicImage = setup_default_test_image();
         let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(360);
+        let block = Instruction::Block(
+            Scope::Region(Region::new(1000, 1000, 10, 10)),
+            vec![Instruction::Operation(ImgOp::Invert)],
+        );
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&[block]);
 
         assert!(done.is_ok());
+        assert_eq!(cmp.raw_pixels(), done.unwrap().raw_pixels());
+    }
 
-        let result_img = done.unwrap();
+    #[test]
+    fn test_block_region_err_dimensions_changed() {
+        let img: DynamicImage = setup_default_test_image();
 
-        // https://docs.rs/image/0.19.0/image/enum.DynamicImage.html#method.huerotate
-        // huerotate(0) should be huerotate(360), but this doesn't seem the case
-        assert_eq!(cmp.huerotate(360).raw_pixels(), result_img.raw_pixels());
+        let block = Instruction::Block(
+            Scope::Region(Region::new(0, 0, 20, 10)),
+            vec![Instruction::Operation(ImgOp::Rotate90)],
+        );
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_360.png"));
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[block]);
+
+        assert!(done.is_err());
     }
 
     #[test]
-    fn test_hue_rotate_over_rotate_pos() {
+    fn test_block_environment_is_scoped() {
         let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::HueRotate(460);
+        let block = Instruction::Block(
+            Scope::Region(Region::new(0, 0, 20, 20)),
+            vec![Instruction::AddToEnv(EnvironmentItem::PreserveAspectRatio)],
+        );
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&[block]);
+
+        assert!(done.is_ok());
+        assert!(operator
+            .environment
+            .get(EnvironmentKind::PreserveAspectRatio)
+            .is_none());
+    }
+
+    #[test]
+    fn test_block_mask() {
+        let img = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
+        let cmp = sic_testing::open_test_image(in_!("rainbow_8x6.bmp"));
+
+        let block = Instruction::Block(
+            Scope::Mask(in_!("mask_8x6.png").to_string()),
+            vec![Instruction::Operation(ImgOp::Invert)],
+        );
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[block]);
 
         assert!(done.is_ok());
 
         let result_img = done.unwrap();
 
-        assert_ne!(cmp.huerotate(100).raw_pixels(), result_img.raw_pixels());
+        // the mask is black at the left edge and white at the right edge
+        let right = cmp.get_pixel(7, 0).0;
+        assert_eq!(cmp.get_pixel(0, 0), result_img.get_pixel(0, 0));
+        assert_eq!(
+            Rgba([255 - right[0], 255 - right[1], 255 - right[2], right[3]]),
+            result_img.get_pixel(7, 0)
+        );
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_hue_rot_pos_460.png"));
+        output_test_image_for_manual_inspection(&result_img, out_!("test_block_mask.png"));
     }
 
     #[test]
-    fn test_invert() {
+    fn test_block_mask_err_dimensions() {
         let img: DynamicImage = setup_default_test_image();
-        let cmp: DynamicImage = setup_default_test_image();
 
-        let operation = ImgOp::Invert;
+        let block = Instruction::Block(
+            Scope::Mask(in_!("mask_8x6.png").to_string()),
+            vec![Instruction::Operation(ImgOp::Invert)],
+        );
 
         let mut operator = ImageEngine::new(img);
-        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+        let done = operator.ignite(&[block]);
 
-        assert!(done.is_ok());
+        assert!(done.is_err());
+    }
 
-        let result_img = done.unwrap();
+    #[test]
+    fn test_block_mask_err_missing() {
+        let img: DynamicImage = setup_default_test_image();
 
-        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        let block = Instruction::Block(
+            Scope::Mask("does/not/exist.png".to_string()),
+            vec![Instruction::Operation(ImgOp::Invert)],
+        );
 
-        output_test_image_for_manual_inspection(&result_img, out_!("test_invert.png"));
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[block]);
+
+        assert!(done.is_err());
     }
 
     #[test]
@@ -1070,6 +2200,149 @@ mod tests {
         output_test_image_for_manual_inspection(&img_result, out_!("test_rotate270.png"));
     }
 
+    #[test]
+    fn test_round_corners() {
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
+
+        let operation = ImgOp::RoundCorners(20);
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_eq!((width, height), result_img.dimensions());
+        assert_eq!(0, result_img.get_pixel(0, 0).0[3]);
+        assert_eq!(0, result_img.get_pixel(width - 1, height - 1).0[3]);
+        assert_eq!(255, result_img.get_pixel(width / 2, height / 2).0[3]);
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_round_corners.png"));
+    }
+
+    #[test]
+    fn test_shadow() {
+        let img: DynamicImage = setup_default_test_image();
+        let (width, height) = img.dimensions();
+
+        let operation = ImgOp::Shadow((10, 15, 5.0, Rgba([0, 0, 0, 200])));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        // the blurred shadow has a margin of 3 * sigma on each side
+        assert_eq!((15 + width + 15, height + 15 + 15), result_img.dimensions());
+        assert_eq!(0, result_img.get_pixel(width + 29, 0).0[3]);
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_shadow.png"));
+    }
+
+    #[test]
+    fn test_shadow_err_negative_blur() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Shadow((10, 15, -5.0, Rgba([0, 0, 0, 200])));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_text() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Text(("I".to_string(), 10, 10));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&[Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        // the built-in font, with a size of 16 pixels and black as color by default
+        assert_eq!(Rgba([0, 0, 0, 255]), result_img.get_pixel(15, 15));
+        assert_eq!(cmp.get_pixel(10, 10), result_img.get_pixel(10, 10));
+        assert_eq!(cmp.get_pixel(15, 30), result_img.get_pixel(15, 30));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_text.png"));
+    }
+
+    #[test]
+    fn test_text_with_modifiers() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::TextFont(
+                in_!("square_font.ttf").to_string(),
+            )),
+            Instruction::AddToEnv(EnvironmentItem::TextSize(16)),
+            Instruction::AddToEnv(EnvironmentItem::TextColor(Rgba([255, 0, 0, 255]))),
+            Instruction::AddToEnv(EnvironmentItem::TextGravity(Gravity::SouthEast)),
+            Instruction::Operation(ImgOp::Text(("A".to_string(), 0, 0))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        // W 217 H 447; the 'A' of the font is a square of 8 by 8 pixels, 2 pixels to the right of
+        // the pen, and 4 pixels above the bottom of the text
+        assert_eq!(Rgba([255, 0, 0, 255]), result_img.get_pixel(207, 435));
+        assert_eq!(Rgba([255, 0, 0, 255]), result_img.get_pixel(214, 442));
+        assert_eq!(cmp.get_pixel(206, 440), result_img.get_pixel(206, 440));
+        assert_eq!(cmp.get_pixel(210, 443), result_img.get_pixel(210, 443));
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_text_with_modifiers.png"));
+    }
+
+    #[test]
+    fn test_text_err_font_not_found() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::TextFont(
+                in_!("does_not_exist.ttf").to_string(),
+            )),
+            Instruction::Operation(ImgOp::Text(("A".to_string(), 0, 0))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_err());
+    }
+
+    #[test]
+    fn test_text_err_not_a_font() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operations = vec![
+            Instruction::AddToEnv(EnvironmentItem::TextFont(
+                in_!("rainbow_8x6.bmp").to_string(),
+            )),
+            Instruction::Operation(ImgOp::Text(("A".to_string(), 0, 0))),
+        ];
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&operations);
+
+        assert!(done.is_err());
+    }
+
     #[test]
     fn test_unsharpen_pos() {
         let img: DynamicImage = setup_default_test_image();
@@ -1109,6 +2382,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vignette() {
+        let img: DynamicImage = setup_default_test_image();
+        let cmp: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Vignette((0.8, 0.3));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_ok());
+
+        let result_img = done.unwrap();
+
+        assert_ne!(cmp.raw_pixels(), result_img.raw_pixels());
+        assert_eq!(cmp.color(), result_img.color());
+
+        output_test_image_for_manual_inspection(&result_img, out_!("test_vignette.png"));
+    }
+
+    #[test]
+    fn test_vignette_err_strength_out_of_range() {
+        let img: DynamicImage = setup_default_test_image();
+
+        let operation = ImgOp::Vignette((1.5, 0.3));
+
+        let mut operator = ImageEngine::new(img);
+        let done = operator.ignite(&vec![Instruction::Operation(operation)]);
+
+        assert!(done.is_err());
+    }
+
     #[test]
     fn test_multi() {
         // 217x447px original
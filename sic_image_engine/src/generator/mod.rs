@@ -0,0 +1,251 @@
+//! Generators produce an image from scratch, so an image can be created without reading an input
+//! image first. The generated image can be used as the starting image of the [ImageEngine].
+//!
+//! [ImageEngine]: crate::engine::ImageEngine
+
+use std::error::Error;
+
+use sic_core::image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::wrapper::noise_kind::NoiseKind;
+
+mod noise;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Generator {
+    /// A canvas of (width, height), filled with a single color.
+    Canvas((u32, u32, Rgba<u8>)),
+    /// A canvas of (width, height), divided in square cells of the given size, which alternate
+    /// between the first and second color. The top left cell has the first color.
+    Checkerboard((u32, u32, u32, Rgba<u8>, Rgba<u8>)),
+    /// A canvas of (width, height) with a gradient from the first to the second color, along
+    /// the given angle in degrees. An angle of 0 runs from left to right, 90 from top to bottom.
+    LinearGradient((u32, u32, Rgba<u8>, Rgba<u8>, f32)),
+    /// A canvas of (width, height), filled with grayscale noise. The scale is the size in pixels of
+    /// a single noise lattice cell; the seed makes the noise reproducible.
+    Noise((NoiseKind, u32, u32, f32, u64)),
+    /// A canvas of (width, height) with a gradient from the first color at the center, to the
+    /// second color at the corners.
+    RadialGradient((u32, u32, Rgba<u8>, Rgba<u8>)),
+}
+
+impl Generator {
+    pub fn generate(&self) -> Result<DynamicImage, Box<dyn Error>> {
+        let buffer = match self {
+            Generator::Canvas((width, height, color)) => {
+                check_dimensions("canvas", *width, *height)?;
+                RgbaImage::from_pixel(*width, *height, *color)
+            }
+            Generator::Checkerboard((width, height, cell, first, second)) => {
+                check_dimensions("checkerboard", *width, *height)?;
+                if *cell == 0 {
+                    return Err(
+                        "Generator: checkerboard -- The cell size should be larger than 0.".into(),
+                    );
+                }
+
+                RgbaImage::from_fn(*width, *height, |x, y| {
+                    if (x / cell + y / cell) % 2 == 0 {
+                        *first
+                    } else {
+                        *second
+                    }
+                })
+            }
+            Generator::LinearGradient((width, height, from, to, angle)) => {
+                check_dimensions("gradient", *width, *height)?;
+                linear_gradient(*width, *height, *from, *to, *angle)
+            }
+            Generator::Noise((kind, width, height, scale, seed)) => {
+                check_dimensions("noise", *width, *height)?;
+                if !scale.is_finite() || *scale <= 0.0 {
+                    return Err("Generator: noise -- The scale should be larger than 0.".into());
+                }
+
+                noise::noise(*kind, *width, *height, *scale, *seed)
+            }
+            Generator::RadialGradient((width, height, inner, outer)) => {
+                check_dimensions("gradient", *width, *height)?;
+                radial_gradient(*width, *height, *inner, *outer)
+            }
+        };
+
+        Ok(DynamicImage::ImageRgba8(buffer))
+    }
+}
+
+fn check_dimensions(name: &str, width: u32, height: u32) -> Result<(), Box<dyn Error>> {
+    if width == 0 || height == 0 {
+        Err(format!(
+            "Generator: {} -- The width and height should be larger than 0.",
+            name
+        )
+        .into())
+    } else {
+        Ok(())
+    }
+}
+
+fn linear_gradient(width: u32, height: u32, from: Rgba<u8>, to: Rgba<u8>, angle: f32) -> RgbaImage {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+
+    // Half of the length of the image, projected on the direction of the gradient, such that the
+    // first and last pixels along the direction receive the from and to colors respectively.
+    let half = (((width - 1) as f32 * cos.abs() + (height - 1) as f32 * sin.abs()) / 2.0).max(0.5);
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let dx = x as f32 + 0.5 - center_x;
+        let dy = y as f32 + 0.5 - center_y;
+        let t = (dx * cos + dy * sin + half) / (2.0 * half);
+
+        interpolate(from, to, t)
+    })
+}
+
+fn radial_gradient(width: u32, height: u32, inner: Rgba<u8>, outer: Rgba<u8>) -> RgbaImage {
+    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+    let radius = (center_x - 0.5).hypot(center_y - 0.5).max(0.5);
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let distance = (x as f32 + 0.5 - center_x).hypot(y as f32 + 0.5 - center_y);
+
+        interpolate(inner, outer, distance / radius)
+    })
+}
+
+/// Linearly interpolates between two colors; `t` is clamped between 0 (from) and 1 (to).
+fn interpolate(from: Rgba<u8>, to: Rgba<u8>, t: f32) -> Rgba<u8> {
+    let t = t.max(0.0).min(1.0);
+    let mut channels = [0u8; 4];
+
+    for (out, (&a, &b)) in channels.iter_mut().zip(from.0.iter().zip(to.0.iter())) {
+        *out = (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+    }
+
+    Rgba(channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::GenericImageView;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn canvas() {
+        let image = Generator::Canvas((3, 2, RED)).generate().unwrap();
+
+        assert_eq!((3, 2), image.dimensions());
+        assert!(image.to_rgba().pixels().all(|pixel| *pixel == RED));
+    }
+
+    #[test]
+    fn canvas_err_empty() {
+        assert!(Generator::Canvas((0, 2, RED)).generate().is_err());
+        assert!(Generator::Canvas((2, 0, RED)).generate().is_err());
+    }
+
+    #[test]
+    fn checkerboard() {
+        let image = Generator::Checkerboard((4, 4, 2, RED, BLUE))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(RED, *image.get_pixel(0, 0));
+        assert_eq!(RED, *image.get_pixel(1, 1));
+        assert_eq!(BLUE, *image.get_pixel(2, 0));
+        assert_eq!(BLUE, *image.get_pixel(0, 3));
+        assert_eq!(RED, *image.get_pixel(3, 3));
+    }
+
+    #[test]
+    fn checkerboard_err_empty_cell() {
+        assert!(Generator::Checkerboard((4, 4, 0, RED, BLUE))
+            .generate()
+            .is_err());
+    }
+
+    #[test]
+    fn linear_gradient_horizontal() {
+        let image = Generator::LinearGradient((5, 2, RED, BLUE, 0.0))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(RED, *image.get_pixel(0, 0));
+        assert_eq!(Rgba([128, 0, 128, 255]), *image.get_pixel(2, 1));
+        assert_eq!(BLUE, *image.get_pixel(4, 1));
+    }
+
+    #[test]
+    fn linear_gradient_vertical() {
+        let image = Generator::LinearGradient((2, 5, RED, BLUE, 90.0))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(RED, *image.get_pixel(1, 0));
+        assert_eq!(BLUE, *image.get_pixel(0, 4));
+    }
+
+    #[test]
+    fn linear_gradient_reversed() {
+        let image = Generator::LinearGradient((5, 2, RED, BLUE, 180.0))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(BLUE, *image.get_pixel(0, 0));
+        assert_eq!(RED, *image.get_pixel(4, 0));
+    }
+
+    #[test]
+    fn linear_gradient_single_pixel() {
+        let image = Generator::LinearGradient((1, 1, RED, BLUE, 0.0))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(Rgba([128, 0, 128, 255]), *image.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn radial_gradient() {
+        let image = Generator::RadialGradient((5, 5, RED, BLUE))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(RED, *image.get_pixel(2, 2));
+        assert_eq!(BLUE, *image.get_pixel(0, 0));
+        assert_eq!(BLUE, *image.get_pixel(4, 4));
+        assert_eq!(*image.get_pixel(2, 0), *image.get_pixel(0, 2));
+    }
+
+    #[test]
+    fn noise() {
+        let image = Generator::Noise((NoiseKind::Perlin, 16, 8, 4.0, 1))
+            .generate()
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!((16, 8), image.dimensions());
+        assert!(image
+            .pixels()
+            .all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2] && pixel[3] == 255));
+    }
+
+    #[test]
+    fn noise_err_scale() {
+        assert!(Generator::Noise((NoiseKind::Value, 4, 4, 0.0, 1))
+            .generate()
+            .is_err());
+        assert!(Generator::Noise((NoiseKind::Value, 4, 4, -1.0, 1))
+            .generate()
+            .is_err());
+    }
+}
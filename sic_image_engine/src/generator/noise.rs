@@ -0,0 +1,184 @@
+use sic_core::image::{Rgba, RgbaImage};
+
+use crate::wrapper::noise_kind::NoiseKind;
+
+/// Produces an opaque grayscale noise image. Lattice points lie `scale` pixels apart.
+/// The same seed always produces the same image.
+pub(crate) fn noise(kind: NoiseKind, width: u32, height: u32, scale: f32, seed: u64) -> RgbaImage {
+    let lattice = Lattice::new(seed);
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let (px, py) = (x as f32 / scale, y as f32 / scale);
+
+        let value = match kind {
+            // Perlin noise lies within [-1, 1].
+            NoiseKind::Perlin => (lattice.perlin(px, py) + 1.0) / 2.0,
+            NoiseKind::Value => lattice.value(px, py),
+        };
+
+        let luma = (value.max(0.0).min(1.0) * 255.0).round() as u8;
+        Rgba([luma, luma, luma, 255])
+    })
+}
+
+/// A permutation table which assigns a pseudo random hash to each point of an (infinite, but
+/// repeating every 256 points) integer lattice.
+struct Lattice {
+    permutation: [u8; 512],
+}
+
+impl Lattice {
+    fn new(seed: u64) -> Self {
+        let mut table = [0u8; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+
+        // Fisher-Yates shuffle, driven by a splitmix64 generator.
+        let mut state = seed;
+        for i in (1..table.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+
+        let mut permutation = [0u8; 512];
+        permutation[..256].copy_from_slice(&table);
+        permutation[256..].copy_from_slice(&table);
+
+        Lattice { permutation }
+    }
+
+    fn hash(&self, x: i64, y: i64) -> u8 {
+        let x = (x & 255) as usize;
+        let y = (y & 255) as usize;
+
+        self.permutation[usize::from(self.permutation[x]) + y]
+    }
+
+    fn perlin(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let corner = |dx: i64, dy: i64| {
+            gradient(self.hash(x0 + dx, y0 + dy), fx - dx as f32, fy - dy as f32)
+        };
+
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+
+        lerp(top, bottom, v)
+    }
+
+    fn value(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let corner = |dx: i64, dy: i64| f32::from(self.hash(x0 + dx, y0 + dy)) / 255.0;
+
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+
+        lerp(top, bottom, v)
+    }
+}
+
+/// Dot product of the offset (x, y) with one of eight gradient directions, picked by the hash.
+fn gradient(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// The quintic smoothstep curve `6t^5 - 15t^4 + 10t^3`.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_noise() {
+        for &kind in &[NoiseKind::Perlin, NoiseKind::Value] {
+            assert_eq!(
+                noise(kind, 16, 16, 4.0, 7).into_raw(),
+                noise(kind, 16, 16, 4.0, 7).into_raw()
+            );
+        }
+    }
+
+    #[test]
+    fn other_seed_other_noise() {
+        for &kind in &[NoiseKind::Perlin, NoiseKind::Value] {
+            assert_ne!(
+                noise(kind, 16, 16, 4.0, 7).into_raw(),
+                noise(kind, 16, 16, 4.0, 8).into_raw()
+            );
+        }
+    }
+
+    #[test]
+    fn perlin_is_zero_on_lattice_points() {
+        let lattice = Lattice::new(42);
+
+        for &(x, y) in &[(0.0, 0.0), (3.0, 5.0), (-2.0, 1.0), (300.0, 255.0)] {
+            assert_eq!(0.0, lattice.perlin(x, y));
+        }
+    }
+
+    #[test]
+    fn value_is_hash_on_lattice_points() {
+        let lattice = Lattice::new(42);
+        let expected = f32::from(lattice.hash(3, 5)) / 255.0;
+
+        assert_eq!(expected, lattice.value(3.0, 5.0));
+    }
+
+    #[test]
+    fn noise_is_continuous() {
+        let image = noise(NoiseKind::Perlin, 64, 1, 16.0, 3);
+
+        for x in 1..64 {
+            let (a, b) = (image.get_pixel(x - 1, 0)[0], image.get_pixel(x, 0)[0]);
+            assert!((i16::from(a) - i16::from(b)).abs() < 32);
+        }
+    }
+
+    #[test]
+    fn permutation_is_a_permutation() {
+        let lattice = Lattice::new(0);
+        let mut seen = [false; 256];
+
+        for &value in lattice.permutation[..256].iter() {
+            seen[usize::from(value)] = true;
+        }
+
+        assert!(seen.iter().all(|&v| v));
+        assert_eq!(lattice.permutation[..256], lattice.permutation[256..]);
+    }
+}
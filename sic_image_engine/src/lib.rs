@@ -1,24 +1,53 @@
 #[macro_use]
 extern crate strum_macros;
 
+pub mod atlas;
 pub mod engine;
+pub mod generator;
+pub mod montage;
+mod operations;
 pub mod wrapper;
 
+use sic_core::image::Rgba;
+
+use crate::wrapper::axis::Axis;
+use crate::wrapper::channel::Channel;
+use crate::wrapper::region::Region;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ImgOp {
+    Append((Axis, Vec<String>)),
+    AutoLevels((f32, f32)),
     Blur(f32),
+    BlurRegions((f32, Vec<Region>)),
     Brighten(i32),
+    CircleCrop,
+    Clahe((u32, f32)),
     Contrast(f32),
     Crop((u32, u32, u32, u32)),
+    Curves((Channel, Vec<(u8, u8)>)),
+    DrawEllipse((i32, i32, u32, u32, Rgba<u8>)),
+    DrawLine((i32, i32, i32, i32, Rgba<u8>)),
+    DrawPolygon((Rgba<u8>, Vec<(i32, i32)>)),
+    DrawRect((i32, i32, u32, u32, Rgba<u8>)),
+    Equalize,
     Filter3x3([f32; 9]),
     FlipHorizontal,
     FlipVertical,
     GrayScale,
     HueRotate(i32),
     Invert,
+    Levels((u8, u8, f32, u8, u8)),
+    MatchHistogram(String),
+    Pixelate(u32),
+    PixelateRegions((u32, Vec<Region>)),
     Resize((u32, u32)),
     Rotate90,
     Rotate180,
     Rotate270,
+    RoundCorners(u32),
+    Shadow((i32, i32, f32, Rgba<u8>)),
+    Text((String, i32, i32)),
     Unsharpen((f32, i32)),
+    Vignette((f32, f32)),
 }
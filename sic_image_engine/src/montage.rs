@@ -0,0 +1,358 @@
+//! A montage (or contact sheet) arranges many images in a grid, each scaled to fit a cell of the
+//! same size. The montage can be used as the starting image of the [ImageEngine].
+//!
+//! [ImageEngine]: crate::engine::ImageEngine
+
+use std::error::Error;
+
+use sic_core::image::{imageops, DynamicImage, FilterType, GenericImageView, Rgba, RgbaImage};
+
+use crate::operations::font::Font;
+use crate::operations::text::{text, TextStyle};
+use crate::wrapper::gravity::Gravity;
+
+/// The size in pixels of the labels below the cells; the built-in font is drawn at its native size.
+const LABEL_SIZE: u32 = 8;
+
+/// The layout of a montage.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Montage {
+    /// The (width, height) of a cell; each image is scaled to fit within its cell, while
+    /// preserving its aspect ratio.
+    pub cell: (u32, u32),
+    /// The number of columns. If none, the images are arranged in a grid which is about as wide as
+    /// it is high.
+    pub columns: Option<u32>,
+    /// The space in pixels between the cells, and between the cells and the edges of the montage.
+    pub spacing: u32,
+    /// The color of the space which isn't covered by an image.
+    pub background: Rgba<u8>,
+    /// Whether each cell is labelled with the name of its image.
+    pub labels: bool,
+}
+
+impl Default for Montage {
+    fn default() -> Self {
+        Montage {
+            cell: (128, 128),
+            columns: None,
+            spacing: 4,
+            background: Rgba([255, 255, 255, 255]),
+            labels: false,
+        }
+    }
+}
+
+impl Montage {
+    /// Arranges the images in a grid, from left to right and top to bottom. Each image is
+    /// accompanied by its label, which is only drawn when labels are enabled.
+    pub fn compose(
+        &self,
+        images: &[(String, DynamicImage)],
+    ) -> Result<DynamicImage, Box<dyn Error>> {
+        let (cell_width, cell_height) = self.cell;
+
+        if images.is_empty() {
+            return Err("Montage: At least one image is required.".into());
+        }
+
+        if cell_width == 0 || cell_height == 0 {
+            return Err("Montage: The cell width and height should be larger than 0.".into());
+        }
+
+        if self.columns == Some(0) {
+            return Err("Montage: The number of columns should be larger than 0.".into());
+        }
+
+        let count = images.len() as u64;
+        let columns = u64::from(self.columns.unwrap_or_else(|| square_columns(count))).min(count);
+        let rows = (count + columns - 1) / columns;
+
+        let spacing = u64::from(self.spacing);
+        let label_height = if self.labels {
+            u64::from(LABEL_SIZE) + spacing
+        } else {
+            0
+        };
+        let (step_x, step_y) = (
+            u64::from(cell_width) + spacing,
+            u64::from(cell_height) + label_height + spacing,
+        );
+
+        // the size of the montage, if it fits within the largest possible image
+        let side = |cells: u64, step: u64| {
+            cells
+                .checked_mul(step)
+                .and_then(|length| length.checked_add(spacing))
+                .filter(|&length| length <= u64::from(std::u32::MAX))
+        };
+
+        let (width, height) = match (side(columns, step_x), side(rows, step_y)) {
+            (Some(width), Some(height)) => (width as u32, height as u32),
+            _ => return Err("Montage: The resulting image would be too large.".into()),
+        };
+
+        let mut canvas = RgbaImage::from_pixel(width, height, self.background);
+
+        for (index, (label, image)) in images.iter().enumerate() {
+            let (column, row) = (index as u64 % columns, index as u64 / columns);
+            let left = (spacing + column * step_x) as u32;
+            let top = (spacing + row * step_y) as u32;
+
+            let thumbnail = self.thumbnail(image);
+            let (thumbnail_width, thumbnail_height) = thumbnail.dimensions();
+            imageops::overlay(
+                &mut canvas,
+                &thumbnail.to_rgba(),
+                left + (cell_width - thumbnail_width) / 2,
+                top + (cell_height - thumbnail_height) / 2,
+            );
+
+            if self.labels {
+                let label = self.label(label)?;
+                imageops::replace(&mut canvas, &label, left, top + cell_height + self.spacing);
+            }
+        }
+
+        Ok(DynamicImage::ImageRgba8(canvas))
+    }
+
+    /// Scales the image to fit within a cell, while preserving its aspect ratio. An image which
+    /// already fits its cell, such as a thumbnail made earlier, isn't scaled again.
+    pub fn thumbnail(&self, image: &DynamicImage) -> DynamicImage {
+        let (cell_width, cell_height) = self.cell;
+        let (width, height) = image.dimensions();
+
+        // a scaled image fills its cell in at least one direction
+        if (width == cell_width && height <= cell_height)
+            || (height == cell_height && width <= cell_width)
+        {
+            image.clone()
+        } else {
+            image.resize(cell_width, cell_height, FilterType::Triangle)
+        }
+    }
+
+    /// Draws a label, truncated to the width of a cell, centered on the background color.
+    fn label(&self, label: &str) -> Result<RgbaImage, Box<dyn Error>> {
+        let (cell_width, _) = self.cell;
+        let label = truncate(label, (cell_width / LABEL_SIZE) as usize);
+
+        let area = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+            cell_width,
+            LABEL_SIZE,
+            self.background,
+        ));
+
+        let style = TextStyle {
+            font: &Font::Builtin,
+            size: LABEL_SIZE,
+            color: contrasting(self.background),
+            gravity: Gravity::North,
+        };
+
+        Ok(text(&area, &label, 0, 0, &style)?.to_rgba())
+    }
+}
+
+/// Shortens a label to at most `max` characters; a shortened label ends with a '~'.
+fn truncate(label: &str, max: usize) -> String {
+    if label.chars().count() <= max {
+        label.to_string()
+    } else if max == 0 {
+        String::new()
+    } else {
+        let mut truncated = label.chars().take(max - 1).collect::<String>();
+        truncated.push('~');
+        truncated
+    }
+}
+
+/// The number of columns for a grid of `count` cells, which is about as wide as it is high.
+fn square_columns(count: u64) -> u32 {
+    let mut columns = (count as f64).sqrt().floor() as u64;
+    if columns * columns < count {
+        columns += 1;
+    }
+
+    columns.max(1) as u32
+}
+
+/// Black or white, whichever stands out the most against the given color.
+fn contrasting(color: Rgba<u8>) -> Rgba<u8> {
+    let Rgba([red, green, blue, _]) = color;
+    let luma = 0.299 * f32::from(red) + 0.587 * f32::from(green) + 0.114 * f32::from(blue);
+
+    if luma > 127.5 {
+        Rgba([0, 0, 0, 255])
+    } else {
+        Rgba([255, 255, 255, 255])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    fn images(colors: &[Rgba<u8>], width: u32, height: u32) -> Vec<(String, DynamicImage)> {
+        colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| {
+                (
+                    format!("{}.png", i),
+                    DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, *color)),
+                )
+            })
+            .collect()
+    }
+
+    fn layout(cell: (u32, u32), columns: Option<u32>, spacing: u32) -> Montage {
+        Montage {
+            cell,
+            columns,
+            spacing,
+            background: WHITE,
+            labels: false,
+        }
+    }
+
+    #[test]
+    fn grid_dimensions() {
+        let montage = layout((10, 6), Some(2), 2)
+            .compose(&images(&[RED, BLUE, RED], 10, 6))
+            .unwrap();
+
+        // two columns and rows of cells, with spacing between and around them
+        assert_eq!((2 * 10 + 3 * 2, 2 * 6 + 3 * 2), montage.dimensions());
+    }
+
+    #[test]
+    fn grid_is_about_square() {
+        let montage = layout((4, 4), None, 0)
+            .compose(&images(&[RED; 5], 4, 4))
+            .unwrap();
+
+        assert_eq!((12, 8), montage.dimensions());
+    }
+
+    #[test]
+    fn columns_are_limited_by_the_number_of_images() {
+        let montage = layout((4, 4), Some(10), 0)
+            .compose(&images(&[RED, BLUE], 4, 4))
+            .unwrap();
+
+        assert_eq!((8, 4), montage.dimensions());
+    }
+
+    #[test]
+    fn cells_are_placed_left_to_right_top_to_bottom() {
+        let montage = layout((4, 4), Some(2), 1)
+            .compose(&images(&[RED, BLUE, BLUE], 4, 4))
+            .unwrap()
+            .to_rgba();
+
+        assert_eq!(WHITE, *montage.get_pixel(0, 0));
+        assert_eq!(RED, *montage.get_pixel(1, 1));
+        assert_eq!(WHITE, *montage.get_pixel(5, 1));
+        assert_eq!(BLUE, *montage.get_pixel(6, 1));
+        assert_eq!(BLUE, *montage.get_pixel(1, 6));
+        assert_eq!(WHITE, *montage.get_pixel(6, 6));
+    }
+
+    #[test]
+    fn images_are_scaled_to_fit_and_centered() {
+        let montage = layout((8, 8), Some(1), 0)
+            .compose(&images(&[RED], 16, 8))
+            .unwrap()
+            .to_rgba();
+
+        // scaled to 8x4, with 2 rows of background above and below
+        assert_eq!(WHITE, *montage.get_pixel(4, 1));
+        assert_eq!(RED, *montage.get_pixel(4, 2));
+        assert_eq!(RED, *montage.get_pixel(4, 5));
+        assert_eq!(WHITE, *montage.get_pixel(4, 6));
+    }
+
+    #[test]
+    fn labels_below_cells() {
+        let mut montage = layout((16, 4), Some(1), 2);
+        montage.labels = true;
+        let montage = montage.compose(&images(&[RED], 16, 4)).unwrap().to_rgba();
+
+        assert_eq!((20, 4 + 2 + LABEL_SIZE + 2 * 2), montage.dimensions());
+
+        let label_area = (8..16).flat_map(|y| (2..18).map(move |x| (x, y)));
+        let inked = label_area
+            .filter(|&(x, y)| *montage.get_pixel(x, y) == BLACK)
+            .count();
+        assert!(inked > 0);
+    }
+
+    #[test]
+    fn labels_are_truncated() {
+        assert_eq!("abc", truncate("abc", 3));
+        assert_eq!("ab~", truncate("abcd", 3));
+        assert_eq!("~", truncate("abcd", 1));
+        assert_eq!("", truncate("abcd", 0));
+    }
+
+    #[test]
+    fn err_no_images() {
+        assert!(Montage::default().compose(&[]).is_err());
+    }
+
+    #[test]
+    fn err_empty_cell() {
+        assert!(layout((0, 4), None, 0)
+            .compose(&images(&[RED], 4, 4))
+            .is_err());
+    }
+
+    #[test]
+    fn thumbnails_are_not_scaled_again() {
+        let montage = layout((8, 8), None, 0);
+        let thumbnail = montage.thumbnail(&images(&[RED], 16, 4)[0].1);
+
+        assert_eq!((8, 2), thumbnail.dimensions());
+        assert_eq!((8, 2), montage.thumbnail(&thumbnail).dimensions());
+    }
+
+    #[test]
+    fn err_too_large() {
+        let mut montage = layout((4, 4), None, std::u32::MAX);
+        montage.labels = true;
+
+        assert!(montage.compose(&images(&[RED], 4, 4)).is_err());
+        assert!(layout((std::u32::MAX, 4), None, 1)
+            .compose(&images(&[RED], 4, 4))
+            .is_err());
+    }
+
+    #[test]
+    fn err_zero_columns() {
+        assert!(layout((4, 4), Some(0), 0)
+            .compose(&images(&[RED], 4, 4))
+            .is_err());
+    }
+
+    #[test]
+    fn square_columns_count() {
+        assert_eq!(1, square_columns(1));
+        assert_eq!(2, square_columns(2));
+        assert_eq!(2, square_columns(4));
+        assert_eq!(3, square_columns(5));
+        assert_eq!(10, square_columns(100));
+    }
+
+    #[test]
+    fn contrasting_color() {
+        assert_eq!(BLACK, contrasting(WHITE));
+        assert_eq!(WHITE, contrasting(BLACK));
+    }
+}
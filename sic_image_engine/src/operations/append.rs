@@ -0,0 +1,201 @@
+use std::error::Error;
+
+use sic_core::image::{imageops, DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::operations::{into_color_type_of, into_color_type_with_alpha_of};
+use crate::wrapper::alignment::Alignment;
+use crate::wrapper::axis::Axis;
+
+/// Places the other images after the image, along the given axis. Images which are smaller than
+/// the largest image along the cross axis are aligned by the alignment; the remaining space is
+/// filled with the background color.
+pub(crate) fn append(
+    image: &DynamicImage,
+    others: &[DynamicImage],
+    axis: Axis,
+    alignment: Alignment,
+    background: Rgba<u8>,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    let images = std::iter::once(image).chain(others).collect::<Vec<_>>();
+
+    // (length along the axis, length along the cross axis)
+    let extents = images
+        .iter()
+        .map(|image| {
+            let (width, height) = image.dimensions();
+            match axis {
+                Axis::X => (width, height),
+                Axis::Y => (height, width),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let length = extents
+        .iter()
+        .map(|&(length, _)| u64::from(length))
+        .sum::<u64>();
+    let cross = extents.iter().map(|&(_, cross)| cross).max().unwrap_or(0);
+
+    if length > u64::from(std::u32::MAX) {
+        return Err("Operation: append -- The resulting image would be too large.".into());
+    }
+
+    let (width, height) = match axis {
+        Axis::X => (length as u32, cross),
+        Axis::Y => (cross, length as u32),
+    };
+
+    let mut canvas = RgbaImage::from_pixel(width, height, background);
+    let mut position = 0;
+
+    for (image, &(length, size)) in images.iter().zip(&extents) {
+        let offset = alignment.offset(cross, size);
+        let (x, y) = match axis {
+            Axis::X => (position, offset),
+            Axis::Y => (offset, position),
+        };
+
+        imageops::overlay(&mut canvas, &image.to_rgba(), x, y);
+        position += length;
+    }
+
+    // a transparent background is only kept if the image can hold it
+    if background.0[3] == std::u8::MAX {
+        Ok(into_color_type_of(image, canvas))
+    } else {
+        Ok(into_color_type_with_alpha_of(image, canvas))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{ColorType, RgbImage};
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+    const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    fn filled(width: u32, height: u32, color: Rgba<u8>) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, color))
+    }
+
+    #[test]
+    fn append_x() {
+        let result = append(
+            &filled(2, 3, RED),
+            &[filled(4, 3, BLUE)],
+            Axis::X,
+            Alignment::Start,
+            TRANSPARENT,
+        )
+        .unwrap();
+
+        assert_eq!((6, 3), result.dimensions());
+        assert_eq!(RED, result.get_pixel(1, 2));
+        assert_eq!(BLUE, result.get_pixel(2, 0));
+        assert_eq!(BLUE, result.get_pixel(5, 2));
+    }
+
+    #[test]
+    fn append_y_multiple() {
+        let result = append(
+            &filled(2, 1, RED),
+            &[filled(2, 2, BLUE), filled(2, 3, RED)],
+            Axis::Y,
+            Alignment::Start,
+            TRANSPARENT,
+        )
+        .unwrap();
+
+        assert_eq!((2, 6), result.dimensions());
+        assert_eq!(RED, result.get_pixel(0, 0));
+        assert_eq!(BLUE, result.get_pixel(0, 1));
+        assert_eq!(BLUE, result.get_pixel(1, 2));
+        assert_eq!(RED, result.get_pixel(1, 3));
+    }
+
+    #[test]
+    fn alignment_start() {
+        let result = append(
+            &filled(1, 4, RED),
+            &[filled(1, 2, BLUE)],
+            Axis::X,
+            Alignment::Start,
+            GREEN,
+        )
+        .unwrap();
+
+        assert_eq!(BLUE, result.get_pixel(1, 0));
+        assert_eq!(BLUE, result.get_pixel(1, 1));
+        assert_eq!(GREEN, result.get_pixel(1, 2));
+        assert_eq!(GREEN, result.get_pixel(1, 3));
+    }
+
+    #[test]
+    fn alignment_center() {
+        let result = append(
+            &filled(1, 4, RED),
+            &[filled(1, 2, BLUE)],
+            Axis::X,
+            Alignment::Center,
+            GREEN,
+        )
+        .unwrap();
+
+        assert_eq!(GREEN, result.get_pixel(1, 0));
+        assert_eq!(BLUE, result.get_pixel(1, 1));
+        assert_eq!(BLUE, result.get_pixel(1, 2));
+        assert_eq!(GREEN, result.get_pixel(1, 3));
+    }
+
+    #[test]
+    fn alignment_end_of_smaller_image() {
+        let result = append(
+            &filled(2, 1, RED),
+            &[filled(4, 1, BLUE)],
+            Axis::Y,
+            Alignment::End,
+            GREEN,
+        )
+        .unwrap();
+
+        assert_eq!((4, 2), result.dimensions());
+        assert_eq!(GREEN, result.get_pixel(0, 0));
+        assert_eq!(GREEN, result.get_pixel(1, 0));
+        assert_eq!(RED, result.get_pixel(2, 0));
+        assert_eq!(RED, result.get_pixel(3, 0));
+    }
+
+    #[test]
+    fn opaque_background_keeps_color_type() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+        let result = append(
+            &image,
+            &[filled(1, 3, RED)],
+            Axis::X,
+            Alignment::Start,
+            GREEN,
+        )
+        .unwrap();
+
+        assert_eq!(ColorType::RGB(8), result.color());
+    }
+
+    #[test]
+    fn transparent_background_adds_alpha() {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(2, 2));
+        let result = append(
+            &image,
+            &[filled(1, 3, RED)],
+            Axis::X,
+            Alignment::Start,
+            TRANSPARENT,
+        )
+        .unwrap();
+
+        assert_eq!(ColorType::RGBA(8), result.color());
+        assert_eq!(TRANSPARENT, result.get_pixel(0, 2));
+    }
+}
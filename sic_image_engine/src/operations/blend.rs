@@ -0,0 +1,134 @@
+use std::error::Error;
+
+use sic_core::image::{imageops, DynamicImage, GenericImageView, GrayImage};
+
+use crate::operations::with_rgba_buffer;
+use crate::wrapper::region::Region;
+
+/// Replaces the given region of the image by `part`, which should have the dimensions of the
+/// region.
+pub(crate) fn replace_region(
+    image: &DynamicImage,
+    part: &DynamicImage,
+    region: Region,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if part.dimensions() != (region.width, region.height) {
+        return Err(format!(
+            "Block: region -- The operations within a region block should not change the \
+             dimensions of the region, but ({}, {}) became ({}, {}).",
+            region.width,
+            region.height,
+            part.width(),
+            part.height()
+        )
+        .into());
+    }
+
+    let part = part.to_rgba();
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        imageops::replace(buffer, &part, region.x, region.y);
+    }))
+}
+
+/// Blends `processed` over the image, weighted by the mask: where the mask is white, the processed
+/// pixel is used, where it is black, the original pixel is kept, and gray values mix both.
+/// The image, the processed image and the mask should all have the same dimensions.
+pub(crate) fn blend_with_mask(
+    image: &DynamicImage,
+    processed: &DynamicImage,
+    mask: &GrayImage,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    let dimensions = image.dimensions();
+
+    if mask.dimensions() != dimensions {
+        return Err(format!(
+            "Block: mask -- The mask should have the same dimensions as the image {:?}, but has \
+             dimensions {:?}.",
+            dimensions,
+            mask.dimensions()
+        )
+        .into());
+    }
+
+    if processed.dimensions() != dimensions {
+        return Err(format!(
+            "Block: mask -- The operations within a mask block should not change the dimensions \
+             of the image, but {:?} became {:?}.",
+            dimensions,
+            processed.dimensions()
+        )
+        .into());
+    }
+
+    let processed = processed.to_rgba();
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+            let weight = u32::from(mask.get_pixel(x, y).0[0]);
+            let source = processed.get_pixel(x, y);
+
+            for (channel, &value) in pixel.0.iter_mut().zip(source.0.iter()) {
+                let mixed = u32::from(*channel) * (255 - weight) + u32::from(value) * weight;
+                *channel = ((mixed + 127) / 255) as u8;
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Luma, Rgb, RgbImage};
+
+    fn filled(width: u32, height: u32, value: u8) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([value; 3])))
+    }
+
+    #[test]
+    fn replace_region_only_changes_region() {
+        let result = replace_region(
+            &filled(4, 4, 0),
+            &filled(2, 1, 200),
+            Region::new(1, 2, 2, 1),
+        )
+        .unwrap()
+        .to_rgb();
+
+        assert_eq!([200; 3], result.get_pixel(1, 2).0);
+        assert_eq!([200; 3], result.get_pixel(2, 2).0);
+        assert_eq!([0; 3], result.get_pixel(0, 2).0);
+        assert_eq!([0; 3], result.get_pixel(1, 1).0);
+    }
+
+    #[test]
+    fn replace_region_err_dimensions() {
+        let result = replace_region(
+            &filled(4, 4, 0),
+            &filled(1, 2, 200),
+            Region::new(1, 2, 2, 1),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn blend_with_mask_weights() {
+        let mask = GrayImage::from_fn(3, 1, |x, _| Luma([[0, 255, 128][x as usize]]));
+        let result = blend_with_mask(&filled(3, 1, 0), &filled(3, 1, 200), &mask)
+            .unwrap()
+            .to_rgb();
+
+        assert_eq!([0; 3], result.get_pixel(0, 0).0);
+        assert_eq!([200; 3], result.get_pixel(1, 0).0);
+        assert_eq!([100; 3], result.get_pixel(2, 0).0);
+    }
+
+    #[test]
+    fn blend_with_mask_err_dimensions() {
+        let mask = GrayImage::new(3, 1);
+
+        assert!(blend_with_mask(&filled(3, 2, 0), &filled(3, 2, 200), &mask).is_err());
+        assert!(blend_with_mask(&filled(3, 1, 0), &filled(1, 3, 200), &mask).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use sic_core::image::{DynamicImage, RgbaImage};
+
+use crate::operations::{clamp_u8, with_rgba_buffer};
+
+const BINS: usize = 256;
+
+/// Remaps each RGB channel of `image`, so its cumulative histogram matches the cumulative
+/// histogram of the same channel of `reference`. The alpha channel is left as is.
+pub(crate) fn match_histogram(image: &DynamicImage, reference: &DynamicImage) -> DynamicImage {
+    let reference = reference.to_rgba();
+
+    with_rgba_buffer(image, |buffer| {
+        for channel in 0..3 {
+            let source_cdf = cumulative_distribution(buffer, channel);
+            let reference_cdf = cumulative_distribution(&reference, channel);
+
+            let mut mapping = [0u8; BINS];
+            for (value, out) in mapping.iter_mut().enumerate() {
+                let target = reference_cdf
+                    .iter()
+                    .position(|&fraction| fraction >= source_cdf[value])
+                    .unwrap_or(BINS - 1);
+
+                *out = target as u8;
+            }
+
+            for pixel in buffer.pixels_mut() {
+                pixel.0[channel] = mapping[usize::from(pixel.0[channel])];
+            }
+        }
+    })
+}
+
+/// Reinhard style color transfer: the mean and standard deviation of each channel of `image` in
+/// the CIE L*a*b* color space are matched to those of `reference`. The alpha channel is left as is.
+pub(crate) fn reinhard(image: &DynamicImage, reference: &DynamicImage) -> DynamicImage {
+    let reference = reference.to_rgba();
+
+    with_rgba_buffer(image, |buffer| {
+        let source_lab = buffer
+            .pixels()
+            .map(|pixel| srgb_to_lab(pixel.0[0], pixel.0[1], pixel.0[2]))
+            .collect::<Vec<_>>();
+
+        let reference_lab = reference
+            .pixels()
+            .map(|pixel| srgb_to_lab(pixel.0[0], pixel.0[1], pixel.0[2]))
+            .collect::<Vec<_>>();
+
+        let source_stats = lab_statistics(&source_lab);
+        let reference_stats = lab_statistics(&reference_lab);
+
+        for (pixel, lab) in buffer.pixels_mut().zip(source_lab) {
+            let mut transferred = [0f32; 3];
+
+            for (channel, out) in transferred.iter_mut().enumerate() {
+                let (source_mean, source_std) = source_stats[channel];
+                let (reference_mean, reference_std) = reference_stats[channel];
+
+                // a channel without any variation can only be shifted
+                let scale = if source_std > std::f32::EPSILON {
+                    reference_std / source_std
+                } else {
+                    1.0
+                };
+
+                *out = (lab[channel] - source_mean) * scale + reference_mean;
+            }
+
+            let (r, g, b) = lab_to_srgb(transferred);
+            pixel.0[0] = r;
+            pixel.0[1] = g;
+            pixel.0[2] = b;
+        }
+    })
+}
+
+/// The cumulative fraction of pixels with a value smaller than or equal to each bin.
+fn cumulative_distribution(buffer: &RgbaImage, channel: usize) -> [f64; BINS] {
+    let mut histogram = [0u64; BINS];
+    for pixel in buffer.pixels() {
+        histogram[usize::from(pixel.0[channel])] += 1;
+    }
+
+    let total = histogram.iter().sum::<u64>().max(1) as f64;
+
+    let mut cdf = [0f64; BINS];
+    let mut cumulative = 0u64;
+    for (count, fraction) in histogram.iter().zip(cdf.iter_mut()) {
+        cumulative += count;
+        *fraction = cumulative as f64 / total;
+    }
+
+    cdf
+}
+
+/// The mean and standard deviation of each L*a*b* channel.
+fn lab_statistics(pixels: &[[f32; 3]]) -> [(f32, f32); 3] {
+    let count = pixels.len().max(1) as f64;
+    let mut statistics = [(0f32, 0f32); 3];
+
+    for (channel, stats) in statistics.iter_mut().enumerate() {
+        let mean = pixels
+            .iter()
+            .map(|lab| f64::from(lab[channel]))
+            .sum::<f64>()
+            / count;
+        let variance = pixels
+            .iter()
+            .map(|lab| (f64::from(lab[channel]) - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        *stats = (mean as f32, variance.sqrt() as f32);
+    }
+
+    statistics
+}
+
+// D65 reference white
+const WHITE_X: f32 = 0.950_47;
+const WHITE_Y: f32 = 1.0;
+const WHITE_Z: f32 = 1.088_83;
+
+const DELTA: f32 = 6.0 / 29.0;
+
+fn srgb_to_lab(r: u8, g: u8, b: u8) -> [f32; 3] {
+    let to_linear = |c: u8| {
+        let c = f32::from(c) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+
+    let x = 0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = 0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b;
+
+    let f = |t: f32| {
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    };
+
+    let (fx, fy, fz) = (f(x / WHITE_X), f(y / WHITE_Y), f(z / WHITE_Z));
+
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+fn lab_to_srgb(lab: [f32; 3]) -> (u8, u8, u8) {
+    let fy = (lab[0] + 16.0) / 116.0;
+    let fx = fy + lab[1] / 500.0;
+    let fz = fy - lab[2] / 200.0;
+
+    let f_inv = |t: f32| {
+        if t > DELTA {
+            t * t * t
+        } else {
+            3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+        }
+    };
+
+    let (x, y, z) = (
+        WHITE_X * f_inv(fx),
+        WHITE_Y * f_inv(fy),
+        WHITE_Z * f_inv(fz),
+    );
+
+    let r = 3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z;
+    let g = -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z;
+    let b = 0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z;
+
+    let from_linear = |c: f32| {
+        let c = c.max(0.0).min(1.0);
+        let c = if c <= 0.003_130_8 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+
+        clamp_u8(c * 255.0)
+    };
+
+    (from_linear(r), from_linear(g), from_linear(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::Rgba;
+
+    fn gradient(from: u8, to: u8) -> DynamicImage {
+        let buffer = RgbaImage::from_fn(64, 4, |x, _| {
+            let v = from + ((u32::from(to - from) * x) / 63) as u8;
+            Rgba([v, v / 2, 255 - v, 255])
+        });
+
+        DynamicImage::ImageRgba8(buffer)
+    }
+
+    fn channel_range(image: &DynamicImage, channel: usize) -> (u8, u8) {
+        let buffer = image.to_rgba();
+        let values = buffer.pixels().map(|p| p.0[channel]).collect::<Vec<_>>();
+        let min = *values.iter().min().unwrap();
+        let max = *values.iter().max().unwrap();
+
+        (min, max)
+    }
+
+    #[test]
+    fn lab_round_trip() {
+        for &(r, g, b) in &[(0, 0, 0), (255, 255, 255), (255, 0, 0), (12, 200, 99)] {
+            let lab = srgb_to_lab(r, g, b);
+            assert_eq!((r, g, b), lab_to_srgb(lab));
+        }
+    }
+
+    #[test]
+    fn lab_white_point() {
+        let lab = srgb_to_lab(255, 255, 255);
+
+        assert!((lab[0] - 100.0).abs() < 0.01);
+        assert!(lab[1].abs() < 0.01);
+        assert!(lab[2].abs() < 0.01);
+    }
+
+    #[test]
+    fn match_histogram_with_itself_is_identity() {
+        let image = gradient(0, 255);
+        let result = match_histogram(&image, &image);
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn match_histogram_takes_range_of_reference() {
+        let image = gradient(0, 255);
+        let reference = gradient(100, 150);
+        let result = match_histogram(&image, &reference);
+
+        assert_eq!(channel_range(&reference, 0), channel_range(&result, 0));
+        assert_eq!(channel_range(&reference, 2), channel_range(&result, 2));
+    }
+
+    #[test]
+    fn reinhard_with_itself_is_identity() {
+        let image = gradient(0, 255);
+        let result = reinhard(&image, &image);
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn reinhard_moves_towards_reference() {
+        let image = gradient(0, 255);
+        let reference = gradient(100, 150);
+        let result = reinhard(&image, &reference);
+
+        let (min, max) = channel_range(&result, 0);
+        assert!(min >= 90 && max <= 160, "range was: ({}, {})", min, max);
+    }
+}
@@ -0,0 +1,436 @@
+use std::error::Error;
+
+use sic_core::image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::operations::{clamp_u8, with_rgba_buffer};
+
+/// The appearance of drawn shapes: the color and width (in pixels) of their outline, the color
+/// with which they are filled, if any, and whether their edges are anti-aliased.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Style {
+    pub(crate) stroke: Rgba<u8>,
+    pub(crate) stroke_width: u32,
+    pub(crate) fill: Option<Rgba<u8>>,
+    pub(crate) antialias: bool,
+}
+
+// Coordinates given to the drawing operations refer to the center of a pixel, so for example a
+// line of width 1 from (0, 0) to (4, 0) exactly covers the pixels (0, 0) up to and including (4, 0).
+
+/// Draws a rectangle with its top left corner at (x, y). The rectangle covers `width` by `height`
+/// pixels; its outline is centered on the outermost of these pixels.
+pub(crate) fn rect(
+    image: &DynamicImage,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    style: &Style,
+) -> DynamicImage {
+    let (left, top) = (x as f32, y as f32);
+    let (right, bottom) = (left + width as f32, top + height as f32);
+
+    with_rgba_buffer(image, |buffer| {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        if let Some(fill) = style.fill {
+            paint(
+                buffer,
+                [left, top, right, bottom],
+                fill,
+                style.antialias,
+                |px, py| px >= left && px < right && py >= top && py < bottom,
+            );
+        }
+
+        // the outline passes through the centers of the outermost pixels
+        let outline = [left + 0.5, top + 0.5, right - 0.5, bottom - 0.5];
+        stroke(buffer, style, outline, |px, py, half| {
+            let outer = px >= outline[0] - half
+                && px <= outline[2] + half
+                && py >= outline[1] - half
+                && py <= outline[3] + half;
+            let inner = px > outline[0] + half
+                && px < outline[2] - half
+                && py > outline[1] + half
+                && py < outline[3] - half;
+
+            outer && !inner
+        });
+    })
+}
+
+/// Draws a straight line between two points. The ends of the line are square, and extend half of
+/// the stroke width beyond the points. Lines are never filled.
+pub(crate) fn line(
+    image: &DynamicImage,
+    from: (i32, i32),
+    to: (i32, i32),
+    style: &Style,
+) -> DynamicImage {
+    let a = pixel_center(from);
+    let b = pixel_center(to);
+
+    with_rgba_buffer(image, |buffer| {
+        let bounds = [a.0.min(b.0), a.1.min(b.1), a.0.max(b.0), a.1.max(b.1)];
+
+        stroke(buffer, style, bounds, |px, py, half| {
+            on_line((px, py), a, b, half)
+        });
+    })
+}
+
+/// Draws an ellipse around the given center, with the given horizontal and vertical radii.
+pub(crate) fn ellipse(
+    image: &DynamicImage,
+    center: (i32, i32),
+    radius_x: u32,
+    radius_y: u32,
+    style: &Style,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if radius_x == 0 || radius_y == 0 {
+        return Err(format!(
+            "Operation: draw ellipse -- The radii should be larger than 0, but were: ({}, {}).",
+            radius_x, radius_y
+        )
+        .into());
+    }
+
+    let (cx, cy) = pixel_center(center);
+    let (rx, ry) = (radius_x as f32, radius_y as f32);
+    let bounds = [cx - rx, cy - ry, cx + rx, cy + ry];
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        if let Some(fill) = style.fill {
+            paint(buffer, bounds, fill, style.antialias, |px, py| {
+                let (nx, ny) = ((px - cx) / rx, (py - cy) / ry);
+                nx * nx + ny * ny <= 1.0
+            });
+        }
+
+        stroke(buffer, style, bounds, |px, py, half| {
+            distance_to_ellipse(px - cx, py - cy, rx, ry) <= half
+        });
+    }))
+}
+
+/// Draws a closed polygon through the given points. Self intersecting polygons are filled
+/// according to the even-odd rule.
+pub(crate) fn polygon(
+    image: &DynamicImage,
+    points: &[(i32, i32)],
+    style: &Style,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if points.len() < 3 {
+        return Err(format!(
+            "Operation: draw polygon -- A polygon should have at least 3 points, but had: {}.",
+            points.len()
+        )
+        .into());
+    }
+
+    let points = points.iter().cloned().map(pixel_center).collect::<Vec<_>>();
+    let bounds = points.iter().fold(
+        [std::f32::MAX, std::f32::MAX, std::f32::MIN, std::f32::MIN],
+        |[left, top, right, bottom], &(x, y)| {
+            [left.min(x), top.min(y), right.max(x), bottom.max(y)]
+        },
+    );
+
+    // each edge of the polygon, including the one which closes it
+    let edges = || points.iter().zip(points.iter().cycle().skip(1));
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        if let Some(fill) = style.fill {
+            paint(buffer, bounds, fill, style.antialias, |px, py| {
+                edges()
+                    .filter(|(a, b)| {
+                        (a.1 > py) != (b.1 > py)
+                            && px < a.0 + (py - a.1) / (b.1 - a.1) * (b.0 - a.0)
+                    })
+                    .count()
+                    % 2
+                    == 1
+            });
+        }
+
+        stroke(buffer, style, bounds, |px, py, half| {
+            edges().any(|(&a, &b)| distance_to_segment((px, py), a, b) <= half)
+        });
+    }))
+}
+
+fn pixel_center((x, y): (i32, i32)) -> (f32, f32) {
+    (x as f32 + 0.5, y as f32 + 0.5)
+}
+
+// Paints the outline of a shape, if the stroke width is larger than 0. The outline consists of
+// the points for which `on_outline` holds, given half of the stroke width; `bounds` is the bounding
+// box of the outline itself, which is expanded by half of the stroke width.
+fn stroke<F>(buffer: &mut RgbaImage, style: &Style, bounds: [f32; 4], on_outline: F)
+where
+    F: Fn(f32, f32, f32) -> bool,
+{
+    if style.stroke_width == 0 {
+        return;
+    }
+
+    let half = style.stroke_width as f32 / 2.0;
+    let [left, top, right, bottom] = bounds;
+    let bounds = [left - half, top - half, right + half, bottom + half];
+
+    paint(buffer, bounds, style.stroke, style.antialias, |px, py| {
+        on_outline(px, py, half)
+    });
+}
+
+/// Paints all pixels within the bounds which are (partially) covered by the shape described by
+/// `contains`. With anti-aliasing, each pixel is sampled 4 by 4 times, and the color is blended
+/// by the covered fraction of the pixel; otherwise, only the center of each pixel is sampled.
+pub(crate) fn paint<F>(
+    buffer: &mut RgbaImage,
+    bounds: [f32; 4],
+    color: Rgba<u8>,
+    antialias: bool,
+    contains: F,
+) where
+    F: Fn(f32, f32) -> bool,
+{
+    const SAMPLES_AA: [f32; 4] = [0.125, 0.375, 0.625, 0.875];
+    const SAMPLES: [f32; 1] = [0.5];
+
+    let samples: &[f32] = if antialias { &SAMPLES_AA } else { &SAMPLES };
+    let total = (samples.len() * samples.len()) as f32;
+
+    let (width, height) = buffer.dimensions();
+    let [left, top, right, bottom] = bounds;
+    let clip = |value: f32, max: u32| value.max(0.0).min(max as f32) as u32;
+
+    for y in clip(top.floor(), height)..clip(bottom.ceil(), height) {
+        for x in clip(left.floor(), width)..clip(right.ceil(), width) {
+            let hits = samples
+                .iter()
+                .flat_map(|sy| samples.iter().map(move |sx| (sx, sy)))
+                .filter(|(sx, sy)| contains(x as f32 + *sx, y as f32 + *sy))
+                .count();
+
+            if hits > 0 {
+                blend(buffer.get_pixel_mut(x, y), color, hits as f32 / total);
+            }
+        }
+    }
+}
+
+// Composites the color, with its alpha scaled by the coverage, over the pixel.
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>, coverage: f32) {
+    let alpha = f32::from(color.0[3]) / 255.0 * coverage;
+    let below = f32::from(pixel.0[3]) / 255.0 * (1.0 - alpha);
+    let out = alpha + below;
+
+    if out <= 0.0 {
+        return;
+    }
+
+    for (channel, &value) in pixel.0.iter_mut().zip(color.0.iter()).take(3) {
+        *channel = clamp_u8((f32::from(value) * alpha + f32::from(*channel) * below) / out);
+    }
+
+    pixel.0[3] = clamp_u8(out * 255.0);
+}
+
+// Whether the point lies on the line from `a` to `b`, with square ends, and `half` as half of its
+// width.
+fn on_line(p: (f32, f32), a: (f32, f32), b: (f32, f32), half: f32) -> bool {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = dx.hypot(dy);
+    let (rx, ry) = (p.0 - a.0, p.1 - a.1);
+
+    if length > 0.0 {
+        let along = (rx * dx + ry * dy) / length;
+        let across = (rx * dy - ry * dx).abs() / length;
+
+        across <= half && along >= -half && along <= length + half
+    } else {
+        rx.abs() <= half && ry.abs() <= half
+    }
+}
+
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+
+    let t = if length_squared > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared)
+            .max(0.0)
+            .min(1.0)
+    } else {
+        0.0
+    };
+
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
+
+// An approximation of the distance from a point (relative to the center) to the edge of an
+// ellipse, which is exact for circles: the deviation of the normalized radius from 1.0, divided by
+// its gradient.
+fn distance_to_ellipse(dx: f32, dy: f32, rx: f32, ry: f32) -> f32 {
+    let k = (dx / rx).hypot(dy / ry);
+    let gradient = (dx / (rx * rx)).hypot(dy / (ry * ry));
+
+    if gradient > 0.0 {
+        (k - 1.0).abs() * k / gradient
+    } else {
+        rx.min(ry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{ColorType, GenericImageView, Rgb, RgbImage};
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    fn canvas(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([255, 255, 255])))
+    }
+
+    fn style(stroke_width: u32, fill: Option<Rgba<u8>>, antialias: bool) -> Style {
+        Style {
+            stroke: RED,
+            stroke_width,
+            fill,
+            antialias,
+        }
+    }
+
+    fn count(image: &DynamicImage, color: Rgba<u8>) -> usize {
+        image
+            .pixels()
+            .filter(|(_, _, pixel)| *pixel == color)
+            .count()
+    }
+
+    #[test]
+    fn rect_outline() {
+        let result = rect(&canvas(10, 10), 2, 2, 4, 3, &style(1, None, true));
+
+        assert_eq!(ColorType::RGB(8), result.color());
+        assert_eq!(RED, result.get_pixel(2, 2));
+        assert_eq!(RED, result.get_pixel(5, 4));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(3, 3));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(6, 2));
+        // the border of a 4 by 3 rectangle has 10 pixels
+        assert_eq!(10, count(&result, RED));
+    }
+
+    #[test]
+    fn rect_filled() {
+        let result = rect(&canvas(10, 10), 2, 2, 4, 3, &style(1, Some(BLUE), false));
+
+        assert_eq!(BLUE, result.get_pixel(3, 3));
+        assert_eq!(BLUE, result.get_pixel(4, 3));
+        assert_eq!(10, count(&result, RED));
+        assert_eq!(2, count(&result, BLUE));
+    }
+
+    #[test]
+    fn rect_wide_stroke_is_centered() {
+        let result = rect(&canvas(12, 12), 3, 3, 6, 6, &style(3, None, false));
+
+        assert_eq!(RED, result.get_pixel(2, 2));
+        assert_eq!(RED, result.get_pixel(4, 4));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(1, 1));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(5, 5));
+    }
+
+    #[test]
+    fn rect_partially_outside() {
+        let result = rect(&canvas(4, 4), -2, -2, 4, 4, &style(1, None, false));
+
+        assert_eq!(RED, result.get_pixel(1, 0));
+        assert_eq!(RED, result.get_pixel(0, 1));
+        assert_eq!(3, count(&result, RED));
+    }
+
+    #[test]
+    fn line_horizontal_is_crisp() {
+        let result = line(&canvas(10, 3), (1, 1), (8, 1), &style(1, None, true));
+
+        assert_eq!(8, count(&result, RED));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(4, 0));
+    }
+
+    #[test]
+    fn line_diagonal_antialiased() {
+        let plain = line(&canvas(10, 10), (0, 0), (9, 4), &style(1, None, false));
+        let smooth = line(&canvas(10, 10), (0, 0), (9, 4), &style(1, None, true));
+
+        let partial = |image: &DynamicImage| {
+            image
+                .pixels()
+                .filter(|(_, _, pixel)| *pixel != RED && *pixel != Rgba([255; 4]))
+                .count()
+        };
+
+        assert_eq!(0, partial(&plain));
+        assert!(partial(&smooth) > 0);
+    }
+
+    #[test]
+    fn line_zero_width_draws_nothing() {
+        let image = canvas(5, 5);
+        let result = line(&image, (0, 0), (4, 4), &style(0, Some(BLUE), true));
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn ellipse_filled() {
+        let result = ellipse(&canvas(21, 11), (10, 5), 8, 4, &style(1, Some(BLUE), false)).unwrap();
+
+        assert_eq!(BLUE, result.get_pixel(10, 5));
+        assert_eq!(RED, result.get_pixel(2, 5));
+        assert_eq!(RED, result.get_pixel(10, 1));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(0, 0));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(20, 10));
+    }
+
+    #[test]
+    fn ellipse_err_zero_radius() {
+        assert!(ellipse(&canvas(5, 5), (2, 2), 0, 2, &style(1, None, false)).is_err());
+    }
+
+    #[test]
+    fn polygon_triangle() {
+        let points = [(1, 1), (8, 1), (1, 8)];
+        let result = polygon(&canvas(10, 10), &points, &style(1, Some(BLUE), false)).unwrap();
+
+        assert_eq!(RED, result.get_pixel(1, 1));
+        assert_eq!(RED, result.get_pixel(4, 1));
+        assert_eq!(RED, result.get_pixel(1, 4));
+        assert_eq!(BLUE, result.get_pixel(3, 3));
+        assert_eq!(Rgba([255; 4]), result.get_pixel(7, 7));
+    }
+
+    #[test]
+    fn polygon_err_too_few_points() {
+        assert!(polygon(&canvas(5, 5), &[(0, 0), (4, 4)], &style(1, None, false)).is_err());
+    }
+
+    #[test]
+    fn blend_translucent() {
+        let mut pixel = Rgba([255, 255, 255, 255]);
+        blend(&mut pixel, Rgba([0, 0, 0, 128]), 1.0);
+
+        assert_eq!(Rgba([127, 127, 127, 255]), pixel);
+
+        let mut pixel = Rgba([0, 0, 0, 0]);
+        blend(&mut pixel, Rgba([200, 100, 0, 255]), 0.5);
+
+        assert_eq!(Rgba([200, 100, 0, 128]), pixel);
+    }
+}
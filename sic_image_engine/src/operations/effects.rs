@@ -0,0 +1,300 @@
+use std::error::Error;
+
+use sic_core::image::{imageops, DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::operations::{clamp_u8, into_color_type_with_alpha_of, with_rgba_buffer};
+
+/// Darkens the image towards its edges.
+/// Distances are relative to half of the diagonal of the image, i.e. the center has distance 0.0
+/// and the corners have distance 1.0. Pixels within `radius` are left as is; beyond it the image is
+/// smoothly darkened, up to `strength` (between 0.0 and 1.0) at the corners.
+pub(crate) fn vignette(
+    image: &DynamicImage,
+    strength: f32,
+    radius: f32,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if !(0.0..=1.0).contains(&strength) {
+        return Err(format!(
+            "Operation: vignette -- The strength should be between 0.0 and 1.0, but was: {}.",
+            strength
+        )
+        .into());
+    }
+
+    if !(0.0..1.0).contains(&radius) {
+        return Err(format!(
+            "Operation: vignette -- The radius should be at least 0.0 and smaller than 1.0, but \
+             was: {}.",
+            radius
+        )
+        .into());
+    }
+
+    let (width, height) = image.dimensions();
+    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+    let half_diagonal = center_x.hypot(center_y).max(std::f32::EPSILON);
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let distance = dx.hypot(dy) / half_diagonal;
+
+            let factor = 1.0 - strength * smooth_step(radius, 1.0, distance);
+
+            for channel in pixel.0.iter_mut().take(3) {
+                *channel = clamp_u8(f32::from(*channel) * factor);
+            }
+        }
+    }))
+}
+
+/// Makes the corners of the image transparent, such that the remaining image has rounded corners
+/// with the given radius (in pixels). The radius is limited to half of the smallest side.
+/// The edges of the corners are anti-aliased.
+pub(crate) fn round_corners(image: &DynamicImage, radius: u32) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let radius = radius.min(width.min(height) / 2) as f32;
+    let (width, height) = (width as f32, height as f32);
+
+    let mut buffer = image.to_rgba();
+
+    if radius == 0.0 {
+        return into_color_type_with_alpha_of(image, buffer);
+    }
+
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+
+        // the center of the corner circle closest to this pixel; for pixels outside the corners
+        // this is the pixel itself
+        let cx = px.max(radius).min(width - radius);
+        let cy = py.max(radius).min(height - radius);
+
+        let distance = (px - cx).hypot(py - cy);
+        scale_alpha(pixel, coverage(radius, distance));
+    }
+
+    into_color_type_with_alpha_of(image, buffer)
+}
+
+/// Crops the image to the largest centered square, and makes everything outside the circle which
+/// fits within this square transparent. The edge of the circle is anti-aliased.
+pub(crate) fn circle_crop(image: &DynamicImage) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let side = width.min(height);
+
+    let mut buffer = image.to_rgba();
+    let mut buffer = imageops::crop(
+        &mut buffer,
+        (width - side) / 2,
+        (height - side) / 2,
+        side,
+        side,
+    )
+    .to_image();
+
+    let radius = side as f32 / 2.0;
+
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        let distance = (x as f32 + 0.5 - radius).hypot(y as f32 + 0.5 - radius);
+        scale_alpha(pixel, coverage(radius, distance));
+    }
+
+    into_color_type_with_alpha_of(image, buffer)
+}
+
+/// Adds a drop shadow behind the image. The shadow takes the shape of the (non transparent part of
+/// the) image, is filled with `color`, moved by the given offset and blurred with `blur` as sigma.
+/// The canvas is expanded, so both the image and its (blurred) shadow fit.
+pub(crate) fn shadow(
+    image: &DynamicImage,
+    offset_x: i32,
+    offset_y: i32,
+    blur: f32,
+    color: Rgba<u8>,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if blur < 0.0 || blur.is_nan() {
+        return Err(format!(
+            "Operation: shadow -- The blur should be at least 0.0, but was: {}.",
+            blur
+        )
+        .into());
+    }
+
+    // a gaussian blur spreads (almost) all of its weight within three times sigma
+    let margin = (3.0 * f64::from(blur)).ceil();
+
+    // with a larger margin, the image would be too large; this also keeps the sums below in range
+    if margin > f64::from(std::u32::MAX) {
+        return Err("Operation: shadow -- The resulting image would be too large.".into());
+    }
+
+    let source = image.to_rgba();
+    let (width, height) = (i64::from(source.width()), i64::from(source.height()));
+    let (offset_x, offset_y) = (i64::from(offset_x), i64::from(offset_y));
+    let margin = margin as i64;
+
+    let left = 0.min(offset_x - margin);
+    let top = 0.min(offset_y - margin);
+    let right = width.max(offset_x + width + margin);
+    let bottom = height.max(offset_y + height + margin);
+
+    let (canvas_width, canvas_height) = (right - left, bottom - top);
+    if canvas_width > i64::from(std::u32::MAX) || canvas_height > i64::from(std::u32::MAX) {
+        return Err("Operation: shadow -- The resulting image would be too large.".into());
+    }
+
+    let Rgba([red, green, blue, alpha]) = color;
+    let mut canvas = RgbaImage::from_pixel(
+        canvas_width as u32,
+        canvas_height as u32,
+        Rgba([red, green, blue, 0]),
+    );
+
+    let (shadow_x, shadow_y) = ((offset_x - left) as u32, (offset_y - top) as u32);
+    for (x, y, pixel) in source.enumerate_pixels() {
+        let shadow_alpha = u16::from(pixel.0[3]) * u16::from(alpha) / 255;
+        canvas.get_pixel_mut(shadow_x + x, shadow_y + y).0[3] = shadow_alpha as u8;
+    }
+
+    // blurring with a sigma of zero is not supported by the image crate
+    if blur > 0.0 {
+        canvas = imageops::blur(&canvas, blur);
+    }
+
+    imageops::overlay(&mut canvas, &source, (-left) as u32, (-top) as u32);
+
+    Ok(into_color_type_with_alpha_of(image, canvas))
+}
+
+/// The fraction of a pixel at `distance` from the center of a circle with the given radius, which
+/// is covered by that circle. This approximation is used to anti-alias the edge of the circle.
+fn coverage(radius: f32, distance: f32) -> f32 {
+    (radius - distance + 0.5).max(0.0).min(1.0)
+}
+
+fn scale_alpha(pixel: &mut Rgba<u8>, factor: f32) {
+    pixel.0[3] = clamp_u8(f32::from(pixel.0[3]) * factor);
+}
+
+/// Hermite interpolation between 0.0 (at or below `edge0`) and 1.0 (at or above `edge1`).
+fn smooth_step(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).max(0.0).min(1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::ColorType;
+
+    fn opaque(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+                width,
+                height,
+                Rgba([200, 150, 100, 255]),
+            ))
+            .to_rgb(),
+        )
+    }
+
+    #[test]
+    fn vignette_keeps_center_darkens_corners() {
+        let result = vignette(&opaque(64, 64), 1.0, 0.5).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_eq!(ColorType::RGB(8), result.color());
+        assert_eq!([200, 150, 100, 255], buffer.get_pixel(32, 32).0);
+        assert!(buffer.get_pixel(0, 0).0[0] < 20);
+    }
+
+    #[test]
+    fn vignette_zero_strength_is_identity() {
+        let image = opaque(16, 9);
+        let result = vignette(&image, 0.0, 0.2).unwrap();
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn vignette_err_invalid_arguments() {
+        assert!(vignette(&opaque(4, 4), 1.5, 0.5).is_err());
+        assert!(vignette(&opaque(4, 4), 0.5, 1.0).is_err());
+        assert!(vignette(&opaque(4, 4), 0.5, -0.1).is_err());
+    }
+
+    #[test]
+    fn round_corners_adds_alpha() {
+        let result = round_corners(&opaque(32, 16), 6);
+        let buffer = result.to_rgba();
+
+        assert_eq!(ColorType::RGBA(8), result.color());
+        assert_eq!(0, buffer.get_pixel(0, 0).0[3]);
+        assert_eq!(0, buffer.get_pixel(31, 15).0[3]);
+        assert_eq!(255, buffer.get_pixel(16, 0).0[3]);
+        assert_eq!(255, buffer.get_pixel(0, 8).0[3]);
+        assert_eq!(255, buffer.get_pixel(6, 6).0[3]);
+    }
+
+    #[test]
+    fn round_corners_zero_radius_is_opaque() {
+        let result = round_corners(&opaque(8, 8), 0);
+
+        assert!(result.to_rgba().pixels().all(|pixel| pixel.0[3] == 255));
+    }
+
+    #[test]
+    fn circle_crop_is_square_and_round() {
+        let result = circle_crop(&opaque(40, 20));
+        let buffer = result.to_rgba();
+
+        assert_eq!((20, 20), result.dimensions());
+        assert_eq!(0, buffer.get_pixel(0, 0).0[3]);
+        assert_eq!(0, buffer.get_pixel(19, 0).0[3]);
+        assert_eq!(255, buffer.get_pixel(10, 10).0[3]);
+        assert_eq!(255, buffer.get_pixel(1, 10).0[3]);
+    }
+
+    #[test]
+    fn circle_crop_luma_gets_alpha() {
+        let result = circle_crop(&DynamicImage::new_luma8(10, 10));
+
+        assert_eq!(ColorType::GrayA(8), result.color());
+    }
+
+    #[test]
+    fn shadow_expands_canvas() {
+        let result = shadow(&opaque(10, 10), 4, -2, 1.0, Rgba([0, 0, 0, 255])).unwrap();
+
+        // the blurred shadow has a margin of 3 on each side
+        assert_eq!((4 + 10 + 3, 2 + 3 + 10 + 1), result.dimensions());
+        assert_eq!(ColorType::RGBA(8), result.color());
+    }
+
+    #[test]
+    fn shadow_without_blur() {
+        let result = shadow(&opaque(4, 4), 2, 2, 0.0, Rgba([0, 0, 255, 128])).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_eq!((6, 6), result.dimensions());
+        assert_eq!([200, 150, 100, 255], buffer.get_pixel(0, 0).0);
+        assert_eq!([0, 0, 255, 128], buffer.get_pixel(5, 5).0);
+        assert_eq!(0, buffer.get_pixel(5, 0).0[3]);
+    }
+
+    #[test]
+    fn shadow_err_negative_blur() {
+        assert!(shadow(&opaque(4, 4), 2, 2, -1.0, Rgba([0, 0, 0, 255])).is_err());
+    }
+
+    #[test]
+    fn shadow_err_too_large() {
+        let color = Rgba([0, 0, 0, 255]);
+
+        assert!(shadow(&opaque(4, 4), 2, 2, std::f32::MAX, color).is_err());
+        assert!(shadow(&opaque(4, 4), 2, 2, std::f32::INFINITY, color).is_err());
+        assert!(shadow(&opaque(4, 4), std::i32::MAX, 0, 1.5e9, color).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Fonts for the `text` operation: the built-in bitmap font, and TrueType fonts loaded from a file.
+
+use std::error::Error;
+
+use crate::operations::font::truetype::TrueTypeFont;
+
+pub(crate) mod builtin;
+pub(crate) mod truetype;
+
+pub(crate) enum Font {
+    Builtin,
+    TrueType(TrueTypeFont),
+}
+
+impl Font {
+    /// Loads a TrueType font (.ttf), or an OpenType font with TrueType outlines (.otf), from a file.
+    pub(crate) fn from_file(path: &str) -> Result<Font, Box<dyn Error>> {
+        let data = std::fs::read(path)?;
+
+        TrueTypeFont::parse(data).map(Font::TrueType)
+    }
+
+    /// The vertical metrics of a line of text of the given size (in pixels).
+    pub(crate) fn metrics(&self, size: f32) -> Metrics {
+        match self {
+            Font::Builtin => Metrics {
+                ascent: builtin::ascent(size),
+                descent: builtin::descent(size),
+                line_gap: 0.0,
+            },
+            Font::TrueType(font) => font.metrics(size),
+        }
+    }
+
+    /// The glyph of a character, for text of the given size (in pixels).
+    pub(crate) fn glyph(&self, c: char, size: f32) -> Result<Glyph, Box<dyn Error>> {
+        match self {
+            Font::Builtin => Ok(builtin::glyph(c, size)),
+            Font::TrueType(font) => font.glyph(c, size),
+        }
+    }
+}
+
+/// Vertical metrics of a font, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Metrics {
+    /// The distance from the baseline to the top of the highest glyphs.
+    pub(crate) ascent: f32,
+    /// The distance from the baseline to the bottom of the lowest glyphs.
+    pub(crate) descent: f32,
+    /// The additional space between two lines.
+    pub(crate) line_gap: f32,
+}
+
+impl Metrics {
+    /// The distance between the baselines of two subsequent lines.
+    pub(crate) fn line_height(&self) -> f32 {
+        self.ascent + self.descent + self.line_gap
+    }
+}
+
+/// The shape of a character, in pixels. Coordinates are relative to the position of the pen on the
+/// baseline, with the y axis pointing down.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Glyph {
+    /// The horizontal distance by which the pen moves after drawing the glyph.
+    pub(crate) advance: f32,
+    /// The bounding box of the shape: left, top, right and bottom.
+    pub(crate) bounds: [f32; 4],
+    shape: Shape,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    /// 8 rows of 8 pixels, with the least significant bit as leftmost pixel, scaled by `scale`.
+    Bitmap { rows: [u8; 8], scale: f32 },
+    /// The edges of the closed outlines of the shape.
+    Outline(Vec<Edge>),
+}
+
+pub(crate) type Edge = ((f32, f32), (f32, f32));
+
+impl Glyph {
+    /// A glyph from an 8 by 8 bitmap, of which the first `ascent` rows lie above the baseline.
+    pub(crate) fn bitmap(rows: [u8; 8], scale: f32, ascent: f32) -> Self {
+        let size = 8.0 * scale;
+        let top = -ascent * scale;
+
+        Glyph {
+            advance: size,
+            bounds: [0.0, top, size, top + size],
+            shape: Shape::Bitmap { rows, scale },
+        }
+    }
+
+    /// A glyph from the edges of its (closed) outlines.
+    pub(crate) fn outline(advance: f32, edges: Vec<Edge>) -> Self {
+        let bounds = edges.iter().fold(None, |bounds, &(from, to)| {
+            let [left, top, right, bottom] = bounds.unwrap_or([from.0, from.1, from.0, from.1]);
+
+            Some([
+                left.min(from.0).min(to.0),
+                top.min(from.1).min(to.1),
+                right.max(from.0).max(to.0),
+                bottom.max(from.1).max(to.1),
+            ])
+        });
+
+        Glyph {
+            advance,
+            bounds: bounds.unwrap_or([0.0; 4]),
+            shape: Shape::Outline(edges),
+        }
+    }
+
+    /// Whether the point is covered by the glyph. Outlines are filled according to the non-zero
+    /// winding rule.
+    pub(crate) fn contains(&self, x: f32, y: f32) -> bool {
+        match &self.shape {
+            Shape::Bitmap { rows, scale } => {
+                let column = ((x - self.bounds[0]) / scale).floor();
+                let row = ((y - self.bounds[1]) / scale).floor();
+
+                if !(0.0..8.0).contains(&column) || !(0.0..8.0).contains(&row) {
+                    return false;
+                }
+
+                rows[row as usize] >> column as u32 & 1 == 1
+            }
+            Shape::Outline(edges) => {
+                let winding = edges
+                    .iter()
+                    .filter(|((_, y0), (_, y1))| (*y0 <= y) != (*y1 <= y))
+                    .filter(|((x0, y0), (x1, y1))| x0 + (y - y0) * (x1 - x0) / (y1 - y0) > x)
+                    .map(|((_, y0), (_, y1))| if y1 > y0 { 1 } else { -1 })
+                    .sum::<i32>();
+
+                winding != 0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(left: f32, top: f32, size: f32, clockwise: bool) -> Vec<Edge> {
+        let mut points = [
+            (left, top),
+            (left + size, top),
+            (left + size, top + size),
+            (left, top + size),
+        ];
+
+        if !clockwise {
+            points.reverse();
+        }
+
+        (0..4).map(|i| (points[i], points[(i + 1) % 4])).collect()
+    }
+
+    #[test]
+    fn outline_bounds() {
+        let glyph = Glyph::outline(5.0, square(1.0, -4.0, 3.0, true));
+
+        assert_eq!([1.0, -4.0, 4.0, -1.0], glyph.bounds);
+        assert_eq!([0.0; 4], Glyph::outline(5.0, Vec::new()).bounds);
+    }
+
+    #[test]
+    fn outline_contains() {
+        let glyph = Glyph::outline(5.0, square(1.0, -4.0, 3.0, false));
+
+        assert!(glyph.contains(2.5, -2.5));
+        assert!(!glyph.contains(0.5, -2.5));
+        assert!(!glyph.contains(4.5, -2.5));
+        assert!(!glyph.contains(2.5, -4.5));
+    }
+
+    #[test]
+    fn outline_non_zero_winding() {
+        // two overlapping squares with the same direction fill the overlap, while a square within
+        // a square of the opposite direction leaves a hole
+        let mut same = square(0.0, 0.0, 4.0, true);
+        same.extend(square(1.0, 1.0, 2.0, true));
+        let mut opposite = square(0.0, 0.0, 4.0, true);
+        opposite.extend(square(1.0, 1.0, 2.0, false));
+
+        assert!(Glyph::outline(4.0, same).contains(2.0, 2.0));
+        assert!(!Glyph::outline(4.0, opposite.clone()).contains(2.0, 2.0));
+        assert!(Glyph::outline(4.0, opposite).contains(0.5, 2.0));
+    }
+
+    #[test]
+    fn bitmap_contains() {
+        let mut rows = [0u8; 8];
+        rows[0] = 0b0000_0001;
+        rows[7] = 0b1000_0000;
+        let glyph = Glyph::bitmap(rows, 2.0, 7.0);
+
+        assert_eq!([0.0, -14.0, 16.0, 2.0], glyph.bounds);
+        assert!(glyph.contains(1.0, -13.0));
+        assert!(!glyph.contains(2.5, -13.0));
+        assert!(glyph.contains(15.0, 1.0));
+        assert!(!glyph.contains(15.0, 2.5));
+        assert!(!glyph.contains(-1.0, -13.0));
+    }
+}
@@ -0,0 +1,651 @@
+//! A minimal reader of TrueType fonts. Only the tables required to lay out and draw the outlines of
+//! glyphs are read; hinting, kerning and other advanced typographic features are not supported.
+//!
+//! See the OpenType specification (https://docs.microsoft.com/en-us/typography/opentype/spec/) for
+//! the layout of the tables.
+
+use std::error::Error;
+
+use crate::operations::font::{Edge, Glyph, Metrics};
+
+const TAG_TRUETYPE: u32 = 0x0001_0000;
+const TAG_TRUE: u32 = 0x7472_7565; // 'true'
+const TAG_CFF: u32 = 0x4F54_544F; // 'OTTO'
+const TAG_COLLECTION: u32 = 0x7474_6366; // 'ttcf'
+
+// flags of the points of simple glyphs
+const ON_CURVE: u8 = 0x01;
+const X_SHORT: u8 = 0x02;
+const Y_SHORT: u8 = 0x04;
+const REPEAT: u8 = 0x08;
+const X_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+// flags of the components of composite glyphs
+const ARGS_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const HAS_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const HAS_XY_SCALE: u16 = 0x0040;
+const HAS_TWO_BY_TWO: u16 = 0x0080;
+
+/// Composite glyphs may consist of other composite glyphs; this limits the nesting, so malformed
+/// fonts with cyclic references can not recurse indefinitely.
+const MAX_COMPOSITE_DEPTH: usize = 8;
+
+/// The maximum length (in pixels) of the line segments with which curves are approximated.
+const CURVE_SEGMENT_LENGTH: f32 = 2.0;
+
+/// A point of a glyph outline in font units, and whether it lies on the curve (or is a control
+/// point of a quadratic Bézier curve).
+type Point = (f32, f32, bool);
+
+pub(crate) struct TrueTypeFont {
+    data: Vec<u8>,
+    cmap: usize,
+    cmap_format: u16,
+    glyf: usize,
+    loca: usize,
+    long_loca: bool,
+    hmtx: usize,
+    num_h_metrics: u16,
+    num_glyphs: u16,
+    units_per_em: f32,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+}
+
+impl TrueTypeFont {
+    pub(crate) fn parse(data: Vec<u8>) -> Result<Self, Box<dyn Error>> {
+        // of a font collection, the first font is used
+        let offset = match read_u32(&data, 0)? {
+            TAG_COLLECTION => read_u32(&data, 12)? as usize,
+            _ => 0,
+        };
+
+        match read_u32(&data, offset)? {
+            TAG_TRUETYPE | TAG_TRUE => {}
+            TAG_CFF => {
+                return Err(
+                    "fonts with CFF outlines are not supported, only fonts with TrueType outlines"
+                        .into(),
+                )
+            }
+            _ => return Err("not a TrueType or OpenType font".into()),
+        }
+
+        let head = find_table(&data, offset, b"head")?;
+        let hhea = find_table(&data, offset, b"hhea")?;
+        let maxp = find_table(&data, offset, b"maxp")?;
+        let (cmap, cmap_format) = find_character_map(&data, find_table(&data, offset, b"cmap")?)?;
+
+        let units_per_em = read_u16(&data, head + 18)?;
+        if units_per_em == 0 {
+            return Err("the font has no units per em".into());
+        }
+
+        Ok(TrueTypeFont {
+            cmap,
+            cmap_format,
+            glyf: find_table(&data, offset, b"glyf")?,
+            loca: find_table(&data, offset, b"loca")?,
+            long_loca: read_i16(&data, head + 50)? != 0,
+            hmtx: find_table(&data, offset, b"hmtx")?,
+            num_h_metrics: read_u16(&data, hhea + 34)?,
+            num_glyphs: read_u16(&data, maxp + 4)?,
+            units_per_em: f32::from(units_per_em),
+            ascender: read_i16(&data, hhea + 4)?,
+            descender: read_i16(&data, hhea + 6)?,
+            line_gap: read_i16(&data, hhea + 8)?,
+            data,
+        })
+    }
+
+    pub(crate) fn metrics(&self, size: f32) -> Metrics {
+        let scale = size / self.units_per_em;
+
+        Metrics {
+            ascent: f32::from(self.ascender) * scale,
+            descent: -f32::from(self.descender) * scale,
+            line_gap: f32::from(self.line_gap) * scale,
+        }
+    }
+
+    pub(crate) fn glyph(&self, c: char, size: f32) -> Result<Glyph, Box<dyn Error>> {
+        let scale = size / self.units_per_em;
+        let id = self.glyph_index(c)?;
+
+        let mut edges = Vec::new();
+        for contour in self.outline(id, 0)? {
+            // font units have the y axis pointing up, while for glyphs it points down
+            let contour = contour
+                .iter()
+                .map(|&(x, y, on_curve)| (x * scale, -y * scale, on_curve))
+                .collect::<Vec<_>>();
+
+            flatten(&contour, &mut edges);
+        }
+
+        Ok(Glyph::outline(
+            f32::from(self.advance_width(id)?) * scale,
+            edges,
+        ))
+    }
+
+    // Looks up the glyph of a character in the character map; characters which are not in the
+    // map get the glyph with index 0, which by convention is the 'missing character' glyph.
+    fn glyph_index(&self, c: char) -> Result<u16, Box<dyn Error>> {
+        let data = &self.data;
+        let table = self.cmap;
+        let code = c as u32;
+
+        let id = match self.cmap_format {
+            4 if code <= 0xFFFF => {
+                let code = code as u16;
+                let segments = read_u16(data, table + 6)? as usize;
+                let ends = table + 14;
+                let starts = ends + segments + 2;
+                let deltas = starts + segments;
+                let range_offsets = deltas + segments;
+
+                let mut id = 0;
+                for segment in (0..segments).step_by(2) {
+                    if read_u16(data, ends + segment)? < code {
+                        continue;
+                    }
+
+                    let start = read_u16(data, starts + segment)?;
+                    if start <= code {
+                        let delta = read_u16(data, deltas + segment)?;
+                        let range_offset = read_u16(data, range_offsets + segment)? as usize;
+
+                        id = if range_offset == 0 {
+                            code.wrapping_add(delta)
+                        } else {
+                            let index = range_offsets
+                                + segment
+                                + range_offset
+                                + 2 * usize::from(code - start);
+
+                            match read_u16(data, index)? {
+                                0 => 0,
+                                id => id.wrapping_add(delta),
+                            }
+                        };
+                    }
+
+                    break;
+                }
+
+                id
+            }
+            12 => {
+                let groups = read_u32(data, table + 12)? as usize;
+
+                let mut id = 0;
+                for group in 0..groups {
+                    let group = table + 16 + group * 12;
+                    let start = read_u32(data, group)?;
+                    let end = read_u32(data, group + 4)?;
+
+                    if start <= code && code <= end {
+                        id = (read_u32(data, group + 8)? + (code - start)) as u16;
+                        break;
+                    }
+                }
+
+                id
+            }
+            _ => 0,
+        };
+
+        Ok(if id < self.num_glyphs { id } else { 0 })
+    }
+
+    fn advance_width(&self, id: u16) -> Result<u16, Box<dyn Error>> {
+        // glyphs beyond the last horizontal metric share its advance width
+        let metric = id.min(self.num_h_metrics.saturating_sub(1)) as usize;
+
+        read_u16(&self.data, self.hmtx + 4 * metric)
+    }
+
+    // The data of a glyph in the 'glyf' table, or None if the glyph has no outline (e.g. a space).
+    fn glyph_data(&self, id: u16) -> Result<Option<&[u8]>, Box<dyn Error>> {
+        let id = id as usize;
+        let (start, end) = if self.long_loca {
+            (
+                read_u32(&self.data, self.loca + 4 * id)? as usize,
+                read_u32(&self.data, self.loca + 4 * id + 4)? as usize,
+            )
+        } else {
+            (
+                read_u16(&self.data, self.loca + 2 * id)? as usize * 2,
+                read_u16(&self.data, self.loca + 2 * id + 2)? as usize * 2,
+            )
+        };
+
+        if start >= end {
+            return Ok(None);
+        }
+
+        self.data
+            .get(self.glyf + start..self.glyf + end)
+            .map(Some)
+            .ok_or_else(|| "glyph data lies outside of the font".into())
+    }
+
+    // The contours of a glyph, in font units.
+    fn outline(&self, id: u16, depth: usize) -> Result<Vec<Vec<Point>>, Box<dyn Error>> {
+        if depth > MAX_COMPOSITE_DEPTH {
+            return Err("composite glyphs are nested too deeply".into());
+        }
+
+        let data = match self.glyph_data(id)? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+
+        match read_i16(data, 0)? {
+            contours if contours >= 0 => simple_outline(data, contours as usize),
+            _ => self.composite_outline(data, depth),
+        }
+    }
+
+    // A composite glyph consists of transformed copies of other glyphs.
+    fn composite_outline(
+        &self,
+        data: &[u8],
+        depth: usize,
+    ) -> Result<Vec<Vec<Point>>, Box<dyn Error>> {
+        let mut contours = Vec::new();
+        let mut pos = 10;
+
+        loop {
+            let flags = read_u16(data, pos)?;
+            let component = read_u16(data, pos + 2)?;
+            pos += 4;
+
+            let (dx, dy) = if flags & ARGS_ARE_WORDS != 0 {
+                pos += 4;
+                (
+                    f32::from(read_i16(data, pos - 4)?),
+                    f32::from(read_i16(data, pos - 2)?),
+                )
+            } else {
+                pos += 2;
+                (
+                    f32::from(read_u8(data, pos - 2)? as i8),
+                    f32::from(read_u8(data, pos - 1)? as i8),
+                )
+            };
+
+            // components positioned by matching points are not supported, and are placed without
+            // offset instead
+            let (dx, dy) = if flags & ARGS_ARE_XY_VALUES != 0 {
+                (dx, dy)
+            } else {
+                (0.0, 0.0)
+            };
+
+            let [a, b, c, d] = if flags & HAS_SCALE != 0 {
+                pos += 2;
+                let scale = read_f2dot14(data, pos - 2)?;
+                [scale, 0.0, 0.0, scale]
+            } else if flags & HAS_XY_SCALE != 0 {
+                pos += 4;
+                [
+                    read_f2dot14(data, pos - 4)?,
+                    0.0,
+                    0.0,
+                    read_f2dot14(data, pos - 2)?,
+                ]
+            } else if flags & HAS_TWO_BY_TWO != 0 {
+                pos += 8;
+                [
+                    read_f2dot14(data, pos - 8)?,
+                    read_f2dot14(data, pos - 6)?,
+                    read_f2dot14(data, pos - 4)?,
+                    read_f2dot14(data, pos - 2)?,
+                ]
+            } else {
+                [1.0, 0.0, 0.0, 1.0]
+            };
+
+            for contour in self.outline(component, depth + 1)? {
+                contours.push(
+                    contour
+                        .into_iter()
+                        .map(|(x, y, on_curve)| (a * x + c * y + dx, b * x + d * y + dy, on_curve))
+                        .collect(),
+                );
+            }
+
+            if flags & MORE_COMPONENTS == 0 {
+                break;
+            }
+        }
+
+        Ok(contours)
+    }
+}
+
+fn simple_outline(data: &[u8], contours: usize) -> Result<Vec<Vec<Point>>, Box<dyn Error>> {
+    let mut ends = Vec::with_capacity(contours);
+    for contour in 0..contours {
+        ends.push(read_u16(data, 10 + 2 * contour)? as usize);
+    }
+
+    let points = ends.last().map_or(0, |end| end + 1);
+    let instructions = read_u16(data, 10 + 2 * contours)? as usize;
+    let mut pos = 12 + 2 * contours + instructions;
+
+    let mut flags = Vec::with_capacity(points);
+    while flags.len() < points {
+        let flag = read_u8(data, pos)?;
+        pos += 1;
+
+        let repeat = if flag & REPEAT != 0 {
+            pos += 1;
+            read_u8(data, pos - 1)? as usize
+        } else {
+            0
+        };
+
+        flags.resize(flags.len() + repeat + 1, flag);
+    }
+    flags.truncate(points);
+
+    let xs = read_coordinates(data, &mut pos, &flags, X_SHORT, X_SAME_OR_POSITIVE)?;
+    let ys = read_coordinates(data, &mut pos, &flags, Y_SHORT, Y_SAME_OR_POSITIVE)?;
+
+    let mut start = 0;
+    let mut outline = Vec::with_capacity(contours);
+    for end in ends {
+        if end < start || end >= points {
+            return Err("invalid glyph contour".into());
+        }
+
+        outline.push(
+            (start..=end)
+                .map(|i| (xs[i], ys[i], flags[i] & ON_CURVE != 0))
+                .collect(),
+        );
+        start = end + 1;
+    }
+
+    Ok(outline)
+}
+
+// Coordinates are stored as deltas from the previous point, either as an unsigned byte with the
+// sign in the flags, as a signed word, or omitted when the coordinate is the same as the previous.
+fn read_coordinates(
+    data: &[u8],
+    pos: &mut usize,
+    flags: &[u8],
+    short: u8,
+    same_or_positive: u8,
+) -> Result<Vec<f32>, Box<dyn Error>> {
+    let mut value = 0i32;
+    let mut coordinates = Vec::with_capacity(flags.len());
+
+    for &flag in flags {
+        if flag & short != 0 {
+            let delta = i32::from(read_u8(data, *pos)?);
+            *pos += 1;
+
+            value += if flag & same_or_positive != 0 {
+                delta
+            } else {
+                -delta
+            };
+        } else if flag & same_or_positive == 0 {
+            value += i32::from(read_i16(data, *pos)?);
+            *pos += 2;
+        }
+
+        coordinates.push(value as f32);
+    }
+
+    Ok(coordinates)
+}
+
+// Converts a closed contour of on-curve points and quadratic Bézier control points to straight
+// edges. Between two subsequent control points, an on-curve point is implied halfway.
+fn flatten(contour: &[Point], edges: &mut Vec<Edge>) {
+    let midpoint = |a: Point, b: Point| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+
+    let (start, rest) = match contour.iter().position(|point| point.2) {
+        Some(i) => (
+            (contour[i].0, contour[i].1),
+            contour[i + 1..]
+                .iter()
+                .chain(&contour[..i])
+                .cloned()
+                .collect(),
+        ),
+        None => match (contour.first(), contour.last()) {
+            (Some(&first), Some(&last)) => (midpoint(last, first), contour.to_vec()),
+            _ => return,
+        },
+    };
+
+    let mut current = start;
+    let mut control = None;
+
+    for &(x, y, on_curve) in rest.iter().chain(Some(&(start.0, start.1, true))) {
+        match (control, on_curve) {
+            (None, true) => {
+                edges.push((current, (x, y)));
+                current = (x, y);
+            }
+            (None, false) => control = Some((x, y)),
+            (Some(ctrl), true) => {
+                curve(current, ctrl, (x, y), edges);
+                current = (x, y);
+                control = None;
+            }
+            (Some(ctrl), false) => {
+                let implied = midpoint((ctrl.0, ctrl.1, false), (x, y, false));
+                curve(current, ctrl, implied, edges);
+                current = implied;
+                control = Some((x, y));
+            }
+        }
+    }
+}
+
+// Approximates a quadratic Bézier curve with line segments.
+fn curve(from: (f32, f32), ctrl: (f32, f32), to: (f32, f32), edges: &mut Vec<Edge>) {
+    let distance = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).hypot(a.1 - b.1);
+    let length = distance(from, ctrl) + distance(ctrl, to);
+    let segments = (length / CURVE_SEGMENT_LENGTH).ceil().max(1.0).min(32.0) as u32;
+
+    let mut previous = from;
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let u = 1.0 - t;
+        let point = (
+            u * u * from.0 + 2.0 * u * t * ctrl.0 + t * t * to.0,
+            u * u * from.1 + 2.0 * u * t * ctrl.1 + t * t * to.1,
+        );
+
+        edges.push((previous, point));
+        previous = point;
+    }
+}
+
+fn find_table(data: &[u8], font: usize, tag: &[u8; 4]) -> Result<usize, Box<dyn Error>> {
+    let tables = read_u16(data, font + 4)? as usize;
+
+    (0..tables)
+        .map(|table| font + 12 + table * 16)
+        .find(|&record| data.get(record..record + 4) == Some(&tag[..]))
+        .map(|record| read_u32(data, record + 8).map(|offset| offset as usize))
+        .unwrap_or_else(|| {
+            Err(format!("the font has no '{}' table", String::from_utf8_lossy(tag)).into())
+        })
+}
+
+// Finds a Unicode character map of a supported format (4: the Basic Multilingual Plane, or 12: all
+// of Unicode), and returns its offset and format.
+fn find_character_map(data: &[u8], cmap: usize) -> Result<(usize, u16), Box<dyn Error>> {
+    let maps = read_u16(data, cmap + 2)? as usize;
+    let mut found = None;
+
+    for map in 0..maps {
+        let record = cmap + 4 + map * 8;
+        let platform = read_u16(data, record)?;
+        let encoding = read_u16(data, record + 2)?;
+        let table = cmap + read_u32(data, record + 4)? as usize;
+        let format = read_u16(data, table)?;
+
+        let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+        if unicode && (format == 12 || (format == 4 && found.is_none())) {
+            found = Some((table, format));
+        }
+    }
+
+    found.ok_or_else(|| "the font has no supported Unicode character map".into())
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, Box<dyn Error>> {
+    data.get(offset)
+        .cloned()
+        .ok_or_else(|| "unexpected end of the font data".into())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Box<dyn Error>> {
+    Ok(u16::from_be_bytes([
+        read_u8(data, offset)?,
+        read_u8(data, offset + 1)?,
+    ]))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Result<i16, Box<dyn Error>> {
+    read_u16(data, offset).map(|value| value as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Box<dyn Error>> {
+    Ok(u32::from(read_u16(data, offset)?) << 16 | u32::from(read_u16(data, offset + 2)?))
+}
+
+// A signed fixed point number with 14 fractional bits.
+fn read_f2dot14(data: &[u8], offset: usize) -> Result<f32, Box<dyn Error>> {
+    read_i16(data, offset).map(|value| f32::from(value) / 16384.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_testing::in_;
+
+    // The test font has 16 units per em, an ascender of 12 and a descender of -4 units, and the
+    // following glyphs:
+    // * 'A': a square from (2, 0) to (10, 8), advance 12
+    // * 'B': a composite of 'A', moved up by 4 units, advance 12
+    // * 'O': a contour of control points only at (2, 4), (6, 8), (10, 4) and (6, 0), advance 12
+    // * ' ': no outline, advance 6
+    fn test_font() -> TrueTypeFont {
+        TrueTypeFont::parse(std::fs::read(in_!("square_font.ttf")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parse_metrics() {
+        let font = test_font();
+
+        assert_eq!(
+            Metrics {
+                ascent: 24.0,
+                descent: 8.0,
+                line_gap: 0.0,
+            },
+            font.metrics(32.0)
+        );
+    }
+
+    #[test]
+    fn glyph_index_of_characters() {
+        let font = test_font();
+
+        assert_eq!(1, font.glyph_index('A').unwrap());
+        assert_eq!(2, font.glyph_index('B').unwrap());
+        assert_eq!(3, font.glyph_index('O').unwrap());
+        assert_eq!(4, font.glyph_index(' ').unwrap());
+        assert_eq!(0, font.glyph_index('Z').unwrap());
+        assert_eq!(0, font.glyph_index('\u{1F600}').unwrap());
+    }
+
+    #[test]
+    fn simple_glyph() {
+        let glyph = test_font().glyph('A', 16.0).unwrap();
+
+        assert_eq!(12.0, glyph.advance);
+        assert_eq!([2.0, -8.0, 10.0, 0.0], glyph.bounds);
+        assert!(glyph.contains(6.0, -4.0));
+        assert!(!glyph.contains(1.0, -4.0));
+        assert!(!glyph.contains(6.0, 1.0));
+    }
+
+    #[test]
+    fn composite_glyph() {
+        let glyph = test_font().glyph('B', 32.0).unwrap();
+
+        assert_eq!(24.0, glyph.advance);
+        assert_eq!([4.0, -24.0, 20.0, -8.0], glyph.bounds);
+    }
+
+    #[test]
+    fn curved_glyph() {
+        let glyph = test_font().glyph('O', 16.0).unwrap();
+        let [left, top, right, bottom] = glyph.bounds;
+
+        // the curves pass through the implied on-curve points, halfway between the control points
+        assert!(left > 2.0 && left < 5.0);
+        assert!(right > 7.0 && right < 10.0);
+        assert!(top > -8.0 && top < -5.0);
+        assert!(bottom > -3.0 && bottom < 0.0);
+        assert!(glyph.contains(6.0, -4.0));
+        assert!(!glyph.contains(2.5, -7.5));
+    }
+
+    #[test]
+    fn empty_glyph() {
+        let glyph = test_font().glyph(' ', 16.0).unwrap();
+
+        assert_eq!(6.0, glyph.advance);
+        assert_eq!([0.0; 4], glyph.bounds);
+    }
+
+    #[test]
+    fn missing_glyph() {
+        let glyph = test_font().glyph('Z', 16.0).unwrap();
+
+        assert_eq!(8.0, glyph.advance);
+        assert_eq!([0.0; 4], glyph.bounds);
+    }
+
+    #[test]
+    fn parse_cff_font() {
+        let mut data = std::fs::read(in_!("square_font.ttf")).unwrap();
+        data[..4].copy_from_slice(b"OTTO");
+
+        let err = TrueTypeFont::parse(data).err().unwrap();
+        assert!(err.to_string().contains("CFF"));
+    }
+
+    #[test]
+    fn parse_not_a_font() {
+        let data = std::fs::read(in_!("rainbow_8x6.bmp")).unwrap();
+
+        assert!(TrueTypeFont::parse(data).is_err());
+    }
+
+    #[test]
+    fn parse_truncated_font() {
+        let mut data = std::fs::read(in_!("square_font.ttf")).unwrap();
+        data.truncate(100);
+
+        assert!(TrueTypeFont::parse(data).is_err());
+    }
+}
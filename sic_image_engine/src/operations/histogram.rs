@@ -0,0 +1,411 @@
+use std::error::Error;
+
+use sic_core::image::{DynamicImage, RgbaImage};
+
+use crate::operations::{clamp_u8, rgb_to_ycbcr, with_rgba_buffer, ycbcr_to_rgb};
+
+const BINS: usize = 256;
+
+type Histogram = [u32; BINS];
+
+/// A lookup table which maps each 8 bit input value to an 8 bit output value.
+type Mapping = [u8; BINS];
+
+/// Histogram equalization of the luma channel.
+/// Chroma is preserved, so colors keep their hue while the tones are redistributed evenly.
+pub(crate) fn equalize(image: &DynamicImage) -> DynamicImage {
+    with_rgba_buffer(image, |buffer| {
+        let histogram = luma_histogram(buffer, 0, 0, buffer.width(), buffer.height());
+        let mapping = equalization_mapping(&histogram);
+
+        map_luma(buffer, |_, _, luma| f32::from(mapping[usize::from(luma)]));
+    })
+}
+
+/// Contrast limited adaptive histogram equalization (CLAHE) of the luma channel.
+///
+/// The image is divided in a grid of `tiles` by `tiles` regions. For each region an equalization
+/// mapping is computed from its histogram, where each histogram bin is limited to `clip_limit` times
+/// the average bin size. The clipped excess is redistributed over all bins, which limits the
+/// amplification of noise. The mappings of the four nearest regions are bilinearly interpolated
+/// for each pixel, so no region boundaries become visible.
+pub(crate) fn clahe(
+    image: &DynamicImage,
+    tiles: u32,
+    clip_limit: f32,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if tiles == 0 {
+        return Err("Operation: clahe -- The amount of tiles should be at least 1.".into());
+    }
+
+    if clip_limit < 1.0 || clip_limit.is_nan() {
+        return Err(format!(
+            "Operation: clahe -- The clip limit should be at least 1.0, but was: {}.",
+            clip_limit
+        )
+        .into());
+    }
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        let (width, height) = buffer.dimensions();
+
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Each region should at least be a single pixel.
+        let tiles_x = tiles.min(width);
+        let tiles_y = tiles.min(height);
+
+        let mappings = (0..tiles_y)
+            .flat_map(|ty| (0..tiles_x).map(move |tx| (tx, ty)))
+            .map(|(tx, ty)| {
+                let (x0, x1) = tile_span(tx, tiles_x, width);
+                let (y0, y1) = tile_span(ty, tiles_y, height);
+
+                let mut histogram = luma_histogram(buffer, x0, y0, x1, y1);
+                let area = (x1 - x0) * (y1 - y0);
+                clip_histogram(&mut histogram, clip_limit, area);
+
+                equalization_mapping(&histogram)
+            })
+            .collect::<Vec<Mapping>>();
+
+        let tile_width = width as f32 / tiles_x as f32;
+        let tile_height = height as f32 / tiles_y as f32;
+
+        map_luma(buffer, |x, y, luma| {
+            // position relative to the centers of the tiles
+            let (tx0, tx1, wx) = neighbouring_tiles(x, tile_width, tiles_x);
+            let (ty0, ty1, wy) = neighbouring_tiles(y, tile_height, tiles_y);
+
+            let lookup = |tx: u32, ty: u32| {
+                f32::from(mappings[(ty * tiles_x + tx) as usize][usize::from(luma)])
+            };
+
+            let top = lookup(tx0, ty0) * (1.0 - wx) + lookup(tx1, ty0) * wx;
+            let bottom = lookup(tx0, ty1) * (1.0 - wx) + lookup(tx1, ty1) * wx;
+
+            top * (1.0 - wy) + bottom * wy
+        });
+    }))
+}
+
+/// Stretches the tones of the image to the full range.
+///
+/// The darkest `black_clip` percent of the channel values is mapped to black, and the brightest
+/// `white_clip` percent is mapped to white. Values in between are stretched linearly.
+/// All color channels use the same mapping, so the hue of colors is preserved.
+pub(crate) fn auto_levels(
+    image: &DynamicImage,
+    black_clip: f32,
+    white_clip: f32,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    let within_range = |v: f32| (0.0..100.0).contains(&v);
+
+    if !within_range(black_clip) || !within_range(white_clip) || black_clip + white_clip >= 100.0 {
+        return Err(format!(
+            "Operation: autolevels -- The black and white clip percentages should be at least 0 \
+             and should sum up to less than 100, but were: (black={}, white={}).",
+            black_clip, white_clip
+        )
+        .into());
+    }
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        let mut histogram = [0u32; BINS];
+        for pixel in buffer.pixels() {
+            for channel in &pixel.0[..3] {
+                histogram[usize::from(*channel)] += 1;
+            }
+        }
+
+        let total = histogram.iter().map(|&count| u64::from(count)).sum::<u64>();
+
+        if total == 0 {
+            return;
+        }
+
+        let low = percentile(histogram.iter().enumerate(), total, black_clip);
+        let high = percentile(histogram.iter().enumerate().rev(), total, white_clip);
+
+        if high <= low {
+            return;
+        }
+
+        let scale = 255.0 / f32::from(high - low);
+        let mut mapping: Mapping = [0u8; BINS];
+        for (value, out) in mapping.iter_mut().enumerate() {
+            *out = clamp_u8((value as f32 - f32::from(low)) * scale);
+        }
+
+        for pixel in buffer.pixels_mut() {
+            for channel in &mut pixel.0[..3] {
+                *channel = mapping[usize::from(*channel)];
+            }
+        }
+    }))
+}
+
+/// Finds the first value for which the cumulative count of the (ordered) histogram exceeds
+/// `percentage` percent of `total`.
+fn percentile<'h, I>(ordered_histogram: I, total: u64, percentage: f32) -> u8
+where
+    I: Iterator<Item = (usize, &'h u32)>,
+{
+    let threshold = (total as f64 * f64::from(percentage) / 100.0) as u64;
+    let mut cumulative = 0u64;
+    let mut last = 0usize;
+
+    for (value, &count) in ordered_histogram {
+        last = value;
+        cumulative += u64::from(count);
+
+        if cumulative > threshold {
+            break;
+        }
+    }
+
+    last as u8
+}
+
+/// Histogram of the (BT.601) luma values within the region [x0, x1) by [y0, y1).
+fn luma_histogram(buffer: &RgbaImage, x0: u32, y0: u32, x1: u32, y1: u32) -> Histogram {
+    let mut histogram = [0u32; BINS];
+
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let [r, g, b, _] = buffer.get_pixel(x, y).0;
+            let (luma, _, _) = rgb_to_ycbcr(r, g, b);
+            histogram[usize::from(clamp_u8(luma))] += 1;
+        }
+    }
+
+    histogram
+}
+
+/// Computes a mapping which spreads the values of the histogram evenly over the full range,
+/// based on the cumulative distribution of the histogram.
+fn equalization_mapping(histogram: &Histogram) -> Mapping {
+    let mut mapping: Mapping = [0u8; BINS];
+
+    let total = histogram.iter().map(|&count| u64::from(count)).sum::<u64>();
+    let cdf_min = histogram
+        .iter()
+        .find(|&&count| count > 0)
+        .map(|&count| u64::from(count))
+        .unwrap_or(0);
+
+    if total == cdf_min {
+        // A single tone can't be redistributed, so we return the identity mapping.
+        for (value, out) in mapping.iter_mut().enumerate() {
+            *out = value as u8;
+        }
+
+        return mapping;
+    }
+
+    let mut cdf = 0u64;
+    for (value, out) in mapping.iter_mut().enumerate() {
+        cdf += u64::from(histogram[value]);
+        let numerator = cdf.saturating_sub(cdf_min) as f64;
+        *out = clamp_u8((numerator / (total - cdf_min) as f64 * 255.0) as f32);
+    }
+
+    mapping
+}
+
+/// Clips each bin of the histogram to `clip_limit` times the average bin size, and redistributes
+/// the clipped excess evenly over all bins.
+fn clip_histogram(histogram: &mut Histogram, clip_limit: f32, area: u32) {
+    let limit = ((clip_limit * area as f32 / BINS as f32) as u32).max(1);
+
+    let mut excess = 0u32;
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+
+    let increment = excess / BINS as u32;
+    let remainder = (excess % BINS as u32) as usize;
+
+    for (value, count) in histogram.iter_mut().enumerate() {
+        *count += increment;
+
+        if value < remainder {
+            *count += 1;
+        }
+    }
+}
+
+/// The pixel range [start, end) covered by tile `index` along an axis of size `size`, divided
+/// in `tiles` tiles.
+fn tile_span(index: u32, tiles: u32, size: u32) -> (u32, u32) {
+    let start = (u64::from(index) * u64::from(size) / u64::from(tiles)) as u32;
+    let end = (u64::from(index + 1) * u64::from(size) / u64::from(tiles)) as u32;
+
+    (start, end)
+}
+
+/// The indices of the two tiles whose centers surround `position` along an axis, together with
+/// the interpolation weight of the second tile.
+fn neighbouring_tiles(position: u32, tile_size: f32, tiles: u32) -> (u32, u32, f32) {
+    let relative = (position as f32 + 0.5) / tile_size - 0.5;
+    let last = (tiles - 1) as f32;
+
+    if relative <= 0.0 {
+        (0, 0, 0.0)
+    } else if relative >= last {
+        (tiles - 1, tiles - 1, 0.0)
+    } else {
+        let first = relative.floor();
+        (first as u32, first as u32 + 1, relative - first)
+    }
+}
+
+/// Replaces the luma of each pixel with the value returned by `f`, which receives the pixel
+/// coordinates and the current luma. The chroma and alpha of pixels are left untouched.
+fn map_luma<F>(buffer: &mut RgbaImage, f: F)
+where
+    F: Fn(u32, u32, u8) -> f32,
+{
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let (luma, cb, cr) = rgb_to_ycbcr(r, g, b);
+        let new_luma = f(x, y, clamp_u8(luma));
+
+        // Shift the luma while keeping the fractional part, so unchanged tones stay unchanged.
+        let (r, g, b) = ycbcr_to_rgb(luma + (new_luma - luma.round()), cb, cr);
+        pixel.0 = [r, g, b, a];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{GenericImageView, Rgba};
+
+    fn low_contrast_gradient() -> DynamicImage {
+        let buffer = RgbaImage::from_fn(64, 8, |x, _| {
+            let v = 100 + (x / 2) as u8;
+            Rgba([v, v, v, 255])
+        });
+
+        DynamicImage::ImageRgba8(buffer)
+    }
+
+    fn value_range(image: &DynamicImage) -> (u8, u8) {
+        let raw = image.to_luma().into_raw();
+        let min = *raw.iter().min().unwrap();
+        let max = *raw.iter().max().unwrap();
+
+        (min, max)
+    }
+
+    #[test]
+    fn equalize_stretches_low_contrast() {
+        let image = low_contrast_gradient();
+        assert_eq!((100, 131), value_range(&image));
+
+        let result = equalize(&image);
+
+        assert_eq!((0, 255), value_range(&result));
+        assert_eq!(image.dimensions(), result.dimensions());
+    }
+
+    #[test]
+    fn equalize_single_tone_unchanged() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, Rgba([50, 90, 10, 255])));
+        let result = equalize(&image);
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn equalization_mapping_uniform_is_identity() {
+        let histogram = [1u32; BINS];
+        let mapping = equalization_mapping(&histogram);
+
+        assert_eq!(0, mapping[0]);
+        assert_eq!(255, mapping[255]);
+        assert!(mapping.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn clip_histogram_preserves_total() {
+        let mut histogram = [0u32; BINS];
+        histogram[10] = 1000;
+        histogram[20] = 24;
+
+        clip_histogram(&mut histogram, 2.0, 1024);
+
+        assert_eq!(1024, histogram.iter().sum::<u32>());
+        assert!(histogram[10] < 1000);
+    }
+
+    #[test]
+    fn clahe_increases_contrast() {
+        let image = low_contrast_gradient();
+        let result = clahe(&image, 4, 2.0).unwrap();
+        let (min, max) = value_range(&result);
+
+        assert!(max - min > 31);
+        assert_eq!(image.dimensions(), result.dimensions());
+    }
+
+    #[test]
+    fn clahe_more_tiles_than_pixels() {
+        let image = DynamicImage::new_rgb8(2, 2);
+        assert!(clahe(&image, 8, 2.0).is_ok());
+    }
+
+    #[test]
+    fn clahe_zero_tiles() {
+        let image = low_contrast_gradient();
+        assert!(clahe(&image, 0, 2.0).is_err());
+    }
+
+    #[test]
+    fn clahe_clip_limit_too_small() {
+        let image = low_contrast_gradient();
+        assert!(clahe(&image, 2, 0.5).is_err());
+    }
+
+    #[test]
+    fn auto_levels_full_range() {
+        let image = low_contrast_gradient();
+        let result = auto_levels(&image, 0.0, 0.0).unwrap();
+
+        assert_eq!((0, 255), value_range(&result));
+    }
+
+    #[test]
+    fn auto_levels_clips_outliers() {
+        let mut buffer = RgbaImage::from_fn(100, 1, |x, _| {
+            let v = 100 + (x / 4) as u8;
+            Rgba([v, v, v, 255])
+        });
+        buffer.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+        buffer.put_pixel(99, 0, Rgba([255, 255, 255, 255]));
+        let image = DynamicImage::ImageRgba8(buffer);
+
+        // without clipping, the outliers already span the full range
+        let unclipped = auto_levels(&image, 0.0, 0.0).unwrap();
+        assert_eq!(image.raw_pixels(), unclipped.raw_pixels());
+
+        let clipped = auto_levels(&image, 2.0, 2.0).unwrap();
+        assert_eq!(Rgba([0, 0, 0, 255]), clipped.get_pixel(1, 0));
+        assert_eq!(Rgba([255, 255, 255, 255]), clipped.get_pixel(98, 0));
+    }
+
+    #[test]
+    fn auto_levels_invalid_percentages() {
+        let image = low_contrast_gradient();
+
+        assert!(auto_levels(&image, -1.0, 0.0).is_err());
+        assert!(auto_levels(&image, 0.0, 100.0).is_err());
+        assert!(auto_levels(&image, 50.0, 50.0).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Image operations which are not provided by the `image` crate, and are implemented by sic instead.
+
+use sic_core::image::{DynamicImage, RgbaImage};
+
+pub(crate) mod append;
+pub(crate) mod blend;
+pub(crate) mod color_transfer;
+pub(crate) mod draw;
+pub(crate) mod effects;
+pub(crate) mod font;
+pub(crate) mod histogram;
+pub(crate) mod redact;
+pub(crate) mod text;
+pub(crate) mod tone;
+
+/// Applies `f` on an RGBA8 copy of the image, and converts the result back to the color type of
+/// the original image.
+/// Operations which work on pixel values directly can use this to avoid having to deal with each
+/// color type separately.
+pub(crate) fn with_rgba_buffer<F>(image: &DynamicImage, f: F) -> DynamicImage
+where
+    F: FnOnce(&mut RgbaImage),
+{
+    let mut buffer = image.to_rgba();
+    f(&mut buffer);
+
+    into_color_type_of(image, buffer)
+}
+
+/// Converts an RGBA8 image buffer to the color type of `original`.
+pub(crate) fn into_color_type_of(original: &DynamicImage, buffer: RgbaImage) -> DynamicImage {
+    let rgba = DynamicImage::ImageRgba8(buffer);
+
+    match original {
+        DynamicImage::ImageLuma8(_) => DynamicImage::ImageLuma8(rgba.to_luma()),
+        DynamicImage::ImageLumaA8(_) => DynamicImage::ImageLumaA8(rgba.to_luma_alpha()),
+        DynamicImage::ImageRgb8(_) => DynamicImage::ImageRgb8(rgba.to_rgb()),
+        DynamicImage::ImageRgba8(_) => rgba,
+        DynamicImage::ImageBgr8(_) => DynamicImage::ImageBgr8(rgba.to_bgr()),
+        DynamicImage::ImageBgra8(_) => DynamicImage::ImageBgra8(rgba.to_bgra()),
+    }
+}
+
+/// Converts an RGBA8 image buffer to the color type of `original`, extended with an alpha channel
+/// if it does not have one yet.
+/// Operations which make (parts of) an image transparent can use this to keep the alpha channel.
+pub(crate) fn into_color_type_with_alpha_of(
+    original: &DynamicImage,
+    buffer: RgbaImage,
+) -> DynamicImage {
+    let rgba = DynamicImage::ImageRgba8(buffer);
+
+    match original {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLumaA8(_) => {
+            DynamicImage::ImageLumaA8(rgba.to_luma_alpha())
+        }
+        DynamicImage::ImageBgr8(_) | DynamicImage::ImageBgra8(_) => {
+            DynamicImage::ImageBgra8(rgba.to_bgra())
+        }
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageRgba8(_) => rgba,
+    }
+}
+
+/// Luma (Y) and chroma (Cb, Cr) components of an RGB color, following ITU-R BT.601.
+pub(crate) fn rgb_to_ycbcr(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (f32::from(r), f32::from(g), f32::from(b));
+
+    let y = 0.299 * r + 0.587 * g + 0.114 * b;
+    let cb = 128.0 - 0.168_736 * r - 0.331_264 * g + 0.5 * b;
+    let cr = 128.0 + 0.5 * r - 0.418_688 * g - 0.081_312 * b;
+
+    (y, cb, cr)
+}
+
+/// Inverse of [rgb_to_ycbcr]; the resulting channels are clamped to the `u8` range.
+pub(crate) fn ycbcr_to_rgb(y: f32, cb: f32, cr: f32) -> (u8, u8, u8) {
+    let r = y + 1.402 * (cr - 128.0);
+    let g = y - 0.344_136 * (cb - 128.0) - 0.714_136 * (cr - 128.0);
+    let b = y + 1.772 * (cb - 128.0);
+
+    (clamp_u8(r), clamp_u8(g), clamp_u8(b))
+}
+
+/// Rounds and clamps a value to the `u8` range.
+pub(crate) fn clamp_u8(value: f32) -> u8 {
+    value.round().max(0.0).min(255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::GenericImageView;
+
+    #[test]
+    fn ycbcr_round_trip() {
+        for &(r, g, b) in &[(0, 0, 0), (255, 255, 255), (255, 0, 0), (12, 200, 99)] {
+            let (y, cb, cr) = rgb_to_ycbcr(r, g, b);
+            assert_eq!((r, g, b), ycbcr_to_rgb(y, cb, cr));
+        }
+    }
+
+    #[test]
+    fn with_rgba_buffer_keeps_color_type() {
+        let image = DynamicImage::new_luma8(2, 3);
+        let result = with_rgba_buffer(&image, |buffer| {
+            buffer
+                .pixels_mut()
+                .for_each(|pixel| pixel.0 = [10, 10, 10, 255])
+        });
+
+        assert_eq!(image.color(), result.color());
+        assert_eq!((2, 3), result.dimensions());
+        assert_eq!(vec![10u8; 6], result.raw_pixels());
+    }
+}
@@ -0,0 +1,180 @@
+use std::error::Error;
+
+use sic_core::image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+
+use crate::operations::with_rgba_buffer;
+use crate::wrapper::region::Region;
+
+/// Pixelates each of the given regions, by replacing each block of `block` by `block` pixels
+/// with its average color. Blocks are aligned to the top left corner of their region.
+/// Regions are clipped to the bounds of the image; regions outside of the image are ignored.
+pub(crate) fn pixelate(
+    image: &DynamicImage,
+    block: u32,
+    regions: &[Region],
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if block == 0 {
+        return Err("Operation: pixelate -- The block size should be larger than 0.".into());
+    }
+
+    let (width, height) = image.dimensions();
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        for region in regions.iter().filter_map(|r| r.within(width, height)) {
+            pixelate_region(buffer, block, region);
+        }
+    }))
+}
+
+fn pixelate_region(buffer: &mut RgbaImage, block: u32, region: Region) {
+    let (right, bottom) = (region.x + region.width, region.y + region.height);
+
+    for top in (region.y..bottom).step_by(block as usize) {
+        for left in (region.x..right).step_by(block as usize) {
+            let block_right = left.saturating_add(block).min(right);
+            let block_bottom = top.saturating_add(block).min(bottom);
+
+            let mut sum = [0u64; 4];
+            for y in top..block_bottom {
+                for x in left..block_right {
+                    let pixel = buffer.get_pixel(x, y);
+                    for (total, &value) in sum.iter_mut().zip(pixel.0.iter()) {
+                        *total += u64::from(value);
+                    }
+                }
+            }
+
+            let count = u64::from((block_right - left) * (block_bottom - top));
+            let mut average = [0u8; 4];
+            for (out, total) in average.iter_mut().zip(sum.iter()) {
+                *out = ((total + count / 2) / count) as u8;
+            }
+
+            for y in top..block_bottom {
+                for x in left..block_right {
+                    buffer.get_pixel_mut(x, y).0 = average;
+                }
+            }
+        }
+    }
+}
+
+/// Blurs each of the given regions with a Gaussian blur, leaving the rest of the image as is.
+/// Only pixels within a region are used to compute its blurred pixels, so no details of the region
+/// can be recovered from its surroundings (or vice versa).
+/// Regions are clipped to the bounds of the image; regions outside of the image are ignored.
+/// Like the blur of a whole image, a negative sigma will use `1.0` instead.
+pub(crate) fn blur(
+    image: &DynamicImage,
+    sigma: f32,
+    regions: &[Region],
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if sigma == 0.0 || sigma.is_nan() {
+        return Err(format!(
+            "Operation: blur -- The sigma of a region blur should not be 0, but was: {}.",
+            sigma
+        )
+        .into());
+    }
+
+    let sigma = if sigma < 0.0 { 1.0 } else { sigma };
+    let (width, height) = image.dimensions();
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        for region in regions.iter().filter_map(|r| r.within(width, height)) {
+            let part =
+                imageops::crop(buffer, region.x, region.y, region.width, region.height).to_image();
+            let blurred = imageops::blur(&part, sigma);
+
+            imageops::replace(buffer, &blurred, region.x, region.y);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::Rgba;
+
+    fn stripes() -> DynamicImage {
+        let buffer = RgbaImage::from_fn(8, 8, |x, _| {
+            if x % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([200, 100, 50, 255])
+            }
+        });
+
+        DynamicImage::ImageRgba8(buffer)
+    }
+
+    #[test]
+    fn pixelate_whole_image() {
+        let result = pixelate(&stripes(), 2, &[Region::new(0, 0, 8, 8)]).unwrap();
+        let buffer = result.to_rgba();
+
+        assert!(buffer.pixels().all(|pixel| pixel.0 == [100, 50, 25, 255]));
+    }
+
+    #[test]
+    fn pixelate_only_region() {
+        let image = stripes();
+        let result = pixelate(&image, 2, &[Region::new(2, 2, 4, 4)]).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_eq!([100, 50, 25, 255], buffer.get_pixel(2, 2).0);
+        assert_eq!([100, 50, 25, 255], buffer.get_pixel(5, 5).0);
+        assert_eq!([0, 0, 0, 255], buffer.get_pixel(0, 0).0);
+        assert_eq!([200, 100, 50, 255], buffer.get_pixel(7, 7).0);
+    }
+
+    #[test]
+    fn pixelate_partial_blocks() {
+        // block 3 within width 8: blocks cover columns 0-2, 3-5 and 6-7
+        let result = pixelate(&stripes(), 3, &[Region::new(0, 0, 8, 8)]).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_eq!([67, 33, 17, 255], buffer.get_pixel(0, 0).0);
+        assert_eq!([133, 67, 33, 255], buffer.get_pixel(3, 0).0);
+        assert_eq!([100, 50, 25, 255], buffer.get_pixel(7, 7).0);
+    }
+
+    #[test]
+    fn pixelate_region_outside_is_ignored() {
+        let image = stripes();
+        let result = pixelate(&image, 2, &[Region::new(20, 20, 4, 4)]).unwrap();
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn pixelate_err_zero_block() {
+        assert!(pixelate(&stripes(), 0, &[Region::new(0, 0, 8, 8)]).is_err());
+    }
+
+    #[test]
+    fn blur_only_region() {
+        let image = stripes();
+        let result = blur(&image, 1.0, &[Region::new(0, 0, 4, 8)]).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_ne!([0, 0, 0, 255], buffer.get_pixel(2, 4).0);
+        assert_eq!([0, 0, 0, 255], buffer.get_pixel(4, 4).0);
+        assert_eq!([200, 100, 50, 255], buffer.get_pixel(5, 4).0);
+    }
+
+    #[test]
+    fn blur_err_sigma() {
+        assert!(blur(&stripes(), 0.0, &[Region::new(0, 0, 8, 8)]).is_err());
+        assert!(blur(&stripes(), std::f32::NAN, &[Region::new(0, 0, 8, 8)]).is_err());
+    }
+
+    #[test]
+    fn blur_negative_sigma_as_one() {
+        let regions = [Region::new(0, 0, 4, 8)];
+        let negative = blur(&stripes(), -1.0, &regions).unwrap();
+        let one = blur(&stripes(), 1.0, &regions).unwrap();
+
+        assert_eq!(one.to_rgba().into_raw(), negative.to_rgba().into_raw());
+    }
+}
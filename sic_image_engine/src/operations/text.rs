@@ -0,0 +1,297 @@
+use std::error::Error;
+
+use sic_core::image::{DynamicImage, GenericImageView, Rgba};
+
+use crate::operations::draw::paint;
+use crate::operations::font::{Font, Glyph};
+use crate::operations::with_rgba_buffer;
+use crate::wrapper::gravity::Gravity;
+
+/// The appearance of text: the font, the size of the font (in pixels), the color of the text, and
+/// the side or corner of the image to which the text is anchored.
+pub(crate) struct TextStyle<'font> {
+    pub(crate) font: &'font Font,
+    pub(crate) size: u32,
+    pub(crate) color: Rgba<u8>,
+    pub(crate) gravity: Gravity,
+}
+
+/// Draws text, which may consist of multiple lines separated by '\n'.
+/// The text is placed `x` and `y` pixels away from the side or corner given by the gravity,
+/// towards the center of the image (for the center, positive offsets move the text to the right
+/// and down). Lines are aligned to the left, center or right, following the gravity as well.
+pub(crate) fn text(
+    image: &DynamicImage,
+    text: &str,
+    x: i32,
+    y: i32,
+    style: &TextStyle,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if style.size == 0 {
+        return Err("Operation: text -- The text size should be larger than 0.".into());
+    }
+
+    let size = style.size as f32;
+    let metrics = style.font.metrics(size);
+
+    let lines = text
+        .split('\n')
+        .map(|line| {
+            line.chars()
+                .map(|c| style.font.glyph(c, size))
+                .collect::<Result<Vec<Glyph>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Operation: text -- Unable to read a glyph: {}", err))?;
+
+    let widths = lines
+        .iter()
+        .map(|glyphs| glyphs.iter().map(|glyph| glyph.advance).sum())
+        .collect::<Vec<f32>>();
+
+    let block_width = widths.iter().cloned().fold(0.0, f32::max);
+    let block_height =
+        metrics.ascent + metrics.descent + metrics.line_height() * (lines.len() - 1) as f32;
+
+    let (width, height) = image.dimensions();
+    let (horizontal, vertical) = anchor(style.gravity);
+    let left = position(horizontal, width, block_width, x);
+    let top = position(vertical, height, block_height, y);
+
+    Ok(with_rgba_buffer(image, |buffer| {
+        for (i, (glyphs, line_width)) in lines.iter().zip(&widths).enumerate() {
+            let baseline = (top + metrics.ascent + metrics.line_height() * i as f32).round();
+            let mut pen = (left + (block_width - line_width) * horizontal).round();
+
+            for glyph in glyphs {
+                let [glyph_left, glyph_top, glyph_right, glyph_bottom] = glyph.bounds;
+                let bounds = [
+                    pen + glyph_left,
+                    baseline + glyph_top,
+                    pen + glyph_right,
+                    baseline + glyph_bottom,
+                ];
+
+                paint(buffer, bounds, style.color, true, |px, py| {
+                    glyph.contains(px - pen, py - baseline)
+                });
+
+                pen += glyph.advance;
+            }
+        }
+    }))
+}
+
+// The horizontal and vertical position of the anchor of the gravity, as fraction of the width and
+// height of the image.
+fn anchor(gravity: Gravity) -> (f32, f32) {
+    match gravity {
+        Gravity::NorthWest => (0.0, 0.0),
+        Gravity::North => (0.5, 0.0),
+        Gravity::NorthEast => (1.0, 0.0),
+        Gravity::West => (0.0, 0.5),
+        Gravity::Center => (0.5, 0.5),
+        Gravity::East => (1.0, 0.5),
+        Gravity::SouthWest => (0.0, 1.0),
+        Gravity::South => (0.5, 1.0),
+        Gravity::SouthEast => (1.0, 1.0),
+    }
+}
+
+// The start of the text block along one axis, placed `offset` pixels away from the anchor,
+// towards the center of the image.
+fn position(anchor: f32, available: u32, size: f32, offset: i32) -> f32 {
+    let start = (available as f32 - size) * anchor;
+
+    if anchor > 0.5 {
+        start - offset as f32
+    } else {
+        start + offset as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage};
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn canvas(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([255, 255, 255])))
+    }
+
+    fn style(font: &Font, size: u32, gravity: Gravity) -> TextStyle<'_> {
+        TextStyle {
+            font,
+            size,
+            color: BLACK,
+            gravity,
+        }
+    }
+
+    // the bounding box (left, top, right, bottom; inclusive) of the pixels which are not white
+    fn ink(image: &DynamicImage) -> Option<(u32, u32, u32, u32)> {
+        image
+            .pixels()
+            .filter(|(_, _, pixel)| *pixel != WHITE)
+            .fold(None, |bounds, (x, y, _)| {
+                let (left, top, right, bottom) = bounds.unwrap_or((x, y, x, y));
+                Some((left.min(x), top.min(y), right.max(x), bottom.max(y)))
+            })
+    }
+
+    #[test]
+    fn builtin_font_glyph() {
+        let font = Font::Builtin;
+        let result = text(
+            &canvas(10, 10),
+            "I",
+            1,
+            1,
+            &style(&font, 8, Gravity::NorthWest),
+        )
+        .unwrap();
+
+        // the 'I' covers columns 1 to 4, rows 0 to 6 of its glyph
+        assert_eq!(Some((2, 1, 5, 7)), ink(&result));
+        assert_eq!(BLACK, result.get_pixel(3, 4));
+        assert_eq!(WHITE, result.get_pixel(2, 4));
+    }
+
+    #[test]
+    fn builtin_font_scaled() {
+        let font = Font::Builtin;
+        let result = text(
+            &canvas(20, 20),
+            "I",
+            0,
+            0,
+            &style(&font, 16, Gravity::NorthWest),
+        )
+        .unwrap();
+
+        assert_eq!(Some((2, 0, 9, 13)), ink(&result));
+
+        // the glyph is scaled by an integer factor, so all pixels are either fully covered or not
+        assert!(result
+            .pixels()
+            .all(|(_, _, pixel)| pixel == BLACK || pixel == WHITE));
+    }
+
+    #[test]
+    fn gravity_south_east() {
+        let font = Font::Builtin;
+        let result = text(
+            &canvas(20, 20),
+            "I",
+            2,
+            3,
+            &style(&font, 8, Gravity::SouthEast),
+        )
+        .unwrap();
+
+        // the text block of 8 by 8 pixels starts at (20 - 8 - 2, 20 - 8 - 3)
+        assert_eq!(Some((11, 9, 14, 15)), ink(&result));
+    }
+
+    #[test]
+    fn gravity_center() {
+        let font = Font::Builtin;
+        let centered = text(
+            &canvas(20, 20),
+            "I",
+            0,
+            0,
+            &style(&font, 8, Gravity::Center),
+        )
+        .unwrap();
+        let moved = text(
+            &canvas(20, 20),
+            "I",
+            2,
+            -1,
+            &style(&font, 8, Gravity::Center),
+        )
+        .unwrap();
+
+        assert_eq!(Some((7, 6, 10, 12)), ink(&centered));
+        assert_eq!(Some((9, 5, 12, 11)), ink(&moved));
+    }
+
+    #[test]
+    fn multiple_lines_follow_gravity() {
+        let font = Font::Builtin;
+        let left = text(
+            &canvas(30, 30),
+            "II\nI",
+            0,
+            0,
+            &style(&font, 8, Gravity::NorthWest),
+        )
+        .unwrap();
+        let right = text(
+            &canvas(30, 30),
+            "II\nI",
+            0,
+            0,
+            &style(&font, 8, Gravity::NorthEast),
+        )
+        .unwrap();
+
+        // the second line starts 8 pixels below the first line
+        assert_eq!(BLACK, left.get_pixel(2, 8));
+        assert_eq!(WHITE, left.get_pixel(10, 8));
+        assert_eq!(BLACK, right.get_pixel(24, 8));
+        assert_eq!(WHITE, right.get_pixel(16, 8));
+        assert_eq!(Some((15, 0, 26, 14)), ink(&right));
+    }
+
+    #[test]
+    fn text_outside_of_image() {
+        let font = Font::Builtin;
+        let image = canvas(10, 10);
+        let result = text(
+            &image,
+            "hello",
+            20,
+            -20,
+            &style(&font, 8, Gravity::NorthWest),
+        )
+        .unwrap();
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn truetype_font() {
+        let font = Font::from_file(sic_testing::in_!("square_font.ttf")).unwrap();
+        let result = text(
+            &canvas(20, 20),
+            "A",
+            0,
+            0,
+            &style(&font, 16, Gravity::NorthWest),
+        )
+        .unwrap();
+
+        // the ascent is 12 pixels, and the square covers 8 by 8 pixels, from 2 pixels to the right
+        // of the pen, up to the baseline
+        assert_eq!(Some((2, 4, 9, 11)), ink(&result));
+    }
+
+    #[test]
+    fn err_zero_size() {
+        let font = Font::Builtin;
+
+        assert!(text(
+            &canvas(10, 10),
+            "I",
+            0,
+            0,
+            &style(&font, 0, Gravity::NorthWest)
+        )
+        .is_err());
+    }
+}
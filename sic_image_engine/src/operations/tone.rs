@@ -0,0 +1,300 @@
+use std::error::Error;
+
+use sic_core::image::DynamicImage;
+
+use crate::operations::{clamp_u8, with_rgba_buffer};
+use crate::wrapper::channel::Channel;
+
+const BINS: usize = 256;
+
+/// A lookup table which maps each 8 bit input value to an 8 bit output value.
+type Mapping = [u8; BINS];
+
+/// Maps the input range [in_black, in_white] to the output range [out_black, out_white], applying
+/// a gamma correction in between. A gamma above 1.0 brightens the mid tones, a gamma below 1.0
+/// darkens them.
+/// The output range may be reversed (i.e. out_black > out_white), which inverts the image.
+pub(crate) fn levels(
+    image: &DynamicImage,
+    in_black: u8,
+    in_white: u8,
+    gamma: f32,
+    out_black: u8,
+    out_white: u8,
+) -> Result<DynamicImage, Box<dyn Error>> {
+    if in_black >= in_white {
+        return Err(format!(
+            "Operation: levels -- The input black point should be smaller than the input white \
+             point, but were: (black={}, white={}).",
+            in_black, in_white
+        )
+        .into());
+    }
+
+    if gamma <= 0.0 || gamma.is_nan() {
+        return Err(format!(
+            "Operation: levels -- The gamma should be larger than 0, but was: {}.",
+            gamma
+        )
+        .into());
+    }
+
+    let (in_black, in_white) = (f32::from(in_black), f32::from(in_white));
+    let (out_black, out_white) = (f32::from(out_black), f32::from(out_white));
+
+    let mut mapping: Mapping = [0u8; BINS];
+    for (value, out) in mapping.iter_mut().enumerate() {
+        let normalized = ((value as f32 - in_black) / (in_white - in_black))
+            .max(0.0)
+            .min(1.0);
+        let corrected = normalized.powf(1.0 / gamma);
+        *out = clamp_u8(out_black + corrected * (out_white - out_black));
+    }
+
+    Ok(apply_mapping(image, Channel::Rgb, &mapping))
+}
+
+/// Maps the values of the selected channel(s) by a curve through the given control points.
+/// The curve is interpolated with a monotone cubic spline (Fritsch-Carlson), so it never
+/// overshoots between two control points. Before the first and after the last control point, the
+/// curve is flat.
+pub(crate) fn curves(
+    image: &DynamicImage,
+    channel: Channel,
+    points: &[(u8, u8)],
+) -> Result<DynamicImage, Box<dyn Error>> {
+    let mapping = curve_mapping(points)?;
+
+    Ok(apply_mapping(image, channel, &mapping))
+}
+
+fn curve_mapping(points: &[(u8, u8)]) -> Result<Mapping, Box<dyn Error>> {
+    if points.len() < 2 {
+        return Err("Operation: curves -- At least two control points are required.".into());
+    }
+
+    let mut points = points.to_vec();
+    points.sort_by_key(|&(x, _)| x);
+
+    if let Some(window) = points.windows(2).find(|window| window[0].0 == window[1].0) {
+        return Err(format!(
+            "Operation: curves -- Each control point should have a unique input value, but {} \
+             was used more than once.",
+            window[0].0
+        )
+        .into());
+    }
+
+    let xs = points
+        .iter()
+        .map(|&(x, _)| f32::from(x))
+        .collect::<Vec<_>>();
+    let ys = points
+        .iter()
+        .map(|&(_, y)| f32::from(y))
+        .collect::<Vec<_>>();
+    let tangents = monotone_tangents(&xs, &ys);
+
+    let last = xs.len() - 1;
+    let mut mapping: Mapping = [0u8; BINS];
+
+    for (value, out) in mapping.iter_mut().enumerate() {
+        let x = value as f32;
+
+        *out = if x <= xs[0] {
+            clamp_u8(ys[0])
+        } else if x >= xs[last] {
+            clamp_u8(ys[last])
+        } else {
+            // the segment [xs[k], xs[k + 1]) which contains x
+            let k = xs.iter().rposition(|&xk| xk <= x).unwrap_or(0);
+            let h = xs[k + 1] - xs[k];
+            let t = (x - xs[k]) / h;
+
+            let (t2, t3) = (t * t, t * t * t);
+            let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+            let h10 = t3 - 2.0 * t2 + t;
+            let h01 = -2.0 * t3 + 3.0 * t2;
+            let h11 = t3 - t2;
+
+            clamp_u8(
+                h00 * ys[k] + h10 * h * tangents[k] + h01 * ys[k + 1] + h11 * h * tangents[k + 1],
+            )
+        };
+    }
+
+    Ok(mapping)
+}
+
+/// Computes the tangents of a monotone cubic Hermite spline through the (sorted) points.
+fn monotone_tangents(xs: &[f32], ys: &[f32]) -> Vec<f32> {
+    let n = xs.len();
+
+    let secants = (0..n - 1)
+        .map(|k| (ys[k + 1] - ys[k]) / (xs[k + 1] - xs[k]))
+        .collect::<Vec<_>>();
+
+    let mut tangents = vec![0f32; n];
+    tangents[0] = secants[0];
+    tangents[n - 1] = secants[n - 2];
+
+    for k in 1..n - 1 {
+        tangents[k] = if secants[k - 1] * secants[k] <= 0.0 {
+            0.0
+        } else {
+            (secants[k - 1] + secants[k]) / 2.0
+        };
+    }
+
+    // Restrict the tangents, so the spline is monotone on each segment.
+    for k in 0..n - 1 {
+        if secants[k] == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+
+        let a = tangents[k] / secants[k];
+        let b = tangents[k + 1] / secants[k];
+        let magnitude = a * a + b * b;
+
+        if magnitude > 9.0 {
+            let tau = 3.0 / magnitude.sqrt();
+            tangents[k] = tau * a * secants[k];
+            tangents[k + 1] = tau * b * secants[k];
+        }
+    }
+
+    tangents
+}
+
+fn apply_mapping(image: &DynamicImage, channel: Channel, mapping: &Mapping) -> DynamicImage {
+    with_rgba_buffer(image, |buffer| {
+        for pixel in buffer.pixels_mut() {
+            for &index in channel.indices() {
+                pixel.0[index] = mapping[usize::from(pixel.0[index])];
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgba, RgbaImage};
+
+    fn gradient() -> DynamicImage {
+        let buffer = RgbaImage::from_fn(256, 1, |x, _| {
+            let v = x as u8;
+            Rgba([v, v, v, 255])
+        });
+
+        DynamicImage::ImageRgba8(buffer)
+    }
+
+    #[test]
+    fn levels_identity() {
+        let image = gradient();
+        let result = levels(&image, 0, 255, 1.0, 0, 255).unwrap();
+
+        assert_eq!(image.raw_pixels(), result.raw_pixels());
+    }
+
+    #[test]
+    fn levels_stretch_input_range() {
+        let result = levels(&gradient(), 50, 200, 1.0, 0, 255).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_eq!(0, buffer.get_pixel(50, 0).0[0]);
+        assert_eq!(0, buffer.get_pixel(10, 0).0[0]);
+        assert_eq!(255, buffer.get_pixel(200, 0).0[0]);
+        assert_eq!(255, buffer.get_pixel(250, 0).0[0]);
+        assert_eq!(128, buffer.get_pixel(125, 0).0[0]);
+    }
+
+    #[test]
+    fn levels_gamma_brightens_mid_tones() {
+        let result = levels(&gradient(), 0, 255, 2.0, 0, 255).unwrap();
+        let buffer = result.to_rgba();
+
+        assert!(buffer.get_pixel(128, 0).0[0] > 128);
+        assert_eq!(0, buffer.get_pixel(0, 0).0[0]);
+        assert_eq!(255, buffer.get_pixel(255, 0).0[0]);
+    }
+
+    #[test]
+    fn levels_output_range() {
+        let result = levels(&gradient(), 0, 255, 1.0, 20, 100).unwrap();
+        let buffer = result.to_rgba();
+
+        assert_eq!(20, buffer.get_pixel(0, 0).0[0]);
+        assert_eq!(100, buffer.get_pixel(255, 0).0[0]);
+        assert_eq!(255, buffer.get_pixel(255, 0).0[3]);
+    }
+
+    #[test]
+    fn levels_err_input_range() {
+        assert!(levels(&gradient(), 200, 50, 1.0, 0, 255).is_err());
+        assert!(levels(&gradient(), 50, 50, 1.0, 0, 255).is_err());
+    }
+
+    #[test]
+    fn levels_err_gamma() {
+        assert!(levels(&gradient(), 0, 255, 0.0, 0, 255).is_err());
+        assert!(levels(&gradient(), 0, 255, -1.0, 0, 255).is_err());
+    }
+
+    #[test]
+    fn curve_mapping_passes_through_control_points() {
+        let points = [(0, 0), (64, 100), (192, 210), (255, 255)];
+        let mapping = curve_mapping(&points).unwrap();
+
+        for &(x, y) in &points {
+            assert_eq!(y, mapping[usize::from(x)]);
+        }
+    }
+
+    #[test]
+    fn curve_mapping_is_monotone() {
+        let mapping = curve_mapping(&[(0, 0), (30, 200), (40, 210), (255, 255)]).unwrap();
+
+        assert!(mapping.windows(2).all(|window| window[0] <= window[1]));
+    }
+
+    #[test]
+    fn curve_mapping_flat_outside_control_points() {
+        let mapping = curve_mapping(&[(200, 100), (50, 20)]).unwrap();
+
+        assert_eq!(20, mapping[0]);
+        assert_eq!(20, mapping[50]);
+        assert_eq!(100, mapping[200]);
+        assert_eq!(100, mapping[255]);
+    }
+
+    #[test]
+    fn curve_mapping_linear() {
+        let mapping = curve_mapping(&[(0, 0), (255, 255)]).unwrap();
+
+        for (value, out) in mapping.iter().enumerate() {
+            assert_eq!(value, usize::from(*out));
+        }
+    }
+
+    #[test]
+    fn curve_mapping_err_too_few_points() {
+        assert!(curve_mapping(&[(0, 0)]).is_err());
+    }
+
+    #[test]
+    fn curve_mapping_err_duplicate_input() {
+        assert!(curve_mapping(&[(0, 0), (10, 20), (10, 30)]).is_err());
+    }
+
+    #[test]
+    fn curves_single_channel() {
+        let result = curves(&gradient(), Channel::Red, &[(0, 255), (255, 0)]).unwrap();
+        let pixel = result.to_rgba().get_pixel(10, 0).0;
+
+        assert_eq!([245, 10, 10, 255], pixel);
+    }
+}
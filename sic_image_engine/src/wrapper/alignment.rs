@@ -0,0 +1,68 @@
+use std::error::Error;
+
+/// The position of an image along the cross axis of `append`, when it is smaller than the
+/// largest of the appended images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Aligned to the top (when appending along the x axis) or left (along the y axis).
+    Start,
+    Center,
+    /// Aligned to the bottom (when appending along the x axis) or right (along the y axis).
+    End,
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Alignment::Start
+    }
+}
+
+impl Alignment {
+    pub fn try_from_str(val: &str) -> Result<Alignment, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "start" => Ok(Alignment::Start),
+            "center" => Ok(Alignment::Center),
+            "end" => Ok(Alignment::End),
+            fail => Err(format!("No such alignment: {}", fail).into()),
+        }
+    }
+
+    /// The offset of an item of the given size, within the available space.
+    pub(crate) fn offset(self, available: u32, size: u32) -> u32 {
+        let space = available.saturating_sub(size);
+
+        match self {
+            Alignment::Start => 0,
+            Alignment::Center => space / 2,
+            Alignment::End => space,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Alignment::Start, Alignment::try_from_str("start").unwrap());
+        assert_eq!(
+            Alignment::Center,
+            Alignment::try_from_str("Center").unwrap()
+        );
+        assert_eq!(Alignment::End, Alignment::try_from_str("END").unwrap());
+    }
+
+    #[test]
+    fn from_str_fail() {
+        assert!(Alignment::try_from_str("middle").is_err());
+    }
+
+    #[test]
+    fn offset() {
+        assert_eq!(0, Alignment::Start.offset(10, 4));
+        assert_eq!(3, Alignment::Center.offset(10, 4));
+        assert_eq!(6, Alignment::End.offset(10, 4));
+        assert_eq!(0, Alignment::End.offset(10, 10));
+    }
+}
@@ -0,0 +1,37 @@
+use std::error::Error;
+
+/// The axis along which the `append` operation places images next to each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// From left to right.
+    X,
+    /// From top to bottom.
+    Y,
+}
+
+impl Axis {
+    pub fn try_from_str(val: &str) -> Result<Axis, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "x" => Ok(Axis::X),
+            "y" => Ok(Axis::Y),
+            fail => Err(format!("No such axis: {}", fail).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Axis::X, Axis::try_from_str("x").unwrap());
+        assert_eq!(Axis::Y, Axis::try_from_str("Y").unwrap());
+    }
+
+    #[test]
+    fn from_str_fail() {
+        assert!(Axis::try_from_str("z").is_err());
+        assert!(Axis::try_from_str("xy").is_err());
+    }
+}
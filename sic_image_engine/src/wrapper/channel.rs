@@ -0,0 +1,50 @@
+use std::error::Error;
+
+/// The color channel(s) an operation such as `curves` is applied to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Rgb,
+    Red,
+    Green,
+    Blue,
+}
+
+impl Channel {
+    pub fn try_from_str(val: &str) -> Result<Channel, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "rgb" => Ok(Channel::Rgb),
+            "red" | "r" => Ok(Channel::Red),
+            "green" | "g" => Ok(Channel::Green),
+            "blue" | "b" => Ok(Channel::Blue),
+            fail => Err(format!("No such channel: {}", fail).into()),
+        }
+    }
+
+    /// The indices of the channels within an RGB(A) pixel.
+    pub fn indices(self) -> &'static [usize] {
+        match self {
+            Channel::Rgb => &[0, 1, 2],
+            Channel::Red => &[0],
+            Channel::Green => &[1],
+            Channel::Blue => &[2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Channel::Rgb, Channel::try_from_str("RGB").unwrap());
+        assert_eq!(Channel::Red, Channel::try_from_str("red").unwrap());
+        assert_eq!(Channel::Green, Channel::try_from_str("g").unwrap());
+        assert_eq!(Channel::Blue, Channel::try_from_str("Blue").unwrap());
+    }
+
+    #[test]
+    fn from_str_fail() {
+        assert!(Channel::try_from_str("alpha").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use std::error::Error;
+
+/// The side or corner of an image to which the `text` operation anchors its text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Gravity {
+    NorthWest,
+    North,
+    NorthEast,
+    West,
+    Center,
+    East,
+    SouthWest,
+    South,
+    SouthEast,
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity::NorthWest
+    }
+}
+
+impl Gravity {
+    pub fn try_from_str(val: &str) -> Result<Gravity, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "northwest" => Ok(Gravity::NorthWest),
+            "north" => Ok(Gravity::North),
+            "northeast" => Ok(Gravity::NorthEast),
+            "west" => Ok(Gravity::West),
+            "center" => Ok(Gravity::Center),
+            "east" => Ok(Gravity::East),
+            "southwest" => Ok(Gravity::SouthWest),
+            "south" => Ok(Gravity::South),
+            "southeast" => Ok(Gravity::SouthEast),
+            fail => Err(format!("No such gravity: {}", fail).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            Gravity::NorthWest,
+            Gravity::try_from_str("northwest").unwrap()
+        );
+        assert_eq!(Gravity::Center, Gravity::try_from_str("Center").unwrap());
+        assert_eq!(
+            Gravity::SouthEast,
+            Gravity::try_from_str("SOUTHEAST").unwrap()
+        );
+    }
+
+    #[test]
+    fn from_str_fail() {
+        assert!(Gravity::try_from_str("middle").is_err());
+        assert!(Gravity::try_from_str("north-west").is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use std::error::Error;
+
+/// The method used by `matchhistogram` to transfer the colors of a reference image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    /// Remaps each RGB channel, so its cumulative histogram matches the one of the reference.
+    Histogram,
+    /// Matches the mean and standard deviation of each L*a*b* channel to those of the reference
+    /// (Reinhard et al., Color Transfer between Images).
+    Reinhard,
+}
+
+impl Default for MatchMode {
+    fn default() -> Self {
+        MatchMode::Histogram
+    }
+}
+
+impl MatchMode {
+    pub fn try_from_str(val: &str) -> Result<MatchMode, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "histogram" => Ok(MatchMode::Histogram),
+            "reinhard" => Ok(MatchMode::Reinhard),
+            fail => Err(format!("No such histogram match mode: {}", fail).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            MatchMode::Histogram,
+            MatchMode::try_from_str("histogram").unwrap()
+        );
+        assert_eq!(
+            MatchMode::Reinhard,
+            MatchMode::try_from_str("Reinhard").unwrap()
+        );
+    }
+
+    #[test]
+    fn from_str_fail() {
+        assert!(MatchMode::try_from_str("lab").is_err());
+    }
+}
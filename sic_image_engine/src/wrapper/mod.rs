@@ -1 +1,8 @@
+pub mod alignment;
+pub mod axis;
+pub mod channel;
 pub mod filter_type;
+pub mod gravity;
+pub mod match_mode;
+pub mod noise_kind;
+pub mod region;
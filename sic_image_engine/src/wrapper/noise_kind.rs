@@ -0,0 +1,39 @@
+use std::error::Error;
+
+/// The kind of noise produced by the noise generator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseKind {
+    /// Gradient noise, as described by Ken Perlin.
+    Perlin,
+    /// Smoothly interpolated random values on a lattice.
+    Value,
+}
+
+impl NoiseKind {
+    pub fn try_from_str(val: &str) -> Result<NoiseKind, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "perlin" => Ok(NoiseKind::Perlin),
+            "value" => Ok(NoiseKind::Value),
+            fail => Err(format!("No such noise kind: {}", fail).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            NoiseKind::Perlin,
+            NoiseKind::try_from_str("perlin").unwrap()
+        );
+        assert_eq!(NoiseKind::Value, NoiseKind::try_from_str("VALUE").unwrap());
+    }
+
+    #[test]
+    fn from_str_fail() {
+        assert!(NoiseKind::try_from_str("simplex").is_err());
+    }
+}
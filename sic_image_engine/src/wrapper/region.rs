@@ -0,0 +1,66 @@
+/// A rectangular area of an image, given by its top left corner and its size in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The part of this region which lies within an image of the given dimensions, or `None` if
+    /// the region and the image do not overlap.
+    pub fn within(self, image_width: u32, image_height: u32) -> Option<Region> {
+        let right = self.x.saturating_add(self.width).min(image_width);
+        let bottom = self.y.saturating_add(self.height).min(image_height);
+
+        if self.x >= right || self.y >= bottom {
+            return None;
+        }
+
+        Some(Region::new(self.x, self.y, right - self.x, bottom - self.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn within_inside() {
+        let region = Region::new(1, 2, 3, 4);
+        assert_eq!(Some(region), region.within(10, 10));
+    }
+
+    #[test]
+    fn within_clipped() {
+        let region = Region::new(8, 6, 10, 10);
+        assert_eq!(Some(Region::new(8, 6, 2, 4)), region.within(10, 10));
+    }
+
+    #[test]
+    fn within_outside() {
+        assert_eq!(None, Region::new(10, 0, 5, 5).within(10, 10));
+        assert_eq!(None, Region::new(0, 12, 5, 5).within(10, 10));
+    }
+
+    #[test]
+    fn within_empty() {
+        assert_eq!(None, Region::new(2, 2, 0, 5).within(10, 10));
+    }
+
+    #[test]
+    fn within_no_overflow() {
+        let region = Region::new(2, 2, std::u32::MAX, std::u32::MAX);
+        assert_eq!(Some(Region::new(2, 2, 8, 8)), region.within(10, 10));
+    }
+}
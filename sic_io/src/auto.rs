@@ -0,0 +1,281 @@
+//! Automatic choice of the output format: the image is encoded in each of a set of candidate
+//! formats, and written in the format in which it's smallest.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use sic_core::image::DynamicImage;
+use sic_core::json::escape_json;
+
+use crate::support::find_format;
+
+/// A format which is tried when the output format is chosen automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    /// The identifier of the format, as known by the [format](crate::format) module.
+    pub identifier: &'static str,
+    pub lossless: bool,
+    /// Whether the format stores the alpha channel of an image. The GIF encoder may lose even fully
+    /// transparent pixels, since their color is quantized together with the opaque colors.
+    pub alpha: bool,
+}
+
+/// The candidate formats, in order of preference: of two formats in which the image has the same
+/// size, the first is chosen. GIF images have at most 256 colors, so GIF isn't lossless in
+/// general.
+pub const CANDIDATES: &[Candidate] = &[
+    Candidate {
+        identifier: "png",
+        lossless: true,
+        alpha: true,
+    },
+    Candidate {
+        identifier: "jpg",
+        lossless: false,
+        alpha: false,
+    },
+    Candidate {
+        identifier: "gif",
+        lossless: false,
+        alpha: false,
+    },
+    Candidate {
+        identifier: "tiff",
+        lossless: true,
+        alpha: true,
+    },
+    Candidate {
+        identifier: "tga",
+        lossless: true,
+        alpha: true,
+    },
+    Candidate {
+        identifier: "bmp",
+        lossless: true,
+        alpha: true,
+    },
+];
+
+/// The identifiers of the candidate formats, optionally only of those which are lossless. Formats
+/// which don't store the alpha channel are skipped if the image has transparent pixels, so they
+/// aren't silently made opaque.
+pub fn candidates(lossless_only: bool, transparent: bool) -> Vec<&'static str> {
+    CANDIDATES
+        .iter()
+        .filter(|candidate| candidate.lossless || !lossless_only)
+        .filter(|candidate| candidate.alpha || !transparent)
+        .map(|candidate| candidate.identifier)
+        .collect()
+}
+
+/// Whether the image has an alpha channel, with at least one pixel which isn't fully opaque.
+pub fn has_transparency(image: &DynamicImage) -> bool {
+    match image {
+        DynamicImage::ImageLumaA8(buffer) => buffer.pixels().any(|pixel| pixel[1] < 255),
+        DynamicImage::ImageRgba8(buffer) => buffer.pixels().any(|pixel| pixel[3] < 255),
+        DynamicImage::ImageBgra8(buffer) => buffer.pixels().any(|pixel| pixel[3] < 255),
+        _ => false,
+    }
+}
+
+/// How the choice of the format is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChoiceReport {
+    Text,
+    Json,
+}
+
+impl Default for ChoiceReport {
+    fn default() -> Self {
+        ChoiceReport::Text
+    }
+}
+
+impl ChoiceReport {
+    pub fn try_from_str(val: &str) -> Result<ChoiceReport, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "text" => Ok(ChoiceReport::Text),
+            "json" => Ok(ChoiceReport::Json),
+            fail => Err(format!("No such report format: {}", fail).into()),
+        }
+    }
+}
+
+/// The format in which the image was written, chosen from the candidate formats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    /// The identifier of the chosen format.
+    pub format: String,
+    pub bytes: u64,
+    /// The path to which the image was written, or none if it was written to the stdout.
+    pub path: Option<PathBuf>,
+    /// The size of the image in each candidate format, or the reason why it couldn't be encoded
+    /// in a format.
+    pub attempts: Vec<(String, Result<u64, String>)>,
+}
+
+impl Choice {
+    pub fn report(&self, report: ChoiceReport) -> String {
+        match report {
+            ChoiceReport::Text => self.text(),
+            ChoiceReport::Json => self.json(),
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut text = format!("Chose {} ({} bytes)", self.format, self.bytes);
+        match &self.path {
+            Some(path) => text.push_str(&format!(", written to '{}'.\n", path.display())),
+            None => text.push_str(".\n"),
+        }
+
+        let width = self
+            .attempts
+            .iter()
+            .map(|(format, _)| format.len())
+            .max()
+            .unwrap_or(0);
+
+        for (format, attempt) in &self.attempts {
+            match attempt {
+                Ok(bytes) => text.push_str(&format!(
+                    "  {:width$}  {} bytes\n",
+                    format,
+                    bytes,
+                    width = width
+                )),
+                Err(err) => text.push_str(&format!(
+                    "  {:width$}  failed: {}\n",
+                    format,
+                    err,
+                    width = width
+                )),
+            }
+        }
+
+        text
+    }
+
+    fn json(&self) -> String {
+        let path = match &self.path {
+            Some(path) => format!("\"{}\"", escape_json(&path.to_string_lossy())),
+            None => "null".to_string(),
+        };
+
+        let candidates = self
+            .attempts
+            .iter()
+            .map(|(format, attempt)| match attempt {
+                Ok(bytes) => format!(
+                    "\t\t{{\"format\": \"{}\", \"bytes\": {}}}",
+                    escape_json(format),
+                    bytes
+                ),
+                Err(err) => format!(
+                    "\t\t{{\"format\": \"{}\", \"error\": \"{}\"}}",
+                    escape_json(format),
+                    escape_json(err)
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        format!(
+            "{{\n\t\"format\": \"{}\",\n\t\"bytes\": {},\n\t\"path\": {},\n\t\"candidates\": [\n{}\n\t]\n}}\n",
+            escape_json(&self.format),
+            self.bytes,
+            path,
+            candidates
+        )
+    }
+}
+
+/// The output path with the extension of the chosen format, unless its extension already
+/// identifies that format, such as `jpeg` for `jpg`.
+pub fn output_path(path: &Path, identifier: &str) -> PathBuf {
+    let extension_format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(find_format);
+
+    match (extension_format, find_format(identifier)) {
+        (Some(current), Some(chosen)) if current == chosen => path.to_path_buf(),
+        _ => path.with_extension(identifier),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgba, RgbaImage};
+
+    fn choice(path: Option<&str>) -> Choice {
+        Choice {
+            format: "png".to_string(),
+            bytes: 120,
+            path: path.map(PathBuf::from),
+            attempts: vec![
+                ("png".to_string(), Ok(120)),
+                ("tiff".to_string(), Err("too \"large\"".to_string())),
+            ],
+        }
+    }
+
+    #[test]
+    fn lossless_candidates() {
+        assert_eq!(vec!["png", "tiff", "tga", "bmp"], candidates(true, false));
+        assert_eq!(CANDIDATES.len(), candidates(false, false).len());
+    }
+
+    #[test]
+    fn transparent_candidates_store_alpha() {
+        assert_eq!(vec!["png", "tiff", "tga", "bmp"], candidates(false, true));
+        assert_eq!(vec!["png", "tiff", "tga", "bmp"], candidates(true, true));
+    }
+
+    #[test]
+    fn transparency() {
+        let mut image = RgbaImage::from_pixel(2, 2, Rgba([10, 20, 30, 255]));
+        assert!(!has_transparency(&DynamicImage::ImageRgba8(image.clone())));
+
+        image.put_pixel(1, 1, Rgba([10, 20, 30, 254]));
+        assert!(has_transparency(&DynamicImage::ImageRgba8(image)));
+
+        assert!(!has_transparency(&DynamicImage::new_rgb8(2, 2)));
+        assert!(has_transparency(&DynamicImage::new_luma_a8(2, 2)));
+    }
+
+    #[test]
+    fn corrected_output_path() {
+        let path = Path::new("out/image.jpeg");
+
+        assert_eq!(PathBuf::from("out/image.jpeg"), output_path(path, "jpg"));
+        assert_eq!(PathBuf::from("out/image.png"), output_path(path, "png"));
+        assert_eq!(
+            PathBuf::from("image.png"),
+            output_path(Path::new("image"), "png")
+        );
+        assert_eq!(
+            PathBuf::from("image.TIF"),
+            output_path(Path::new("image.TIF"), "tiff")
+        );
+    }
+
+    #[test]
+    fn text_report() {
+        let report = choice(Some("image.png")).report(ChoiceReport::Text);
+
+        assert_eq!(
+            "Chose png (120 bytes), written to 'image.png'.\n  png   120 bytes\n  tiff  failed: too \"large\"\n",
+            report
+        );
+    }
+
+    #[test]
+    fn json_report() {
+        let report = choice(None).report(ChoiceReport::Json);
+
+        assert!(report.contains("\"format\": \"png\",\n\t\"bytes\": 120,\n\t\"path\": null,"));
+        assert!(report.contains("{\"format\": \"tiff\", \"error\": \"too \\\"large\\\"\"}"));
+    }
+}
@@ -3,7 +3,10 @@ use std::path::Path;
 
 use sic_core::image;
 
+use crate::format::OutputFormat;
 use crate::save::ExportMethod;
+use crate::size_limit::SizeLimit;
+use crate::text::TextEncoding;
 
 #[derive(Clone, Copy, Debug)]
 pub enum AutomaticColorTypeAdjustment {
@@ -21,22 +24,52 @@ impl Default for AutomaticColorTypeAdjustment {
 /// Use the ConversionWriter to convert and write image buffers to an output.
 pub struct ConversionWriter<'a> {
     image: &'a image::DynamicImage,
+    text_encoding: Option<TextEncoding>,
+    size_limit: Option<(SizeLimit, image::FilterType)>,
 }
 
 impl<'a> ConversionWriter<'a> {
     pub fn new(image: &image::DynamicImage) -> ConversionWriter {
-        ConversionWriter { image }
+        ConversionWriter {
+            image,
+            text_encoding: None,
+            size_limit: None,
+        }
+    }
+
+    /// Write the encoded image as text instead of as bytes.
+    pub fn with_text_encoding(mut self, text_encoding: Option<TextEncoding>) -> Self {
+        self.text_encoding = text_encoding;
+        self
+    }
+
+    /// Limit the size of the written image, by lowering the quality of lossy formats, or by
+    /// scaling the image down with the given filter if allowed.
+    pub fn with_size_limit(mut self, size_limit: Option<(SizeLimit, image::FilterType)>) -> Self {
+        self.size_limit = size_limit;
+        self
     }
 
     pub fn write<P: AsRef<Path>>(
         &self,
         export: ExportMethod<P>,
-        output_format: image::ImageOutputFormat,
+        output_format: OutputFormat,
         color_type_adjustment: AutomaticColorTypeAdjustment,
     ) -> Result<(), String> {
+        let bytes = self.convert(&output_format, color_type_adjustment)?;
+
+        ConversionWriter::write_bytes(&bytes, export)
+    }
+
+    /// Converts the image to the output format, as it would be written, without writing it.
+    pub fn convert(
+        &self,
+        output_format: &OutputFormat,
+        color_type_adjustment: AutomaticColorTypeAdjustment,
+    ) -> Result<Vec<u8>, String> {
         let color_processing = &ConversionWriter::pre_process_color_type(
             &self.image,
-            &output_format,
+            output_format,
             color_type_adjustment,
         );
 
@@ -45,15 +78,41 @@ impl<'a> ConversionWriter<'a> {
             None => &self.image,
         };
 
+        match self.size_limit {
+            Some((limit, filter)) => {
+                limit.fit(&export_buffer, output_format, filter, |image, format| {
+                    self.encode(image, format)
+                })
+            }
+            None => self.encode(&export_buffer, output_format),
+        }
+    }
+
+    /// Writes converted bytes to the output.
+    pub fn write_bytes<P: AsRef<Path>>(
+        bytes: &[u8],
+        export: ExportMethod<P>,
+    ) -> Result<(), String> {
         match export {
             // Some() => write to file
-            ExportMethod::File(v) => {
-                ConversionWriter::save_to_file(&export_buffer, output_format, v)
-            }
+            ExportMethod::File(v) => ConversionWriter::save_to_file(bytes, v),
             // None => write to stdout
-            ExportMethod::StdoutBytes => {
-                ConversionWriter::export_to_stdout(&export_buffer, output_format)
-            }
+            ExportMethod::StdoutBytes => ConversionWriter::export_to_stdout(bytes),
+        }
+    }
+
+    /// Encodes the image in the output format, and as text if a text encoding was set.
+    fn encode(
+        &self,
+        buffer: &image::DynamicImage,
+        format: &OutputFormat,
+    ) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        format.encode(buffer, &mut bytes)?;
+
+        match self.text_encoding {
+            Some(encoding) => Ok(encoding.encode(&bytes, format.media_type()).into_bytes()),
+            None => Ok(bytes),
         }
     }
 
@@ -64,7 +123,7 @@ impl<'a> ConversionWriter<'a> {
     /// If no pre-processing of the color type is required will return None.
     fn pre_process_color_type(
         image: &image::DynamicImage,
-        output_format: &image::ImageOutputFormat,
+        output_format: &OutputFormat,
         color_type_adjustment: AutomaticColorTypeAdjustment,
     ) -> Option<image::DynamicImage> {
         // A remaining open question: does a user expect for an image to be able to convert to a format even if the color type is not supported?
@@ -76,45 +135,30 @@ impl<'a> ConversionWriter<'a> {
 
         match color_type_adjustment {
             AutomaticColorTypeAdjustment::Enabled => match output_format {
-                image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Bitmap(_)) => {
-                    Some(image.grayscale())
-                }
-                image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Graymap(_)) => {
-                    Some(image.grayscale())
-                }
-                image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Pixmap(_)) => {
-                    Some(image::DynamicImage::ImageRgb8(image.to_rgb()))
-                }
+                OutputFormat::Image(image::ImageOutputFormat::PNM(
+                    image::pnm::PNMSubtype::Bitmap(_),
+                )) => Some(image.grayscale()),
+                OutputFormat::Image(image::ImageOutputFormat::PNM(
+                    image::pnm::PNMSubtype::Graymap(_),
+                )) => Some(image.grayscale()),
+                OutputFormat::Image(image::ImageOutputFormat::PNM(
+                    image::pnm::PNMSubtype::Pixmap(_),
+                )) => Some(image::DynamicImage::ImageRgb8(image.to_rgb())),
                 _ => None,
             },
             AutomaticColorTypeAdjustment::Disabled => None,
         }
     }
 
-    fn save_to_file<P: AsRef<Path>>(
-        buffer: &image::DynamicImage,
-        format: image::ImageOutputFormat,
-        path: P,
-    ) -> Result<(), String> {
+    fn save_to_file<P: AsRef<Path>>(bytes: &[u8], path: P) -> Result<(), String> {
         let mut out = std::fs::File::create(path).map_err(|err| err.to_string())?;
 
-        buffer
-            .write_to(&mut out, format)
-            .map_err(|err| err.to_string())
+        out.write_all(bytes).map_err(|err| err.to_string())
     }
 
-    fn export_to_stdout(
-        buffer: &image::DynamicImage,
-        format: image::ImageOutputFormat,
-    ) -> Result<(), String> {
-        let mut write_buffer = Vec::new();
-
-        buffer
-            .write_to(&mut write_buffer, format)
-            .map_err(|err| err.to_string())?;
-
+    fn export_to_stdout(bytes: &[u8]) -> Result<(), String> {
         io::stdout()
-            .write(&write_buffer)
+            .write(bytes)
             .map(|_| ())
             .map_err(|err| err.to_string())
     }
@@ -144,7 +188,7 @@ mod tests {
         conversion_processor
             .write(
                 ExportMethod::File(&output_path),
-                example_output_format,
+                example_output_format.into(),
                 AutomaticColorTypeAdjustment::Enabled,
             )
             .expect("Unable to save file to the test computer.");
@@ -165,7 +209,7 @@ mod tests {
         conversion_processor
             .write(
                 ExportMethod::File(&output_path),
-                example_output_format,
+                example_output_format.into(),
                 AutomaticColorTypeAdjustment::Enabled,
             )
             .expect("Unable to save file to the test computer.");
@@ -189,7 +233,7 @@ mod tests {
         conversion_processor
             .write(
                 ExportMethod::File(&output_path),
-                example_output_format,
+                example_output_format.into(),
                 AutomaticColorTypeAdjustment::Enabled,
             )
             .expect("Unable to save file to the test computer.");
@@ -261,7 +305,7 @@ mod tests {
         let method = ExportMethod::File(&output_path);
 
         conversion_processor
-            .write(method, format, AutomaticColorTypeAdjustment::Enabled)
+            .write(method, format.into(), AutomaticColorTypeAdjustment::Enabled)
             .expect("Unable to save file to the test computer.");
 
         let mut file = std::fs::File::open(setup_output_path(our_output))
@@ -0,0 +1,41 @@
+//! A farbfeld encoder. Farbfeld stores an image as 16 bit big endian RGBA samples, after a header
+//! with the magic value and the width and height of the image.
+
+use std::io::Write;
+
+use sic_core::image::{DynamicImage, GenericImageView};
+
+pub fn encode<W: Write>(image: &DynamicImage, writer: &mut W) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+    let pixels = image.to_rgba().into_raw();
+
+    let mut file = Vec::with_capacity(16 + pixels.len() * 2);
+    file.extend_from_slice(b"farbfeld");
+    file.extend_from_slice(&width.to_be_bytes());
+    file.extend_from_slice(&height.to_be_bytes());
+
+    // 8 bit samples are scaled to 16 bits, so 0xff becomes 0xffff
+    for sample in pixels {
+        file.extend_from_slice(&[sample, sample]);
+    }
+
+    writer.write_all(&file).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgba, RgbaImage};
+
+    #[test]
+    fn header_and_samples() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 1, Rgba([255, 0, 18, 52])));
+        let mut bytes = Vec::new();
+        encode(&image, &mut bytes).unwrap();
+
+        assert_eq!(b"farbfeld", &bytes[..8]);
+        assert_eq!(&[0, 0, 0, 2, 0, 0, 0, 1], &bytes[8..16]);
+        assert_eq!(&[0xff, 0xff, 0, 0, 0x12, 0x12, 0x34, 0x34], &bytes[16..24]);
+        assert_eq!(16 + 2 * 8, bytes.len());
+    }
+}
@@ -0,0 +1,286 @@
+//! An ICO encoder which embeds the image at several sizes, so the size which fits best can be
+//! shown wherever the icon is used. Each size is stored as a PNG image, which is supported by
+//! Windows Vista and later, and by all browsers.
+
+use std::error::Error;
+use std::io::Write;
+use std::mem::discriminant;
+
+use sic_core::image::{imageops, DynamicImage, FilterType, GenericImageView, RgbaImage};
+
+use crate::encoders::png::{self, PngCompression, PngFilter, PngSettings};
+
+/// The largest width and height of an image in an ICO file.
+pub const MAX_ICON_SIZE: u32 = 256;
+
+const HEADER_SIZE: u32 = 6;
+const DIRECTORY_ENTRY_SIZE: u32 = 16;
+/// Icons are small, so they are compressed as well as possible.
+const ENTRY_SETTINGS: PngSettings = PngSettings {
+    compression: PngCompression::Best,
+    filter: PngFilter::Adaptive,
+    sixteen_bit: false,
+    palette: false,
+};
+
+#[derive(Debug, Clone)]
+pub struct IcoSettings {
+    /// The width and height of each embedded image.
+    pub sizes: Vec<u32>,
+    /// The filter used to scale the image to each size.
+    pub filter: FilterType,
+    /// Whether the image is scaled up to sizes which are larger than the image. Otherwise, these
+    /// sizes are skipped; if no sizes remain, the image is embedded at its own (largest) size.
+    pub upscale: bool,
+}
+
+impl Default for IcoSettings {
+    fn default() -> Self {
+        IcoSettings {
+            sizes: vec![16, 32, 48, 256],
+            filter: FilterType::Gaussian,
+            upscale: false,
+        }
+    }
+}
+
+// FilterType doesn't implement PartialEq, but its variants have no fields.
+impl PartialEq for IcoSettings {
+    fn eq(&self, other: &Self) -> bool {
+        self.sizes == other.sizes
+            && discriminant(&self.filter) == discriminant(&other.filter)
+            && self.upscale == other.upscale
+    }
+}
+
+impl Eq for IcoSettings {}
+
+impl IcoSettings {
+    /// Parses the width and height of an embedded image, which is between 1 and 256 pixels.
+    pub fn parse_size(val: &str) -> Result<u32, Box<dyn Error>> {
+        match val.parse::<u32>() {
+            Ok(size) if (1..=MAX_ICON_SIZE).contains(&size) => Ok(size),
+            _ => Err(format!(
+                "The size of an icon should be between 1 and {} pixels (inclusive), but was '{}'.",
+                MAX_ICON_SIZE, val
+            )
+            .into()),
+        }
+    }
+}
+
+/// Scales the image to fit within a square of the given size, keeping its aspect ratio. The
+/// scaled image is centered, and the rest of the square is transparent.
+pub fn square_icon(image: &DynamicImage, size: u32, filter: FilterType) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let longest = width.max(height);
+    let scale = |side: u32| {
+        ((f64::from(side) * f64::from(size) / f64::from(longest)).round() as u32)
+            .max(1)
+            .min(size)
+    };
+    let (scaled_width, scaled_height) = (scale(width), scale(height));
+
+    let scaled = if (scaled_width, scaled_height) == (width, height) {
+        image.to_rgba()
+    } else {
+        image
+            .resize_exact(scaled_width, scaled_height, filter)
+            .to_rgba()
+    };
+
+    if (scaled_width, scaled_height) == (size, size) {
+        return scaled;
+    }
+
+    let mut icon = RgbaImage::new(size, size);
+    imageops::replace(
+        &mut icon,
+        &scaled,
+        (size - scaled_width) / 2,
+        (size - scaled_height) / 2,
+    );
+    icon
+}
+
+/// Encodes the image as an ICO file, with the image scaled to each of the sizes.
+pub fn encode<W: Write>(
+    image: &DynamicImage,
+    settings: &IcoSettings,
+    writer: &mut W,
+) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+
+    if width == 0 || height == 0 {
+        return Err("An ICO image should have at least a single pixel.".to_string());
+    }
+
+    let mut sizes = settings.sizes.clone();
+    sizes.sort();
+    sizes.dedup();
+
+    if sizes.is_empty() {
+        return Err("An ICO image should contain the image at one or more sizes.".to_string());
+    }
+
+    if !settings.upscale {
+        let longest = width.max(height);
+        sizes.retain(|&size| size <= longest);
+
+        if sizes.is_empty() {
+            sizes.push(longest);
+        }
+    }
+
+    let entries = sizes
+        .into_iter()
+        .map(|size| {
+            if !(1..=MAX_ICON_SIZE).contains(&size) {
+                return Err(format!(
+                    "The size of an icon should be between 1 and {} pixels (inclusive), but was {}.",
+                    MAX_ICON_SIZE, size
+                ));
+            }
+
+            let icon = square_icon(image, size, settings.filter);
+            let mut data = Vec::new();
+            png::encode(&DynamicImage::ImageRgba8(icon), ENTRY_SETTINGS, &mut data)?;
+
+            Ok((size, data))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut bytes = Vec::new();
+
+    // header: reserved, type (1 for icons), number of images
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+
+    let mut offset = HEADER_SIZE + DIRECTORY_ENTRY_SIZE * entries.len() as u32;
+
+    for (size, data) in &entries {
+        // a width and height of 256 pixels is stored as 0
+        let side = (*size % MAX_ICON_SIZE) as u8;
+
+        bytes.push(side);
+        bytes.push(side);
+        // number of colors in the palette (none), reserved
+        bytes.push(0);
+        bytes.push(0);
+        // color planes, bits per pixel
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&32u16.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&offset.to_le_bytes());
+
+        offset += data.len() as u32;
+    }
+
+    for (_, data) in &entries {
+        bytes.extend_from_slice(data);
+    }
+
+    writer.write_all(&bytes).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgba, RgbaImage};
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([(x * 4) as u8, (y * 4) as u8, 128, 255])
+        }))
+    }
+
+    fn read_u16(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn read_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(16, IcoSettings::parse_size("16").unwrap());
+        assert_eq!(256, IcoSettings::parse_size("256").unwrap());
+        assert!(IcoSettings::parse_size("0").is_err());
+        assert!(IcoSettings::parse_size("257").is_err());
+        assert!(IcoSettings::parse_size("large").is_err());
+    }
+
+    #[test]
+    fn directory_of_each_size() {
+        let settings = IcoSettings {
+            sizes: vec![256, 16, 32, 16],
+            filter: FilterType::Triangle,
+            upscale: true,
+        };
+
+        let mut bytes = Vec::new();
+        encode(&gradient(40, 40), &settings, &mut bytes).unwrap();
+
+        assert_eq!(1, read_u16(&bytes, 2));
+        assert_eq!(3, read_u16(&bytes, 4));
+
+        for (index, &size) in [16u32, 32, 256].iter().enumerate() {
+            let entry = 6 + index * 16;
+            assert_eq!((size % 256) as u8, bytes[entry]);
+            assert_eq!((size % 256) as u8, bytes[entry + 1]);
+
+            let length = read_u32(&bytes, entry + 8) as usize;
+            let offset = read_u32(&bytes, entry + 12) as usize;
+            let icon = sic_core::image::load_from_memory(&bytes[offset..offset + length]).unwrap();
+
+            assert_eq!((size, size), icon.dimensions());
+        }
+    }
+
+    #[test]
+    fn readable_by_image() {
+        let mut bytes = Vec::new();
+        encode(&gradient(64, 64), &IcoSettings::default(), &mut bytes).unwrap();
+
+        // the image isn't scaled up to 256 pixels
+        assert_eq!(3, read_u16(&bytes, 4));
+
+        let icon = sic_core::image::load_from_memory(&bytes).unwrap();
+        assert_eq!((48, 48), icon.dimensions());
+    }
+
+    #[test]
+    fn small_image_at_own_size() {
+        let mut bytes = Vec::new();
+        encode(&gradient(8, 6), &IcoSettings::default(), &mut bytes).unwrap();
+
+        assert_eq!(1, read_u16(&bytes, 4));
+
+        let icon = sic_core::image::load_from_memory(&bytes).unwrap();
+        assert_eq!((8, 8), icon.dimensions());
+    }
+
+    #[test]
+    fn square_icon_centers_image() {
+        let icon = square_icon(&gradient(40, 20), 16, FilterType::Nearest);
+
+        assert_eq!((16, 16), icon.dimensions());
+        assert_eq!(0, icon.get_pixel(8, 0)[3]);
+        assert_eq!(255, icon.get_pixel(8, 8)[3]);
+        assert_eq!(0, icon.get_pixel(8, 15)[3]);
+    }
+
+    #[test]
+    fn err_size_too_large() {
+        let settings = IcoSettings {
+            sizes: vec![512],
+            upscale: true,
+            ..IcoSettings::default()
+        };
+
+        let mut bytes = Vec::new();
+        assert!(encode(&gradient(8, 8), &settings, &mut bytes).is_err());
+    }
+}
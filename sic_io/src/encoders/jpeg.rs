@@ -0,0 +1,1084 @@
+//! A JPEG (JFIF) encoder for baseline and progressive images, with a choice of chroma subsampling,
+//! optimized Huffman tables and restart intervals.
+//!
+//! Progressive images are encoded by spectral selection only: the DC coefficients of all
+//! components are sent first, followed by the low and high frequency AC coefficients of each
+//! component.
+
+use std::error::Error;
+use std::io::Write;
+
+use sic_core::image::{DynamicImage, GenericImageView};
+
+/// The resolution of the chroma (color) components, relative to the luma (brightness) component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChromaSubsampling {
+    /// Chroma at full resolution.
+    Ratio444,
+    /// Chroma at half the horizontal resolution.
+    Ratio422,
+    /// Chroma at half the horizontal and half the vertical resolution.
+    Ratio420,
+}
+
+impl Default for ChromaSubsampling {
+    fn default() -> Self {
+        ChromaSubsampling::Ratio444
+    }
+}
+
+impl ChromaSubsampling {
+    pub fn try_from_str(val: &str) -> Result<ChromaSubsampling, Box<dyn Error>> {
+        match val {
+            "4:4:4" | "444" => Ok(ChromaSubsampling::Ratio444),
+            "4:2:2" | "422" => Ok(ChromaSubsampling::Ratio422),
+            "4:2:0" | "420" => Ok(ChromaSubsampling::Ratio420),
+            fail => Err(format!("No such chroma subsampling: {}", fail).into()),
+        }
+    }
+
+    /// The horizontal and vertical sampling factors of the luma component.
+    fn luma_factors(self) -> (usize, usize) {
+        match self {
+            ChromaSubsampling::Ratio444 => (1, 1),
+            ChromaSubsampling::Ratio422 => (2, 1),
+            ChromaSubsampling::Ratio420 => (2, 2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct JpegSettings {
+    /// Write a progressive image, which is shown at a low quality while it is loading.
+    pub progressive: bool,
+    pub subsampling: ChromaSubsampling,
+    /// Use Huffman tables fitted to the image, instead of the standard tables. The image gets
+    /// smaller, but takes longer to encode.
+    pub optimize_huffman_tables: bool,
+    /// The number of MCUs (blocks of pixels) between restart markers, which allow a decoder to
+    /// recover from corrupted data. No restart markers are written if zero.
+    pub restart_interval: u16,
+    /// Write a grayscale image. Grayscale images are always written as such.
+    pub grayscale: bool,
+}
+
+// markers
+const SOF0: u8 = 0xc0;
+const SOF2: u8 = 0xc2;
+const DHT: u8 = 0xc4;
+const RST0: u8 = 0xd0;
+const SOI: u8 = 0xd8;
+const EOI: u8 = 0xd9;
+const SOS: u8 = 0xda;
+const DQT: u8 = 0xdb;
+const DRI: u8 = 0xdd;
+const APP0: u8 = 0xe0;
+
+/// The natural (row by row) index of each coefficient in zigzag order.
+#[rustfmt::skip]
+const ZIGZAG: [usize; 64] = [
+     0,  1,  8, 16,  9,  2,  3, 10,
+    17, 24, 32, 25, 18, 11,  4,  5,
+    12, 19, 26, 33, 40, 48, 41, 34,
+    27, 20, 13,  6,  7, 14, 21, 28,
+    35, 42, 49, 56, 57, 50, 43, 36,
+    29, 22, 15, 23, 30, 37, 44, 51,
+    58, 59, 52, 45, 38, 31, 39, 46,
+    53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+// The quantization tables and Huffman tables of annex K of the specification, of which the
+// quantization tables are scaled by the quality.
+
+#[rustfmt::skip]
+const LUMA_QUANTIZATION: [u16; 64] = [
+    16, 11, 10, 16,  24,  40,  51,  61,
+    12, 12, 14, 19,  26,  58,  60,  55,
+    14, 13, 16, 24,  40,  57,  69,  56,
+    14, 17, 22, 29,  51,  87,  80,  62,
+    18, 22, 37, 56,  68, 109, 103,  77,
+    24, 35, 55, 64,  81, 104, 113,  92,
+    49, 64, 78, 87, 103, 121, 120, 101,
+    72, 92, 95, 98, 112, 100, 103,  99,
+];
+
+#[rustfmt::skip]
+const CHROMA_QUANTIZATION: [u16; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99,
+    18, 21, 26, 66, 99, 99, 99, 99,
+    24, 26, 56, 99, 99, 99, 99, 99,
+    47, 66, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+const LUMA_DC_LENGTHS: [u8; 16] = [0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+const CHROMA_DC_LENGTHS: [u8; 16] = [0, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
+const DC_VALUES: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+const LUMA_AC_LENGTHS: [u8; 16] = [0, 2, 1, 3, 3, 2, 4, 3, 5, 5, 4, 4, 0, 0, 1, 0x7d];
+#[rustfmt::skip]
+const LUMA_AC_VALUES: [u8; 162] = [
+    0x01, 0x02, 0x03, 0x00, 0x04, 0x11, 0x05, 0x12, 0x21, 0x31, 0x41, 0x06, 0x13, 0x51, 0x61, 0x07,
+    0x22, 0x71, 0x14, 0x32, 0x81, 0x91, 0xa1, 0x08, 0x23, 0x42, 0xb1, 0xc1, 0x15, 0x52, 0xd1, 0xf0,
+    0x24, 0x33, 0x62, 0x72, 0x82, 0x09, 0x0a, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x25, 0x26, 0x27, 0x28,
+    0x29, 0x2a, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49,
+    0x4a, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69,
+    0x6a, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89,
+    0x8a, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9a, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7,
+    0xa8, 0xa9, 0xaa, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6, 0xb7, 0xb8, 0xb9, 0xba, 0xc2, 0xc3, 0xc4, 0xc5,
+    0xc6, 0xc7, 0xc8, 0xc9, 0xca, 0xd2, 0xd3, 0xd4, 0xd5, 0xd6, 0xd7, 0xd8, 0xd9, 0xda, 0xe1, 0xe2,
+    0xe3, 0xe4, 0xe5, 0xe6, 0xe7, 0xe8, 0xe9, 0xea, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8,
+    0xf9, 0xfa,
+];
+
+const CHROMA_AC_LENGTHS: [u8; 16] = [0, 2, 1, 2, 4, 4, 3, 4, 7, 5, 4, 4, 0, 1, 2, 0x77];
+#[rustfmt::skip]
+const CHROMA_AC_VALUES: [u8; 162] = [
+    0x00, 0x01, 0x02, 0x03, 0x11, 0x04, 0x05, 0x21, 0x31, 0x06, 0x12, 0x41, 0x51, 0x07, 0x61, 0x71,
+    0x13, 0x22, 0x32, 0x81, 0x08, 0x14, 0x42, 0x91, 0xa1, 0xb1, 0xc1, 0x09, 0x23, 0x33, 0x52, 0xf0,
+    0x15, 0x62, 0x72, 0xd1, 0x0a, 0x16, 0x24, 0x34, 0xe1, 0x25, 0xf1, 0x17, 0x18, 0x19, 0x1a, 0x26,
+    0x27, 0x28, 0x29, 0x2a, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48,
+    0x49, 0x4a, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68,
+    0x69, 0x6a, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87,
+    0x88, 0x89, 0x8a, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9a, 0xa2, 0xa3, 0xa4, 0xa5,
+    0xa6, 0xa7, 0xa8, 0xa9, 0xaa, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6, 0xb7, 0xb8, 0xb9, 0xba, 0xc2, 0xc3,
+    0xc4, 0xc5, 0xc6, 0xc7, 0xc8, 0xc9, 0xca, 0xd2, 0xd3, 0xd4, 0xd5, 0xd6, 0xd7, 0xd8, 0xd9, 0xda,
+    0xe2, 0xe3, 0xe4, 0xe5, 0xe6, 0xe7, 0xe8, 0xe9, 0xea, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8,
+    0xf9, 0xfa,
+];
+
+/// The longest run of blocks which can be ended by a single end-of-band symbol.
+const MAX_END_OF_BAND_RUN: u16 = 0x7fff;
+
+/// A Huffman table, given by the number of codes of each length (of 1 up to 16 bits), and the
+/// symbols ordered by the length of their code.
+#[derive(Debug, Clone)]
+struct HuffmanTable {
+    lengths: [u8; 16],
+    symbols: Vec<u8>,
+}
+
+impl HuffmanTable {
+    fn new(lengths: &[u8; 16], symbols: &[u8]) -> Self {
+        HuffmanTable {
+            lengths: *lengths,
+            symbols: symbols.to_vec(),
+        }
+    }
+
+    /// The standard tables: the luma DC, luma AC, chroma DC and chroma AC table.
+    fn standard() -> [HuffmanTable; 4] {
+        [
+            HuffmanTable::new(&LUMA_DC_LENGTHS, &DC_VALUES),
+            HuffmanTable::new(&LUMA_AC_LENGTHS, &LUMA_AC_VALUES),
+            HuffmanTable::new(&CHROMA_DC_LENGTHS, &DC_VALUES),
+            HuffmanTable::new(&CHROMA_AC_LENGTHS, &CHROMA_AC_VALUES),
+        ]
+    }
+
+    /// Builds the table which encodes the symbols with the given frequencies in the fewest bits, with
+    /// codes of at most 16 bits, as described in section K.2 of the specification.
+    fn optimal(frequencies: &[u64; 256]) -> Self {
+        // a reserved symbol ensures that no code consists of only 1-bits
+        let mut frequencies = frequencies
+            .iter()
+            .copied()
+            .chain(Some(1))
+            .collect::<Vec<_>>();
+        let mut code_sizes = [0usize; 257];
+        let mut others = [None; 257];
+
+        // merges the two least frequent trees, until a single tree remains
+        loop {
+            let least = |exclude: Option<usize>| {
+                (0..257)
+                    .filter(|&i| frequencies[i] > 0 && Some(i) != exclude)
+                    .min_by_key(|&i| (frequencies[i], std::cmp::Reverse(i)))
+            };
+
+            let (mut first, mut second) = match least(None) {
+                Some(first) => match least(Some(first)) {
+                    Some(second) => (first, second),
+                    None => break,
+                },
+                None => break,
+            };
+
+            frequencies[first] += frequencies[second];
+            frequencies[second] = 0;
+
+            code_sizes[first] += 1;
+            while let Some(next) = others[first] {
+                first = next;
+                code_sizes[first] += 1;
+            }
+            others[first] = Some(second);
+
+            code_sizes[second] += 1;
+            while let Some(next) = others[second] {
+                second = next;
+                code_sizes[second] += 1;
+            }
+        }
+
+        let mut counts = [0u8; 33];
+        for &size in code_sizes.iter().filter(|&&size| size > 0) {
+            counts[size.min(32)] += 1;
+        }
+
+        // codes longer than 16 bits are shortened, by lengthening shorter codes
+        for length in (17..=32).rev() {
+            while counts[length] > 0 {
+                let mut shorter = length - 2;
+                while counts[shorter] == 0 {
+                    shorter -= 1;
+                }
+
+                counts[length] -= 2;
+                counts[length - 1] += 1;
+                counts[shorter + 1] += 2;
+                counts[shorter] -= 1;
+            }
+        }
+
+        // the reserved symbol has (one of) the longest code(s)
+        if let Some(longest) = (1..=16).rev().find(|&length| counts[length] > 0) {
+            counts[longest] -= 1;
+        }
+
+        let mut lengths = [0u8; 16];
+        lengths.copy_from_slice(&counts[1..=16]);
+
+        let mut symbols = (0..256)
+            .filter(|&symbol| code_sizes[symbol] > 0)
+            .collect::<Vec<_>>();
+        symbols.sort_by_key(|&symbol| code_sizes[symbol]);
+
+        HuffmanTable {
+            lengths,
+            symbols: symbols.into_iter().map(|symbol| symbol as u8).collect(),
+        }
+    }
+
+    /// The (code, length of the code) of each symbol.
+    fn codes(&self) -> Vec<(u16, u8)> {
+        let mut codes = vec![(0, 0); 256];
+        let mut code = 0u16;
+        let mut symbols = self.symbols.iter();
+
+        for (length, &count) in self.lengths.iter().enumerate() {
+            for _ in 0..count {
+                if let Some(&symbol) = symbols.next() {
+                    codes[usize::from(symbol)] = (code, length as u8 + 1);
+                }
+                code += 1;
+            }
+            code <<= 1;
+        }
+
+        codes
+    }
+}
+
+/// A color component of the image, of which the samples are transformed to blocks of quantized
+/// coefficients, in zigzag order.
+struct Component {
+    id: u8,
+    horizontal_factor: usize,
+    vertical_factor: usize,
+    /// 0 for luma, 1 for chroma; selects the quantization table and the Huffman tables.
+    table: usize,
+    /// The size in blocks of the component, padded to a whole number of MCUs.
+    blocks_wide: usize,
+    /// The size in blocks of the part of the component which covers the image.
+    scan_blocks: (usize, usize),
+    blocks: Vec<[i16; 64]>,
+}
+
+impl Component {
+    fn dc_table(&self) -> usize {
+        2 * self.table
+    }
+
+    fn ac_table(&self) -> usize {
+        2 * self.table + 1
+    }
+}
+
+/// A scan writes the coefficients from `start` up to and including `end` of some components.
+struct Scan {
+    components: Vec<usize>,
+    start: usize,
+    end: usize,
+}
+
+/// Encodes the image as a JPEG file, at the given quality (from 1 up to and including 100). The
+/// alpha channel, if any, is dropped.
+pub fn encode<W: Write>(
+    image: &DynamicImage,
+    quality: u8,
+    settings: JpegSettings,
+    writer: &mut W,
+) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+    let max_side = u32::from(std::u16::MAX);
+
+    if width == 0 || height == 0 || width > max_side || height > max_side {
+        return Err(format!(
+            "The image ({}x{}) can't be stored as JPEG image, which supports images of at least 1x1 \
+             and at most {}x{} pixels.",
+            width,
+            height,
+            max_side,
+            max_side
+        ));
+    }
+
+    let grayscale =
+        settings.grayscale || image.as_luma8().is_some() || image.as_luma_alpha8().is_some();
+
+    let quantization = [
+        scale_quantization(&LUMA_QUANTIZATION, quality),
+        scale_quantization(&CHROMA_QUANTIZATION, quality),
+    ];
+
+    let components = if grayscale {
+        let luma: Vec<f32> = image.to_luma().iter().map(|&s| f32::from(s)).collect();
+
+        vec![component(1, &luma, (1, 1), (1, 1), 0, image, &quantization)]
+    } else {
+        let (luma, blue, red) = color_planes(image);
+        let factors = settings.subsampling.luma_factors();
+
+        vec![
+            component(1, &luma, factors, factors, 0, image, &quantization),
+            component(2, &blue, (1, 1), factors, 1, image, &quantization),
+            component(3, &red, (1, 1), factors, 1, image, &quantization),
+        ]
+    };
+
+    let scans = scans(&components, settings.progressive);
+
+    let mut file = Vec::new();
+    file.extend_from_slice(&[0xff, SOI]);
+
+    // JFIF 1.01, without units and thumbnail
+    write_segment(&mut file, APP0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+
+    for (id, table) in quantization
+        .iter()
+        .enumerate()
+        .take(if grayscale { 1 } else { 2 })
+    {
+        let mut segment = vec![id as u8];
+        segment.extend(ZIGZAG.iter().map(|&index| table[index] as u8));
+        write_segment(&mut file, DQT, &segment);
+    }
+
+    let mut frame = vec![8];
+    frame.extend_from_slice(&(height as u16).to_be_bytes());
+    frame.extend_from_slice(&(width as u16).to_be_bytes());
+    frame.push(components.len() as u8);
+    for component in &components {
+        frame.push(component.id);
+        frame.push(((component.horizontal_factor << 4) | component.vertical_factor) as u8);
+        frame.push(component.table as u8);
+    }
+    write_segment(
+        &mut file,
+        if settings.progressive { SOF2 } else { SOF0 },
+        &frame,
+    );
+
+    if settings.restart_interval > 0 {
+        write_segment(&mut file, DRI, &settings.restart_interval.to_be_bytes());
+    }
+
+    let standard_tables = HuffmanTable::standard();
+    let restart_interval = usize::from(settings.restart_interval);
+
+    for scan in &scans {
+        // runs of end-of-band symbols are only part of optimized tables
+        let end_of_band_runs = settings.optimize_huffman_tables;
+
+        let tables = if settings.optimize_huffman_tables {
+            let mut counter = SymbolCounter::new();
+            encode_scan(
+                &components,
+                scan,
+                restart_interval,
+                end_of_band_runs,
+                &mut counter,
+            );
+
+            let mut tables = standard_tables.clone();
+            for (table, frequencies) in tables.iter_mut().zip(counter.frequencies.iter()) {
+                if frequencies.iter().any(|&frequency| frequency > 0) {
+                    *table = HuffmanTable::optimal(frequencies);
+                }
+            }
+            tables
+        } else {
+            standard_tables.clone()
+        };
+
+        for index in used_tables(&components, scan) {
+            let table = &tables[index];
+            // the class (DC or AC) and the destination
+            let mut segment = vec![(((index % 2) << 4) | (index / 2)) as u8];
+            segment.extend_from_slice(&table.lengths);
+            segment.extend_from_slice(&table.symbols);
+            write_segment(&mut file, DHT, &segment);
+        }
+
+        let mut header = vec![scan.components.len() as u8];
+        for &index in &scan.components {
+            let component = &components[index];
+            header.push(component.id);
+            header.push(((component.table << 4) | component.table) as u8);
+        }
+        // no successive approximation
+        header.extend_from_slice(&[scan.start as u8, scan.end as u8, 0]);
+        write_segment(&mut file, SOS, &header);
+
+        let codes = tables.iter().map(HuffmanTable::codes).collect::<Vec<_>>();
+        let mut bits = BitWriter::new(&codes);
+        encode_scan(
+            &components,
+            scan,
+            restart_interval,
+            end_of_band_runs,
+            &mut bits,
+        );
+        file.extend_from_slice(&bits.finish());
+    }
+
+    file.extend_from_slice(&[0xff, EOI]);
+
+    writer.write_all(&file).map_err(|err| err.to_string())
+}
+
+/// Scales a quantization table by the quality, like the reference implementation of the IJG does.
+fn scale_quantization(table: &[u16; 64], quality: u8) -> [u16; 64] {
+    let quality = u32::from(quality.max(1).min(100));
+    let scale = if quality < 50 {
+        5000 / quality
+    } else {
+        200 - 2 * quality
+    };
+
+    let mut scaled = [0; 64];
+    for (scaled, &value) in scaled.iter_mut().zip(table.iter()) {
+        *scaled = ((u32::from(value) * scale + 50) / 100).max(1).min(255) as u16;
+    }
+    scaled
+}
+
+/// The luma, blue difference and red difference samples of each pixel, as defined by JFIF.
+fn color_planes(image: &DynamicImage) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+    let rgb = image.to_rgb();
+    let pixels = rgb.pixels().count();
+
+    let mut luma = Vec::with_capacity(pixels);
+    let mut blue = Vec::with_capacity(pixels);
+    let mut red = Vec::with_capacity(pixels);
+
+    for pixel in rgb.pixels() {
+        let [r, g, b] = [
+            f32::from(pixel.0[0]),
+            f32::from(pixel.0[1]),
+            f32::from(pixel.0[2]),
+        ];
+
+        luma.push(0.299 * r + 0.587 * g + 0.114 * b);
+        blue.push(-0.168_736 * r - 0.331_264 * g + 0.5 * b + 128.0);
+        red.push(0.5 * r - 0.418_688 * g - 0.081_312 * b + 128.0);
+    }
+
+    (luma, blue, red)
+}
+
+/// Samples the plane at the resolution of the component, and transforms it into blocks of
+/// quantized coefficients. Samples beyond the edges of the image repeat the samples at the edges.
+fn component(
+    id: u8,
+    plane: &[f32],
+    factors: (usize, usize),
+    max_factors: (usize, usize),
+    table: usize,
+    image: &DynamicImage,
+    quantization: &[[u16; 64]; 2],
+) -> Component {
+    let (width, height) = image.dimensions();
+    let (width, height) = (width as usize, height as usize);
+
+    let mcus_wide = div_ceil(width, 8 * max_factors.0);
+    let mcus_high = div_ceil(height, 8 * max_factors.1);
+    let blocks_wide = mcus_wide * factors.0;
+    let blocks_high = mcus_high * factors.1;
+
+    // the number of pixels of the image which are averaged into a sample of the component
+    let (step_x, step_y) = (max_factors.0 / factors.0, max_factors.1 / factors.1);
+
+    let sample = |x: usize, y: usize| {
+        let mut sum = 0.0;
+        for j in 0..step_y {
+            for i in 0..step_x {
+                let px = (x * step_x + i).min(width - 1);
+                let py = (y * step_y + j).min(height - 1);
+                sum += plane[py * width + px];
+            }
+        }
+        sum / (step_x * step_y) as f32
+    };
+
+    let cosines = cosine_table();
+    let mut blocks = Vec::with_capacity(blocks_wide * blocks_high);
+
+    for block_y in 0..blocks_high {
+        for block_x in 0..blocks_wide {
+            let mut samples = [0.0; 64];
+            for (index, value) in samples.iter_mut().enumerate() {
+                *value = sample(block_x * 8 + index % 8, block_y * 8 + index / 8) - 128.0;
+            }
+
+            let coefficients = forward_dct(&samples, &cosines);
+
+            let mut block = [0i16; 64];
+            for (zigzag, &natural) in ZIGZAG.iter().enumerate() {
+                let quantized = (coefficients[natural] / f32::from(quantization[table][natural]))
+                    .round()
+                    .max(-1023.0)
+                    .min(1023.0);
+                block[zigzag] = quantized as i16;
+            }
+            blocks.push(block);
+        }
+    }
+
+    let scan_width = div_ceil(width * factors.0, max_factors.0);
+    let scan_height = div_ceil(height * factors.1, max_factors.1);
+
+    Component {
+        id,
+        horizontal_factor: factors.0,
+        vertical_factor: factors.1,
+        table,
+        blocks_wide,
+        scan_blocks: (div_ceil(scan_width, 8), div_ceil(scan_height, 8)),
+        blocks,
+    }
+}
+
+/// Divides, rounding up.
+fn div_ceil(value: usize, divisor: usize) -> usize {
+    (value + divisor - 1) / divisor
+}
+
+/// `cos((2x + 1) * u * pi / 16)` for each sample `x` and frequency `u`.
+fn cosine_table() -> [[f32; 8]; 8] {
+    let mut table = [[0.0; 8]; 8];
+    for (x, row) in table.iter_mut().enumerate() {
+        for (u, value) in row.iter_mut().enumerate() {
+            *value = ((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0).cos();
+        }
+    }
+    table
+}
+
+/// The two dimensional discrete cosine transform of a block, computed by rows and then columns.
+fn forward_dct(samples: &[f32; 64], cosines: &[[f32; 8]; 8]) -> [f32; 64] {
+    let scale = |u: usize| {
+        if u == 0 {
+            std::f32::consts::FRAC_1_SQRT_2 / 2.0
+        } else {
+            0.5
+        }
+    };
+
+    let mut rows = [0.0; 64];
+    for y in 0..8 {
+        for u in 0..8 {
+            let sum: f32 = (0..8).map(|x| samples[y * 8 + x] * cosines[x][u]).sum();
+            rows[y * 8 + u] = sum * scale(u);
+        }
+    }
+
+    let mut coefficients = [0.0; 64];
+    for u in 0..8 {
+        for v in 0..8 {
+            let sum: f32 = (0..8).map(|y| rows[y * 8 + u] * cosines[y][v]).sum();
+            coefficients[v * 8 + u] = sum * scale(v);
+        }
+    }
+
+    coefficients
+}
+
+/// The scans of the image: a single scan of all coefficients for a baseline image. A progressive
+/// image starts with the DC coefficients, followed by the AC coefficients of the luma component
+/// (low frequencies first) and then those of the chroma components.
+fn scans(components: &[Component], progressive: bool) -> Vec<Scan> {
+    let all = (0..components.len()).collect::<Vec<_>>();
+
+    if !progressive {
+        return vec![Scan {
+            components: all,
+            start: 0,
+            end: 63,
+        }];
+    }
+
+    let mut scans = vec![Scan {
+        components: all,
+        start: 0,
+        end: 0,
+    }];
+
+    scans.push(Scan {
+        components: vec![0],
+        start: 1,
+        end: 5,
+    });
+    scans.push(Scan {
+        components: vec![0],
+        start: 6,
+        end: 63,
+    });
+
+    for index in 1..components.len() {
+        scans.push(Scan {
+            components: vec![index],
+            start: 1,
+            end: 63,
+        });
+    }
+
+    scans
+}
+
+/// The Huffman tables used by a scan.
+fn used_tables(components: &[Component], scan: &Scan) -> Vec<usize> {
+    let mut tables = scan
+        .components
+        .iter()
+        .flat_map(|&index| {
+            let component = &components[index];
+            let mut tables = Vec::new();
+            if scan.start == 0 {
+                tables.push(component.dc_table());
+            }
+            if scan.end > 0 {
+                tables.push(component.ac_table());
+            }
+            tables
+        })
+        .collect::<Vec<_>>();
+
+    tables.sort_unstable();
+    tables.dedup();
+    tables
+}
+
+/// The receiver of the entropy coded data of a scan.
+trait EntropySink {
+    /// A Huffman coded symbol, of the given table.
+    fn symbol(&mut self, table: usize, symbol: u8);
+    /// The additional bits of a coefficient.
+    fn bits(&mut self, value: u16, length: u8);
+    /// A restart marker, which ends a restart interval.
+    fn restart(&mut self);
+}
+
+/// Counts the symbols of each table, to build optimal Huffman tables.
+struct SymbolCounter {
+    frequencies: [[u64; 256]; 4],
+}
+
+impl SymbolCounter {
+    fn new() -> Self {
+        SymbolCounter {
+            frequencies: [[0; 256]; 4],
+        }
+    }
+}
+
+impl EntropySink for SymbolCounter {
+    fn symbol(&mut self, table: usize, symbol: u8) {
+        self.frequencies[table][usize::from(symbol)] += 1;
+    }
+
+    fn bits(&mut self, _value: u16, _length: u8) {}
+
+    fn restart(&mut self) {}
+}
+
+/// Writes the Huffman coded data, in which each 0xff byte is followed by a 0x00 byte.
+struct BitWriter<'a> {
+    codes: &'a [Vec<(u16, u8)>],
+    bytes: Vec<u8>,
+    buffer: u32,
+    length: u8,
+    restarts: u8,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(codes: &'a [Vec<(u16, u8)>]) -> Self {
+        BitWriter {
+            codes,
+            bytes: Vec::new(),
+            buffer: 0,
+            length: 0,
+            restarts: 0,
+        }
+    }
+
+    /// Pads the last byte with 1-bits.
+    fn pad(&mut self) {
+        if self.length > 0 {
+            let padding = 8 - self.length;
+            self.bits((1 << padding) - 1, padding);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.pad();
+        self.bytes
+    }
+}
+
+impl EntropySink for BitWriter<'_> {
+    fn symbol(&mut self, table: usize, symbol: u8) {
+        let (code, length) = self.codes[table][usize::from(symbol)];
+        self.bits(code, length);
+    }
+
+    fn bits(&mut self, value: u16, length: u8) {
+        if length == 0 {
+            return;
+        }
+
+        self.buffer = (self.buffer << length) | (u32::from(value) & ((1 << length) - 1));
+        self.length += length;
+
+        while self.length >= 8 {
+            self.length -= 8;
+            let byte = (self.buffer >> self.length) as u8;
+            self.bytes.push(byte);
+            if byte == 0xff {
+                self.bytes.push(0);
+            }
+        }
+    }
+
+    fn restart(&mut self) {
+        self.pad();
+        self.bytes
+            .extend_from_slice(&[0xff, RST0 + self.restarts % 8]);
+        self.restarts = self.restarts.wrapping_add(1);
+    }
+}
+
+/// The number of bits of the magnitude of a coefficient, and the bits which encode it: negative
+/// values are encoded as their one's complement.
+fn magnitude(value: i16) -> (u8, u16) {
+    let length = (32 - (i32::from(value).abs() as u32).leading_zeros()) as u8;
+    let bits = if value < 0 { value - 1 } else { value };
+
+    (length, bits as u16 & ((1 << length) - 1))
+}
+
+/// Encodes the coefficients of a scan. A scan of a single component covers only the blocks of the
+/// component which cover the image; a scan of several components covers all MCUs.
+fn encode_scan<S: EntropySink>(
+    components: &[Component],
+    scan: &Scan,
+    restart_interval: usize,
+    end_of_band_runs: bool,
+    sink: &mut S,
+) {
+    // the blocks of each MCU, as (component, block index)
+    let mcus: Vec<Vec<(usize, usize)>> = if let [index] = scan.components[..] {
+        let component = &components[index];
+        let (wide, high) = component.scan_blocks;
+
+        (0..high)
+            .flat_map(|y| (0..wide).map(move |x| vec![(index, y * component.blocks_wide + x)]))
+            .collect()
+    } else {
+        let first = &components[scan.components[0]];
+        let mcus_wide = first.blocks_wide / first.horizontal_factor;
+        let mcus_high = first.blocks.len() / first.blocks_wide / first.vertical_factor;
+
+        (0..mcus_high)
+            .flat_map(|mcu_y| (0..mcus_wide).map(move |mcu_x| (mcu_x, mcu_y)))
+            .map(|(mcu_x, mcu_y)| {
+                scan.components
+                    .iter()
+                    .flat_map(|&index| {
+                        let component = &components[index];
+                        (0..component.vertical_factor).flat_map(move |v| {
+                            (0..component.horizontal_factor).map(move |h| {
+                                let x = mcu_x * component.horizontal_factor + h;
+                                let y = mcu_y * component.vertical_factor + v;
+                                (index, y * component.blocks_wide + x)
+                            })
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+
+    let mut predictions = vec![0i16; components.len()];
+    let mut end_of_band_run = 0u16;
+    let mut ac_table = 0;
+
+    for (index, mcu) in mcus.iter().enumerate() {
+        if restart_interval > 0 && index > 0 && index % restart_interval == 0 {
+            flush_end_of_band_run(&mut end_of_band_run, ac_table, sink);
+            sink.restart();
+            predictions
+                .iter_mut()
+                .for_each(|prediction| *prediction = 0);
+        }
+
+        for &(component_index, block_index) in mcu {
+            let component = &components[component_index];
+            let block = &component.blocks[block_index];
+            ac_table = component.ac_table();
+
+            if scan.start == 0 {
+                let difference = block[0] - predictions[component_index];
+                predictions[component_index] = block[0];
+
+                let (length, bits) = magnitude(difference);
+                sink.symbol(component.dc_table(), length);
+                sink.bits(bits, length);
+            }
+
+            if scan.end == 0 {
+                continue;
+            }
+
+            let mut zeros = 0;
+            for &coefficient in &block[scan.start.max(1)..=scan.end] {
+                if coefficient == 0 {
+                    zeros += 1;
+                    continue;
+                }
+
+                flush_end_of_band_run(&mut end_of_band_run, ac_table, sink);
+
+                // runs of more than 15 zeros are split off
+                while zeros > 15 {
+                    sink.symbol(ac_table, 0xf0);
+                    zeros -= 16;
+                }
+
+                let (length, bits) = magnitude(coefficient);
+                sink.symbol(ac_table, (zeros << 4) as u8 | length);
+                sink.bits(bits, length);
+                zeros = 0;
+            }
+
+            if zeros > 0 {
+                // the remaining coefficients are zero
+                end_of_band_run += 1;
+
+                let progressive = scan.start > 0;
+                if !progressive || !end_of_band_runs || end_of_band_run == MAX_END_OF_BAND_RUN {
+                    flush_end_of_band_run(&mut end_of_band_run, ac_table, sink);
+                }
+            }
+        }
+    }
+
+    flush_end_of_band_run(&mut end_of_band_run, ac_table, sink);
+}
+
+/// Ends a run of blocks of which the remaining coefficients are zero.
+fn flush_end_of_band_run<S: EntropySink>(run: &mut u16, table: usize, sink: &mut S) {
+    if *run == 0 {
+        return;
+    }
+
+    let length = (15 - run.leading_zeros()) as u8;
+    sink.symbol(table, length << 4);
+    sink.bits(*run - (1 << length), length);
+    *run = 0;
+}
+
+fn write_segment(file: &mut Vec<u8>, marker: u8, data: &[u8]) {
+    file.extend_from_slice(&[0xff, marker]);
+    file.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+    file.extend_from_slice(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{self, ImageFormat, Rgb, RgbImage};
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x * 4) as u8, (y * 6) as u8, ((x + y) * 2) as u8])
+        }))
+    }
+
+    fn encode_with(image: &DynamicImage, settings: JpegSettings) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encode(image, 90, settings, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> DynamicImage {
+        image::load_from_memory_with_format(bytes, ImageFormat::JPEG).unwrap()
+    }
+
+    /// The mean absolute difference between the samples of two images.
+    fn difference(first: &DynamicImage, second: &DynamicImage) -> f64 {
+        let first = first.to_rgb().into_raw();
+        let second = second.to_rgb().into_raw();
+
+        let sum: f64 = first
+            .iter()
+            .zip(second.iter())
+            .map(|(&a, &b)| (f64::from(a) - f64::from(b)).abs())
+            .sum();
+        sum / first.len() as f64
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let image = gradient(37, 23);
+        let decoded = decode(&encode_with(&image, JpegSettings::default()));
+
+        assert_eq!((37, 23), decoded.dimensions());
+        assert!(difference(&image, &decoded) < 2.0);
+    }
+
+    #[test]
+    fn subsampling_round_trip() {
+        let image = gradient(37, 23);
+
+        for &subsampling in &[ChromaSubsampling::Ratio422, ChromaSubsampling::Ratio420] {
+            let settings = JpegSettings {
+                subsampling,
+                ..JpegSettings::default()
+            };
+            let decoded = decode(&encode_with(&image, settings));
+
+            assert_eq!((37, 23), decoded.dimensions());
+            assert!(difference(&image, &decoded) < 3.0);
+        }
+    }
+
+    #[test]
+    fn progressive_equals_baseline() {
+        let image = gradient(50, 41);
+        let settings = JpegSettings {
+            subsampling: ChromaSubsampling::Ratio420,
+            ..JpegSettings::default()
+        };
+        let progressive = JpegSettings {
+            progressive: true,
+            ..settings
+        };
+
+        let bytes = encode_with(&image, progressive);
+        assert!(bytes.windows(2).any(|marker| marker == [0xff, SOF2]));
+
+        // the same coefficients are encoded, in a different order
+        let baseline = decode(&encode_with(&image, settings));
+        assert_eq!(baseline.raw_pixels(), decode(&bytes).raw_pixels());
+    }
+
+    #[test]
+    fn optimized_tables_are_smaller() {
+        let image = gradient(64, 64);
+
+        for &progressive in &[false, true] {
+            let settings = JpegSettings {
+                progressive,
+                ..JpegSettings::default()
+            };
+            let optimized = JpegSettings {
+                optimize_huffman_tables: true,
+                ..settings
+            };
+
+            let standard = encode_with(&image, settings);
+            let smaller = encode_with(&image, optimized);
+
+            assert!(smaller.len() < standard.len());
+            assert_eq!(
+                decode(&standard).raw_pixels(),
+                decode(&smaller).raw_pixels()
+            );
+        }
+    }
+
+    #[test]
+    fn restart_interval() {
+        let image = gradient(40, 40);
+        let settings = JpegSettings {
+            restart_interval: 2,
+            progressive: true,
+            ..JpegSettings::default()
+        };
+
+        let bytes = encode_with(&image, settings);
+        assert!(bytes.windows(2).any(|marker| marker == [0xff, RST0]));
+
+        let without = decode(&encode_with(&image, JpegSettings::default()));
+        assert_eq!(without.raw_pixels(), decode(&bytes).raw_pixels());
+    }
+
+    #[test]
+    fn grayscale() {
+        let settings = JpegSettings {
+            grayscale: true,
+            ..JpegSettings::default()
+        };
+        let decoded = decode(&encode_with(&gradient(9, 9), settings));
+
+        match decoded {
+            DynamicImage::ImageLuma8(_) => {}
+            _ => panic!("Expected a grayscale image."),
+        }
+    }
+
+    #[test]
+    fn optimal_table() {
+        let mut frequencies = [0; 256];
+        frequencies[1] = 10;
+        frequencies[2] = 5;
+        frequencies[3] = 1;
+
+        let table = HuffmanTable::optimal(&frequencies);
+        assert_eq!(vec![1, 2, 3], table.symbols);
+        assert_eq!(&[1, 1, 1, 0], &table.lengths[..4]);
+    }
+
+    #[test]
+    fn magnitudes() {
+        assert_eq!((0, 0), magnitude(0));
+        assert_eq!((1, 1), magnitude(1));
+        assert_eq!((1, 0), magnitude(-1));
+        assert_eq!((3, 0b010), magnitude(-5));
+        assert_eq!((10, 1023), magnitude(1023));
+    }
+
+    #[test]
+    fn subsampling_from_str() {
+        assert_eq!(
+            ChromaSubsampling::Ratio420,
+            ChromaSubsampling::try_from_str("4:2:0").unwrap()
+        );
+        assert!(ChromaSubsampling::try_from_str("4:1:1").is_err());
+    }
+}
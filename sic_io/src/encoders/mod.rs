@@ -0,0 +1,8 @@
+//! Encoders for the output formats which aren't supported by the image crate.
+
+pub mod farbfeld;
+pub mod ico;
+pub mod jpeg;
+pub mod png;
+pub mod tga;
+pub mod tiff;
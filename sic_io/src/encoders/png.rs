@@ -0,0 +1,527 @@
+//! A PNG encoder which can be tuned for smaller files: the compression level, the filtering of the
+//! scan lines and the bit depth can be chosen, and images with few colors can be written as indexed
+//! (palette) images.
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::io::Write;
+
+use sic_core::image::{DynamicImage, GenericImageView};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngCompression {
+    Fast,
+    Default,
+    /// The smallest files, but the slowest to encode.
+    Best,
+}
+
+impl Default for PngCompression {
+    fn default() -> Self {
+        PngCompression::Default
+    }
+}
+
+impl PngCompression {
+    pub fn try_from_str(val: &str) -> Result<PngCompression, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "fast" => Ok(PngCompression::Fast),
+            "default" => Ok(PngCompression::Default),
+            "best" => Ok(PngCompression::Best),
+            fail => Err(format!("No such PNG compression level: {}", fail).into()),
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> Vec<u8> {
+        let compression = match self {
+            PngCompression::Fast => deflate::Compression::Fast,
+            PngCompression::Default => deflate::Compression::Default,
+            PngCompression::Best => deflate::Compression::Best,
+        };
+
+        deflate::deflate_bytes_zlib_conf(data, compression)
+    }
+}
+
+/// The filter applied to each scan line before it is compressed. Filters predict each byte from its
+/// neighbours, so that only the (usually small) differences have to be compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngFilter {
+    None,
+    Sub,
+    Up,
+    Average,
+    Paeth,
+    /// Each scan line is filtered with the filter which results in the smallest sum of differences,
+    /// which is a good estimate of the filter which compresses best.
+    Adaptive,
+}
+
+impl Default for PngFilter {
+    fn default() -> Self {
+        PngFilter::Adaptive
+    }
+}
+
+impl PngFilter {
+    pub fn try_from_str(val: &str) -> Result<PngFilter, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "none" => Ok(PngFilter::None),
+            "sub" => Ok(PngFilter::Sub),
+            "up" => Ok(PngFilter::Up),
+            "average" => Ok(PngFilter::Average),
+            "paeth" => Ok(PngFilter::Paeth),
+            "adaptive" => Ok(PngFilter::Adaptive),
+            fail => Err(format!("No such PNG filter: {}", fail).into()),
+        }
+    }
+
+    /// The filter types which are tried for each scan line.
+    fn filter_types(self) -> &'static [u8] {
+        match self {
+            PngFilter::None => &[0],
+            PngFilter::Sub => &[1],
+            PngFilter::Up => &[2],
+            PngFilter::Average => &[3],
+            PngFilter::Paeth => &[4],
+            PngFilter::Adaptive => &[0, 1, 2, 3, 4],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PngSettings {
+    pub compression: PngCompression,
+    pub filter: PngFilter,
+    /// Write samples of 16 bits instead of 8 bits, scaled up from the 8 bit samples of the image.
+    pub sixteen_bit: bool,
+    /// Write an indexed image if the image has at most 256 colors. Has no effect on 16 bit output.
+    pub palette: bool,
+}
+
+/// The maximum number of colors of an indexed image.
+const MAX_PALETTE_SIZE: usize = 256;
+
+/// The pixels as they are written: the color type and bit depth of the header, the palette of an
+/// indexed image, and the scan lines, each of which contains the bytes of the samples of a row.
+struct Pixels {
+    color_type: u8,
+    bit_depth: u8,
+    palette: Vec<[u8; 4]>,
+    rows: Vec<Vec<u8>>,
+}
+
+/// Encodes the image as a PNG file. Images with a BGR(A) color type are written as RGB(A).
+pub fn encode<W: Write>(
+    image: &DynamicImage,
+    settings: PngSettings,
+    writer: &mut W,
+) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+
+    if width == 0 || height == 0 {
+        return Err("A PNG image should have at least a single pixel.".to_string());
+    }
+
+    let indexed = if settings.palette && !settings.sixteen_bit {
+        indexed_pixels(image)
+    } else {
+        None
+    };
+
+    let pixels = match indexed {
+        Some(pixels) => pixels,
+        None => true_color_pixels(image, settings.sixteen_bit),
+    };
+
+    let channels = match pixels.color_type {
+        0 | 3 => 1,
+        4 => 2,
+        2 => 3,
+        _ => 4,
+    };
+    // filters work on bytes, of whole pixels if a pixel is at least a byte
+    let bytes_per_pixel = (channels * usize::from(pixels.bit_depth) / 8).max(1);
+
+    // the indices of an indexed image don't predict each other, so they are best left unfiltered
+    let row_filter = match settings.filter {
+        PngFilter::Adaptive if pixels.color_type == 3 => PngFilter::None,
+        row_filter => row_filter,
+    };
+
+    let mut data = Vec::with_capacity(pixels.rows.iter().map(|row| row.len() + 1).sum());
+    let mut previous = vec![0; pixels.rows[0].len()];
+
+    for row in &pixels.rows {
+        let (filter_type, filtered) = row_filter
+            .filter_types()
+            .iter()
+            .map(|&filter_type| {
+                (
+                    filter_type,
+                    filter(filter_type, bytes_per_pixel, &previous, row),
+                )
+            })
+            .min_by_key(|(_, filtered)| sum_of_differences(filtered))
+            .unwrap_or_else(|| (0, row.clone()));
+
+        data.push(filter_type);
+        data.extend_from_slice(&filtered);
+        previous.copy_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // no interlacing, and the only compression and filter methods
+    header.extend_from_slice(&[pixels.bit_depth, pixels.color_type, 0, 0, 0]);
+
+    let mut file = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_chunk(&mut file, b"IHDR", &header);
+
+    if !pixels.palette.is_empty() {
+        let colors = pixels
+            .palette
+            .iter()
+            .flat_map(|color| color[..3].to_vec())
+            .collect::<Vec<_>>();
+        write_chunk(&mut file, b"PLTE", &colors);
+
+        // translucent colors are ordered first, so the opaque colors can be left out
+        let alpha = pixels
+            .palette
+            .iter()
+            .map(|color| color[3])
+            .take_while(|&alpha| alpha != 255)
+            .collect::<Vec<_>>();
+        if !alpha.is_empty() {
+            write_chunk(&mut file, b"tRNS", &alpha);
+        }
+    }
+
+    write_chunk(&mut file, b"IDAT", &settings.compression.compress(&data));
+    write_chunk(&mut file, b"IEND", &[]);
+
+    writer.write_all(&file).map_err(|err| err.to_string())
+}
+
+/// The pixels of a grayscale or true color image, with or without an alpha channel.
+fn true_color_pixels(image: &DynamicImage, sixteen_bit: bool) -> Pixels {
+    let (width, _) = image.dimensions();
+
+    let (color_type, channels, samples) = match image {
+        DynamicImage::ImageLuma8(buffer) => (0, 1, buffer.clone().into_raw()),
+        DynamicImage::ImageLumaA8(buffer) => (4, 2, buffer.clone().into_raw()),
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageBgr8(_) => {
+            (2, 3, image.to_rgb().into_raw())
+        }
+        DynamicImage::ImageRgba8(_) | DynamicImage::ImageBgra8(_) => {
+            (6, 4, image.to_rgba().into_raw())
+        }
+    };
+
+    let rows = samples
+        .chunks(width as usize * channels)
+        .map(|row| {
+            if sixteen_bit {
+                // 0xff becomes 0xffff
+                row.iter()
+                    .flat_map(|&sample| (u16::from(sample) * 257).to_be_bytes().to_vec())
+                    .collect()
+            } else {
+                row.to_vec()
+            }
+        })
+        .collect();
+
+    Pixels {
+        color_type,
+        bit_depth: if sixteen_bit { 16 } else { 8 },
+        palette: Vec::new(),
+        rows,
+    }
+}
+
+/// The pixels of an indexed image, if the image has at most 256 colors. Images with at most 16
+/// colors use fewer than 8 bits per pixel. Grayscale images are only indexed if that takes fewer
+/// than 8 bits per pixel, since they would not be any smaller otherwise.
+fn indexed_pixels(image: &DynamicImage) -> Option<Pixels> {
+    let (width, _) = image.dimensions();
+    let rgba = image.to_rgba();
+
+    let mut palette = Vec::new();
+    let mut colors = HashSet::new();
+    for pixel in rgba.pixels() {
+        if colors.insert(pixel.0) {
+            if palette.len() == MAX_PALETTE_SIZE {
+                return None;
+            }
+
+            palette.push(pixel.0);
+        }
+    }
+
+    palette.sort_by_key(|color| color[3] == 255);
+
+    let indices = palette
+        .iter()
+        .enumerate()
+        .map(|(index, color)| (*color, index as u8))
+        .collect::<HashMap<_, _>>();
+
+    let bit_depth = match palette.len() {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    };
+    let pixels_per_byte = 8 / bit_depth as usize;
+
+    if bit_depth == 8 {
+        if let DynamicImage::ImageLuma8(_) = image {
+            return None;
+        }
+    }
+
+    let rows = rgba
+        .into_raw()
+        .chunks(width as usize * 4)
+        .map(|row| {
+            // the pixels are packed into bytes, starting at the most significant bits
+            row.chunks(4 * pixels_per_byte)
+                .map(|pixels| {
+                    pixels.chunks(4).enumerate().fold(0u8, |byte, (i, pixel)| {
+                        let index = indices[&[pixel[0], pixel[1], pixel[2], pixel[3]]];
+                        byte | index << (8 - bit_depth * (i as u8 + 1))
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(Pixels {
+        color_type: 3,
+        bit_depth,
+        palette,
+        rows,
+    })
+}
+
+/// Filters a scan line, given the (unfiltered) scan line above it.
+fn filter(filter_type: u8, bytes_per_pixel: usize, previous: &[u8], current: &[u8]) -> Vec<u8> {
+    (0..current.len())
+        .map(|i| {
+            let left = if i >= bytes_per_pixel {
+                current[i - bytes_per_pixel]
+            } else {
+                0
+            };
+            let above = previous[i];
+            let above_left = if i >= bytes_per_pixel {
+                previous[i - bytes_per_pixel]
+            } else {
+                0
+            };
+
+            let prediction = match filter_type {
+                1 => left,
+                2 => above,
+                3 => ((u16::from(left) + u16::from(above)) / 2) as u8,
+                4 => paeth(left, above, above_left),
+                _ => 0,
+            };
+
+            current[i].wrapping_sub(prediction)
+        })
+        .collect()
+}
+
+/// Predicts a byte by whichever of its neighbours is closest to `left + above - above_left`.
+fn paeth(left: u8, above: u8, above_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(above) - i16::from(above_left);
+    let distance_left = (estimate - i16::from(left)).abs();
+    let distance_above = (estimate - i16::from(above)).abs();
+    let distance_above_left = (estimate - i16::from(above_left)).abs();
+
+    if distance_left <= distance_above && distance_left <= distance_above_left {
+        left
+    } else if distance_above <= distance_above_left {
+        above
+    } else {
+        above_left
+    }
+}
+
+/// The sum of the filtered bytes, each taken as a signed difference.
+fn sum_of_differences(filtered: &[u8]) -> u64 {
+    filtered
+        .iter()
+        .map(|&byte| i16::from(byte as i8).abs() as u64)
+        .sum()
+}
+
+fn write_chunk(file: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    file.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = file.len();
+    file.extend_from_slice(kind);
+    file.extend_from_slice(data);
+
+    // the checksum covers the kind and the data of the chunk
+    let checksum = crc32(&file[start..]);
+    file.extend_from_slice(&checksum.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let table = crc_table();
+
+    !bytes.iter().fold(0xffff_ffff, |crc, &byte| {
+        table[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// The checksum of each byte value, so a checksum is updated a byte at a time instead of a bit at
+/// a time.
+fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+
+    for (byte, entry) in table.iter_mut().enumerate() {
+        *entry = (0..8).fold(byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        });
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{self, ImageFormat, Rgba, RgbaImage};
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([(x * 3) as u8, (y * 5) as u8, ((x + y) / 4) as u8, 200])
+        }))
+    }
+
+    fn round_trip(image: &DynamicImage, settings: PngSettings) -> DynamicImage {
+        let mut bytes = Vec::new();
+        encode(image, settings, &mut bytes).unwrap();
+
+        image::load_from_memory_with_format(&bytes, ImageFormat::PNG).unwrap()
+    }
+
+    #[test]
+    fn round_trip_with_each_filter() {
+        let image = gradient(37, 23);
+
+        for &filter in &[
+            PngFilter::None,
+            PngFilter::Sub,
+            PngFilter::Up,
+            PngFilter::Average,
+            PngFilter::Paeth,
+            PngFilter::Adaptive,
+        ] {
+            let settings = PngSettings {
+                filter,
+                ..PngSettings::default()
+            };
+            let decoded = round_trip(&image, settings);
+
+            assert_eq!(image.raw_pixels(), decoded.raw_pixels());
+        }
+    }
+
+    #[test]
+    fn round_trip_gray() {
+        let image = DynamicImage::ImageLuma8(image::GrayImage::from_fn(20, 9, |x, y| {
+            image::Luma([(x * y) as u8])
+        }));
+        let settings = PngSettings {
+            compression: PngCompression::Best,
+            ..PngSettings::default()
+        };
+        let decoded = round_trip(&image, settings);
+
+        assert_eq!(image.raw_pixels(), decoded.raw_pixels());
+    }
+
+    #[test]
+    fn round_trip_palette() {
+        // three colors, one of which is translucent
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(13, 5, |x, y| match (x + y) % 3 {
+            0 => Rgba([255, 0, 0, 255]),
+            1 => Rgba([0, 0, 255, 255]),
+            _ => Rgba([0, 255, 0, 100]),
+        }));
+        let settings = PngSettings {
+            palette: true,
+            ..PngSettings::default()
+        };
+
+        let mut bytes = Vec::new();
+        encode(&image, settings, &mut bytes).unwrap();
+        // indexed with 2 bits per pixel
+        assert_eq!(&[2, 3], &bytes[24..26]);
+
+        let decoded = image::load_from_memory_with_format(&bytes, ImageFormat::PNG).unwrap();
+        assert_eq!(image.to_rgba().into_raw(), decoded.to_rgba().into_raw());
+    }
+
+    #[test]
+    fn palette_requires_few_colors() {
+        let settings = PngSettings {
+            palette: true,
+            ..PngSettings::default()
+        };
+
+        let mut bytes = Vec::new();
+        encode(&gradient(40, 40), settings, &mut bytes).unwrap();
+
+        // true color with alpha, with 8 bits per sample
+        assert_eq!(&[8, 6], &bytes[24..26]);
+    }
+
+    #[test]
+    fn sixteen_bit_header() {
+        let settings = PngSettings {
+            sixteen_bit: true,
+            ..PngSettings::default()
+        };
+
+        let mut bytes = Vec::new();
+        let image = DynamicImage::ImageRgb8(image::RgbImage::new(3, 2));
+        encode(&image, settings, &mut bytes).unwrap();
+
+        assert_eq!(b"IHDR", &bytes[12..16]);
+        assert_eq!(&[0, 0, 0, 3, 0, 0, 0, 2, 16, 2], &bytes[16..26]);
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn paeth_prediction() {
+        assert_eq!(10, paeth(10, 20, 20));
+        assert_eq!(20, paeth(10, 20, 10));
+        assert_eq!(15, paeth(10, 20, 15));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(PngFilter::Paeth, PngFilter::try_from_str("Paeth").unwrap());
+        assert_eq!(
+            PngCompression::Best,
+            PngCompression::try_from_str("best").unwrap()
+        );
+        assert!(PngFilter::try_from_str("adam7").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! A TGA (Truevision) encoder for grayscale and true color images, optionally run-length encoded.
+
+use std::io::Write;
+
+use sic_core::image::{DynamicImage, GenericImageView};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TgaEncoding {
+    /// The pixels are stored as is.
+    Raw,
+    /// Runs of equal pixels are stored once, with the length of the run.
+    RunLength,
+}
+
+impl Default for TgaEncoding {
+    fn default() -> Self {
+        TgaEncoding::Raw
+    }
+}
+
+/// A run-length encoded packet contains at most 128 pixels.
+const MAX_PACKET_LENGTH: usize = 128;
+
+/// Encodes the image as a TGA file, with its origin at the top left. Grayscale images are stored as
+/// such; any other image is stored as true color image, with an alpha channel if it has one.
+pub fn encode<W: Write>(
+    image: &DynamicImage,
+    encoding: TgaEncoding,
+    writer: &mut W,
+) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+
+    if width > u32::from(std::u16::MAX) || height > u32::from(std::u16::MAX) {
+        return Err(format!(
+            "The image ({}x{}) is too large for the TGA format, which supports images of at most {}x{} pixels.",
+            width,
+            height,
+            std::u16::MAX,
+            std::u16::MAX
+        ));
+    }
+
+    // (image type, bits per pixel, alpha bits, pixels as stored in the file)
+    let (image_type, depth, alpha_bits, pixels) = match image {
+        DynamicImage::ImageLuma8(buffer) => (3u8, 8u8, 0u8, buffer.clone().into_raw()),
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageBgr8(_) => (
+            2,
+            24,
+            0,
+            image
+                .to_bgra()
+                .pixels()
+                .flat_map(|p| p.0[..3].to_vec())
+                .collect(),
+        ),
+        _ => (2, 32, 8, image.to_bgra().into_raw()),
+    };
+
+    let pixel_size = usize::from(depth / 8);
+    let row_size = width as usize * pixel_size;
+
+    let (image_type, data) = match encoding {
+        TgaEncoding::Raw => (image_type, pixels),
+        // packets don't cross scan lines
+        TgaEncoding::RunLength => (
+            image_type + 8,
+            pixels
+                .chunks(row_size.max(1))
+                .flat_map(|row| run_length_encode(row, pixel_size))
+                .collect(),
+        ),
+    };
+
+    let mut file = Vec::with_capacity(18 + data.len() + 26);
+
+    // header: no image id and no color map
+    file.extend_from_slice(&[0, 0, image_type, 0, 0, 0, 0, 0]);
+    // the x and y origin
+    file.extend_from_slice(&[0, 0, 0, 0]);
+    file.extend_from_slice(&(width as u16).to_le_bytes());
+    file.extend_from_slice(&(height as u16).to_le_bytes());
+    file.push(depth);
+    // the number of alpha bits, and the first pixel is at the top left
+    file.push(alpha_bits | 0x20);
+
+    file.extend_from_slice(&data);
+
+    // footer of TGA 2.0, without extension and developer areas
+    file.extend_from_slice(&[0; 8]);
+    file.extend_from_slice(b"TRUEVISION-XFILE.\0");
+
+    writer.write_all(&file).map_err(|err| err.to_string())
+}
+
+/// Encodes a scan line as packets: a run of equal pixels is stored as a run-length packet,
+/// other pixels are stored in raw packets.
+fn run_length_encode(row: &[u8], pixel_size: usize) -> Vec<u8> {
+    let pixels = row.chunks(pixel_size).collect::<Vec<_>>();
+    let mut packets = Vec::with_capacity(row.len());
+    let mut index = 0;
+
+    while index < pixels.len() {
+        let run = pixels[index..]
+            .iter()
+            .take(MAX_PACKET_LENGTH)
+            .take_while(|&&pixel| pixel == pixels[index])
+            .count();
+
+        if run > 1 {
+            packets.push(0x80 | (run - 1) as u8);
+            packets.extend_from_slice(pixels[index]);
+            index += run;
+            continue;
+        }
+
+        // a raw packet lasts until the next run of equal pixels
+        let raw = (index..pixels.len())
+            .take(MAX_PACKET_LENGTH)
+            .take_while(|&i| i + 1 == pixels.len() || pixels[i] != pixels[i + 1])
+            .count()
+            .max(1);
+
+        packets.push((raw - 1) as u8);
+        for pixel in &pixels[index..index + raw] {
+            packets.extend_from_slice(pixel);
+        }
+        index += raw;
+    }
+
+    packets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{self, ImageFormat, Rgba, RgbaImage};
+
+    fn stripes() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(9, 5, |x, y| {
+            if x < 4 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([(x * 20) as u8, (y * 40) as u8, 7, 128])
+            }
+        }))
+    }
+
+    fn round_trip(image: &DynamicImage, encoding: TgaEncoding) -> DynamicImage {
+        let mut bytes = Vec::new();
+        encode(image, encoding, &mut bytes).unwrap();
+
+        image::load_from_memory_with_format(&bytes, ImageFormat::TGA).unwrap()
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        let image = stripes();
+        let decoded = round_trip(&image, TgaEncoding::Raw);
+
+        assert_eq!(image.to_rgba().into_raw(), decoded.to_rgba().into_raw());
+    }
+
+    #[test]
+    fn run_length_round_trip() {
+        let image = stripes();
+        let decoded = round_trip(&image, TgaEncoding::RunLength);
+
+        assert_eq!(image.to_rgba().into_raw(), decoded.to_rgba().into_raw());
+    }
+
+    #[test]
+    fn run_length_packets() {
+        let row = [1, 1, 1, 2, 3, 3];
+
+        assert_eq!(vec![0x82, 1, 0x00, 2, 0x81, 3], run_length_encode(&row, 1));
+    }
+
+    #[test]
+    fn run_length_packets_are_limited() {
+        let row = [5; 130];
+
+        assert_eq!(vec![0xff, 5, 0x81, 5], run_length_encode(&row, 1));
+    }
+
+    #[test]
+    fn gray_header() {
+        let mut bytes = Vec::new();
+        let image = DynamicImage::ImageLuma8(image::GrayImage::new(3, 2));
+        encode(&image, TgaEncoding::RunLength, &mut bytes).unwrap();
+
+        // run-length encoded grayscale, of 3x2 pixels and 8 bits per pixel
+        assert_eq!(11, bytes[2]);
+        assert_eq!(&[3, 0, 2, 0, 8, 0x20], &bytes[12..18]);
+        assert!(bytes.ends_with(b"TRUEVISION-XFILE.\0"));
+    }
+}
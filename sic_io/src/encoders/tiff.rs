@@ -0,0 +1,368 @@
+//! A baseline TIFF encoder for 8 bit grayscale and RGB images, with or without an alpha channel.
+//! The image is written in strips, each of which may be compressed.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Write;
+
+use sic_core::image::{DynamicImage, GenericImageView};
+
+/// The (uncompressed) size of a strip should be about 8 KiB, as recommended by the specification.
+const STRIP_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiffCompression {
+    None,
+    /// Lempel-Ziv-Welch compression, supported by practically all TIFF readers.
+    Lzw,
+    /// (Adobe) Deflate compression, which usually compresses better than LZW.
+    Deflate,
+}
+
+impl Default for TiffCompression {
+    fn default() -> Self {
+        TiffCompression::None
+    }
+}
+
+impl TiffCompression {
+    pub fn try_from_str(val: &str) -> Result<TiffCompression, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "none" => Ok(TiffCompression::None),
+            "lzw" => Ok(TiffCompression::Lzw),
+            "deflate" | "zip" => Ok(TiffCompression::Deflate),
+            fail => Err(format!("No such TIFF compression: {}", fail).into()),
+        }
+    }
+
+    /// The value of the compression tag.
+    fn tag_value(self) -> u16 {
+        match self {
+            TiffCompression::None => 1,
+            TiffCompression::Lzw => 5,
+            TiffCompression::Deflate => 8,
+        }
+    }
+
+    fn compress(self, strip: &[u8]) -> Vec<u8> {
+        match self {
+            TiffCompression::None => strip.to_vec(),
+            TiffCompression::Lzw => lzw(strip),
+            TiffCompression::Deflate => deflate::deflate_bytes_zlib(strip),
+        }
+    }
+}
+
+// tags
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const COMPRESSION: u16 = 259;
+const PHOTOMETRIC_INTERPRETATION: u16 = 262;
+const STRIP_OFFSETS: u16 = 273;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const ROWS_PER_STRIP: u16 = 278;
+const STRIP_BYTE_COUNTS: u16 = 279;
+const X_RESOLUTION: u16 = 282;
+const Y_RESOLUTION: u16 = 283;
+const PLANAR_CONFIGURATION: u16 = 284;
+const RESOLUTION_UNIT: u16 = 296;
+const EXTRA_SAMPLES: u16 = 338;
+
+/// The value of an entry of the image file directory.
+enum Value {
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(u32, u32),
+}
+
+impl Value {
+    /// The (type, count, little endian bytes) of the value.
+    fn encode(&self) -> (u16, u32, Vec<u8>) {
+        match self {
+            Value::Short(values) => (
+                3,
+                values.len() as u32,
+                values
+                    .iter()
+                    .flat_map(|v| v.to_le_bytes().to_vec())
+                    .collect(),
+            ),
+            Value::Long(values) => (
+                4,
+                values.len() as u32,
+                values
+                    .iter()
+                    .flat_map(|v| v.to_le_bytes().to_vec())
+                    .collect(),
+            ),
+            Value::Rational(numerator, denominator) => {
+                let mut bytes = numerator.to_le_bytes().to_vec();
+                bytes.extend_from_slice(&denominator.to_le_bytes());
+                (5, 1, bytes)
+            }
+        }
+    }
+}
+
+/// Encodes the image as a little endian TIFF file. Images with a BGR(A) color type are converted
+/// to RGB(A).
+pub fn encode<W: Write>(
+    image: &DynamicImage,
+    compression: TiffCompression,
+    writer: &mut W,
+) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+
+    // (samples per pixel, photometric interpretation, has alpha, pixels)
+    let (samples, photometric, alpha, pixels) = match image {
+        DynamicImage::ImageLuma8(buffer) => (1u16, 1u16, false, buffer.clone().into_raw()),
+        DynamicImage::ImageLumaA8(buffer) => (2, 1, true, buffer.clone().into_raw()),
+        DynamicImage::ImageRgb8(buffer) => (3, 2, false, buffer.clone().into_raw()),
+        DynamicImage::ImageBgr8(_) => (3, 2, false, image.to_rgb().into_raw()),
+        DynamicImage::ImageRgba8(buffer) => (4, 2, true, buffer.clone().into_raw()),
+        DynamicImage::ImageBgra8(_) => (4, 2, true, image.to_rgba().into_raw()),
+    };
+
+    let row_size = (width as usize * samples as usize).max(1);
+    let rows_per_strip = (STRIP_SIZE / row_size).max(1);
+
+    // header, followed by the strips
+    let mut file = b"II\x2a\x00\x00\x00\x00\x00".to_vec();
+    let mut strip_offsets = Vec::new();
+    let mut strip_byte_counts = Vec::new();
+
+    for strip in pixels.chunks(row_size * rows_per_strip) {
+        let compressed = compression.compress(strip);
+        strip_offsets.push(offset(file.len())?);
+        strip_byte_counts.push(compressed.len() as u32);
+        file.extend_from_slice(&compressed);
+        align(&mut file);
+    }
+
+    let mut entries = vec![
+        (IMAGE_WIDTH, Value::Long(vec![width])),
+        (IMAGE_LENGTH, Value::Long(vec![height])),
+        (BITS_PER_SAMPLE, Value::Short(vec![8; samples as usize])),
+        (COMPRESSION, Value::Short(vec![compression.tag_value()])),
+        (PHOTOMETRIC_INTERPRETATION, Value::Short(vec![photometric])),
+        (STRIP_OFFSETS, Value::Long(strip_offsets)),
+        (SAMPLES_PER_PIXEL, Value::Short(vec![samples])),
+        (ROWS_PER_STRIP, Value::Long(vec![rows_per_strip as u32])),
+        (STRIP_BYTE_COUNTS, Value::Long(strip_byte_counts)),
+        (X_RESOLUTION, Value::Rational(72, 1)),
+        (Y_RESOLUTION, Value::Rational(72, 1)),
+        (PLANAR_CONFIGURATION, Value::Short(vec![1])),
+        (RESOLUTION_UNIT, Value::Short(vec![2])),
+    ];
+
+    if alpha {
+        // unassociated alpha
+        entries.push((EXTRA_SAMPLES, Value::Short(vec![2])));
+    }
+
+    // values which don't fit in an entry are written before the image file directory
+    let mut offsets = HashMap::new();
+    for (tag, value) in &entries {
+        let (_, _, bytes) = value.encode();
+        if bytes.len() > 4 {
+            offsets.insert(*tag, offset(file.len())?);
+            file.extend_from_slice(&bytes);
+            align(&mut file);
+        }
+    }
+
+    let directory_offset = offset(file.len())?;
+    file[4..8].copy_from_slice(&directory_offset.to_le_bytes());
+
+    file.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, value) in &entries {
+        let (kind, count, mut bytes) = value.encode();
+        file.extend_from_slice(&tag.to_le_bytes());
+        file.extend_from_slice(&kind.to_le_bytes());
+        file.extend_from_slice(&count.to_le_bytes());
+
+        match offsets.get(tag) {
+            Some(offset) => file.extend_from_slice(&offset.to_le_bytes()),
+            None => {
+                bytes.resize(4, 0);
+                file.extend_from_slice(&bytes);
+            }
+        }
+    }
+    // no next image file directory
+    file.extend_from_slice(&[0, 0, 0, 0]);
+
+    writer.write_all(&file).map_err(|err| err.to_string())
+}
+
+/// Offsets within a TIFF file are 32 bit, so data can't be written past 4 GiB.
+fn offset(length: usize) -> Result<u32, String> {
+    if length as u64 > u64::from(std::u32::MAX) {
+        return Err("The image is too large for TIFF; its file would exceed 4 GiB.".to_string());
+    }
+
+    Ok(length as u32)
+}
+
+/// Offsets within a TIFF file should be on a word boundary.
+fn align(file: &mut Vec<u8>) {
+    if file.len() % 2 == 1 {
+        file.push(0);
+    }
+}
+
+/// Compresses the data with the LZW variant used by TIFF: codes are written most significant bit
+/// first, and the code width grows one code early.
+fn lzw(data: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END_OF_INFORMATION: u16 = 257;
+    const MAX_CODE: u16 = 4094;
+
+    let mut output = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = 258;
+    let mut width = 9;
+
+    output.write(CLEAR, width);
+
+    let mut bytes = data.iter();
+    let mut current = match bytes.next() {
+        Some(&byte) => u16::from(byte),
+        None => {
+            output.write(END_OF_INFORMATION, width);
+            return output.finish();
+        }
+    };
+
+    for &byte in bytes {
+        if let Some(&code) = table.get(&(current, byte)) {
+            current = code;
+            continue;
+        }
+
+        output.write(current, width);
+        table.insert((current, byte), next_code);
+        next_code += 1;
+        current = u16::from(byte);
+
+        if next_code == 1 << width {
+            width += 1;
+        }
+
+        // start over before the codes no longer fit in 12 bits
+        if next_code >= MAX_CODE {
+            output.write(CLEAR, width);
+            table.clear();
+            next_code = 258;
+            width = 9;
+        }
+    }
+
+    output.write(current, width);
+    // the decoder adds an entry after reading the last code, so the code width may grow
+    if next_code + 1 == 1 << width {
+        width += 1;
+    }
+    output.write(END_OF_INFORMATION, width);
+
+    output.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer = (self.buffer << width) | u32::from(code);
+        self.bits += width;
+
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.buffer >> self.bits) as u8);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push((self.buffer << (8 - self.bits)) as u8);
+        }
+
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{self, ImageFormat, Rgba, RgbaImage};
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([(x * 3) as u8, (y * 5) as u8, ((x + y) / 4) as u8, 200])
+        }))
+    }
+
+    fn round_trip(image: &DynamicImage, compression: TiffCompression) -> DynamicImage {
+        let mut bytes = Vec::new();
+        encode(image, compression, &mut bytes).unwrap();
+
+        image::load_from_memory_with_format(&bytes, ImageFormat::TIFF).unwrap()
+    }
+
+    #[test]
+    fn uncompressed_round_trip() {
+        let image = gradient(31, 17);
+        let decoded = round_trip(&image, TiffCompression::None);
+
+        assert_eq!(image.raw_pixels(), decoded.raw_pixels());
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        // large enough to span several strips, and to fill the code table
+        let image = gradient(300, 200);
+        let decoded = round_trip(&image, TiffCompression::Lzw);
+
+        assert_eq!(image.raw_pixels(), decoded.raw_pixels());
+    }
+
+    #[test]
+    fn lzw_round_trip_gray() {
+        let image = DynamicImage::ImageLuma8(image::GrayImage::from_fn(64, 64, |x, y| {
+            image::Luma([((x / 8) ^ (y / 8)) as u8 * 30])
+        }));
+        let decoded = round_trip(&image, TiffCompression::Lzw);
+
+        assert_eq!(image.raw_pixels(), decoded.raw_pixels());
+    }
+
+    #[test]
+    fn deflate_strips() {
+        let mut bytes = Vec::new();
+        encode(&gradient(8, 8), TiffCompression::Deflate, &mut bytes).unwrap();
+
+        assert_eq!(b"II\x2a\x00", &bytes[..4]);
+        // the first strip directly follows the header, and is a zlib stream
+        assert_eq!(0x78, bytes[8]);
+    }
+
+    #[test]
+    fn err_offset_past_4_gib() {
+        assert_eq!(Ok(std::u32::MAX), offset(std::u32::MAX as usize));
+        assert!(offset(std::u32::MAX as usize + 1).is_err());
+    }
+
+    #[test]
+    fn compression_from_str() {
+        assert_eq!(
+            TiffCompression::Deflate,
+            TiffCompression::try_from_str("ZIP").unwrap()
+        );
+        assert!(TiffCompression::try_from_str("jpeg").is_err());
+    }
+}
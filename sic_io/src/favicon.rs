@@ -0,0 +1,131 @@
+//! A favicon pack: the icons with which a web site is shown in browser tabs and on home screens,
+//! and a web app manifest snippet which refers to the Android icons; see
+//! [export_favicon](crate::save::export_favicon).
+
+use sic_core::image::{DynamicImage, FilterType, Rgba, RgbaImage};
+use sic_core::json::escape_json;
+
+use crate::encoders::ico::square_icon;
+
+/// The file name of the ICO file, which contains the image at each of the ICO sizes.
+pub const FAVICON: &str = "favicon.ico";
+/// The file name of the web app manifest snippet.
+pub const WEB_MANIFEST: &str = "site.webmanifest";
+
+/// A square PNG icon of the pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Icon {
+    pub file_name: &'static str,
+    /// The width and height of the icon.
+    pub size: u32,
+    /// Whether the icon may be transparent. iOS shows transparent pixels of a home screen icon as
+    /// black, so the Apple touch icon is placed on a white background instead.
+    pub transparent: bool,
+}
+
+pub const APPLE_TOUCH_ICON: Icon = Icon {
+    file_name: "apple-touch-icon.png",
+    size: 180,
+    transparent: false,
+};
+
+/// The icons which are referred to by the web app manifest, as used by Android.
+pub const ANDROID_ICONS: &[Icon] = &[
+    Icon {
+        file_name: "android-chrome-192x192.png",
+        size: 192,
+        transparent: true,
+    },
+    Icon {
+        file_name: "android-chrome-512x512.png",
+        size: 512,
+        transparent: true,
+    },
+];
+
+/// The PNG icons of the pack.
+pub fn png_icons() -> impl Iterator<Item = &'static Icon> {
+    std::iter::once(&APPLE_TOUCH_ICON).chain(ANDROID_ICONS.iter())
+}
+
+impl Icon {
+    /// Scales the image to the size of the icon, keeping its aspect ratio. The rest of the icon is
+    /// transparent, or white if the icon may not be transparent.
+    pub fn render(&self, image: &DynamicImage, filter: FilterType) -> DynamicImage {
+        let icon = square_icon(image, self.size, filter);
+
+        if self.transparent {
+            DynamicImage::ImageRgba8(icon)
+        } else {
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(on_white(&icon)).to_rgb())
+        }
+    }
+}
+
+fn on_white(icon: &RgbaImage) -> RgbaImage {
+    RgbaImage::from_fn(icon.width(), icon.height(), |x, y| {
+        let Rgba([r, g, b, a]) = *icon.get_pixel(x, y);
+        let blend = |channel: u8| {
+            ((u32::from(channel) * u32::from(a) + 255 * (255 - u32::from(a)) + 127) / 255) as u8
+        };
+
+        Rgba([blend(r), blend(g), blend(b), 255])
+    })
+}
+
+/// The `icons` member of a web app manifest, which refers to the Android icons. The icons are
+/// referred to relative to the manifest, which is written to the same directory.
+pub fn web_manifest() -> String {
+    let icons = ANDROID_ICONS
+        .iter()
+        .map(|icon| {
+            format!(
+                "\t\t{{\"src\": \"{}\", \"sizes\": \"{}x{}\", \"type\": \"image/png\"}}",
+                escape_json(icon.file_name),
+                icon.size,
+                icon.size
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!("{{\n\t\"icons\": [\n{}\n\t]\n}}\n", icons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::GenericImageView;
+
+    #[test]
+    fn apple_touch_icon_is_opaque() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(20, 10, Rgba([0, 0, 0, 255])));
+        let icon = APPLE_TOUCH_ICON.render(&image, FilterType::Nearest);
+
+        assert_eq!((180, 180), icon.dimensions());
+        assert_eq!(Rgba([255, 255, 255, 255]), icon.get_pixel(90, 0));
+        assert_eq!(Rgba([0, 0, 0, 255]), icon.get_pixel(90, 90));
+    }
+
+    #[test]
+    fn android_icons_are_transparent() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(20, 10, Rgba([0, 0, 0, 255])));
+        let icon = ANDROID_ICONS[0].render(&image, FilterType::Nearest);
+
+        assert_eq!((192, 192), icon.dimensions());
+        assert_eq!(0, icon.get_pixel(96, 0)[3]);
+    }
+
+    #[test]
+    fn manifest_refers_to_android_icons() {
+        let manifest = web_manifest();
+
+        assert!(manifest.starts_with("{\n\t\"icons\": [\n"));
+        assert!(manifest.contains(
+            "{\"src\": \"android-chrome-192x192.png\", \"sizes\": \"192x192\", \"type\": \"image/png\"},\n"
+        ));
+        assert!(manifest.contains(
+            "{\"src\": \"android-chrome-512x512.png\", \"sizes\": \"512x512\", \"type\": \"image/png\"}\n"
+        ));
+    }
+}
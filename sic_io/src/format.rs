@@ -1,32 +1,143 @@
+use std::io::Write;
 use std::path::Path;
 
 use sic_core::image;
 use std::error::Error;
 
+use crate::encoders::ico::IcoSettings;
+use crate::encoders::jpeg::JpegSettings;
+use crate::encoders::png::PngSettings;
+use crate::encoders::tga::TgaEncoding;
+use crate::encoders::tiff::TiffCompression;
+use crate::encoders::{farbfeld, ico, jpeg, png, tga, tiff};
+use crate::raw::RawFormat;
 use crate::save::ExportMethod;
+use crate::support::find_format;
 
 const DEFAULT_PIPED_OUTPUT_FORMAT: image::ImageOutputFormat = image::ImageOutputFormat::BMP;
 
+/// The format in which an image is encoded: one of the formats supported by the image crate, or
+/// one of the formats for which an encoder is provided by the [encoders](crate::encoders) module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    Image(image::ImageOutputFormat),
+    Jpeg(JPEGQuality, JpegSettings),
+    Png(PngSettings),
+    /// An icon, which contains the image at several sizes.
+    Ico(IcoSettings),
+    Tiff(TiffCompression),
+    Tga(TgaEncoding),
+    Farbfeld,
+    /// Pixel data without a header.
+    Raw(RawFormat),
+}
+
+impl OutputFormat {
+    /// Encodes the image in this format, and writes it to the writer.
+    pub fn encode<W: Write>(
+        &self,
+        image: &image::DynamicImage,
+        writer: &mut W,
+    ) -> Result<(), String> {
+        match self {
+            OutputFormat::Image(format) => image
+                .write_to(writer, format.clone())
+                .map_err(|err| err.to_string()),
+            OutputFormat::Jpeg(quality, settings) => {
+                jpeg::encode(image, quality.as_u8(), *settings, writer)
+            }
+            OutputFormat::Png(settings) => png::encode(image, *settings, writer),
+            OutputFormat::Ico(settings) => ico::encode(image, settings, writer),
+            OutputFormat::Tiff(compression) => tiff::encode(image, *compression, writer),
+            OutputFormat::Tga(encoding) => tga::encode(image, *encoding, writer),
+            OutputFormat::Farbfeld => farbfeld::encode(image, writer),
+            OutputFormat::Raw(format) => format.encode(image, writer),
+        }
+    }
+
+    /// The quality of a lossy format, which trades the size of the encoded image for its
+    /// fidelity. Lossless formats have no quality.
+    pub fn quality(&self) -> Option<JPEGQuality> {
+        match self {
+            OutputFormat::Jpeg(quality, _) => Some(*quality),
+            OutputFormat::Image(image::ImageOutputFormat::JPEG(quality)) => {
+                JPEGQuality::try_from(*quality).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// This format at another quality; formats without a quality are returned unchanged.
+    pub fn with_quality(&self, quality: JPEGQuality) -> OutputFormat {
+        match self {
+            OutputFormat::Jpeg(_, settings) => OutputFormat::Jpeg(quality, *settings),
+            OutputFormat::Image(image::ImageOutputFormat::JPEG(_)) => {
+                image::ImageOutputFormat::JPEG(quality.as_u8()).into()
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// The media (MIME) type of an image encoded in this format.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Image(image::ImageOutputFormat::BMP) => "image/bmp",
+            OutputFormat::Image(image::ImageOutputFormat::GIF) => "image/gif",
+            OutputFormat::Image(image::ImageOutputFormat::ICO) | OutputFormat::Ico(_) => {
+                "image/x-icon"
+            }
+            OutputFormat::Image(image::ImageOutputFormat::JPEG(_)) | OutputFormat::Jpeg(..) => {
+                "image/jpeg"
+            }
+            OutputFormat::Image(image::ImageOutputFormat::PNG) | OutputFormat::Png(_) => {
+                "image/png"
+            }
+            OutputFormat::Image(image::ImageOutputFormat::PNM(subtype)) => match subtype {
+                image::pnm::PNMSubtype::Bitmap(_) => "image/x-portable-bitmap",
+                image::pnm::PNMSubtype::Graymap(_) => "image/x-portable-graymap",
+                image::pnm::PNMSubtype::Pixmap(_) => "image/x-portable-pixmap",
+                image::pnm::PNMSubtype::ArbitraryMap => "image/x-portable-arbitrarymap",
+            },
+            OutputFormat::Tiff(_) => "image/tiff",
+            OutputFormat::Tga(_) => "image/x-tga",
+            OutputFormat::Farbfeld => "image/x-farbfeld",
+            OutputFormat::Image(_) | OutputFormat::Raw(_) => "application/octet-stream",
+        }
+    }
+}
+
+impl From<image::ImageOutputFormat> for OutputFormat {
+    fn from(format: image::ImageOutputFormat) -> Self {
+        OutputFormat::Image(format)
+    }
+}
+
+impl PartialEq<image::ImageOutputFormat> for OutputFormat {
+    fn eq(&self, other: &image::ImageOutputFormat) -> bool {
+        match self {
+            OutputFormat::Image(format) => format == other,
+            _ => false,
+        }
+    }
+}
+
 pub trait EncodingFormatByMethod {
     /// Determine the encoding format based on the method of exporting.
     fn by_method<P: AsRef<Path>>(
         &self,
         method: &ExportMethod<P>,
-    ) -> Result<image::ImageOutputFormat, Box<dyn Error>>;
+    ) -> Result<OutputFormat, Box<dyn Error>>;
 }
 
 pub trait EncodingFormatByExtension {
     /// Determine the encoding format based on the extension of a file path.
-    fn by_extension<P: AsRef<Path>>(
-        &self,
-        path: P,
-    ) -> Result<image::ImageOutputFormat, Box<dyn Error>>;
+    fn by_extension<P: AsRef<Path>>(&self, path: P) -> Result<OutputFormat, Box<dyn Error>>;
 }
 
 pub trait EncodingFormatByIdentifier {
     /// Determine the encoding format based on the method of exporting.
     /// Determine the encoding format based on a recognized given identifier.
-    fn by_identifier(&self, identifier: &str) -> Result<image::ImageOutputFormat, Box<dyn Error>>;
+    fn by_identifier(&self, identifier: &str) -> Result<OutputFormat, Box<dyn Error>>;
 }
 
 pub trait EncodingFormatJPEGQuality {
@@ -35,12 +146,48 @@ pub trait EncodingFormatJPEGQuality {
     fn jpeg_quality(&self) -> Result<JPEGQuality, Box<dyn Error>>;
 }
 
+pub trait EncodingFormatJPEGSettings {
+    /// Returns the settings of the JPEG encoder, besides the quality.
+    /// If no such value exists, it will return an error instead.
+    fn jpeg_settings(&self) -> Result<JpegSettings, Box<dyn Error>>;
+}
+
 pub trait EncodingFormatPNMSampleEncoding {
     /// Returns a pnm sample encoding type.
     /// If no such value exists, it will return an error instead.
     fn pnm_encoding_type(&self) -> Result<image::pnm::SampleEncoding, Box<dyn Error>>;
 }
 
+pub trait EncodingFormatPNGSettings {
+    /// Returns the settings of the PNG encoder.
+    /// If no such value exists, it will return an error instead.
+    fn png_settings(&self) -> Result<PngSettings, Box<dyn Error>>;
+}
+
+pub trait EncodingFormatICOSettings {
+    /// Returns the settings of the ICO encoder.
+    /// If no such value exists, it will return an error instead.
+    fn ico_settings(&self) -> Result<IcoSettings, Box<dyn Error>>;
+}
+
+pub trait EncodingFormatTIFFCompression {
+    /// Returns a tiff compression method.
+    /// If no such value exists, it will return an error instead.
+    fn tiff_compression(&self) -> Result<TiffCompression, Box<dyn Error>>;
+}
+
+pub trait EncodingFormatTGAEncoding {
+    /// Returns a tga encoding type.
+    /// If no such value exists, it will return an error instead.
+    fn tga_encoding(&self) -> Result<TgaEncoding, Box<dyn Error>>;
+}
+
+pub trait EncodingFormatRawFormat {
+    /// Returns the layout of raw pixel data.
+    /// If no such value exists, it will return an error instead.
+    fn raw_format(&self) -> Result<RawFormat, Box<dyn Error>>;
+}
+
 /// This struct ensures no invalid JPEG qualities can be stored.
 /// Using this struct instead of `u8` directly should ensure no panics occur because of invalid
 /// quality values.
@@ -81,9 +228,9 @@ impl EncodingFormatByMethod for DetermineEncodingFormat {
     fn by_method<P: AsRef<Path>>(
         &self,
         method: &ExportMethod<P>,
-    ) -> Result<image::ImageOutputFormat, Box<dyn Error>> {
+    ) -> Result<OutputFormat, Box<dyn Error>> {
         match method {
-            ExportMethod::StdoutBytes => Ok(DEFAULT_PIPED_OUTPUT_FORMAT),
+            ExportMethod::StdoutBytes => Ok(DEFAULT_PIPED_OUTPUT_FORMAT.into()),
             ExportMethod::File(path) => self.by_extension(path),
         }
     }
@@ -94,10 +241,7 @@ impl EncodingFormatByExtension for DetermineEncodingFormat {
     /// If the path has no extension, it will return an error.
     /// The extension if existing is matched against the identifiers, which currently
     /// are the extensions used.
-    fn by_extension<P: AsRef<Path>>(
-        &self,
-        path: P,
-    ) -> Result<image::ImageOutputFormat, Box<dyn Error>> {
+    fn by_extension<P: AsRef<Path>>(&self, path: P) -> Result<OutputFormat, Box<dyn Error>> {
         let extension = path.as_ref().extension().and_then(|v| v.to_str());
 
         match extension {
@@ -113,29 +257,49 @@ impl EncodingFormatByExtension for DetermineEncodingFormat {
 impl EncodingFormatByIdentifier for DetermineEncodingFormat {
     /// Determines an image output format based on a given `&str` identifier.
     /// Identifiers are based on common output file extensions.
-    fn by_identifier(&self, identifier: &str) -> Result<image::ImageOutputFormat, Box<dyn Error>> {
+    fn by_identifier(&self, identifier: &str) -> Result<OutputFormat, Box<dyn Error>> {
         match identifier {
-            "bmp" => Ok(image::ImageOutputFormat::BMP),
-            "gif" => Ok(image::ImageOutputFormat::GIF),
-            "ico" => Ok(image::ImageOutputFormat::ICO),
-            "jpeg" | "jpg" => Ok(image::ImageOutputFormat::JPEG(self.jpeg_quality()?.as_u8())),
-            "png" => Ok(image::ImageOutputFormat::PNG),
-            "pbm" => Ok(image::ImageOutputFormat::PNM(
-                image::pnm::PNMSubtype::Bitmap(self.pnm_encoding_type()?),
-            )),
-            "pgm" => Ok(image::ImageOutputFormat::PNM(
-                image::pnm::PNMSubtype::Graymap(self.pnm_encoding_type()?),
+            "bmp" => Ok(image::ImageOutputFormat::BMP.into()),
+            "gif" => Ok(image::ImageOutputFormat::GIF.into()),
+            "ico" => Ok(OutputFormat::Ico(self.ico_settings()?)),
+            "jpeg" | "jpg" => Ok(OutputFormat::Jpeg(
+                self.jpeg_quality()?,
+                self.jpeg_settings()?,
             )),
-            "ppm" => Ok(image::ImageOutputFormat::PNM(
-                image::pnm::PNMSubtype::Pixmap(self.pnm_encoding_type()?),
-            )),
-            "pam" => Ok(image::ImageOutputFormat::PNM(
-                image::pnm::PNMSubtype::ArbitraryMap,
-            )),
-            _ => Err(From::from(format!(
-                "No supported image output format was found, input: {}.",
-                identifier
-            ))),
+            "png" => Ok(OutputFormat::Png(self.png_settings()?)),
+            "pbm" => Ok(
+                image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Bitmap(
+                    self.pnm_encoding_type()?,
+                ))
+                .into(),
+            ),
+            "pgm" => Ok(
+                image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Graymap(
+                    self.pnm_encoding_type()?,
+                ))
+                .into(),
+            ),
+            "ppm" => Ok(
+                image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Pixmap(
+                    self.pnm_encoding_type()?,
+                ))
+                .into(),
+            ),
+            "pam" => Ok(image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::ArbitraryMap).into()),
+            "tif" | "tiff" => Ok(OutputFormat::Tiff(self.tiff_compression()?)),
+            "tga" => Ok(OutputFormat::Tga(self.tga_encoding()?)),
+            "ff" | "farbfeld" => Ok(OutputFormat::Farbfeld),
+            "raw" => Ok(OutputFormat::Raw(self.raw_format()?)),
+            _ => match find_format(identifier) {
+                Some(format) if !format.write => Err(From::from(format!(
+                    "The {} format ('{}') can only be read; it is not supported as image output format.",
+                    format.name, identifier
+                ))),
+                _ => Err(From::from(format!(
+                    "No supported image output format was found, input: {}.",
+                    identifier
+                ))),
+            },
         }
     }
 }
@@ -143,6 +307,12 @@ impl EncodingFormatByIdentifier for DetermineEncodingFormat {
 pub struct DetermineEncodingFormat {
     pub pnm_sample_encoding: Option<image::pnm::SampleEncoding>,
     pub jpeg_quality: Option<JPEGQuality>,
+    pub jpeg_settings: Option<JpegSettings>,
+    pub png_settings: Option<PngSettings>,
+    pub ico_settings: Option<IcoSettings>,
+    pub tiff_compression: Option<TiffCompression>,
+    pub tga_encoding: Option<TgaEncoding>,
+    pub raw_format: Option<RawFormat>,
 }
 
 impl EncodingFormatPNMSampleEncoding for DetermineEncodingFormat {
@@ -164,20 +334,68 @@ impl EncodingFormatJPEGQuality for DetermineEncodingFormat {
     }
 }
 
+impl EncodingFormatJPEGSettings for DetermineEncodingFormat {
+    fn jpeg_settings(&self) -> Result<JpegSettings, Box<dyn Error>> {
+        self.jpeg_settings.ok_or_else(|| {
+            let message = "Using JPEG requires the encoder settings to be set.";
+            From::from(message.to_string())
+        })
+    }
+}
+
+impl EncodingFormatPNGSettings for DetermineEncodingFormat {
+    fn png_settings(&self) -> Result<PngSettings, Box<dyn Error>> {
+        self.png_settings.ok_or_else(|| {
+            let message = "Using PNG requires the encoder settings to be set.";
+            From::from(message.to_string())
+        })
+    }
+}
+
+impl EncodingFormatICOSettings for DetermineEncodingFormat {
+    fn ico_settings(&self) -> Result<IcoSettings, Box<dyn Error>> {
+        self.ico_settings.clone().ok_or_else(|| {
+            let message = "Using ICO requires the encoder settings to be set.";
+            From::from(message.to_string())
+        })
+    }
+}
+
+impl EncodingFormatTIFFCompression for DetermineEncodingFormat {
+    fn tiff_compression(&self) -> Result<TiffCompression, Box<dyn Error>> {
+        self.tiff_compression.ok_or_else(|| {
+            let message = "Using TIFF requires the compression to be set.";
+            From::from(message.to_string())
+        })
+    }
+}
+
+impl EncodingFormatTGAEncoding for DetermineEncodingFormat {
+    fn tga_encoding(&self) -> Result<TgaEncoding, Box<dyn Error>> {
+        self.tga_encoding.ok_or_else(|| {
+            let message = "Using TGA requires the encoding to be set.";
+            From::from(message.to_string())
+        })
+    }
+}
+
+impl EncodingFormatRawFormat for DetermineEncodingFormat {
+    fn raw_format(&self) -> Result<RawFormat, Box<dyn Error>> {
+        self.raw_format.ok_or_else(|| {
+            let message = "Using raw pixel data requires its layout to be set.";
+            From::from(message.to_string())
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT_FORMATS: &[&str] = &[
-        "bmp", "gif", "ico", "jpg", "jpeg", "png", "pbm", "pgm", "ppm", "pam",
-    ];
+    const INPUT_FORMATS: &[&str] = &["bmp", "gif", "pbm", "pgm", "ppm", "pam"];
     const EXPECTED_VALUES: &[image::ImageOutputFormat] = &[
         image::ImageOutputFormat::BMP,
         image::ImageOutputFormat::GIF,
-        image::ImageOutputFormat::ICO,
-        image::ImageOutputFormat::JPEG(80),
-        image::ImageOutputFormat::JPEG(80),
-        image::ImageOutputFormat::PNG,
         image::ImageOutputFormat::PNM(image::pnm::PNMSubtype::Bitmap(
             image::pnm::SampleEncoding::Binary,
         )),
@@ -194,6 +412,12 @@ mod tests {
         DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Binary),
             jpeg_quality: Some(JPEGQuality::try_from(80).unwrap()),
+            jpeg_settings: Some(JpegSettings::default()),
+            png_settings: Some(PngSettings::default()),
+            ico_settings: Some(IcoSettings::default()),
+            tiff_compression: Some(TiffCompression::default()),
+            tga_encoding: Some(TgaEncoding::default()),
+            raw_format: Some(RawFormat::default()),
         }
     }
 
@@ -305,6 +529,12 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Ascii),
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         let result = format_determiner.by_identifier("pbm").unwrap();
@@ -321,6 +551,12 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Ascii),
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         let result = format_determiner.by_identifier("pgm").unwrap();
@@ -337,6 +573,12 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: Some(image::pnm::SampleEncoding::Ascii),
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         let result = format_determiner.by_identifier("ppm").unwrap();
@@ -353,10 +595,17 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: Some(JPEGQuality::try_from(1).unwrap()),
+            jpeg_settings: Some(JpegSettings::default()),
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         let result = format_determiner.by_identifier("jpg").unwrap();
-        let expected = image::ImageOutputFormat::JPEG(1);
+        let expected =
+            OutputFormat::Jpeg(JPEGQuality::try_from(1).unwrap(), JpegSettings::default());
 
         assert_eq!(result, expected);
     }
@@ -367,10 +616,17 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: Some(JPEGQuality::try_from(100).unwrap()),
+            jpeg_settings: Some(JpegSettings::default()),
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         let result = format_determiner.by_identifier("jpg").unwrap();
-        let expected = image::ImageOutputFormat::JPEG(100);
+        let expected =
+            OutputFormat::Jpeg(JPEGQuality::try_from(100).unwrap(), JpegSettings::default());
 
         assert_eq!(result, expected);
     }
@@ -425,6 +681,12 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         format_determiner.by_identifier("pbm").unwrap();
@@ -437,6 +699,12 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         format_determiner.by_identifier("pgm").unwrap();
@@ -449,6 +717,12 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         format_determiner.by_identifier("ppm").unwrap();
@@ -461,8 +735,137 @@ mod tests {
         let format_determiner = DetermineEncodingFormat {
             pnm_sample_encoding: None,
             jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
         };
 
         format_determiner.by_identifier("jpg").unwrap();
     }
+
+    #[test]
+    fn identifier_tiff() {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: Some(TiffCompression::Lzw),
+            tga_encoding: None,
+            raw_format: None,
+        };
+
+        let result = format_determiner.by_identifier("tif").unwrap();
+        assert_eq!(result, OutputFormat::Tiff(TiffCompression::Lzw));
+
+        let result = format_determiner.by_identifier("tiff").unwrap();
+        assert_eq!(result, OutputFormat::Tiff(TiffCompression::Lzw));
+    }
+
+    #[test]
+    fn identifier_tga_and_farbfeld() {
+        let format_determiner = setup_default_format_determiner();
+
+        let result = format_determiner.by_extension("w_ext.tga").unwrap();
+        assert_eq!(result, OutputFormat::Tga(TgaEncoding::Raw));
+
+        let result = format_determiner.by_extension("w_ext.ff").unwrap();
+        assert_eq!(result, OutputFormat::Farbfeld);
+    }
+
+    // DetermineEncodingFormat has None, while Some required: tiff
+    #[test]
+    #[should_panic]
+    fn identifier_requires_tiff_compression_to_be_set() {
+        let format_determiner = DetermineEncodingFormat {
+            pnm_sample_encoding: None,
+            jpeg_quality: None,
+            jpeg_settings: None,
+            png_settings: None,
+            ico_settings: None,
+            tiff_compression: None,
+            tga_encoding: None,
+            raw_format: None,
+        };
+
+        format_determiner.by_identifier("tiff").unwrap();
+    }
+
+    #[test]
+    fn identifier_read_only_format() {
+        let format_determiner = setup_default_format_determiner();
+
+        let result = format_determiner.by_identifier("webp");
+        assert!(result.unwrap_err().to_string().contains("can only be read"));
+    }
+
+    #[test]
+    fn identifier_unknown_format() {
+        let format_determiner = setup_default_format_determiner();
+
+        let result = format_determiner.by_identifier("exr");
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("No supported image output format was found"));
+    }
+
+    #[test]
+    fn identifier_raw() {
+        let format_determiner = setup_default_format_determiner();
+
+        let result = format_determiner.by_extension("w_ext.raw").unwrap();
+        assert_eq!(result, OutputFormat::Raw(RawFormat::default()));
+    }
+
+    #[test]
+    fn media_types() {
+        let format_determiner = setup_default_format_determiner();
+        let media_type = |identifier| {
+            format_determiner
+                .by_identifier(identifier)
+                .unwrap()
+                .media_type()
+        };
+
+        assert_eq!("image/jpeg", media_type("jpg"));
+        assert_eq!("image/x-portable-graymap", media_type("pgm"));
+        assert_eq!("image/tiff", media_type("tif"));
+        assert_eq!("application/octet-stream", media_type("raw"));
+    }
+
+    #[test]
+    fn quality_of_lossy_formats() {
+        let format_determiner = setup_default_format_determiner();
+
+        let jpeg = format_determiner.by_identifier("jpg").unwrap();
+        let lower = JPEGQuality::try_from(40).unwrap();
+        assert_eq!(Some(lower), jpeg.with_quality(lower).quality());
+
+        let png = format_determiner.by_identifier("png").unwrap();
+        assert_eq!(None, png.quality());
+        assert_eq!(png, png.with_quality(lower));
+    }
+
+    #[test]
+    fn identifier_png() {
+        let format_determiner = setup_default_format_determiner();
+
+        let result = format_determiner.by_identifier("png").unwrap();
+        assert_eq!(result, OutputFormat::Png(PngSettings::default()));
+        assert_eq!("image/png", result.media_type());
+    }
+
+    #[test]
+    fn identifier_ico() {
+        let format_determiner = setup_default_format_determiner();
+
+        let result = format_determiner.by_identifier("ico").unwrap();
+        assert_eq!(result, OutputFormat::Ico(IcoSettings::default()));
+        assert_eq!("image/x-icon", result.media_type());
+    }
 }
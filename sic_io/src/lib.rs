@@ -2,7 +2,17 @@
 pub mod load;
 
 // exporting
+pub mod auto;
+pub mod favicon;
+pub mod pyramid;
+pub mod responsive;
 pub mod save;
+pub mod size_limit;
+pub mod split;
 
 pub mod conversion;
+pub mod encoders;
 pub mod format;
+pub mod raw;
+pub mod support;
+pub mod text;
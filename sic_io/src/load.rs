@@ -4,20 +4,64 @@ use std::io::{BufReader, Read};
 use std::path::Path;
 
 use sic_core::image;
+use sic_core::image::dxt::{DXTDecoder, DXTVariant};
 use sic_core::image::AnimationDecoder;
 
+use crate::raw::RawFormat;
+use crate::support::{find_format, readable_formats};
+
 /// Load an image using a reader.
 /// All images are currently loaded from memory.
 pub fn load_image<R: Read>(
     reader: &mut R,
     config: &ImportConfig,
 ) -> ImportResult<image::DynamicImage> {
+    load_image_with_format(reader, config).map(|(image, _)| image)
+}
+
+/// Load an image using a reader, together with the format in which it was read: the input format
+/// of the import config, or otherwise the format recognized by the contents of the image.
+pub fn load_image_with_format<R: Read>(
+    reader: &mut R,
+    config: &ImportConfig,
+) -> ImportResult<(image::DynamicImage, InputFormat)> {
     let buffer = load(reader)?;
 
-    if starts_with_gif_magic_number(&buffer) {
-        load_gif(&buffer, config.selected_frame)
+    let format = match config.input_format {
+        Some(format) => format,
+        None => guess_format(&buffer)?,
+    };
+
+    let image = match format {
+        InputFormat::Image(image::ImageFormat::GIF) => load_gif(&buffer, config.selected_frame),
+        InputFormat::Image(format) => {
+            image::load_from_memory_with_format(&buffer, format).map_err(From::from)
+        }
+        InputFormat::Dds => load_dds(&buffer),
+        InputFormat::Raw {
+            format,
+            width,
+            height,
+        } => format
+            .decode(&buffer, width, height)
+            .map_err(ImportError::Raw),
+    }?;
+
+    Ok((image, format))
+}
+
+/// Recognizes the format of an image by its contents.
+fn guess_format(buffer: &[u8]) -> ImportResult<InputFormat> {
+    if starts_with_gif_magic_number(buffer) {
+        Ok(InputFormat::Image(image::ImageFormat::GIF))
+    } else if starts_with_dds_magic_number(buffer) {
+        Ok(InputFormat::Dds)
+    } else if let Ok(format) = image::guess_format(buffer) {
+        Ok(InputFormat::Image(format))
+    } else if has_tga_header(buffer) {
+        Ok(InputFormat::Image(image::ImageFormat::TGA))
     } else {
-        image::load_from_memory(&buffer).map_err(From::from)
+        Err(ImportError::UnknownFormat)
     }
 }
 
@@ -45,6 +89,74 @@ fn load<R: Read>(reader: &mut R) -> ImportResult<Vec<u8>> {
 pub struct ImportConfig {
     /// For animated images; decides which frame will be used as static image.
     pub selected_frame: FrameIndex,
+    /// The format of the image. If none is given, the format is recognized by the contents of
+    /// the image.
+    pub input_format: Option<InputFormat>,
+}
+
+/// The format of an input image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    /// A format which is decoded by the image crate.
+    Image(image::ImageFormat),
+    /// A DXT compressed DDS image.
+    Dds,
+    /// Pixel data without a header, of an image of the given size.
+    Raw {
+        format: RawFormat,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl InputFormat {
+    /// Determines the input format based on a recognized identifier, which is based on the common
+    /// file extension of the format. Raw pixel data is excluded, since its layout and size have to
+    /// be given as well.
+    pub fn by_identifier(identifier: &str) -> Result<InputFormat, String> {
+        match identifier.to_lowercase().as_str() {
+            "bmp" => Ok(InputFormat::Image(image::ImageFormat::BMP)),
+            "dds" => Ok(InputFormat::Dds),
+            "gif" => Ok(InputFormat::Image(image::ImageFormat::GIF)),
+            "hdr" => Ok(InputFormat::Image(image::ImageFormat::HDR)),
+            "ico" => Ok(InputFormat::Image(image::ImageFormat::ICO)),
+            "jpeg" | "jpg" => Ok(InputFormat::Image(image::ImageFormat::JPEG)),
+            "png" => Ok(InputFormat::Image(image::ImageFormat::PNG)),
+            "pbm" | "pgm" | "ppm" | "pam" => Ok(InputFormat::Image(image::ImageFormat::PNM)),
+            "tga" => Ok(InputFormat::Image(image::ImageFormat::TGA)),
+            "tif" | "tiff" => Ok(InputFormat::Image(image::ImageFormat::TIFF)),
+            "webp" => Ok(InputFormat::Image(image::ImageFormat::WEBP)),
+            _ => match find_format(identifier) {
+                Some(format) if !format.read => Err(format!(
+                    "The {} format ('{}') can only be written; it is not supported as image input format.",
+                    format.name, identifier
+                )),
+                _ => Err(format!(
+                    "No supported image input format was found, input: {}.",
+                    identifier
+                )),
+            },
+        }
+    }
+    /// The identifier of the format, which can be used to write an image in the same format.
+    /// Since the PNM subtypes aren't distinguished, PNM images are identified as PAM, which can
+    /// store images of each subtype.
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            InputFormat::Image(image::ImageFormat::BMP) => "bmp",
+            InputFormat::Image(image::ImageFormat::GIF) => "gif",
+            InputFormat::Image(image::ImageFormat::HDR) => "hdr",
+            InputFormat::Image(image::ImageFormat::ICO) => "ico",
+            InputFormat::Image(image::ImageFormat::JPEG) => "jpg",
+            InputFormat::Image(image::ImageFormat::PNG) => "png",
+            InputFormat::Image(image::ImageFormat::PNM) => "pam",
+            InputFormat::Image(image::ImageFormat::TGA) => "tga",
+            InputFormat::Image(image::ImageFormat::TIFF) => "tiff",
+            InputFormat::Image(image::ImageFormat::WEBP) => "webp",
+            InputFormat::Dds => "dds",
+            InputFormat::Raw { .. } => "raw",
+        }
+    }
 }
 
 /// Zero-indexed frame index.
@@ -65,6 +177,98 @@ fn starts_with_gif_magic_number(buffer: &[u8]) -> bool {
     buffer.starts_with(b"GIF87a") || buffer.starts_with(b"GIF89a")
 }
 
+fn starts_with_dds_magic_number(buffer: &[u8]) -> bool {
+    buffer.starts_with(b"DDS ")
+}
+
+/// TGA files don't start with a magic number, so instead we check whether the header describes a
+/// (color mapped, true color or grayscale) image with a known pixel depth.
+fn has_tga_header(buffer: &[u8]) -> bool {
+    const IMAGE_TYPES: [u8; 6] = [1, 2, 3, 9, 10, 11];
+    const PIXEL_DEPTHS: [u8; 5] = [8, 15, 16, 24, 32];
+
+    buffer.len() >= 18
+        && buffer[1] <= 1
+        && IMAGE_TYPES.contains(&buffer[2])
+        && buffer[12..16].iter().any(|&byte| byte != 0)
+        && PIXEL_DEPTHS.contains(&buffer[16])
+}
+
+/// The size of the header of a DDS file, including the magic number.
+const DDS_HEADER_SIZE: usize = 128;
+
+/// Loads the main image of a DXT compressed DDS file; mipmaps and other surfaces are ignored.
+fn load_dds(buffer: &[u8]) -> Result<image::DynamicImage, ImportError> {
+    if buffer.len() < DDS_HEADER_SIZE {
+        return Err(ImportError::Dds("The header is incomplete.".to_string()));
+    }
+
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            buffer[offset],
+            buffer[offset + 1],
+            buffer[offset + 2],
+            buffer[offset + 3],
+        ])
+    };
+
+    let (height, width) = (read_u32(12), read_u32(16));
+
+    if width == 0 || height == 0 {
+        return Err(ImportError::Dds("The image has no pixels.".to_string()));
+    }
+
+    // the number of bytes of each compressed block
+    let (variant, block_size) = match &buffer[84..88] {
+        b"DXT1" => (DXTVariant::DXT1, 8),
+        b"DXT3" => (DXTVariant::DXT3, 16),
+        b"DXT5" => (DXTVariant::DXT5, 16),
+        other => {
+            return Err(ImportError::Dds(format!(
+                "The pixel format '{}' is not supported; supported are DXT1, DXT3 and DXT5.",
+                String::from_utf8_lossy(other)
+            )))
+        }
+    };
+
+    // The pixels are compressed in blocks of 4x4 pixels, which may extend beyond the image.
+    let blocks = |length: u32| {
+        length
+            .checked_add((4 - length % 4) % 4)
+            .ok_or_else(|| ImportError::Dds("The image is too large.".to_string()))
+    };
+    let (block_width, block_height) = (blocks(width)?, blocks(height)?);
+
+    let expected = (u64::from(block_width) / 4)
+        .checked_mul(u64::from(block_height) / 4)
+        .and_then(|count| count.checked_mul(block_size));
+    let available = (buffer.len() - DDS_HEADER_SIZE) as u64;
+
+    match expected {
+        Some(expected) if expected <= available => {}
+        _ => {
+            return Err(ImportError::Dds(format!(
+                "The image data is incomplete for an image of {}x{} pixels.",
+                width, height
+            )))
+        }
+    }
+
+    let decoder = DXTDecoder::new(
+        &buffer[DDS_HEADER_SIZE..],
+        block_width,
+        block_height,
+        variant,
+    )?;
+    let mut image = image::DynamicImage::from_decoder(decoder)?;
+
+    if (block_width, block_height) == (width, height) {
+        Ok(image)
+    } else {
+        Ok(image.crop(0, 0, width, height))
+    }
+}
+
 fn load_gif(buffer: &[u8], frame: FrameIndex) -> Result<image::DynamicImage, ImportError> {
     let decoder = image::gif::Decoder::new(&buffer[..])?;
     let frames = decoder.into_frames();
@@ -110,6 +314,12 @@ pub enum ImportError {
     Image(image::ImageError),
     Io(std::io::Error),
     NoSuchFrame(usize, String),
+    /// The DDS file could not be read.
+    Dds(String),
+    /// The raw pixel data doesn't match its given layout or size.
+    Raw(String),
+    /// The format of the image is not one of the formats which can be read.
+    UnknownFormat,
 }
 
 impl From<std::io::Error> for ImportError {
@@ -135,6 +345,12 @@ impl From<ImportError> for String {
                 which + 1,
                 reason,
             ),
+            ImportError::Dds(reason) => format!("Unable to read the DDS image: {}", reason),
+            ImportError::Raw(reason) => format!("Unable to read the raw pixel data: {}", reason),
+            ImportError::UnknownFormat => format!(
+                "The format of the input image could not be recognized. Supported input formats are: {}.",
+                readable_formats().join(", ")
+            ),
         }
     }
 }
@@ -155,6 +371,7 @@ mod tests {
 
         let config = ImportConfig {
             selected_frame: FrameIndex::First,
+            ..ImportConfig::default()
         };
 
         let image = load_image(&mut file_reader(load_path).unwrap(), &config).unwrap();
@@ -170,10 +387,12 @@ mod tests {
 
         let first = ImportConfig {
             selected_frame: FrameIndex::First,
+            ..ImportConfig::default()
         };
 
         let zero = ImportConfig {
             selected_frame: FrameIndex::Nth(0),
+            ..ImportConfig::default()
         };
 
         let first = load_image(&mut file_reader(&load_path).unwrap(), &first).unwrap();
@@ -188,10 +407,12 @@ mod tests {
 
         let first = ImportConfig {
             selected_frame: FrameIndex::First,
+            ..ImportConfig::default()
         };
 
         let zero = ImportConfig {
             selected_frame: FrameIndex::Nth(0),
+            ..ImportConfig::default()
         };
 
         let first = load_image(&mut file_reader(&load_path).unwrap(), &first).unwrap();
@@ -220,6 +441,7 @@ mod tests {
 
             let config = ImportConfig {
                 selected_frame: FrameIndex::Nth(i),
+                ..ImportConfig::default()
             };
 
             let image = load_image(&mut file_reader(load_path).unwrap(), &config).unwrap();
@@ -235,6 +457,7 @@ mod tests {
 
             let config = ImportConfig {
                 selected_frame: FrameIndex::Nth(i),
+                ..ImportConfig::default()
             };
 
             let image = load_image(&mut file_reader(load_path).unwrap(), &config).unwrap();
@@ -249,6 +472,7 @@ mod tests {
 
         let config = ImportConfig {
             selected_frame: FrameIndex::Nth(8),
+            ..ImportConfig::default()
         };
 
         let result = load_image(&mut file_reader(load_path).unwrap(), &config);
@@ -262,6 +486,7 @@ mod tests {
 
         let config = ImportConfig {
             selected_frame: FrameIndex::Nth(8),
+            ..ImportConfig::default()
         };
 
         let result = load_image(&mut file_reader(load_path).unwrap(), &config);
@@ -274,10 +499,12 @@ mod tests {
 
         let last = ImportConfig {
             selected_frame: FrameIndex::Last,
+            ..ImportConfig::default()
         };
 
         let seven = ImportConfig {
             selected_frame: FrameIndex::Nth(7),
+            ..ImportConfig::default()
         };
 
         let last = load_image(&mut file_reader(&load_path).unwrap(), &last).unwrap();
@@ -292,10 +519,12 @@ mod tests {
 
         let last = ImportConfig {
             selected_frame: FrameIndex::Last,
+            ..ImportConfig::default()
         };
 
         let seven = ImportConfig {
             selected_frame: FrameIndex::Nth(7),
+            ..ImportConfig::default()
         };
 
         let last = load_image(&mut file_reader(&load_path).unwrap(), &last).unwrap();
@@ -319,4 +548,162 @@ mod tests {
             assert!(result.is_ok());
         }
     }
+
+    fn load_test_image(path: &str) -> ImportResult<image::DynamicImage> {
+        let config = ImportConfig::default();
+        load_image(&mut file_reader(setup_test_image(path)).unwrap(), &config)
+    }
+
+    // Each is a lossless copy of rainbow_8x6.bmp.
+    const LOSSLESS_RAINBOWS: [&str; 3] = ["rainbow_8x6.ico", "rainbow_8x6.tga", "rainbow_8x6.tiff"];
+
+    #[test]
+    fn load_lossless_formats() {
+        let expected = load_test_image("rainbow_8x6.bmp")
+            .unwrap()
+            .to_rgb()
+            .into_raw();
+
+        for path in LOSSLESS_RAINBOWS.iter() {
+            let image = load_test_image(path).unwrap();
+            assert_eq!(expected, image.to_rgb().into_raw(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn load_hdr() {
+        let image = load_test_image("rainbow_8x6.hdr").unwrap();
+
+        // the high dynamic range colors are mapped to 8 bit colors, so only the size is compared
+        assert_eq!((8, 6), image.dimensions());
+    }
+
+    #[test]
+    fn load_dds() {
+        let image = load_test_image("rainbow_8x6.dds").unwrap();
+
+        // the last row of compressed blocks is cropped
+        assert_eq!((8, 6), image.dimensions());
+    }
+
+    #[test]
+    fn load_dds_unsupported_pixel_format() {
+        let mut buffer = std::fs::read(setup_test_image("rainbow_8x6.dds")).unwrap();
+        buffer[84..88].copy_from_slice(b"DX10");
+
+        match load_image(&mut buffer.as_slice(), &ImportConfig::default()) {
+            Err(ImportError::Dds(reason)) => assert!(reason.contains("DX10")),
+            _ => panic!("Expected an unsupported pixel format."),
+        }
+    }
+
+    #[test]
+    fn load_dds_truncated() {
+        let buffer = std::fs::read(setup_test_image("rainbow_8x6.dds")).unwrap();
+
+        match load_image(&mut &buffer[..buffer.len() - 1], &ImportConfig::default()) {
+            Err(ImportError::Dds(reason)) => assert!(reason.contains("incomplete")),
+            _ => panic!("Expected incomplete image data."),
+        }
+    }
+
+    #[test]
+    fn load_dds_oversized_header() {
+        let mut buffer = std::fs::read(setup_test_image("rainbow_8x6.dds")).unwrap();
+        // the height and width are the largest possible
+        buffer[12..20].copy_from_slice(&[0xff; 8]);
+
+        match load_image(&mut buffer.as_slice(), &ImportConfig::default()) {
+            Err(ImportError::Dds(_)) => {}
+            _ => panic!("Expected an invalid DDS image."),
+        }
+    }
+
+    #[test]
+    fn load_webp() {
+        let image = load_test_image("gray_20x12.webp").unwrap();
+
+        assert_eq!((20, 12), image.dimensions());
+        assert_eq!(128, image.to_luma().get_pixel(10, 6).0[0]);
+    }
+
+    #[test]
+    fn load_unknown_format() {
+        let mut buffer: &[u8] = b"not an image";
+        let result = load_image(&mut buffer, &ImportConfig::default());
+
+        match result {
+            Err(ImportError::UnknownFormat) => {}
+            _ => panic!("Expected an unknown format."),
+        }
+    }
+
+    #[test]
+    fn load_with_input_format() {
+        let config = ImportConfig {
+            input_format: Some(InputFormat::Image(image::ImageFormat::TGA)),
+            ..ImportConfig::default()
+        };
+
+        let path = setup_test_image("rainbow_8x6.tga");
+        let image = load_image(&mut file_reader(path).unwrap(), &config).unwrap();
+        assert_eq!((8, 6), image.dimensions());
+    }
+
+    #[test]
+    fn load_with_input_format_does_not_guess() {
+        let config = ImportConfig {
+            input_format: Some(InputFormat::Image(image::ImageFormat::PNG)),
+            ..ImportConfig::default()
+        };
+
+        let path = setup_test_image("rainbow_8x6.bmp");
+        assert!(load_image(&mut file_reader(path).unwrap(), &config).is_err());
+    }
+
+    #[test]
+    fn load_raw() {
+        let config = ImportConfig {
+            input_format: Some(InputFormat::Raw {
+                format: RawFormat::default(),
+                width: 2,
+                height: 1,
+            }),
+            ..ImportConfig::default()
+        };
+
+        let mut buffer: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+        let image = load_image(&mut buffer, &config).unwrap();
+        assert_eq!([5, 6, 7, 8], image.get_pixel(1, 0).0);
+
+        let mut buffer: &[u8] = &[1, 2, 3, 4, 5, 6, 7];
+        match load_image(&mut buffer, &config) {
+            Err(ImportError::Raw(_)) => {}
+            _ => panic!("Expected too few bytes for a raw image."),
+        }
+    }
+
+    #[test]
+    fn load_recognized_format() {
+        let path = setup_test_image("rainbow_8x6.tga");
+        let (_, format) =
+            load_image_with_format(&mut file_reader(path).unwrap(), &ImportConfig::default())
+                .unwrap();
+
+        assert_eq!(InputFormat::Image(image::ImageFormat::TGA), format);
+        assert_eq!("tga", format.identifier());
+    }
+
+    #[test]
+    fn input_format_by_identifier() {
+        assert_eq!(
+            InputFormat::Image(image::ImageFormat::TIFF),
+            InputFormat::by_identifier("TIF").unwrap()
+        );
+        assert_eq!(InputFormat::Dds, InputFormat::by_identifier("dds").unwrap());
+
+        let write_only = InputFormat::by_identifier("ff").unwrap_err();
+        assert!(write_only.contains("can only be written"));
+        assert!(InputFormat::by_identifier("exr").is_err());
+    }
 }
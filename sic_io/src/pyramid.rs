@@ -0,0 +1,256 @@
+//! Builds a pyramid of tiles from a (large) image, which can be shown by deep zoom viewers such as
+//! OpenSeadragon or Leaflet. Each level of the pyramid is half the size of the level above it, and
+//! is cut into tiles; see [export_pyramid](crate::save::export_pyramid).
+
+use std::path::{Path, PathBuf};
+
+use sic_core::image::FilterType;
+
+use crate::split::{EdgePolicy, Split, SplitMode, Tile};
+
+/// How the tiles of the pyramid are laid out on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyramidLayout {
+    /// DeepZoom: a `.dzi` descriptor, with the tiles at `<name>_files/<level>/<col>_<row>.<ext>`.
+    /// Level 0 is a single pixel, and the last level is the image at its full size.
+    DeepZoom,
+    /// Slippy map tiles at `<directory>/<z>/<x>/<y>.<ext>`. At zoom level 0 the image fits a
+    /// single tile, and the last zoom level is the image at its full size. Tiles at the right and
+    /// bottom edges are padded with transparent pixels to the full tile size.
+    Xyz,
+}
+
+impl PyramidLayout {
+    pub fn try_from_str(val: &str) -> Result<PyramidLayout, String> {
+        match val.to_lowercase().as_str() {
+            "dzi" | "deepzoom" => Ok(PyramidLayout::DeepZoom),
+            "xyz" => Ok(PyramidLayout::Xyz),
+            fail => Err(format!("No such pyramid layout: {}", fail)),
+        }
+    }
+
+    /// The format of the tiles, unless another output format is given.
+    pub fn default_format(self) -> &'static str {
+        match self {
+            PyramidLayout::DeepZoom => "jpg",
+            PyramidLayout::Xyz => "png",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pyramid {
+    pub layout: PyramidLayout,
+    /// The width and height of a tile, without its overlap.
+    pub tile_size: u32,
+    /// The number of pixels by which each tile extends into its neighbouring tiles.
+    pub overlap: u32,
+    /// The filter used to scale the image down to each level.
+    pub filter: FilterType,
+}
+
+impl Pyramid {
+    /// A pyramid with the tile size and overlap commonly used for the layout: tiles of 254 pixels
+    /// with an overlap of 1 pixel for DeepZoom, and tiles of 256 pixels for XYZ.
+    pub fn new(layout: PyramidLayout) -> Self {
+        let (tile_size, overlap) = match layout {
+            PyramidLayout::DeepZoom => (254, 1),
+            PyramidLayout::Xyz => (256, 0),
+        };
+
+        Pyramid {
+            layout,
+            tile_size,
+            overlap,
+            filter: FilterType::Gaussian,
+        }
+    }
+
+    /// The (width, height) of each level of the pyramid of an image with the given dimensions,
+    /// from the smallest level (level 0) to the image at its full size.
+    pub fn levels(&self, width: u32, height: u32) -> Result<Vec<(u32, u32)>, String> {
+        if self.tile_size == 0 {
+            return Err("Pyramid: The tile size should be larger than 0.".into());
+        }
+
+        let longest = width.max(height);
+        let deepest = match self.layout {
+            PyramidLayout::DeepZoom => ceil_log2(longest),
+            PyramidLayout::Xyz => ceil_log2(div_ceil(longest, u64::from(self.tile_size))),
+        };
+
+        Ok((0..=deepest)
+            .map(|level| {
+                let scale = deepest - level;
+                // a level is at least 1 pixel wide and high
+                let shrink = |length| div_ceil(length, 1 << scale).max(1);
+                (shrink(width), shrink(height))
+            })
+            .collect())
+    }
+
+    /// Divides a level into tiles.
+    pub fn split(&self) -> Split {
+        Split {
+            mode: SplitMode::TileSize(self.tile_size, self.tile_size),
+            overlap: self.overlap,
+            edge: match self.layout {
+                PyramidLayout::DeepZoom => EdgePolicy::Keep,
+                PyramidLayout::Xyz => EdgePolicy::Pad,
+            },
+        }
+    }
+
+    /// The directory in which the tiles are written, given the output path: for DeepZoom the
+    /// `<name>_files` directory next to the `.dzi` descriptor, for XYZ the output path itself.
+    pub fn tiles_directory(&self, output: &Path) -> PathBuf {
+        match self.layout {
+            PyramidLayout::DeepZoom => {
+                let stem = output
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                output.with_file_name(format!("{}_files", stem))
+            }
+            PyramidLayout::Xyz => output.to_path_buf(),
+        }
+    }
+
+    /// The path of a tile of the given level, within the tiles directory.
+    pub fn tile_path(&self, directory: &Path, level: u32, tile: &Tile, extension: &str) -> PathBuf {
+        let level = directory.join(level.to_string());
+
+        match self.layout {
+            PyramidLayout::DeepZoom => {
+                level.join(format!("{}_{}.{}", tile.column, tile.row, extension))
+            }
+            PyramidLayout::Xyz => level
+                .join(tile.column.to_string())
+                .join(format!("{}.{}", tile.row, extension)),
+        }
+    }
+
+    /// The descriptor of the pyramid of an image with the given dimensions, if the layout has one.
+    pub fn descriptor(&self, width: u32, height: u32, extension: &str) -> Option<String> {
+        match self.layout {
+            PyramidLayout::DeepZoom => Some(format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <Image xmlns=\"http://schemas.microsoft.com/deepzoom/2008\" Format=\"{}\" Overlap=\"{}\" TileSize=\"{}\">\n    \
+                 <Size Width=\"{}\" Height=\"{}\"/>\n\
+                 </Image>\n",
+                extension, self.overlap, self.tile_size, width, height
+            )),
+            PyramidLayout::Xyz => None,
+        }
+    }
+}
+
+/// The smallest n for which 2^n is at least the given value.
+fn ceil_log2(value: u32) -> u32 {
+    if value <= 1 {
+        0
+    } else {
+        32 - (value - 1).leading_zeros()
+    }
+}
+
+fn div_ceil(length: u32, divisor: u64) -> u32 {
+    let length = u64::from(length);
+    let rounded = length / divisor + (length % divisor).min(1);
+    rounded as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(row: u32, column: u32) -> Tile {
+        Tile {
+            row,
+            column,
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        }
+    }
+
+    #[test]
+    fn deep_zoom_levels() {
+        let levels = Pyramid::new(PyramidLayout::DeepZoom).levels(8, 5).unwrap();
+
+        assert_eq!(vec![(1, 1), (2, 2), (4, 3), (8, 5)], levels);
+    }
+
+    #[test]
+    fn xyz_levels() {
+        let mut pyramid = Pyramid::new(PyramidLayout::Xyz);
+        pyramid.tile_size = 4;
+
+        assert_eq!(
+            vec![(3, 2), (5, 3), (10, 5)],
+            pyramid.levels(10, 5).unwrap()
+        );
+        assert_eq!(vec![(4, 4)], pyramid.levels(4, 4).unwrap());
+    }
+
+    #[test]
+    fn single_pixel() {
+        let levels = Pyramid::new(PyramidLayout::DeepZoom).levels(1, 1).unwrap();
+
+        assert_eq!(vec![(1, 1)], levels);
+    }
+
+    #[test]
+    fn err_empty_tile() {
+        let mut pyramid = Pyramid::new(PyramidLayout::Xyz);
+        pyramid.tile_size = 0;
+
+        assert!(pyramid.levels(4, 4).is_err());
+    }
+
+    #[test]
+    fn deep_zoom_paths() {
+        let pyramid = Pyramid::new(PyramidLayout::DeepZoom);
+        let directory = pyramid.tiles_directory(Path::new("out/scan.dzi"));
+
+        assert_eq!(PathBuf::from("out/scan_files"), directory);
+        assert_eq!(
+            PathBuf::from("out/scan_files/12/3_1.jpg"),
+            pyramid.tile_path(&directory, 12, &tile(1, 3), "jpg")
+        );
+    }
+
+    #[test]
+    fn xyz_paths() {
+        let pyramid = Pyramid::new(PyramidLayout::Xyz);
+        let directory = pyramid.tiles_directory(Path::new("out/tiles"));
+
+        assert_eq!(
+            PathBuf::from("out/tiles/2/3/1.png"),
+            pyramid.tile_path(&directory, 2, &tile(1, 3), "png")
+        );
+    }
+
+    #[test]
+    fn deep_zoom_descriptor() {
+        let descriptor = Pyramid::new(PyramidLayout::DeepZoom)
+            .descriptor(1000, 800, "jpg")
+            .unwrap();
+
+        assert!(descriptor.contains("Format=\"jpg\" Overlap=\"1\" TileSize=\"254\">"));
+        assert!(descriptor.contains("<Size Width=\"1000\" Height=\"800\"/>"));
+        assert!(Pyramid::new(PyramidLayout::Xyz)
+            .descriptor(1000, 800, "png")
+            .is_none());
+    }
+
+    #[test]
+    fn layout_from_str() {
+        assert_eq!(
+            PyramidLayout::DeepZoom,
+            PyramidLayout::try_from_str("DZI").unwrap()
+        );
+        assert!(PyramidLayout::try_from_str("tms").is_err());
+    }
+}
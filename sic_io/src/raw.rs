@@ -0,0 +1,308 @@
+//! Raw pixel data: the samples of the pixels, row by row from the top left, without a header.
+//! Since nothing describes the pixels, their layout has to be given by a [RawFormat](RawFormat).
+
+use std::error::Error;
+use std::io::Write;
+
+use sic_core::image::{DynamicImage, ImageBuffer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawColorType {
+    Gray,
+    GrayAlpha,
+    Rgb,
+    Rgba,
+    Bgr,
+    Bgra,
+}
+
+impl RawColorType {
+    pub fn try_from_str(val: &str) -> Result<RawColorType, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "gray" | "grey" => Ok(RawColorType::Gray),
+            "graya" | "greya" => Ok(RawColorType::GrayAlpha),
+            "rgb" => Ok(RawColorType::Rgb),
+            "rgba" => Ok(RawColorType::Rgba),
+            "bgr" => Ok(RawColorType::Bgr),
+            "bgra" => Ok(RawColorType::Bgra),
+            fail => Err(format!("No such raw color type: {}", fail).into()),
+        }
+    }
+
+    fn channels(self) -> usize {
+        match self {
+            RawColorType::Gray => 1,
+            RawColorType::GrayAlpha => 2,
+            RawColorType::Rgb | RawColorType::Bgr => 3,
+            RawColorType::Rgba | RawColorType::Bgra => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleDepth {
+    Eight,
+    /// Samples of 16 bits are scaled to 8 bits when read, and scaled up from 8 bits when written.
+    Sixteen,
+}
+
+impl SampleDepth {
+    pub fn try_from_str(val: &str) -> Result<SampleDepth, Box<dyn Error>> {
+        match val {
+            "8" => Ok(SampleDepth::Eight),
+            "16" => Ok(SampleDepth::Sixteen),
+            fail => Err(format!("No such raw sample depth: {}", fail).into()),
+        }
+    }
+
+    fn bytes(self) -> usize {
+        match self {
+            SampleDepth::Eight => 1,
+            SampleDepth::Sixteen => 2,
+        }
+    }
+}
+
+/// The order of the bytes of a sample, if a sample is larger than a single byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl ByteOrder {
+    pub fn try_from_str(val: &str) -> Result<ByteOrder, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "little" | "le" => Ok(ByteOrder::LittleEndian),
+            "big" | "be" => Ok(ByteOrder::BigEndian),
+            fail => Err(format!("No such byte order: {}", fail).into()),
+        }
+    }
+}
+
+/// The layout of raw pixel data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawFormat {
+    pub color_type: RawColorType,
+    pub sample_depth: SampleDepth,
+    pub byte_order: ByteOrder,
+}
+
+impl Default for RawFormat {
+    fn default() -> Self {
+        RawFormat {
+            color_type: RawColorType::Rgba,
+            sample_depth: SampleDepth::Eight,
+            byte_order: ByteOrder::LittleEndian,
+        }
+    }
+}
+
+impl RawFormat {
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.color_type.channels() * self.sample_depth.bytes()
+    }
+
+    /// Reads an image of the given size from raw pixel data. The data should contain exactly the
+    /// pixels of the image.
+    pub fn decode(&self, buffer: &[u8], width: u32, height: u32) -> Result<DynamicImage, String> {
+        let expected = u64::from(width) * u64::from(height) * self.bytes_per_pixel() as u64;
+
+        if width == 0 || height == 0 {
+            return Err("The width and height of raw pixel data should be positive.".to_string());
+        }
+
+        if buffer.len() as u64 != expected {
+            return Err(format!(
+                "Expected {} bytes of raw pixel data for an image of {}x{} pixels, with {} bytes per pixel, but found {} bytes.",
+                expected,
+                width,
+                height,
+                self.bytes_per_pixel(),
+                buffer.len()
+            ));
+        }
+
+        let samples = match self.sample_depth {
+            SampleDepth::Eight => buffer.to_vec(),
+            SampleDepth::Sixteen => buffer
+                .chunks(2)
+                .map(|bytes| {
+                    let sample = match self.byte_order {
+                        ByteOrder::LittleEndian => u16::from_le_bytes([bytes[0], bytes[1]]),
+                        ByteOrder::BigEndian => u16::from_be_bytes([bytes[0], bytes[1]]),
+                    };
+
+                    // rounds to the nearest 8 bit sample
+                    ((u32::from(sample) * 255 + 32767) / 65535) as u8
+                })
+                .collect(),
+        };
+
+        let invalid = || "Unable to create an image from the raw pixel data.".to_string();
+
+        let image = match self.color_type {
+            RawColorType::Gray => DynamicImage::ImageLuma8(
+                ImageBuffer::from_raw(width, height, samples).ok_or_else(invalid)?,
+            ),
+            RawColorType::GrayAlpha => DynamicImage::ImageLumaA8(
+                ImageBuffer::from_raw(width, height, samples).ok_or_else(invalid)?,
+            ),
+            RawColorType::Rgb => DynamicImage::ImageRgb8(
+                ImageBuffer::from_raw(width, height, samples).ok_or_else(invalid)?,
+            ),
+            RawColorType::Rgba => DynamicImage::ImageRgba8(
+                ImageBuffer::from_raw(width, height, samples).ok_or_else(invalid)?,
+            ),
+            RawColorType::Bgr => DynamicImage::ImageBgr8(
+                ImageBuffer::from_raw(width, height, samples).ok_or_else(invalid)?,
+            ),
+            RawColorType::Bgra => DynamicImage::ImageBgra8(
+                ImageBuffer::from_raw(width, height, samples).ok_or_else(invalid)?,
+            ),
+        };
+
+        Ok(image)
+    }
+
+    /// Writes the pixels of the image as raw pixel data; the image is converted to the color type
+    /// of the layout first.
+    pub fn encode<W: Write>(&self, image: &DynamicImage, writer: &mut W) -> Result<(), String> {
+        let samples = match self.color_type {
+            RawColorType::Gray => image.to_luma().into_raw(),
+            RawColorType::GrayAlpha => image.to_luma_alpha().into_raw(),
+            RawColorType::Rgb => image.to_rgb().into_raw(),
+            RawColorType::Rgba => image.to_rgba().into_raw(),
+            RawColorType::Bgr => image.to_bgr().into_raw(),
+            RawColorType::Bgra => image.to_bgra().into_raw(),
+        };
+
+        let bytes = match self.sample_depth {
+            SampleDepth::Eight => samples,
+            SampleDepth::Sixteen => samples
+                .into_iter()
+                .flat_map(|sample| {
+                    // 0xff becomes 0xffff
+                    let sample = u16::from(sample) * 257;
+
+                    match self.byte_order {
+                        ByteOrder::LittleEndian => sample.to_le_bytes(),
+                        ByteOrder::BigEndian => sample.to_be_bytes(),
+                    }
+                    .to_vec()
+                })
+                .collect(),
+        };
+
+        writer.write_all(&bytes).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::GenericImageView;
+
+    #[test]
+    fn decode_rgba() {
+        let buffer = [255, 0, 0, 255, 0, 255, 0, 128];
+        let image = RawFormat::default().decode(&buffer, 2, 1).unwrap();
+
+        assert_eq!([255, 0, 0, 255], image.get_pixel(0, 0).0);
+        assert_eq!([0, 255, 0, 128], image.get_pixel(1, 0).0);
+    }
+
+    #[test]
+    fn decode_bgr() {
+        let format = RawFormat {
+            color_type: RawColorType::Bgr,
+            ..RawFormat::default()
+        };
+        let image = format.decode(&[1, 2, 3], 1, 1).unwrap();
+
+        assert_eq!([3, 2, 1, 255], image.get_pixel(0, 0).0);
+    }
+
+    #[test]
+    fn decode_sixteen_bits() {
+        let little = RawFormat {
+            color_type: RawColorType::Gray,
+            sample_depth: SampleDepth::Sixteen,
+            byte_order: ByteOrder::LittleEndian,
+        };
+        let big = RawFormat {
+            byte_order: ByteOrder::BigEndian,
+            ..little
+        };
+
+        // 0xffff, 0x8080 and 0x0000
+        let buffer = [0xff, 0xff, 0x80, 0x80, 0x00, 0x00];
+        let expected = vec![255, 128, 0];
+
+        assert_eq!(expected, little.decode(&buffer, 3, 1).unwrap().raw_pixels());
+        assert_eq!(expected, big.decode(&buffer, 3, 1).unwrap().raw_pixels());
+
+        // 0x00ff is 0xff00 in the other byte order
+        let buffer = [0xff, 0x00];
+        assert_eq!(vec![1], little.decode(&buffer, 1, 1).unwrap().raw_pixels());
+        assert_eq!(vec![254], big.decode(&buffer, 1, 1).unwrap().raw_pixels());
+    }
+
+    #[test]
+    fn encode_gray_sixteen_bits() {
+        let format = RawFormat {
+            color_type: RawColorType::Gray,
+            sample_depth: SampleDepth::Sixteen,
+            byte_order: ByteOrder::BigEndian,
+        };
+        let image =
+            DynamicImage::ImageLuma8(ImageBuffer::from_raw(2, 1, vec![0x12, 0xff]).unwrap());
+
+        let mut bytes = Vec::new();
+        format.encode(&image, &mut bytes).unwrap();
+
+        assert_eq!(vec![0x12, 0x12, 0xff, 0xff], bytes);
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let format = RawFormat {
+            color_type: RawColorType::Bgra,
+            sample_depth: SampleDepth::Sixteen,
+            byte_order: ByteOrder::LittleEndian,
+        };
+        let image = DynamicImage::ImageRgba8(
+            ImageBuffer::from_raw(2, 1, vec![1, 2, 3, 4, 250, 251, 252, 253]).unwrap(),
+        );
+
+        let mut bytes = Vec::new();
+        format.encode(&image, &mut bytes).unwrap();
+        assert_eq!(16, bytes.len());
+
+        let decoded = format.decode(&bytes, 2, 1).unwrap();
+        assert_eq!(image.to_rgba().into_raw(), decoded.to_rgba().into_raw());
+    }
+
+    #[test]
+    fn err_size_mismatch() {
+        match RawFormat::default().decode(&[0; 15], 2, 2) {
+            Err(message) => assert!(message.contains("Expected 16 bytes")),
+            Ok(_) => panic!("Expected a size mismatch."),
+        }
+    }
+
+    #[test]
+    fn err_empty() {
+        assert!(RawFormat::default().decode(&[], 0, 4).is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            RawColorType::GrayAlpha,
+            RawColorType::try_from_str("GrayA").unwrap()
+        );
+        assert_eq!(ByteOrder::BigEndian, ByteOrder::try_from_str("be").unwrap());
+        assert!(SampleDepth::try_from_str("12").is_err());
+    }
+}
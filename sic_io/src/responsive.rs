@@ -0,0 +1,356 @@
+//! Scales an image to several widths, each of which is exported in one or more formats, for use
+//! in the `srcset` of an HTML image; see [export_responsive](crate::save::export_responsive).
+//! The exported images are described by a manifest.
+
+use std::path::{Path, PathBuf};
+
+use sic_core::image::{DynamicImage, FilterType, GenericImageView};
+use sic_core::json::escape_json;
+
+const WIDTH_PLACEHOLDER: &str = "{width}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponsiveSet {
+    /// The widths to which the image is scaled, keeping its aspect ratio. Widths larger than the
+    /// width of the image are skipped, so images are never scaled up.
+    pub widths: Vec<u32>,
+}
+
+/// An exported image of the set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub path: PathBuf,
+    /// The identifier of the format of the image, which is also its extension.
+    pub format: String,
+    pub width: u32,
+    pub height: u32,
+    /// The size of the encoded image in bytes.
+    pub bytes: u64,
+}
+
+/// A path template should contain the `{width}` placeholder, so each width is written to a
+/// separate file.
+pub fn validate_path_template(template: &str) -> Result<(), String> {
+    if template.contains(WIDTH_PLACEHOLDER) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The output path '{}' should contain the '{}' placeholder, when creating a responsive image set.",
+            template, WIDTH_PLACEHOLDER
+        ))
+    }
+}
+
+/// The path of an image of the given width and format: the path template with `{width}` replaced
+/// by the width, and with the format as its extension.
+pub fn variant_path(template: &str, width: u32, format: &str) -> PathBuf {
+    PathBuf::from(template.replace(WIDTH_PLACEHOLDER, &width.to_string())).with_extension(format)
+}
+
+impl ResponsiveSet {
+    /// The (width, height) of each image of the set, from small to large.
+    pub fn sizes(&self, width: u32, height: u32) -> Result<Vec<(u32, u32)>, String> {
+        let mut widths = self
+            .widths
+            .iter()
+            .cloned()
+            .filter(|&target| target > 0 && target <= width)
+            .collect::<Vec<_>>();
+        widths.sort();
+        widths.dedup();
+
+        if widths.is_empty() {
+            return Err(format!(
+                "Responsive: None of the widths is positive and at most the width of the image ({}).",
+                width
+            ));
+        }
+
+        Ok(widths
+            .into_iter()
+            .map(|target| {
+                let scaled = (f64::from(height) * f64::from(target) / f64::from(width)).round();
+                (target, (scaled as u32).max(1))
+            })
+            .collect())
+    }
+
+    /// Scales the image to each size of the set, with the given filter.
+    pub fn scale(
+        &self,
+        image: &DynamicImage,
+        filter: FilterType,
+    ) -> Result<Vec<DynamicImage>, String> {
+        let (width, height) = image.dimensions();
+
+        Ok(self
+            .sizes(width, height)?
+            .into_iter()
+            .map(|(target_width, target_height)| {
+                if (target_width, target_height) == (width, height) {
+                    image.clone()
+                } else {
+                    image.resize_exact(target_width, target_height, filter)
+                }
+            })
+            .collect())
+    }
+}
+
+/// The format of the manifest which describes the exported images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Manifest {
+    /// The sizes and byte counts of the images, and the `srcset` of each format.
+    Json,
+    /// A `<picture>` element, with a `<source>` for each format but the last, and an `<img>` for
+    /// the last format.
+    Html,
+}
+
+impl Manifest {
+    /// The format of the manifest, determined by the extension of its path.
+    pub fn by_extension(path: &Path) -> Result<Manifest, String> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+
+        match extension.as_ref().map(String::as_str) {
+            Some("json") => Ok(Manifest::Json),
+            Some("html") | Some("htm") => Ok(Manifest::Html),
+            _ => Err(format!(
+                "Unable to determine the format of the manifest '{}'; its extension should be 'json' or 'html'.",
+                path.display()
+            )),
+        }
+    }
+
+    /// Describes the exported images, which are referred to relative to the directory of the
+    /// manifest at the given path.
+    pub fn describe(self, variants: &[Variant], manifest_path: &Path) -> String {
+        let base = manifest_path.parent().unwrap_or_else(|| Path::new(""));
+
+        match self {
+            Manifest::Json => json(variants, base),
+            Manifest::Html => html(variants, base),
+        }
+    }
+}
+
+/// The formats of the variants, in order of first appearance.
+fn formats(variants: &[Variant]) -> Vec<&str> {
+    let mut formats = Vec::new();
+
+    for variant in variants {
+        if !formats.contains(&variant.format.as_str()) {
+            formats.push(variant.format.as_str());
+        }
+    }
+
+    formats
+}
+
+/// The `srcset` attribute value of the variants of a format: `<path> <width>w, ...`.
+fn srcset(variants: &[Variant], format: &str, base: &Path) -> String {
+    variants
+        .iter()
+        .filter(|variant| variant.format == format)
+        .map(|variant| format!("{} {}w", relative(&variant.path, base), variant.width))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The path as used in the manifest: relative to the base directory if possible, with forward
+/// slashes.
+fn relative(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn json(variants: &[Variant], base: &Path) -> String {
+    let images = variants
+        .iter()
+        .map(|variant| {
+            format!(
+                "\t\t{{\"path\": \"{}\", \"format\": \"{}\", \"width\": {}, \"height\": {}, \"bytes\": {}}}",
+                escape_json(&relative(&variant.path, base)),
+                escape_json(&variant.format),
+                variant.width,
+                variant.height,
+                variant.bytes
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    let srcsets = formats(variants)
+        .into_iter()
+        .map(|format| {
+            format!(
+                "\t\t\"{}\": \"{}\"",
+                escape_json(format),
+                escape_json(&srcset(variants, format, base))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        "{{\n\t\"images\": [\n{}\n\t],\n\t\"srcset\": {{\n{}\n\t}}\n}}\n",
+        images, srcsets
+    )
+}
+
+fn html(variants: &[Variant], base: &Path) -> String {
+    let formats = formats(variants);
+    let (fallback, sources) = match formats.split_last() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+
+    let mut lines = vec!["<picture>".to_string()];
+
+    for format in sources {
+        lines.push(format!(
+            "    <source type=\"{}\" srcset=\"{}\" sizes=\"100vw\">",
+            mime_type(format),
+            escape_xml(&srcset(variants, format, base))
+        ));
+    }
+
+    // the largest image of the fallback format is used by browsers which don't support srcset
+    if let Some(largest) = variants
+        .iter()
+        .filter(|variant| variant.format == *fallback)
+        .max_by_key(|variant| variant.width)
+    {
+        lines.push(format!(
+            "    <img src=\"{}\" srcset=\"{}\" sizes=\"100vw\" width=\"{}\" height=\"{}\" alt=\"\">",
+            escape_xml(&relative(&largest.path, base)),
+            escape_xml(&srcset(variants, fallback, base)),
+            largest.width,
+            largest.height
+        ));
+    }
+
+    lines.push("</picture>\n".to_string());
+    lines.join("\n")
+}
+
+fn mime_type(format: &str) -> String {
+    match format {
+        "jpg" | "jpeg" => "image/jpeg".to_string(),
+        "tif" => "image/tiff".to_string(),
+        "ico" => "image/x-icon".to_string(),
+        "pbm" | "pgm" | "ppm" | "pam" => format!("image/x-portable-{}", format),
+        other => format!("image/{}", other),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(path: &str, format: &str, width: u32, bytes: u64) -> Variant {
+        Variant {
+            path: PathBuf::from(path),
+            format: format.to_string(),
+            width,
+            height: width / 2,
+            bytes,
+        }
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![
+            variant("out/hero-320.png", "png", 320, 1000),
+            variant("out/hero-640.png", "png", 640, 3000),
+            variant("out/hero-320.jpg", "jpg", 320, 500),
+            variant("out/hero-640.jpg", "jpg", 640, 1500),
+        ]
+    }
+
+    #[test]
+    fn sizes_keep_aspect_ratio() {
+        let set = ResponsiveSet {
+            widths: vec![640, 320, 320, 5000],
+        };
+
+        assert_eq!(vec![(320, 213), (640, 427)], set.sizes(1280, 853).unwrap());
+    }
+
+    #[test]
+    fn err_no_widths_fit() {
+        let set = ResponsiveSet {
+            widths: vec![0, 64],
+        };
+
+        assert!(set.sizes(32, 32).is_err());
+    }
+
+    #[test]
+    fn scale() {
+        let set = ResponsiveSet { widths: vec![2, 8] };
+        let images = set
+            .scale(&DynamicImage::new_rgb8(8, 4), FilterType::Triangle)
+            .unwrap()
+            .iter()
+            .map(|image| image.dimensions())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(2, 1), (8, 4)], images);
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            PathBuf::from("out/hero-320.jpg"),
+            variant_path("out/hero-{width}.png", 320, "jpg")
+        );
+        assert!(validate_path_template("hero-{width}.png").is_ok());
+        assert!(validate_path_template("hero.png").is_err());
+    }
+
+    #[test]
+    fn json_manifest() {
+        let json = Manifest::Json.describe(&variants(), Path::new("out/hero.json"));
+
+        assert!(json.contains(
+            "{\"path\": \"hero-640.png\", \"format\": \"png\", \"width\": 640, \"height\": 320, \"bytes\": 3000}"
+        ));
+        assert!(json.contains("\"png\": \"hero-320.png 320w, hero-640.png 640w\""));
+        assert!(json.contains("\"jpg\": \"hero-320.jpg 320w, hero-640.jpg 640w\""));
+    }
+
+    #[test]
+    fn html_manifest() {
+        let html = Manifest::Html.describe(&variants(), Path::new("hero.html"));
+
+        assert!(html.starts_with("<picture>\n"));
+        assert!(html.contains(
+            "<source type=\"image/png\" srcset=\"out/hero-320.png 320w, out/hero-640.png 640w\" sizes=\"100vw\">"
+        ));
+        assert!(html.contains(
+            "<img src=\"out/hero-640.jpg\" srcset=\"out/hero-320.jpg 320w, out/hero-640.jpg 640w\""
+        ));
+        assert!(html.contains("width=\"640\" height=\"320\" alt=\"\">"));
+        assert!(html.ends_with("</picture>\n"));
+    }
+
+    #[test]
+    fn manifest_by_extension() {
+        assert_eq!(
+            Manifest::Html,
+            Manifest::by_extension(Path::new("a/b.HTML")).unwrap()
+        );
+        assert!(Manifest::by_extension(Path::new("a/b.txt")).is_err());
+    }
+}
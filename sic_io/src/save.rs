@@ -1,22 +1,278 @@
 use std::path::Path;
 
 use sic_core::image;
+use sic_core::image::GenericImageView;
 
+use crate::auto::{self, Choice};
 use crate::conversion::{AutomaticColorTypeAdjustment, ConversionWriter};
+use crate::encoders::ico::IcoSettings;
+use crate::encoders::png::PngSettings;
+use crate::favicon::{self, FAVICON, WEB_MANIFEST};
+use crate::format::OutputFormat;
+use crate::pyramid::Pyramid;
+use crate::responsive::{self, variant_path, ResponsiveSet, Variant};
+use crate::size_limit::SizeLimit;
+use crate::split::{validate_path_template, Split};
+use crate::text::TextEncoding;
 
 pub fn export<P: AsRef<Path>>(
     image: &image::DynamicImage,
     method: ExportMethod<P>,
-    format: image::ImageOutputFormat,
+    format: OutputFormat,
     export_settings: ExportSettings,
 ) -> Result<(), String> {
-    let writer = ConversionWriter::new(image);
+    let writer = ConversionWriter::new(image)
+        .with_text_encoding(export_settings.text_encoding)
+        .with_size_limit(export_settings.size_limit);
     writer.write(method, format, export_settings.adjust_color_type)
 }
 
+/// Encodes the image in each of the candidate formats, and exports it in the format in which it's
+/// smallest. When exporting to a file, the extension of the path is replaced by the identifier of
+/// the chosen format, unless it already identifies that format.
+pub fn export_smallest<P: AsRef<Path>>(
+    image: &image::DynamicImage,
+    method: ExportMethod<P>,
+    candidates: &[(&str, OutputFormat)],
+    export_settings: ExportSettings,
+) -> Result<Choice, String> {
+    let writer = ConversionWriter::new(image)
+        .with_text_encoding(export_settings.text_encoding)
+        .with_size_limit(export_settings.size_limit);
+
+    let mut attempts = Vec::with_capacity(candidates.len());
+    let mut smallest: Option<(&str, Vec<u8>)> = None;
+
+    for (identifier, format) in candidates {
+        match writer.convert(format, export_settings.adjust_color_type) {
+            Ok(bytes) => {
+                attempts.push((identifier.to_string(), Ok(bytes.len() as u64)));
+
+                let smaller = match &smallest {
+                    Some((_, current)) => bytes.len() < current.len(),
+                    None => true,
+                };
+
+                if smaller {
+                    smallest = Some((identifier, bytes));
+                }
+            }
+            Err(err) => attempts.push((identifier.to_string(), Err(err))),
+        }
+    }
+
+    let (identifier, bytes) = smallest.ok_or_else(|| {
+        let reasons = attempts
+            .iter()
+            .filter_map(|(format, attempt)| {
+                attempt
+                    .as_ref()
+                    .err()
+                    .map(|err| format!("{}: {}", format, err))
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "Unable to encode the image in any of the candidate formats ({}).",
+            reasons.join("; ")
+        )
+    })?;
+
+    let path = match method {
+        ExportMethod::File(path) => {
+            let path = auto::output_path(path.as_ref(), identifier);
+            ConversionWriter::write_bytes(&bytes, ExportMethod::File(&path))?;
+            Some(path)
+        }
+        ExportMethod::StdoutBytes => {
+            ConversionWriter::write_bytes(&bytes, ExportMethod::<&Path>::StdoutBytes)?;
+            None
+        }
+    };
+
+    Ok(Choice {
+        format: identifier.to_string(),
+        bytes: bytes.len() as u64,
+        path,
+        attempts,
+    })
+}
+
+/// Exports each tile of the split image to a separate file. The path of a tile is the path template,
+/// with its `{row}` and `{col}` placeholders replaced by the (zero-indexed) row and column of the tile.
+pub fn export_tiles(
+    image: &image::DynamicImage,
+    split: &Split,
+    path_template: &str,
+    format: OutputFormat,
+    export_settings: ExportSettings,
+) -> Result<(), String> {
+    validate_path_template(path_template)?;
+
+    split.cut(image, |tile, part| {
+        let writer = ConversionWriter::new(&part);
+        writer.write(
+            ExportMethod::File(tile.path(path_template)),
+            format.clone(),
+            export_settings.adjust_color_type,
+        )
+    })
+}
+
+/// Exports the tiles of each level of the pyramid of the image, and its descriptor if the layout
+/// of the pyramid has one. The tiles are encoded in the given format, and named with the given
+/// extension.
+pub fn export_pyramid(
+    image: &image::DynamicImage,
+    pyramid: &Pyramid,
+    output: &Path,
+    format: OutputFormat,
+    extension: &str,
+    export_settings: ExportSettings,
+) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+    let levels = pyramid.levels(width, height)?;
+    let directory = pyramid.tiles_directory(output);
+    let split = pyramid.split();
+
+    // each level is scaled down from the level above it; the full size level is cut from the
+    // image itself, so it isn't copied
+    let mut level_image: Option<image::DynamicImage> = None;
+
+    for (level, &(level_width, level_height)) in levels.iter().enumerate().rev() {
+        if (level_width, level_height) != (width, height) {
+            let above = level_image.as_ref().unwrap_or(image);
+            level_image = Some(above.resize_exact(level_width, level_height, pyramid.filter));
+        }
+
+        split.cut(level_image.as_ref().unwrap_or(image), |tile, part| {
+            let path = pyramid.tile_path(&directory, level as u32, &tile, extension);
+            create_parent_dir(&path)?;
+
+            let writer = ConversionWriter::new(&part);
+            writer.write(
+                ExportMethod::File(path),
+                format.clone(),
+                export_settings.adjust_color_type,
+            )
+        })?;
+    }
+
+    if let Some(descriptor) = pyramid.descriptor(width, height, extension) {
+        std::fs::write(output, descriptor).map_err(|err| {
+            format!(
+                "Unable to write the descriptor '{}': {}",
+                output.display(),
+                err
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Exports the image at each width of the responsive set, in each of the given formats. The path of
+/// an image is the path template with its `{width}` placeholder replaced by the width, and with the
+/// identifier of the format as its extension. The images are scaled with the given filter.
+pub fn export_responsive(
+    image: &image::DynamicImage,
+    set: &ResponsiveSet,
+    filter: image::FilterType,
+    path_template: &str,
+    formats: &[(&str, OutputFormat)],
+    export_settings: ExportSettings,
+) -> Result<Vec<Variant>, String> {
+    responsive::validate_path_template(path_template)?;
+
+    let mut variants = Vec::with_capacity(set.widths.len() * formats.len());
+
+    for scaled in set.scale(image, filter)? {
+        let (width, height) = scaled.dimensions();
+
+        for (identifier, format) in formats {
+            let path = variant_path(path_template, width, identifier);
+
+            let writer = ConversionWriter::new(&scaled);
+            writer.write(
+                ExportMethod::File(&path),
+                format.clone(),
+                export_settings.adjust_color_type,
+            )?;
+
+            let bytes = std::fs::metadata(&path)
+                .map(|metadata| metadata.len())
+                .map_err(|err| format!("Unable to read '{}': {}", path.display(), err))?;
+
+            variants.push(Variant {
+                path,
+                format: identifier.to_string(),
+                width,
+                height,
+                bytes,
+            });
+        }
+    }
+
+    Ok(variants)
+}
+
+/// Exports a favicon pack to the directory: an ICO file with the image at each of the ICO sizes,
+/// the Apple touch icon and the Android icons, and a web app manifest snippet which refers to the
+/// Android icons. The PNG icons are encoded with the given settings.
+pub fn export_favicon(
+    image: &image::DynamicImage,
+    ico_settings: &IcoSettings,
+    png_settings: PngSettings,
+    directory: &Path,
+    export_settings: ExportSettings,
+) -> Result<(), String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|err| format!("Unable to create '{}': {}", directory.display(), err))?;
+
+    let writer = ConversionWriter::new(image);
+    writer.write(
+        ExportMethod::File(directory.join(FAVICON)),
+        OutputFormat::Ico(ico_settings.clone()),
+        export_settings.adjust_color_type,
+    )?;
+
+    for icon in favicon::png_icons() {
+        let rendered = icon.render(image, ico_settings.filter);
+
+        let writer = ConversionWriter::new(&rendered);
+        writer.write(
+            ExportMethod::File(directory.join(icon.file_name)),
+            OutputFormat::Png(png_settings),
+            export_settings.adjust_color_type,
+        )?;
+    }
+
+    let manifest_path = directory.join(WEB_MANIFEST);
+    std::fs::write(&manifest_path, favicon::web_manifest()).map_err(|err| {
+        format!(
+            "Unable to write the manifest '{}': {}",
+            manifest_path.display(),
+            err
+        )
+    })
+}
+
+fn create_parent_dir(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) => std::fs::create_dir_all(parent)
+            .map_err(|err| format!("Unable to create '{}': {}", parent.display(), err)),
+        None => Ok(()),
+    }
+}
+
 #[derive(Debug)]
 pub struct ExportSettings {
     pub adjust_color_type: AutomaticColorTypeAdjustment,
+    /// Only used when exporting a single image.
+    pub text_encoding: Option<TextEncoding>,
+    /// Only used when exporting a single image. If it's allowed to scale the image down to fit the
+    /// limit, the image is scaled with the filter.
+    pub size_limit: Option<(SizeLimit, image::FilterType)>,
 }
 
 #[derive(Debug)]
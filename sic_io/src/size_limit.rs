@@ -0,0 +1,253 @@
+//! A limit on the size of an encoded image. The quality of lossy formats is lowered until the
+//! encoded image fits, and if that doesn't suffice, the image may be scaled down as a last resort.
+
+use std::error::Error;
+
+use sic_core::image::{DynamicImage, FilterType, GenericImageView};
+
+use crate::format::{JPEGQuality, OutputFormat};
+
+/// Each step down scales the image by at least this factor.
+const MAX_DOWNSCALE_FACTOR: f64 = 0.9;
+/// Each step down scales the image by at most this factor.
+const MIN_DOWNSCALE_FACTOR: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeLimit {
+    /// The maximum size of the encoded image, in bytes.
+    pub max_bytes: u64,
+    /// Scale the image down if it doesn't fit at the lowest quality.
+    pub downscale: bool,
+}
+
+impl SizeLimit {
+    /// Parses a size such as `150KB`: a number of bytes, optionally followed by a unit, which
+    /// is one of B, KB (1000 bytes), KiB (1024 bytes), MB and MiB. Units are case insensitive.
+    pub fn parse_size(val: &str) -> Result<u64, Box<dyn Error>> {
+        let val = val.trim();
+        let split = val
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(val.len());
+        let (number, unit) = val.split_at(split);
+
+        let multiplier = match unit.trim().to_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kb" => 1000,
+            "kib" => 1024,
+            "m" | "mb" => 1_000_000,
+            "mib" => 1024 * 1024,
+            _ => return Err(format!("No such unit of size: '{}'.", unit).into()),
+        };
+
+        let number = number
+            .parse::<f64>()
+            .map_err(|_| format!("Unable to parse the size '{}'.", val))?;
+        let bytes = (number * f64::from(multiplier)).floor();
+
+        if bytes < 1.0 {
+            return Err(format!("The size '{}' should be at least 1 byte.", val).into());
+        }
+
+        Ok(bytes as u64)
+    }
+
+    /// Encodes the image with the given encode function, at the highest quality (up to the quality
+    /// of the format) at which it fits within the limit. If it doesn't fit at the lowest quality,
+    /// or the format has no quality, the image is scaled down step by step with the given filter,
+    /// if allowed.
+    pub fn fit<F>(
+        &self,
+        image: &DynamicImage,
+        format: &OutputFormat,
+        filter: FilterType,
+        encode: F,
+    ) -> Result<Vec<u8>, String>
+    where
+        F: Fn(&DynamicImage, &OutputFormat) -> Result<Vec<u8>, String>,
+    {
+        let mut scaled: Option<DynamicImage> = None;
+
+        loop {
+            let current = scaled.as_ref().unwrap_or(image);
+            let smallest = match self.fit_quality(current, format, &encode)? {
+                Ok(bytes) => return Ok(bytes),
+                Err(smallest) => smallest,
+            };
+
+            let (width, height) = current.dimensions();
+
+            if !self.downscale || (width == 1 && height == 1) {
+                return Err(format!(
+                    "Unable to encode the image ({}x{}) in at most {} bytes; the smallest encoded \
+                     image is {} bytes.",
+                    width, height, self.max_bytes, smallest
+                ));
+            }
+
+            // the size of the encoded image is roughly proportional to its area
+            let factor = (self.max_bytes as f64 / smallest as f64)
+                .sqrt()
+                .max(MIN_DOWNSCALE_FACTOR)
+                .min(MAX_DOWNSCALE_FACTOR);
+            let scale = |side: u32| ((f64::from(side) * factor) as u32).max(1);
+
+            scaled = Some(current.resize_exact(scale(width), scale(height), filter));
+        }
+    }
+
+    /// Searches the highest quality at which the encoded image fits. If it doesn't fit at all, the
+    /// size of the smallest encoded image is returned instead.
+    fn fit_quality<F>(
+        &self,
+        image: &DynamicImage,
+        format: &OutputFormat,
+        encode: &F,
+    ) -> Result<Result<Vec<u8>, u64>, String>
+    where
+        F: Fn(&DynamicImage, &OutputFormat) -> Result<Vec<u8>, String>,
+    {
+        let fits = |bytes: &[u8]| bytes.len() as u64 <= self.max_bytes;
+
+        let bytes = encode(image, format)?;
+        if fits(&bytes) {
+            return Ok(Ok(bytes));
+        }
+
+        let highest = match format.quality() {
+            Some(quality) if quality.as_u8() > 1 => quality.as_u8(),
+            _ => return Ok(Err(bytes.len() as u64)),
+        };
+
+        let encode_at = |quality: u8| {
+            let quality = JPEGQuality::try_from(quality).map_err(|err| err.to_string())?;
+            encode(image, &format.with_quality(quality))
+        };
+
+        let mut best = encode_at(1)?;
+        if !fits(&best) {
+            return Ok(Err(best.len() as u64));
+        }
+
+        // the image fits at quality `low`, and doesn't at quality `high`
+        let (mut low, mut high) = (1, highest);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            let bytes = encode_at(middle)?;
+
+            if fits(&bytes) {
+                low = middle;
+                best = bytes;
+            } else {
+                high = middle;
+            }
+        }
+
+        Ok(Ok(best))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoders::jpeg::JpegSettings;
+    use sic_core::image::{Rgb, RgbImage};
+
+    fn noise(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let value = (x * 7919 + y * 104_729) ^ (x * y);
+            Rgb([value as u8, (value >> 3) as u8, (value >> 6) as u8])
+        }))
+    }
+
+    fn encode(image: &DynamicImage, format: &OutputFormat) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        format.encode(image, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn jpeg(quality: u8) -> OutputFormat {
+        OutputFormat::Jpeg(
+            JPEGQuality::try_from(quality).unwrap(),
+            JpegSettings::default(),
+        )
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(150_000, SizeLimit::parse_size("150KB").unwrap());
+        assert_eq!(150 * 1024, SizeLimit::parse_size("150 KiB").unwrap());
+        assert_eq!(1_500_000, SizeLimit::parse_size("1.5mb").unwrap());
+        assert_eq!(512, SizeLimit::parse_size("512").unwrap());
+        assert!(SizeLimit::parse_size("150 parsecs").is_err());
+        assert!(SizeLimit::parse_size("KB").is_err());
+        assert!(SizeLimit::parse_size("0").is_err());
+    }
+
+    #[test]
+    fn fits_without_changes() {
+        let image = noise(16, 16);
+        let limit = SizeLimit {
+            max_bytes: 1_000_000,
+            downscale: false,
+        };
+
+        let bytes = limit
+            .fit(&image, &jpeg(80), FilterType::Triangle, encode)
+            .unwrap();
+        assert_eq!(encode(&image, &jpeg(80)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn highest_fitting_quality() {
+        let image = noise(64, 64);
+        let at_quality = |quality| encode(&image, &jpeg(quality)).unwrap().len() as u64;
+
+        let limit = SizeLimit {
+            max_bytes: (at_quality(30) + at_quality(31)) / 2,
+            downscale: false,
+        };
+        let bytes = limit
+            .fit(&image, &jpeg(90), FilterType::Triangle, encode)
+            .unwrap();
+
+        assert!(bytes.len() as u64 <= limit.max_bytes);
+        assert!(at_quality(31) > limit.max_bytes);
+        assert_eq!(encode(&image, &jpeg(30)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn downscale_as_last_resort() {
+        let image = noise(256, 256);
+        let limit = SizeLimit {
+            max_bytes: encode(&image, &jpeg(1)).unwrap().len() as u64 / 2,
+            downscale: true,
+        };
+
+        let bytes = limit
+            .fit(&image, &jpeg(80), FilterType::Triangle, encode)
+            .unwrap();
+        assert!(bytes.len() as u64 <= limit.max_bytes);
+
+        let decoded = sic_core::image::load_from_memory(&bytes).unwrap();
+        assert!(decoded.width() < 256);
+    }
+
+    #[test]
+    fn err_does_not_fit() {
+        let image = noise(64, 64);
+        let limit = SizeLimit {
+            max_bytes: 100,
+            downscale: false,
+        };
+
+        let result = limit.fit(
+            &image,
+            &OutputFormat::Farbfeld,
+            FilterType::Triangle,
+            encode,
+        );
+        assert!(result
+            .unwrap_err()
+            .starts_with("Unable to encode the image"));
+    }
+}
@@ -0,0 +1,470 @@
+//! Splits an image into tiles, for example to cut a large map into the tiles of a game level, or
+//! a sprite sheet into its cells. Each tile is exported to its own file; see
+//! [export_tiles](crate::save::export_tiles).
+
+use std::path::PathBuf;
+
+use sic_core::image::{DynamicImage, GenericImageView, RgbaImage};
+
+const ROW_PLACEHOLDER: &str = "{row}";
+const COLUMN_PLACEHOLDER: &str = "{col}";
+
+/// How an image is divided into tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitMode {
+    /// Tiles of (width, height) pixels, starting at the top left corner of the image.
+    TileSize(u32, u32),
+    /// A grid of (columns, rows) tiles of (about) equal size, which covers the whole image.
+    Grid(u32, u32),
+}
+
+/// What to do with the tiles at the right and bottom edges of the image, when the image can't be
+/// divided into whole tiles. Only applies to tiles of a fixed size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgePolicy {
+    /// Edge tiles are smaller than the other tiles.
+    Keep,
+    /// Edge tiles are padded with transparent pixels to the full tile size.
+    Pad,
+    /// Edge tiles which are smaller than the tile size are not exported.
+    Discard,
+}
+
+impl Default for EdgePolicy {
+    fn default() -> Self {
+        EdgePolicy::Keep
+    }
+}
+
+impl EdgePolicy {
+    pub fn try_from_str(val: &str) -> Result<EdgePolicy, String> {
+        match val.to_lowercase().as_str() {
+            "keep" => Ok(EdgePolicy::Keep),
+            "pad" => Ok(EdgePolicy::Pad),
+            "discard" => Ok(EdgePolicy::Discard),
+            fail => Err(format!("No such edge policy: {}", fail)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    pub mode: SplitMode,
+    /// The number of pixels by which each tile extends into its neighbouring tiles.
+    pub overlap: u32,
+    pub edge: EdgePolicy,
+}
+
+/// A rectangular part of an image, at a (zero-indexed) row and column of the grid of tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub row: u32,
+    pub column: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    /// The path of the tile: the path template with `{row}` and `{col}` replaced by the row and
+    /// column of the tile.
+    pub fn path(&self, template: &str) -> PathBuf {
+        PathBuf::from(
+            template
+                .replace(ROW_PLACEHOLDER, &self.row.to_string())
+                .replace(COLUMN_PLACEHOLDER, &self.column.to_string()),
+        )
+    }
+}
+
+/// A path template should contain both the `{row}` and `{col}` placeholders, so each tile is
+/// written to a separate file.
+pub fn validate_path_template(template: &str) -> Result<(), String> {
+    if template.contains(ROW_PLACEHOLDER) && template.contains(COLUMN_PLACEHOLDER) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The output path '{}' should contain both the '{}' and '{}' placeholders, when splitting an image into tiles.",
+            template, ROW_PLACEHOLDER, COLUMN_PLACEHOLDER
+        ))
+    }
+}
+
+impl Split {
+    /// The tiles of an image with the given dimensions, from left to right and top to bottom.
+    pub fn tiles(&self, width: u32, height: u32) -> Result<Vec<Tile>, String> {
+        let (width, height) = self.padded_dimensions(width, height);
+
+        // the cells along both axes, and the minimum size of a cell which isn't discarded
+        let (columns, rows, minimum) = match self.mode {
+            SplitMode::TileSize(tile_width, tile_height) => {
+                if tile_width == 0 || tile_height == 0 {
+                    return Err("Split: The tile width and height should be larger than 0.".into());
+                }
+
+                (
+                    cells(width, |n| u64::from(n) * u64::from(tile_width)),
+                    cells(height, |n| u64::from(n) * u64::from(tile_height)),
+                    (tile_width, tile_height),
+                )
+            }
+            SplitMode::Grid(columns, rows) => {
+                if columns == 0 || rows == 0 || columns > width || rows > height {
+                    return Err(format!(
+                        "Split: The number of columns and rows should be larger than 0, and at most \
+                         the width and height of the image ({}x{}).",
+                        width, height
+                    ));
+                }
+
+                (
+                    cells(width, |n| grid_line(width, columns, n)),
+                    cells(height, |n| grid_line(height, rows, n)),
+                    (0, 0),
+                )
+            }
+        };
+
+        let keep = |cell_width: u32, cell_height: u32| {
+            self.edge != EdgePolicy::Discard
+                || (cell_width >= minimum.0 && cell_height >= minimum.1)
+        };
+
+        let mut tiles = Vec::with_capacity(columns.len() * rows.len());
+
+        for (row, &(y, cell_height)) in rows.iter().enumerate() {
+            for (column, &(x, cell_width)) in columns.iter().enumerate() {
+                if !keep(cell_width, cell_height) {
+                    continue;
+                }
+
+                let (x, tile_width) = extend(x, cell_width, self.overlap, width);
+                let (y, tile_height) = extend(y, cell_height, self.overlap, height);
+
+                tiles.push(Tile {
+                    row: row as u32,
+                    column: column as u32,
+                    x,
+                    y,
+                    width: tile_width,
+                    height: tile_height,
+                });
+            }
+        }
+
+        if tiles.is_empty() {
+            return Err("Split: The image is smaller than a single tile.".into());
+        }
+
+        Ok(tiles)
+    }
+
+    /// Cuts the image into its tiles, and passes each tile to `each` as soon as it's cut, so only
+    /// one tile is kept in memory at a time.
+    pub fn cut<F>(&self, image: &DynamicImage, mut each: F) -> Result<(), String>
+    where
+        F: FnMut(Tile, DynamicImage) -> Result<(), String>,
+    {
+        let (width, height) = image.dimensions();
+        let tiles = self.tiles(width, height)?;
+        let padded = self.padded_dimensions(width, height) != (width, height);
+
+        for tile in tiles {
+            let part = if padded {
+                pad(image, &tile)
+            } else {
+                crop(image, &tile)
+            };
+
+            each(tile, part)?;
+        }
+
+        Ok(())
+    }
+
+    /// With the pad edge policy, the dimensions are rounded up to a whole number of tiles.
+    fn padded_dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        match (self.mode, self.edge) {
+            (SplitMode::TileSize(tile_width, tile_height), EdgePolicy::Pad) => {
+                (round_up(width, tile_width), round_up(height, tile_height))
+            }
+            _ => (width, height),
+        }
+    }
+}
+
+/// The (offset, length) of each cell along an axis of the given length, where `start` gives the
+/// offset of the n-th cell.
+fn cells(length: u32, start: impl Fn(u32) -> u64) -> Vec<(u32, u32)> {
+    let length = u64::from(length);
+
+    (0..)
+        .map(|n| (start(n), start(n + 1).min(length)))
+        .take_while(|&(begin, _)| begin < length)
+        .map(|(begin, end)| (begin as u32, (end - begin) as u32))
+        .collect()
+}
+
+/// The offset of the n-th of `count` cells of (about) equal size, along an axis of the given length.
+fn grid_line(length: u32, count: u32, n: u32) -> u64 {
+    u64::from(length) * u64::from(n.min(count)) / u64::from(count)
+}
+
+/// Extends a cell by the overlap on both sides, without exceeding the bounds of the image.
+fn extend(offset: u32, length: u32, overlap: u32, bound: u32) -> (u32, u32) {
+    let begin = offset.saturating_sub(overlap);
+    let end = offset
+        .saturating_add(length)
+        .saturating_add(overlap)
+        .min(bound);
+
+    (begin, end - begin)
+}
+
+/// A copy of the part of the image covered by the tile, which lies within the image.
+fn crop(image: &DynamicImage, tile: &Tile) -> DynamicImage {
+    let (x, y, width, height) = (tile.x, tile.y, tile.width, tile.height);
+
+    match image {
+        DynamicImage::ImageLuma8(p) => {
+            DynamicImage::ImageLuma8(p.view(x, y, width, height).to_image())
+        }
+        DynamicImage::ImageLumaA8(p) => {
+            DynamicImage::ImageLumaA8(p.view(x, y, width, height).to_image())
+        }
+        DynamicImage::ImageRgb8(p) => {
+            DynamicImage::ImageRgb8(p.view(x, y, width, height).to_image())
+        }
+        DynamicImage::ImageRgba8(p) => {
+            DynamicImage::ImageRgba8(p.view(x, y, width, height).to_image())
+        }
+        DynamicImage::ImageBgr8(p) => {
+            DynamicImage::ImageBgr8(p.view(x, y, width, height).to_image())
+        }
+        DynamicImage::ImageBgra8(p) => {
+            DynamicImage::ImageBgra8(p.view(x, y, width, height).to_image())
+        }
+    }
+}
+
+/// The tile as an rgba image, where the pixels outside of the image are transparent.
+fn pad(image: &DynamicImage, tile: &Tile) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let mut padded = RgbaImage::new(tile.width, tile.height);
+
+    for y in tile.y..(tile.y + tile.height).min(height) {
+        for x in tile.x..(tile.x + tile.width).min(width) {
+            padded.put_pixel(x - tile.x, y - tile.y, image.get_pixel(x, y));
+        }
+    }
+
+    DynamicImage::ImageRgba8(padded)
+}
+
+fn round_up(length: u32, multiple: u32) -> u32 {
+    match length.checked_rem(multiple) {
+        Some(0) | None => length,
+        Some(remainder) => length.saturating_add(multiple - remainder),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sic_core::image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn split(mode: SplitMode, overlap: u32, edge: EdgePolicy) -> Split {
+        Split {
+            mode,
+            overlap,
+            edge,
+        }
+    }
+
+    fn rects(tiles: &[Tile]) -> Vec<(u32, u32, u32, u32)> {
+        tiles
+            .iter()
+            .map(|tile| (tile.x, tile.y, tile.width, tile.height))
+            .collect()
+    }
+
+    #[test]
+    fn tile_size_whole_tiles() {
+        let tiles = split(SplitMode::TileSize(2, 3), 0, EdgePolicy::Keep)
+            .tiles(4, 3)
+            .unwrap();
+
+        assert_eq!(vec![(0, 0, 2, 3), (2, 0, 2, 3)], rects(&tiles));
+        assert_eq!((0, 1), (tiles[1].row, tiles[1].column));
+    }
+
+    #[test]
+    fn tile_size_keep_edges() {
+        let tiles = split(SplitMode::TileSize(3, 2), 0, EdgePolicy::Keep)
+            .tiles(4, 3)
+            .unwrap();
+
+        assert_eq!(
+            vec![(0, 0, 3, 2), (3, 0, 1, 2), (0, 2, 3, 1), (3, 2, 1, 1)],
+            rects(&tiles)
+        );
+    }
+
+    #[test]
+    fn tile_size_pad_edges() {
+        let tiles = split(SplitMode::TileSize(3, 2), 0, EdgePolicy::Pad)
+            .tiles(4, 3)
+            .unwrap();
+
+        assert_eq!(
+            vec![(0, 0, 3, 2), (3, 0, 3, 2), (0, 2, 3, 2), (3, 2, 3, 2)],
+            rects(&tiles)
+        );
+    }
+
+    #[test]
+    fn tile_size_discard_edges() {
+        let tiles = split(SplitMode::TileSize(3, 2), 0, EdgePolicy::Discard)
+            .tiles(4, 3)
+            .unwrap();
+
+        assert_eq!(vec![(0, 0, 3, 2)], rects(&tiles));
+    }
+
+    #[test]
+    fn tile_size_overlap() {
+        let tiles = split(SplitMode::TileSize(2, 4), 1, EdgePolicy::Keep)
+            .tiles(6, 4)
+            .unwrap();
+
+        assert_eq!(
+            vec![(0, 0, 3, 4), (1, 0, 4, 4), (3, 0, 3, 4)],
+            rects(&tiles)
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let tiles = split(SplitMode::Grid(3, 2), 0, EdgePolicy::Keep)
+            .tiles(10, 4)
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                (0, 0, 3, 2),
+                (3, 0, 3, 2),
+                (6, 0, 4, 2),
+                (0, 2, 3, 2),
+                (3, 2, 3, 2),
+                (6, 2, 4, 2)
+            ],
+            rects(&tiles)
+        );
+        assert_eq!((1, 2), (tiles[5].row, tiles[5].column));
+    }
+
+    #[test]
+    fn grid_overlap() {
+        let tiles = split(SplitMode::Grid(2, 1), 2, EdgePolicy::Keep)
+            .tiles(8, 2)
+            .unwrap();
+
+        assert_eq!(vec![(0, 0, 6, 2), (2, 0, 6, 2)], rects(&tiles));
+    }
+
+    #[test]
+    fn err_empty_tile() {
+        assert!(split(SplitMode::TileSize(0, 2), 0, EdgePolicy::Keep)
+            .tiles(4, 4)
+            .is_err());
+    }
+
+    #[test]
+    fn err_grid_larger_than_image() {
+        assert!(split(SplitMode::Grid(5, 1), 0, EdgePolicy::Keep)
+            .tiles(4, 4)
+            .is_err());
+    }
+
+    #[test]
+    fn err_discard_all() {
+        assert!(split(SplitMode::TileSize(8, 8), 0, EdgePolicy::Discard)
+            .tiles(4, 4)
+            .is_err());
+    }
+
+    #[test]
+    fn cut_with_padding() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 1, Rgba([255, 0, 0, 255])));
+        let mut tiles = Vec::new();
+        split(SplitMode::TileSize(2, 2), 0, EdgePolicy::Pad)
+            .cut(&image, |tile, part| {
+                tiles.push((tile, part));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(2, tiles.len());
+
+        let (_, last) = &tiles[1];
+        assert_eq!((2, 2), last.dimensions());
+        assert_eq!(Rgba([255, 0, 0, 255]), last.get_pixel(0, 0));
+        assert_eq!(Rgba([0, 0, 0, 0]), last.get_pixel(1, 0));
+        assert_eq!(Rgba([0, 0, 0, 0]), last.get_pixel(0, 1));
+    }
+
+    #[test]
+    fn cut_keeps_color_type() {
+        let mut buffer = RgbImage::new(3, 1);
+        buffer.put_pixel(2, 0, Rgb([0, 0, 255]));
+        let image = DynamicImage::ImageRgb8(buffer);
+
+        let mut tiles = Vec::new();
+        split(SplitMode::TileSize(2, 1), 0, EdgePolicy::Keep)
+            .cut(&image, |tile, part| {
+                tiles.push((tile, part));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(2, tiles.len());
+
+        let (_, last) = &tiles[1];
+        assert_eq!((1, 1), last.dimensions());
+        assert_eq!(
+            Some(&Rgb([0, 0, 255])),
+            last.as_rgb8().map(|p| p.get_pixel(0, 0))
+        );
+    }
+
+    #[test]
+    fn tile_path_from_template() {
+        let tile = Tile {
+            row: 2,
+            column: 11,
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        };
+
+        assert_eq!(
+            PathBuf::from("out/tile_2_11.png"),
+            tile.path("out/tile_{row}_{col}.png")
+        );
+    }
+
+    #[test]
+    fn path_template_requires_placeholders() {
+        assert!(validate_path_template("{row}_{col}.png").is_ok());
+        assert!(validate_path_template("{row}.png").is_err());
+        assert!(validate_path_template("tile.png").is_err());
+    }
+
+    #[test]
+    fn edge_policy_from_str() {
+        assert_eq!(EdgePolicy::Pad, EdgePolicy::try_from_str("PAD").unwrap());
+        assert!(EdgePolicy::try_from_str("crop").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! The image formats known to sic, and whether they can be read (decoded) and written (encoded).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSupport {
+    pub name: &'static str,
+    /// The identifiers of the format, which are also the extensions of its files. The first
+    /// identifier is the preferred one.
+    pub identifiers: &'static [&'static str],
+    pub read: bool,
+    pub write: bool,
+}
+
+/// The supported formats, ordered by name.
+pub const SUPPORTED_FORMATS: &[FormatSupport] = &[
+    FormatSupport {
+        name: "BMP",
+        identifiers: &["bmp"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "DDS (DXT1, DXT3 and DXT5)",
+        identifiers: &["dds"],
+        read: true,
+        write: false,
+    },
+    FormatSupport {
+        name: "farbfeld",
+        identifiers: &["ff", "farbfeld"],
+        read: false,
+        write: true,
+    },
+    FormatSupport {
+        name: "GIF",
+        identifiers: &["gif"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "HDR (Radiance)",
+        identifiers: &["hdr"],
+        read: true,
+        write: false,
+    },
+    FormatSupport {
+        name: "ICO",
+        identifiers: &["ico"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "JPEG",
+        identifiers: &["jpg", "jpeg"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "PNG",
+        identifiers: &["png"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "PNM",
+        identifiers: &["pbm", "pgm", "ppm", "pam"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "raw pixel data",
+        identifiers: &["raw"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "TGA",
+        identifiers: &["tga"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "TIFF",
+        identifiers: &["tif", "tiff"],
+        read: true,
+        write: true,
+    },
+    FormatSupport {
+        name: "WebP (lossy)",
+        identifiers: &["webp"],
+        read: true,
+        write: false,
+    },
+];
+
+/// Finds the format with the given identifier; identifiers are matched case insensitively.
+pub fn find_format(identifier: &str) -> Option<&'static FormatSupport> {
+    let identifier = identifier.to_lowercase();
+
+    SUPPORTED_FORMATS
+        .iter()
+        .find(|format| format.identifiers.contains(&identifier.as_str()))
+}
+
+/// The names of the formats which can be read.
+pub fn readable_formats() -> Vec<&'static str> {
+    SUPPORTED_FORMATS
+        .iter()
+        .filter(|format| format.read)
+        .map(|format| format.name)
+        .collect()
+}
+
+/// A table which lists the identifiers of each format, and whether it can be read and written.
+pub fn formats_report() -> String {
+    let rows = SUPPORTED_FORMATS
+        .iter()
+        .map(|format| {
+            (
+                format.name,
+                format.identifiers.join(", "),
+                if format.read { "yes" } else { "no" },
+                if format.write { "yes" } else { "no" },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let name_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let identifiers_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+
+    let mut report = format!(
+        "{:name$}  {:identifiers$}  read  write\n",
+        "format",
+        "identifiers",
+        name = name_width,
+        identifiers = identifiers_width
+    );
+
+    for (name, identifiers, read, write) in rows {
+        report.push_str(&format!(
+            "{:name$}  {:identifiers$}  {:4}  {}\n",
+            name,
+            identifiers,
+            read,
+            write,
+            name = name_width,
+            identifiers = identifiers_width
+        ));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_by_any_identifier() {
+        assert_eq!("TIFF", find_format("TIF").unwrap().name);
+        assert_eq!("PNM", find_format("pam").unwrap().name);
+        assert!(find_format("exr").is_none());
+    }
+
+    #[test]
+    fn read_only_formats() {
+        let read_only = SUPPORTED_FORMATS
+            .iter()
+            .filter(|format| format.read && !format.write)
+            .map(|format| format.identifiers[0])
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["dds", "hdr", "webp"], read_only);
+    }
+
+    #[test]
+    fn report() {
+        let report = formats_report();
+        let mut lines = report.lines();
+
+        assert!(lines.next().unwrap().starts_with("format"));
+        assert!(report.contains("WebP (lossy)               webp                yes   no\n"));
+        assert!(report.contains("farbfeld                   ff, farbfeld        no    yes\n"));
+        assert_eq!(SUPPORTED_FORMATS.len() + 1, report.lines().count());
+    }
+}
@@ -0,0 +1,92 @@
+//! Text representations of an encoded image, for embedding the image in text based documents,
+//! such as HTML, CSS or JSON.
+
+use std::error::Error;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// The bytes of the image, encoded with the standard base64 alphabet, with padding.
+    Base64,
+    /// A `data:` URI with the media type of the image and its base64 encoded bytes.
+    DataUri,
+}
+
+impl TextEncoding {
+    pub fn try_from_str(val: &str) -> Result<TextEncoding, Box<dyn Error>> {
+        match val.to_lowercase().as_str() {
+            "base64" => Ok(TextEncoding::Base64),
+            "data-uri" | "datauri" => Ok(TextEncoding::DataUri),
+            fail => Err(format!("No such text encoding: {}", fail).into()),
+        }
+    }
+
+    /// Encodes the bytes of an image of the given media type as text.
+    pub fn encode(self, bytes: &[u8], media_type: &str) -> String {
+        match self {
+            TextEncoding::Base64 => base64(bytes),
+            TextEncoding::DataUri => format!("data:{};base64,{}", media_type, base64(bytes)),
+        }
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() / 3 * 4 + 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+
+        // a chunk of n bytes is encoded by n + 1 characters, and padded to 4 characters
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                text.push(char::from(BASE64_ALPHABET[index as usize]));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!("", base64(b""));
+        assert_eq!("Zg==", base64(b"f"));
+        assert_eq!("Zm8=", base64(b"fo"));
+        assert_eq!("Zm9v", base64(b"foo"));
+        assert_eq!("Zm9vYg==", base64(b"foob"));
+        assert_eq!("Zm9vYmFy", base64(b"foobar"));
+    }
+
+    #[test]
+    fn base64_alphabet() {
+        assert_eq!("+/8A", base64(&[0xfb, 0xff, 0x00]));
+    }
+
+    #[test]
+    fn data_uri() {
+        assert_eq!(
+            "data:image/png;base64,iVBORw==",
+            TextEncoding::DataUri.encode(&[0x89, b'P', b'N', b'G'], "image/png")
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            TextEncoding::DataUri,
+            TextEncoding::try_from_str("data-uri").unwrap()
+        );
+        assert!(TextEncoding::try_from_str("hex").is_err());
+    }
+}
@@ -3,9 +3,11 @@ extern crate pest_derive;
 
 use pest::Parser;
 
-use crate::rule_parser::parse_image_operations;
+use crate::rule_parser::{parse_generator, parse_image_operations};
 use sic_image_engine::engine::Instruction;
+use sic_image_engine::generator::Generator;
 
+pub mod region_file;
 pub mod rule_parser;
 pub mod value_parser;
 
@@ -23,9 +25,19 @@ pub fn parse_script(script: &str) -> Result<Vec<Instruction>, String> {
         .and_then(parse_image_operations)
 }
 
+pub fn parse_generator_spec(spec: &str) -> Result<Generator, String> {
+    let parsed_spec = SICParser::parse(Rule::generate, spec);
+
+    parsed_spec
+        .map_err(|err| format!("Unable to parse sic generator: {:?}", err))
+        .and_then(parse_generator)
+}
+
 #[cfg(test)]
 mod tests {
+    use sic_core::image::Rgba;
     use sic_image_engine::engine::Instruction;
+    use sic_image_engine::wrapper::noise_kind::NoiseKind;
     use sic_image_engine::ImgOp;
 
     use super::*;
@@ -63,4 +75,78 @@ mod tests {
 
         assert!(parsed.is_err());
     }
+
+    #[test]
+    fn test_generator_canvas() {
+        let parsed = parse_generator_spec("canvas 16 8 #ff000080");
+
+        assert_eq!(
+            parsed.unwrap(),
+            Generator::Canvas((16, 8, Rgba([255, 0, 0, 128])))
+        );
+    }
+
+    #[test]
+    fn test_generator_checkerboard() {
+        let parsed = parse_generator_spec("checkerboard 16 8 4 white black");
+
+        assert_eq!(
+            parsed.unwrap(),
+            Generator::Checkerboard((16, 8, 4, Rgba([255, 255, 255, 255]), Rgba([0, 0, 0, 255])))
+        );
+    }
+
+    #[test]
+    fn test_generator_gradient_linear() {
+        let parsed = parse_generator_spec("gradient linear 16 8 red blue -45");
+
+        assert_eq!(
+            parsed.unwrap(),
+            Generator::LinearGradient((
+                16,
+                8,
+                Rgba([255, 0, 0, 255]),
+                Rgba([0, 0, 255, 255]),
+                -45.0
+            ))
+        );
+    }
+
+    #[test]
+    fn test_generator_gradient_linear_default_angle() {
+        let parsed = parse_generator_spec("gradient linear 16 8 red blue");
+
+        assert_eq!(
+            parsed.unwrap(),
+            Generator::LinearGradient((16, 8, Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255]), 0.0))
+        );
+    }
+
+    #[test]
+    fn test_generator_gradient_radial() {
+        let parsed = parse_generator_spec("gradient radial 16 8 red blue");
+
+        assert_eq!(
+            parsed.unwrap(),
+            Generator::RadialGradient((16, 8, Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255])))
+        );
+    }
+
+    #[test]
+    fn test_generator_noise() {
+        let parsed = parse_generator_spec("noise perlin 16 8 4.5 42");
+
+        assert_eq!(
+            parsed.unwrap(),
+            Generator::Noise((NoiseKind::Perlin, 16, 8, 4.5, 42))
+        );
+    }
+
+    #[test]
+    fn test_generator_fail() {
+        assert!(parse_generator_spec("canvas 16 red").is_err());
+        assert!(parse_generator_spec("gradient conic 16 8 red blue").is_err());
+        assert!(parse_generator_spec("noise simplex 16 8 4 1").is_err());
+        assert!(parse_generator_spec("canvas 16 8 red; blur 1").is_err());
+    }
 }
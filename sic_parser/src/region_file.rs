@@ -0,0 +1,369 @@
+/// The region file module reads lists of image regions from JSON or CSV files, so for example the
+/// detections of an external object detector can be used as input for region based operations.
+///
+/// A JSON region file contains an array of regions. Each region is either an array of the form
+/// `[x, y, w, h]`, or an object with the members `x`, `y`, `w` (or `width`) and `h` (or `height`);
+/// other members are ignored.
+///
+/// A CSV region file contains a region per line. If the first line is a header, the columns named
+/// `x`, `y`, `w` (or `width`) and `h` (or `height`) are used, otherwise the first four columns are
+/// used, in the order x, y, w, h. Empty lines and lines starting with `#` are ignored.
+///
+/// Coordinates may be fractional; a region is rounded outwards to whole pixels, so it always
+/// covers at least the given area.
+use std::path::Path;
+
+use pest::iterators::Pair;
+use pest::Parser;
+use sic_image_engine::wrapper::region::Region;
+
+#[derive(Parser)]
+#[grammar = "regions.pest"]
+struct RegionsParser;
+
+/// Reads a list of regions from a JSON or CSV file. The format is determined by the file
+/// extension, or if the extension is neither `json` nor `csv`, by the contents of the file.
+pub fn load_regions<P: AsRef<Path>>(path: P) -> Result<Vec<Region>, String> {
+    let path = path.as_ref();
+
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        format!(
+            "Unable to read regions from '{}': {}",
+            path.to_string_lossy(),
+            err
+        )
+    })?;
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+
+    let regions = match extension.as_ref().map(String::as_str) {
+        Some("json") => parse_json_regions(&contents),
+        Some("csv") => parse_csv_regions(&contents),
+        _ if contents.trim_start().starts_with(|c| c == '[' || c == '{') => {
+            parse_json_regions(&contents)
+        }
+        _ => parse_csv_regions(&contents),
+    };
+
+    regions.map_err(|err| {
+        format!(
+            "Unable to read regions from '{}': {}",
+            path.to_string_lossy(),
+            err
+        )
+    })
+}
+
+/// Parses a JSON array of regions.
+pub fn parse_json_regions(contents: &str) -> Result<Vec<Region>, String> {
+    let document = RegionsParser::parse(Rule::json, contents)
+        .map_err(|err| format!("invalid JSON: {}", err))?
+        .next()
+        .ok_or_else(|| "the JSON document is empty.".to_string())?;
+
+    if document.as_rule() != Rule::array {
+        return Err("expected a JSON array of regions.".to_string());
+    }
+
+    document
+        .into_inner()
+        .enumerate()
+        .map(|(index, element)| {
+            json_region(element).map_err(|err| format!("region {}: {}", index + 1, err))
+        })
+        .collect()
+}
+
+fn json_region(element: Pair<'_, Rule>) -> Result<Region, String> {
+    match element.as_rule() {
+        Rule::array => {
+            let values = element
+                .into_inner()
+                .map(json_number)
+                .collect::<Result<Vec<f64>, String>>()?;
+
+            match values.as_slice() {
+                [x, y, w, h] => to_region(*x, *y, *w, *h),
+                _ => Err("expected an array of the form [x, y, w, h].".to_string()),
+            }
+        }
+        Rule::object => {
+            let (mut x, mut y, mut w, mut h) = (None, None, None, None);
+
+            for member in element.into_inner() {
+                let mut member = member.into_inner();
+                let key = member.next().map(|key| key.into_inner().as_str());
+                let value = member.next();
+
+                let field = match key {
+                    Some("x") => &mut x,
+                    Some("y") => &mut y,
+                    Some("w") | Some("width") => &mut w,
+                    Some("h") | Some("height") => &mut h,
+                    _ => continue,
+                };
+
+                if let Some(value) = value {
+                    *field = Some(json_number(value)?);
+                }
+            }
+
+            match (x, y, w, h) {
+                (Some(x), Some(y), Some(w), Some(h)) => to_region(x, y, w, h),
+                _ => Err("expected an object with the members x, y, w and h.".to_string()),
+            }
+        }
+        _ => Err("expected an array or object.".to_string()),
+    }
+}
+
+fn json_number(value: Pair<'_, Rule>) -> Result<f64, String> {
+    if value.as_rule() != Rule::number {
+        return Err(format!("expected a number, but found: {}", value.as_str()));
+    }
+
+    value
+        .as_str()
+        .parse::<f64>()
+        .map_err(|_| format!("invalid number: {}", value.as_str()))
+}
+
+/// Parses comma separated regions, one per line, with an optional header.
+pub fn parse_csv_regions(contents: &str) -> Result<Vec<Region>, String> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    // the indices of the x, y, w and h columns
+    let mut columns = [0, 1, 2, 3];
+
+    if let Some(&(_, first)) = lines.peek() {
+        let fields = csv_fields(first);
+
+        if fields.iter().any(|field| field.parse::<f64>().is_err()) {
+            let position = |names: &[&str]| {
+                fields
+                    .iter()
+                    .position(|field| names.contains(&field.to_lowercase().as_str()))
+                    .ok_or_else(|| {
+                        format!("the CSV header has no '{}' column.", names.join("' or '"))
+                    })
+            };
+
+            columns = [
+                position(&["x"])?,
+                position(&["y"])?,
+                position(&["w", "width"])?,
+                position(&["h", "height"])?,
+            ];
+
+            lines.next();
+        }
+    }
+
+    lines
+        .map(|(number, line)| {
+            let fields = csv_fields(line);
+
+            let mut values = [0f64; 4];
+            for (value, &column) in values.iter_mut().zip(columns.iter()) {
+                let field = fields.get(column).ok_or_else(|| {
+                    format!("line {}: expected at least {} columns.", number, column + 1)
+                })?;
+
+                *value = field
+                    .parse::<f64>()
+                    .map_err(|_| format!("line {}: invalid number: {}", number, field))?;
+            }
+
+            to_region(values[0], values[1], values[2], values[3])
+                .map_err(|err| format!("line {}: {}", number, err))
+        })
+        .collect()
+}
+
+fn csv_fields(line: &str) -> Vec<&str> {
+    line.split(',')
+        .map(|field| field.trim().trim_matches('"'))
+        .collect()
+}
+
+/// Converts a (possibly fractional) region to whole pixels, rounding outwards. Parts of the region
+/// with negative coordinates are dropped.
+fn to_region(x: f64, y: f64, w: f64, h: f64) -> Result<Region, String> {
+    if !(x.is_finite() && y.is_finite() && w.is_finite() && h.is_finite()) {
+        return Err("the coordinates and size should be finite numbers.".to_string());
+    }
+
+    if w < 0.0 || h < 0.0 {
+        return Err(format!(
+            "the width and height should not be negative, but were: (w={}, h={}).",
+            w, h
+        ));
+    }
+
+    let max = f64::from(std::u32::MAX);
+    let clamp = |v: f64| v.max(0.0).min(max) as u32;
+
+    let (left, top) = (clamp(x.floor()), clamp(y.floor()));
+    let (right, bottom) = (clamp((x + w).ceil()), clamp((y + h).ceil()));
+
+    Ok(Region::new(
+        left,
+        top,
+        right.saturating_sub(left),
+        bottom.saturating_sub(top),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_arrays() {
+        let regions = parse_json_regions("[[1, 2, 3, 4], [10, 20, 30, 40]]").unwrap();
+
+        assert_eq!(
+            vec![Region::new(1, 2, 3, 4), Region::new(10, 20, 30, 40)],
+            regions
+        );
+    }
+
+    #[test]
+    fn json_objects() {
+        let regions = parse_json_regions(
+            r#"[
+                {"label": "face", "score": 0.98, "x": 1, "y": 2, "w": 3, "h": 4},
+                {"x": 10, "y": 20, "width": 30, "height": 40, "tags": ["a", {"b": null}]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Region::new(1, 2, 3, 4), Region::new(10, 20, 30, 40)],
+            regions
+        );
+    }
+
+    #[test]
+    fn json_empty() {
+        assert_eq!(Vec::<Region>::new(), parse_json_regions(" [ ] ").unwrap());
+    }
+
+    #[test]
+    fn json_fractional_rounds_outwards() {
+        let regions = parse_json_regions("[[1.5, 2.25, 3.0, 4.5e0]]").unwrap();
+
+        assert_eq!(vec![Region::new(1, 2, 4, 5)], regions);
+    }
+
+    #[test]
+    fn json_negative_origin_is_clipped() {
+        let regions = parse_json_regions("[[-5, -2, 10, 10]]").unwrap();
+
+        assert_eq!(vec![Region::new(0, 0, 5, 8)], regions);
+    }
+
+    #[test]
+    fn json_err_not_an_array() {
+        assert!(parse_json_regions(r#"{"x": 1, "y": 2, "w": 3, "h": 4}"#).is_err());
+    }
+
+    #[test]
+    fn json_err_missing_member() {
+        assert!(parse_json_regions(r#"[{"x": 1, "y": 2, "w": 3}]"#).is_err());
+    }
+
+    #[test]
+    fn json_err_too_few_values() {
+        assert!(parse_json_regions("[[1, 2, 3]]").is_err());
+    }
+
+    #[test]
+    fn json_err_negative_size() {
+        assert!(parse_json_regions("[[1, 2, -3, 4]]").is_err());
+    }
+
+    #[test]
+    fn json_err_invalid() {
+        assert!(parse_json_regions("[[1, 2, 3, 4]").is_err());
+        assert!(parse_json_regions(r#"[{"x": "1", "y": 2, "w": 3, "h": 4}]"#).is_err());
+    }
+
+    #[test]
+    fn csv_without_header() {
+        let regions = parse_csv_regions("1,2,3,4\n\n# a comment\n10, 20, 30, 40\n").unwrap();
+
+        assert_eq!(
+            vec![Region::new(1, 2, 3, 4), Region::new(10, 20, 30, 40)],
+            regions
+        );
+    }
+
+    #[test]
+    fn csv_with_header() {
+        let regions =
+            parse_csv_regions("label,score,x,y,width,height\nface,0.9,1,2,3,4\nplate,0.8,5,6,7,8")
+                .unwrap();
+
+        assert_eq!(
+            vec![Region::new(1, 2, 3, 4), Region::new(5, 6, 7, 8)],
+            regions
+        );
+    }
+
+    #[test]
+    fn csv_empty() {
+        assert_eq!(Vec::<Region>::new(), parse_csv_regions("").unwrap());
+        assert_eq!(
+            Vec::<Region>::new(),
+            parse_csv_regions("x,y,w,h\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn csv_err_header_without_column() {
+        assert!(parse_csv_regions("x,y,w,depth\n1,2,3,4").is_err());
+    }
+
+    #[test]
+    fn csv_err_too_few_columns() {
+        assert!(parse_csv_regions("1,2,3,4\n1,2,3").is_err());
+    }
+
+    #[test]
+    fn csv_err_invalid_number() {
+        assert!(parse_csv_regions("x,y,w,h\n1,2,3,four").is_err());
+    }
+
+    #[test]
+    fn load_json_file() {
+        let regions = load_regions(sic_testing::in_!("regions.json")).unwrap();
+
+        assert_eq!(
+            vec![Region::new(1, 1, 3, 2), Region::new(4, 3, 4, 3)],
+            regions
+        );
+    }
+
+    #[test]
+    fn load_csv_file() {
+        let regions = load_regions(sic_testing::in_!("regions.csv")).unwrap();
+
+        assert_eq!(
+            vec![Region::new(1, 1, 3, 2), Region::new(4, 3, 4, 3)],
+            regions
+        );
+    }
+
+    #[test]
+    fn load_err_missing_file() {
+        assert!(load_regions("does/not/exist.json").is_err());
+    }
+}
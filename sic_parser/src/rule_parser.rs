@@ -1,10 +1,19 @@
 /// The rule parser module has a goal to parse pairs/span from Pest data structures to image operations.
 use pest::iterators::{Pair, Pairs};
-use sic_image_engine::engine::{EnvironmentItem, EnvironmentKind, Instruction};
+use sic_core::image::Rgba;
+use sic_image_engine::engine::{EnvironmentItem, EnvironmentKind, Instruction, Scope};
+use sic_image_engine::generator::Generator;
+use sic_image_engine::wrapper::alignment::Alignment;
+use sic_image_engine::wrapper::channel::Channel;
 use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+use sic_image_engine::wrapper::gravity::Gravity;
+use sic_image_engine::wrapper::match_mode::MatchMode;
+use sic_image_engine::wrapper::noise_kind::NoiseKind;
+use sic_image_engine::wrapper::region::Region;
 use sic_image_engine::ImgOp;
 
 use super::Rule;
+use crate::region_file::load_regions;
 use crate::value_parser::ParseInputsFromIter;
 
 // This function parses statements provided as a single 'script' to an image operations program.
@@ -22,21 +31,39 @@ pub fn parse_image_operations(pairs: Pairs<'_, Rule>) -> Result<Vec<Instruction>
     pairs
         .filter(|pair| pair.as_rule() != Rule::EOI)
         .map(|pair| match pair.as_rule() {
-            Rule::blur => Blur(pair),
+            Rule::append => parse_append(pair),
+            Rule::autolevels => parse_auto_levels(pair),
+            Rule::block => parse_block(pair),
+            Rule::blur => parse_blur(pair),
             Rule::brighten => Brighten(pair),
+            Rule::circlecrop => Ok(Instruction::Operation(ImgOp::CircleCrop)),
+            Rule::clahe => Clahe(pair),
             Rule::contrast => Contrast(pair),
             Rule::crop => Crop(pair),
+            Rule::curves => Curves(pair),
+            Rule::draw_ellipse => DrawEllipse(pair),
+            Rule::draw_line => DrawLine(pair),
+            Rule::draw_polygon => DrawPolygon(pair),
+            Rule::draw_rect => DrawRect(pair),
+            Rule::equalize => Ok(Instruction::Operation(ImgOp::Equalize)),
             Rule::filter3x3 => Filter3x3(pair),
             Rule::flip_horizontal => Ok(Instruction::Operation(ImgOp::FlipHorizontal)),
             Rule::flip_vertical => Ok(Instruction::Operation(ImgOp::FlipVertical)),
             Rule::grayscale => Ok(Instruction::Operation(ImgOp::GrayScale)),
             Rule::huerotate => HueRotate(pair),
             Rule::invert => Ok(Instruction::Operation(ImgOp::Invert)),
+            Rule::levels => Levels(pair),
+            Rule::matchhistogram => parse_match_histogram(pair),
+            Rule::pixelate => parse_pixelate(pair),
             Rule::resize => Resize(pair),
             Rule::rotate90 => Ok(Instruction::Operation(ImgOp::Rotate90)),
             Rule::rotate180 => Ok(Instruction::Operation(ImgOp::Rotate180)),
             Rule::rotate270 => Ok(Instruction::Operation(ImgOp::Rotate270)),
+            Rule::roundcorners => RoundCorners(pair),
+            Rule::shadow => Shadow(pair),
+            Rule::text => parse_text(pair),
             Rule::unsharpen => Unsharpen(pair),
+            Rule::vignette => Vignette(pair),
             Rule::setopt => parse_set_environment(pair.into_inner().next().ok_or_else(|| {
                 "Unable to parse `set` environment command. Error: expected a single `set` inner element.".to_string()
             })?),
@@ -68,19 +95,192 @@ macro_rules! parse_op_from_pair {
     };
 }
 
-parse_op_from_pair!(Blur, f32);
 parse_op_from_pair!(Brighten, i32);
+parse_op_from_pair!(Clahe, (u32, f32));
 parse_op_from_pair!(Contrast, f32);
 parse_op_from_pair!(Crop, (u32, u32, u32, u32));
+parse_op_from_pair!(Curves, (Channel, Vec<(u8, u8)>));
+parse_op_from_pair!(DrawEllipse, (i32, i32, u32, u32, Rgba<u8>));
+parse_op_from_pair!(DrawLine, (i32, i32, i32, i32, Rgba<u8>));
+parse_op_from_pair!(DrawPolygon, (Rgba<u8>, Vec<(i32, i32)>));
+parse_op_from_pair!(DrawRect, (i32, i32, u32, u32, Rgba<u8>));
 parse_op_from_pair!(HueRotate, i32);
+parse_op_from_pair!(Levels, (u8, u8, f32, u8, u8));
 parse_op_from_pair!(Resize, (u32, u32));
+parse_op_from_pair!(RoundCorners, u32);
+parse_op_from_pair!(Shadow, (i32, i32, f32, Rgba<u8>));
 parse_op_from_pair!(Unsharpen, (f32, i32));
+parse_op_from_pair!(Vignette, (f32, f32));
 parse_op_from_pair!(Filter3x3, [f32; 9]);
 
+// The axis is followed by one or more double quoted image paths.
+fn parse_append(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    let mut inner = pair.into_inner();
+    let axis = inner.next().ok_or_else(|| {
+        "Unable to parse `append` operation. Error: expected an axis.".to_string()
+    })?;
+
+    let paths = inner
+        .map(parse_string_unicode)
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let values = std::iter::once(axis.as_str()).chain(paths.iter().map(|path| path.as_str()));
+    let stmt = Instruction::Operation(ImgOp::Append(ParseInputsFromIter::parse(values)?));
+
+    Ok(stmt)
+}
+
+// The clip percentages of autolevels are optional; when omitted, the defaults are used.
+fn parse_auto_levels(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    const DEFAULT_CLIP_PERCENTAGES: (f32, f32) = (0.5, 0.5);
+
+    let clip = if pair.clone().into_inner().next().is_none() {
+        DEFAULT_CLIP_PERCENTAGES
+    } else {
+        parse_primitive_from_pair!(pair, (f32, f32))?
+    };
+
+    Ok(Instruction::Operation(ImgOp::AutoLevels(clip)))
+}
+
+// A block consists of a scope (a region or a mask), followed by the statements which are applied
+// within that scope. Blocks may be nested.
+fn parse_block(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    let mut inner = pair.into_inner();
+
+    let scope = inner.next().ok_or_else(|| {
+        "Unable to parse block. Error: expected a `region` or `mask` scope.".to_string()
+    })?;
+
+    let scope = match scope.as_rule() {
+        Rule::region_scope => {
+            let (x, y, w, h) = parse_primitive_from_pair!(scope, (u32, u32, u32, u32))?;
+            Scope::Region(Region::new(x, y, w, h))
+        }
+        Rule::mask_scope => {
+            let mask = scope.into_inner().next().ok_or_else(|| {
+                "Unable to parse `mask` block. Error: expected a mask image path.".to_string()
+            })?;
+
+            Scope::Mask(parse_string_unicode(mask)?)
+        }
+        _ => {
+            return Err(format!(
+                "Unable to parse block. Error on element: {}",
+                scope
+            ));
+        }
+    };
+
+    Ok(Instruction::Block(scope, parse_image_operations(inner)?))
+}
+
+fn parse_blur(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    let mut inner = pair.into_inner();
+    let sigma: f32 = ParseInputsFromIter::parse(inner.next().map(|pair| pair.as_str()))?;
+
+    let stmt = match parse_regions(inner)? {
+        Some(regions) => ImgOp::BlurRegions((sigma, regions)),
+        None => ImgOp::Blur(sigma),
+    };
+
+    Ok(Instruction::Operation(stmt))
+}
+
+fn parse_pixelate(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    let mut inner = pair.into_inner();
+    let block: u32 = ParseInputsFromIter::parse(inner.next().map(|pair| pair.as_str()))?;
+
+    let stmt = match parse_regions(inner)? {
+        Some(regions) => ImgOp::PixelateRegions((block, regions)),
+        None => ImgOp::Pixelate(block),
+    };
+
+    Ok(Instruction::Operation(stmt))
+}
+
+// The optional regions of an operation: either none (the whole image), a single region given as
+// `x y w h`, or a (double quoted) path to a JSON or CSV file which lists the regions.
+fn parse_regions(mut pairs: Pairs<'_, Rule>) -> Result<Option<Vec<Region>>, String> {
+    let first = match pairs.peek() {
+        Some(pair) => pair,
+        None => return Ok(None),
+    };
+
+    if first.as_rule() == Rule::string_unicode {
+        let path = parse_string_unicode(first)?;
+        return load_regions(path).map(Some);
+    }
+
+    let (x, y, w, h): (u32, u32, u32, u32) =
+        ParseInputsFromIter::parse(pairs.by_ref().map(|pair| pair.as_str()))?;
+
+    Ok(Some(vec![Region::new(x, y, w, h)]))
+}
+
+fn parse_match_histogram(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    let reference = pair.into_inner().next().ok_or_else(|| {
+        "Unable to parse `matchhistogram` operation. Error: expected a reference image path."
+            .to_string()
+    })?;
+
+    Ok(Instruction::Operation(ImgOp::MatchHistogram(
+        parse_string_unicode(reference)?,
+    )))
+}
+
+// The text is given as double quoted string, followed by its position.
+fn parse_text(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
+    let mut inner = pair.into_inner();
+    let text = inner.next().ok_or_else(|| {
+        "Unable to parse `text` operation. Error: expected a double quoted text.".to_string()
+    })?;
+    let text = parse_string_unicode(text)?;
+
+    let values = std::iter::once(text.as_str()).chain(inner.map(|pair| pair.as_str()));
+    let stmt = Instruction::Operation(ImgOp::Text(ParseInputsFromIter::parse(values)?));
+
+    Ok(stmt)
+}
+
+// Takes the contents of a double quoted string, and unescapes any escaped quotes and backslashes.
+fn parse_string_unicode(pair: Pair<'_, Rule>) -> Result<String, String> {
+    let inner = pair
+        .into_inner()
+        .next()
+        .ok_or_else(|| "Unable to parse string. Error: expected string contents.".to_string())?;
+
+    let mut unescaped = String::with_capacity(inner.as_str().len());
+    let mut chars = inner.as_str().chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    Ok(unescaped)
+}
+
 fn parse_set_environment(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
     let environment_item = match pair.as_rule() {
+        Rule::set_append_alignment => parse_set_append_alignment(pair)?,
+        Rule::set_append_background => {
+            EnvironmentItem::AppendBackground(parse_set_value(pair, "append_background")?)
+        }
+        Rule::set_draw_antialias => EnvironmentItem::DrawAntialias,
+        Rule::set_draw_fill => EnvironmentItem::DrawFill(parse_set_value(pair, "draw_fill")?),
+        Rule::set_draw_stroke_width => {
+            EnvironmentItem::DrawStrokeWidth(parse_set_value(pair, "draw_stroke_width")?)
+        }
+        Rule::set_match_histogram_mode => parse_set_match_histogram_mode(pair)?,
         Rule::set_resize_sampling_filter => parse_set_resize_sampling_filter(pair)?,
         Rule::set_resize_preserve_aspect_ratio => EnvironmentItem::PreserveAspectRatio,
+        Rule::set_text_color => EnvironmentItem::TextColor(parse_set_value(pair, "text_color")?),
+        Rule::set_text_font => parse_set_text_font(pair)?,
+        Rule::set_text_gravity => parse_set_text_gravity(pair)?,
+        Rule::set_text_size => EnvironmentItem::TextSize(parse_set_value(pair, "text_size")?),
         _ => {
             return Err(format!(
                 "Unable to parse `set` environment command. Error on element: {}",
@@ -92,6 +292,71 @@ fn parse_set_environment(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
     Ok(Instruction::AddToEnv(environment_item))
 }
 
+// Parses the value of a `set` command, for modifiers which take a single value.
+fn parse_set_value<T>(pair: Pair<'_, Rule>, name: &str) -> Result<T, String>
+where
+    T: ParseInputsFromIter<Error = String>,
+{
+    let mut inner = pair.into_inner();
+
+    // skip over the compound atomic 'env_available' rule
+    inner.next().ok_or_else(|| {
+        format!(
+            "Unable to parse the 'set_{}' option. No options exist for the command.",
+            name
+        )
+    })?;
+
+    ParseInputsFromIter::parse(inner.map(|pair| pair.as_str()))
+        .map_err(|err| format!("Unable to parse the 'set_{}' option: {}", name, err))
+}
+
+fn parse_set_append_alignment(pair: Pair<'_, Rule>) -> Result<EnvironmentItem, String> {
+    let mut inner = pair.into_inner();
+
+    // skip over the compound atomic 'env_available' rule
+    inner.next().ok_or_else(|| {
+        "Unable to parse the 'set_append_alignment' option. No options exist for the command."
+            .to_string()
+    })?;
+
+    inner
+        .next()
+        .ok_or_else(|| {
+            format!(
+                "Unable to parse the 'set_append_alignment' option. Error on element: {}",
+                inner
+            )
+        })
+        .and_then(|val| {
+            Alignment::try_from_str(val.as_str()).map_err(|err| format!("Unable to parse: {}", err))
+        })
+        .map(EnvironmentItem::AppendAlignment)
+}
+
+fn parse_set_match_histogram_mode(pair: Pair<'_, Rule>) -> Result<EnvironmentItem, String> {
+    let mut inner = pair.into_inner();
+
+    // skip over the compound atomic 'env_available' rule
+    inner.next().ok_or_else(|| {
+        "Unable to parse the 'set_match_histogram_mode' option. No options exist for the command."
+            .to_string()
+    })?;
+
+    inner
+        .next()
+        .ok_or_else(|| {
+            format!(
+                "Unable to parse the 'set_match_histogram_mode' option. Error on element: {}",
+                inner
+            )
+        })
+        .and_then(|val| {
+            MatchMode::try_from_str(val.as_str()).map_err(|err| format!("Unable to parse: {}", err))
+        })
+        .map(EnvironmentItem::MatchHistogramMode)
+}
+
 fn parse_set_resize_sampling_filter(pair: Pair<'_, Rule>) -> Result<EnvironmentItem, String> {
     let mut inner = pair.into_inner();
 
@@ -115,10 +380,63 @@ fn parse_set_resize_sampling_filter(pair: Pair<'_, Rule>) -> Result<EnvironmentI
         .map(EnvironmentItem::CustomSamplingFilter)
 }
 
+fn parse_set_text_font(pair: Pair<'_, Rule>) -> Result<EnvironmentItem, String> {
+    let mut inner = pair.into_inner();
+
+    // skip over the compound atomic 'env_available' rule
+    inner.next().ok_or_else(|| {
+        "Unable to parse the 'set_text_font' option. No options exist for the command.".to_string()
+    })?;
+
+    inner
+        .next()
+        .ok_or_else(|| {
+            format!(
+                "Unable to parse the 'set_text_font' option. Error on element: {}",
+                inner
+            )
+        })
+        .and_then(parse_string_unicode)
+        .map(EnvironmentItem::TextFont)
+}
+
+fn parse_set_text_gravity(pair: Pair<'_, Rule>) -> Result<EnvironmentItem, String> {
+    let mut inner = pair.into_inner();
+
+    // skip over the compound atomic 'env_available' rule
+    inner.next().ok_or_else(|| {
+        "Unable to parse the 'set_text_gravity' option. No options exist for the command."
+            .to_string()
+    })?;
+
+    inner
+        .next()
+        .ok_or_else(|| {
+            format!(
+                "Unable to parse the 'set_text_gravity' option. Error on element: {}",
+                inner
+            )
+        })
+        .and_then(|val| {
+            Gravity::try_from_str(val.as_str()).map_err(|err| format!("Unable to parse: {}", err))
+        })
+        .map(EnvironmentItem::TextGravity)
+}
+
 fn parse_unset_environment(pair: Pair<'_, Rule>) -> Result<Instruction, String> {
     let environment_item = match pair.as_rule() {
+        Rule::env_append_alignment_name => EnvironmentKind::AppendAlignment,
+        Rule::env_append_background_name => EnvironmentKind::AppendBackground,
+        Rule::env_draw_antialias_name => EnvironmentKind::DrawAntialias,
+        Rule::env_draw_fill_name => EnvironmentKind::DrawFill,
+        Rule::env_draw_stroke_width_name => EnvironmentKind::DrawStrokeWidth,
+        Rule::env_match_histogram_mode_name => EnvironmentKind::MatchHistogramMode,
         Rule::env_resize_sampling_filter_name => EnvironmentKind::CustomSamplingFilter,
         Rule::env_resize_preserve_aspect_ratio_name => EnvironmentKind::PreserveAspectRatio,
+        Rule::env_text_color_name => EnvironmentKind::TextColor,
+        Rule::env_text_font_name => EnvironmentKind::TextFont,
+        Rule::env_text_gravity_name => EnvironmentKind::TextGravity,
+        Rule::env_text_size_name => EnvironmentKind::TextSize,
         _ => {
             return Err(format!(
                 "Unable to parse `del` environment command. Error on element: {}",
@@ -130,12 +448,59 @@ fn parse_unset_environment(pair: Pair<'_, Rule>) -> Result<Instruction, String>
     Ok(Instruction::RemoveFromEnv(environment_item))
 }
 
+// Parses a single generator, which produces the starting image when no input image is used.
+pub fn parse_generator(mut pairs: Pairs<'_, Rule>) -> Result<Generator, String> {
+    let pair = pairs
+        .next()
+        .ok_or_else(|| "Unable to parse generator. Error: expected a generator.".to_string())?;
+
+    match pair.as_rule() {
+        Rule::canvas => Ok(Generator::Canvas(parse_primitive_from_pair!(
+            pair,
+            (u32, u32, Rgba<u8>)
+        )?)),
+        Rule::checkerboard => Ok(Generator::Checkerboard(parse_primitive_from_pair!(
+            pair,
+            (u32, u32, u32, Rgba<u8>, Rgba<u8>)
+        )?)),
+        Rule::gradient_linear => parse_gradient_linear(pair),
+        Rule::gradient_radial => Ok(Generator::RadialGradient(parse_primitive_from_pair!(
+            pair,
+            (u32, u32, Rgba<u8>, Rgba<u8>)
+        )?)),
+        Rule::noise => Ok(Generator::Noise(parse_primitive_from_pair!(
+            pair,
+            (NoiseKind, u32, u32, f32, u64)
+        )?)),
+        _ => Err("Parse failed: Generator doesn't exist".to_string()),
+    }
+}
+
+// The angle of a linear gradient is optional; when omitted, the gradient runs from left to right.
+fn parse_gradient_linear(pair: Pair<'_, Rule>) -> Result<Generator, String> {
+    const DEFAULT_ANGLE: &str = "0";
+
+    let mut values = pair
+        .into_inner()
+        .map(|pair| pair.as_str())
+        .collect::<Vec<_>>();
+
+    if values.len() == 4 {
+        values.push(DEFAULT_ANGLE);
+    }
+
+    Ok(Generator::LinearGradient(ParseInputsFromIter::parse(
+        values,
+    )?))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::SICParser;
     use pest::Parser;
     use sic_core::image;
     use sic_image_engine::engine::EnvironmentItem;
+    use sic_image_engine::wrapper::axis::Axis;
 
     use super::*;
 
@@ -262,6 +627,83 @@ mod tests {
             .unwrap_or_else(|e| panic!("error: {:?}", e));
     }
 
+    #[test]
+    fn test_autolevels_defaults() {
+        let pairs = SICParser::parse(Rule::main, "autolevels;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::AutoLevels((0.5, 0.5)))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_append_single_path() {
+        let pairs = SICParser::parse(Rule::main, "append x \"other.png\";")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Append((
+                Axis::X,
+                vec!["other.png".to_string()]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_append_multiple_paths() {
+        let pairs = SICParser::parse(Rule::main, r#"append Y "a.png" "my dir/b.png""#)
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Append((
+                Axis::Y,
+                vec!["a.png".to_string(), "my dir/b.png".to_string()]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_append_no_path_fail() {
+        let pairs = SICParser::parse(Rule::main, "append x;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_append_unknown_axis_fail() {
+        let pairs = SICParser::parse(Rule::main, "append z \"other.png\";");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_autolevels_with_args() {
+        let pairs = SICParser::parse(Rule::main, "autolevels 1 2.5;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::AutoLevels((1.0, 2.5)))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_autolevels_single_arg_fail() {
+        let pairs = SICParser::parse(Rule::main, "autolevels 1;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_autolevels_followed_by_operation() {
+        let pairs = SICParser::parse(Rule::main, "autolevels; blur 1")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![
+                Instruction::Operation(ImgOp::AutoLevels((0.5, 0.5))),
+                Instruction::Operation(ImgOp::Blur(1.0))
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
     #[test]
     fn test_blur_with_int_accept() {
         let pairs = SICParser::parse(Rule::main, "blur 15;")
@@ -293,6 +735,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blur_region_accept() {
+        let pairs = SICParser::parse(Rule::main, "blur 2.5 10 20 30 40;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::BlurRegions((
+                2.5,
+                vec![Region::new(10, 20, 30, 40)]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_blur_region_file_accept() {
+        let script = format!("blur 2.5 \"{}\";", sic_testing::in_!("regions.csv"));
+
+        let pairs = SICParser::parse(Rule::main, &script)
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::BlurRegions((
+                2.5,
+                vec![Region::new(1, 1, 3, 2), Region::new(4, 3, 4, 3)]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_blur_region_file_not_found_fail() {
+        let pairs = SICParser::parse(Rule::main, "blur 2.5 \"does/not/exist.json\";")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_blur_region_incomplete_fail() {
+        let pairs = SICParser::parse(Rule::main, "blur 2.5 10 20 30;");
+        assert!(pairs.is_err());
+    }
+
     #[test]
     #[should_panic]
     fn test_blur_with_fp_reject() {
@@ -398,6 +881,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clahe_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "clahe 8 2;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Clahe((8, 2.0)))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_clahe_neg_tiles_fail() {
+        let pairs = SICParser::parse(Rule::main, "clahe -8 2.0;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_clahe_fp_tiles_fail() {
+        let pairs = SICParser::parse(Rule::main, "clahe 8.0 2.0;");
+        assert!(pairs.is_err());
+    }
+
     #[test]
     fn test_contrast_single_stmt_int_parse_correct() {
         let pairs = SICParser::parse(Rule::main, "contrast 15;")
@@ -430,6 +935,16 @@ mod tests {
         assert!(pairs.is_err());
     }
 
+    #[test]
+    fn test_circle_crop_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "circlecrop;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::CircleCrop)]),
+            parse_image_operations(pairs)
+        );
+    }
+
     #[test]
     fn test_brighten_pos_single_stmt_parse_correct() {
         let pairs = SICParser::parse(Rule::main, "brighten 3579;")
@@ -451,35 +966,106 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn test_filter3x3_triplets_f3_with_end_triplet_sep_fail() {
-        SICParser::parse(Rule::main, "filter3x3 0 0 0 | 1 1 1 | 2 2 2 |")
+    fn test_curves_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "curves rgb 0:0 64:90 255:255;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Curves((
+                Channel::Rgb,
+                vec![(0, 0), (64, 90), (255, 255)]
+            )))]),
+            parse_image_operations(pairs)
+        );
     }
 
     #[test]
-    fn test_filter3x3_triplets_f3_no_end_triplet_sep() {
+    fn test_curves_multi_stmt_parse_correct() {
         let pairs = SICParser::parse(
             Rule::main,
-            "filter3x3 0 0.1 0.2 | 1.3 1.4 1.5 | 2.6 2.7 2.8",
+            "curves red 0:20 255:235;\ncurves blue 0:0 255:200",
         )
         .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            Ok(vec![Instruction::Operation(ImgOp::Filter3x3([
-                0.0, 0.1, 0.2, 1.3, 1.4, 1.5, 2.6, 2.7, 2.8
-            ]))]),
+            Ok(vec![
+                Instruction::Operation(ImgOp::Curves((Channel::Red, vec![(0, 20), (255, 235)]))),
+                Instruction::Operation(ImgOp::Curves((Channel::Blue, vec![(0, 0), (255, 200)])))
+            ]),
             parse_image_operations(pairs)
         );
     }
 
     #[test]
-    fn test_filter3x3_triplets_f3_ensure_f32() {
-        let pairs = SICParser::parse(Rule::main, "filter3x3 0 0 0 | 1 1 1 | 2 2 2")
+    fn test_curves_short_channel_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "curves r 0:0 255:255;\ncurves G 0:10 255:245;")
             .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
         assert_eq!(
-            Ok(vec![Instruction::Operation(ImgOp::Filter3x3([
-                0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0
-            ]))]),
+            Ok(vec![
+                Instruction::Operation(ImgOp::Curves((Channel::Red, vec![(0, 0), (255, 255)]))),
+                Instruction::Operation(ImgOp::Curves((Channel::Green, vec![(0, 10), (255, 245)])))
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_curves_no_points_fail() {
+        let pairs = SICParser::parse(Rule::main, "curves rgb;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_curves_unknown_channel_fail() {
+        let pairs = SICParser::parse(Rule::main, "curves alpha 0:0 255:255;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_curves_point_out_of_range_fail() {
+        let pairs = SICParser::parse(Rule::main, "curves rgb 0:0 255:256;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_equalize_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "equalize;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Equalize)]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_filter3x3_triplets_f3_with_end_triplet_sep_fail() {
+        SICParser::parse(Rule::main, "filter3x3 0 0 0 | 1 1 1 | 2 2 2 |")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+    }
+
+    #[test]
+    fn test_filter3x3_triplets_f3_no_end_triplet_sep() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            "filter3x3 0 0.1 0.2 | 1.3 1.4 1.5 | 2.6 2.7 2.8",
+        )
+        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Filter3x3([
+                0.0, 0.1, 0.2, 1.3, 1.4, 1.5, 2.6, 2.7, 2.8
+            ]))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_filter3x3_triplets_f3_ensure_f32() {
+        let pairs = SICParser::parse(Rule::main, "filter3x3 0 0 0 | 1 1 1 | 2 2 2")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Filter3x3([
+                0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0
+            ]))]),
             parse_image_operations(pairs)
         );
     }
@@ -684,6 +1270,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_levels_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "levels 10 240 1.2 0 255;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Levels((
+                10, 240, 1.2, 0, 255
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_levels_too_few_args_fail() {
+        let pairs = SICParser::parse(Rule::main, "levels 10 240 1.2 0;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_levels_out_of_range_fail() {
+        let pairs = SICParser::parse(Rule::main, "levels 10 300 1.2 0 255;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_match_histogram_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "matchhistogram \"reference.png\";")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::MatchHistogram(
+                "reference.png".to_string()
+            ))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_match_histogram_path_with_spaces_and_escapes() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            r#"matchhistogram "my dir/a \"quoted\" \\ ref.png""#,
+        )
+        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::MatchHistogram(
+                r#"my dir/a "quoted" \ ref.png"#.to_string()
+            ))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_match_histogram_unquoted_fail() {
+        let pairs = SICParser::parse(Rule::main, "matchhistogram reference.png;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_match_histogram_unterminated_fail() {
+        let pairs = SICParser::parse(Rule::main, "matchhistogram \"reference.png;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_pixelate_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "pixelate 8;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Pixelate(8))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_pixelate_region_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "pixelate 8 0 5 10 15;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::PixelateRegions((
+                8,
+                vec![Region::new(0, 5, 10, 15)]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_pixelate_region_file_parse_correct() {
+        let script = format!("pixelate 4 \"{}\";", sic_testing::in_!("regions.json"));
+
+        let pairs = SICParser::parse(Rule::main, &script)
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::PixelateRegions((
+                4,
+                vec![Region::new(1, 1, 3, 2), Region::new(4, 3, 4, 3)]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_pixelate_neg_block_fail() {
+        let pairs = SICParser::parse(Rule::main, "pixelate -8;");
+        assert!(pairs.is_err());
+    }
+
     #[test]
     fn test_resize_single_stmt_parse_correct() {
         let pairs = SICParser::parse(Rule::main, "resize 99 88;")
@@ -724,6 +1418,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_round_corners_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "roundcorners 16;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::RoundCorners(16))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_round_corners_neg_fail() {
+        let pairs = SICParser::parse(Rule::main, "roundcorners -16;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_shadow_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "shadow 8 -4 3.5 #00000080;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Shadow((
+                8,
+                -4,
+                3.5,
+                Rgba([0, 0, 0, 128])
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_shadow_named_color_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "shadow 8 8 0 black;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Shadow((
+                8,
+                8,
+                0.0,
+                Rgba([0, 0, 0, 255])
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_shadow_invalid_color_fail() {
+        let pairs = SICParser::parse(Rule::main, "shadow 8 8 0 #12345;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_shadow_missing_color_fail() {
+        let pairs = SICParser::parse(Rule::main, "shadow 8 8 0;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_text_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "text \"Hello, world!\" 10 -20;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Text((
+                "Hello, world!".to_string(),
+                10,
+                -20
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_text_with_escapes_and_newline() {
+        let pairs = SICParser::parse(Rule::main, "text \"say \\\"hi\\\"\nbye\" 0 0;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Text((
+                "say \"hi\"\nbye".to_string(),
+                0,
+                0
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_text_unquoted_fail() {
+        let pairs = SICParser::parse(Rule::main, "text hello 0 0;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_text_missing_position_fail() {
+        let pairs = SICParser::parse(Rule::main, "text \"hello\" 0;");
+        assert!(pairs.is_err());
+    }
+
     #[test]
     fn test_unsharpen_single_stmt_parse_correct_ints() {
         let pairs = SICParser::parse(Rule::main, "unsharpen 99 88;")
@@ -760,6 +1553,22 @@ mod tests {
         assert!(pairs.is_err());
     }
 
+    #[test]
+    fn test_vignette_single_stmt_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "vignette 0.8 0.25;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::Vignette((0.8, 0.25)))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_vignette_too_few_args_fail() {
+        let pairs = SICParser::parse(Rule::main, "vignette 0.8;");
+        assert!(pairs.is_err());
+    }
+
     #[test]
     fn test_multi_stmt_parse_correct() {
         let pairs = SICParser::parse(Rule::main, "blur 10;fliph;flipv;resize 100 200;")
@@ -868,6 +1677,223 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_draw_ellipse_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "draw ellipse -5 10 20 15 #00ff00;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::DrawEllipse((
+                -5,
+                10,
+                20,
+                15,
+                image::Rgba([0, 255, 0, 255])
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_draw_line_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "draw line 0 0 -10 100 red;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::DrawLine((
+                0,
+                0,
+                -10,
+                100,
+                image::Rgba([255, 0, 0, 255])
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_draw_polygon_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "draw polygon yellow 0:0 10:-5 20:30 5:40;")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::DrawPolygon((
+                image::Rgba([255, 255, 0, 255]),
+                vec![(0, 0), (10, -5), (20, 30), (5, 40)]
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_draw_polygon_too_few_points_fail() {
+        let pairs = SICParser::parse(Rule::main, "draw polygon yellow 0:0 10:-5;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_draw_rect_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "draw rect 10 20 30 40 #f00")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Operation(ImgOp::DrawRect((
+                10,
+                20,
+                30,
+                40,
+                image::Rgba([255, 0, 0, 255])
+            )))]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_draw_rect_negative_size_fail() {
+        let pairs = SICParser::parse(Rule::main, "draw rect 10 20 -30 40 red");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_draw_unknown_shape_fail() {
+        let pairs = SICParser::parse(Rule::main, "draw star 10 20 30 40 red");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_block_region_parse_correct() {
+        let pairs = SICParser::parse(Rule::main, "region 10 10 200 200 { blur 3; invert; }")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Block(
+                Scope::Region(Region::new(10, 10, 200, 200)),
+                vec![
+                    Instruction::Operation(ImgOp::Blur(3.0)),
+                    Instruction::Operation(ImgOp::Invert),
+                ]
+            )]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_block_last_sep_optional() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 { blur 3; invert }")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Block(
+                Scope::Region(Region::new(1, 2, 3, 4)),
+                vec![
+                    Instruction::Operation(ImgOp::Blur(3.0)),
+                    Instruction::Operation(ImgOp::Invert),
+                ]
+            )]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_block_empty() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 {}")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Block(
+                Scope::Region(Region::new(1, 2, 3, 4)),
+                vec![]
+            )]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_block_mask_parse_correct() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            r#"mask "mask.png" { set resize preserve_aspect_ratio; contrast 2 }"#,
+        )
+        .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![Instruction::Block(
+                Scope::Mask("mask.png".to_string()),
+                vec![
+                    Instruction::AddToEnv(EnvironmentItem::PreserveAspectRatio),
+                    Instruction::Operation(ImgOp::Contrast(2.0)),
+                ]
+            )]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_block_multi_line_nested() {
+        let script = "fliph;\nregion 0 0 50 50 {\n    blur 1;\n    mask \"m.png\" {\n        invert\n    }\n};\nflipv;";
+
+        let pairs = SICParser::parse(Rule::main, script)
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![
+                Instruction::Operation(ImgOp::FlipHorizontal),
+                Instruction::Block(
+                    Scope::Region(Region::new(0, 0, 50, 50)),
+                    vec![
+                        Instruction::Operation(ImgOp::Blur(1.0)),
+                        Instruction::Block(
+                            Scope::Mask("m.png".to_string()),
+                            vec![Instruction::Operation(ImgOp::Invert)]
+                        ),
+                    ]
+                ),
+                Instruction::Operation(ImgOp::FlipVertical),
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_block_followed_by_sep() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 { invert }; fliph")
+            .unwrap_or_else(|e| panic!("Unable to parse sic image operations script: {:?}", e));
+        assert_eq!(
+            Ok(vec![
+                Instruction::Block(
+                    Scope::Region(Region::new(1, 2, 3, 4)),
+                    vec![Instruction::Operation(ImgOp::Invert)]
+                ),
+                Instruction::Operation(ImgOp::FlipHorizontal),
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_block_followed_without_sep_fail() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 { invert }\nfliph;");
+        assert!(pairs.is_err());
+
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 { invert } fliph;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_block_unclosed_fail() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 { invert;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_block_missing_sep_fail() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 4 { invert fliph }");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_block_region_too_few_args_fail() {
+        let pairs = SICParser::parse(Rule::main, "region 1 2 3 { invert }");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_block_mask_unquoted_fail() {
+        let pairs = SICParser::parse(Rule::main, "mask mask.png { invert }");
+        assert!(pairs.is_err());
+    }
+
     #[test]
     fn test_parse_setopt_resize_sampling_filter_catmullrom() {
         let pairs = SICParser::parse(Rule::main, "set resize sampling_filter CatmullRom;")
@@ -1062,6 +2088,162 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_set_and_del_opt_match_histogram_mode() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            "set matchhistogram mode reinhard;\
+             matchhistogram \"ref.png\";\
+             del matchhistogram mode;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            Ok(vec![
+                Instruction::AddToEnv(EnvironmentItem::MatchHistogramMode(MatchMode::Reinhard)),
+                Instruction::Operation(ImgOp::MatchHistogram("ref.png".to_string())),
+                Instruction::RemoveFromEnv(EnvironmentKind::MatchHistogramMode),
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_parse_set_and_del_opt_append() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            "set append alignment center;\
+             set append background #ffffff;\
+             append x \"other.png\";\
+             del append alignment;\
+             del append background;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            Ok(vec![
+                Instruction::AddToEnv(EnvironmentItem::AppendAlignment(Alignment::Center)),
+                Instruction::AddToEnv(EnvironmentItem::AppendBackground(image::Rgba([
+                    255, 255, 255, 255
+                ]))),
+                Instruction::Operation(ImgOp::Append((Axis::X, vec!["other.png".to_string()]))),
+                Instruction::RemoveFromEnv(EnvironmentKind::AppendAlignment),
+                Instruction::RemoveFromEnv(EnvironmentKind::AppendBackground),
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_parse_set_and_del_opt_draw() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            "set draw antialias;\
+             set draw fill #ff000080;\
+             set draw stroke_width 3;\
+             draw rect 1 2 3 4 blue;\
+             del draw antialias;\
+             del draw fill;\
+             del draw stroke_width;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            Ok(vec![
+                Instruction::AddToEnv(EnvironmentItem::DrawAntialias),
+                Instruction::AddToEnv(EnvironmentItem::DrawFill(image::Rgba([255, 0, 0, 128]))),
+                Instruction::AddToEnv(EnvironmentItem::DrawStrokeWidth(3)),
+                Instruction::Operation(ImgOp::DrawRect((
+                    1,
+                    2,
+                    3,
+                    4,
+                    image::Rgba([0, 0, 255, 255])
+                ))),
+                Instruction::RemoveFromEnv(EnvironmentKind::DrawAntialias),
+                Instruction::RemoveFromEnv(EnvironmentKind::DrawFill),
+                Instruction::RemoveFromEnv(EnvironmentKind::DrawStrokeWidth),
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_parse_set_and_del_opt_text() {
+        let pairs = SICParser::parse(
+            Rule::main,
+            "set text color #ff0000;\
+             set text font \"fonts/my font.ttf\";\
+             set text gravity southeast;\
+             set text size 32;\
+             text \"sic\" 4 4;\
+             del text color;\
+             del text font;\
+             del text gravity;\
+             del text size;",
+        )
+        .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert_eq!(
+            Ok(vec![
+                Instruction::AddToEnv(EnvironmentItem::TextColor(image::Rgba([255, 0, 0, 255]))),
+                Instruction::AddToEnv(EnvironmentItem::TextFont("fonts/my font.ttf".to_string())),
+                Instruction::AddToEnv(EnvironmentItem::TextGravity(Gravity::SouthEast)),
+                Instruction::AddToEnv(EnvironmentItem::TextSize(32)),
+                Instruction::Operation(ImgOp::Text(("sic".to_string(), 4, 4))),
+                Instruction::RemoveFromEnv(EnvironmentKind::TextColor),
+                Instruction::RemoveFromEnv(EnvironmentKind::TextFont),
+                Instruction::RemoveFromEnv(EnvironmentKind::TextGravity),
+                Instruction::RemoveFromEnv(EnvironmentKind::TextSize),
+            ]),
+            parse_image_operations(pairs)
+        );
+    }
+
+    #[test]
+    fn test_parse_setopt_text_gravity_unknown_fail() {
+        let pairs = SICParser::parse(Rule::main, "set text gravity middle;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_text_font_unquoted_fail() {
+        let pairs = SICParser::parse(Rule::main, "set text font font.ttf;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_draw_fill_invalid_color_fail() {
+        let pairs = SICParser::parse(Rule::main, "set draw fill #12345;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_draw_stroke_width_negative_fail() {
+        let pairs = SICParser::parse(Rule::main, "set draw stroke_width -3;");
+        assert!(pairs.is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_append_alignment_unknown_fail() {
+        let pairs = SICParser::parse(Rule::main, "set append alignment middle;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
+    #[test]
+    fn test_parse_setopt_match_histogram_mode_unknown_fail() {
+        let pairs = SICParser::parse(Rule::main, "set matchhistogram mode lab;")
+            .unwrap_or_else(|e| panic!("error: {:?}", e));
+
+        assert!(parse_image_operations(pairs).is_err());
+    }
+
     #[test]
     fn test_parse_delopt_resize_preserve_aspect_ratio_single() {
         let pairs = SICParser::parse(Rule::main, "del resize preserve_aspect_ratio;")
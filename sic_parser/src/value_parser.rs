@@ -1,4 +1,9 @@
 /// The value parser module has a goal to parse image operation inputs.
+use sic_core::image::Rgba;
+use sic_image_engine::wrapper::axis::Axis;
+use sic_image_engine::wrapper::channel::Channel;
+use sic_image_engine::wrapper::noise_kind::NoiseKind;
+use sic_image_engine::wrapper::region::Region;
 
 #[derive(Clone, Debug)]
 pub struct Describable<'a>(&'a str);
@@ -188,187 +193,1290 @@ impl ParseInputsFromIter for (f32, i32) {
     }
 }
 
+// for: clahe
+impl ParseInputsFromIter for (u32, f32) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, f32). v2";
+
+        let res: (u32, f32) = (
+            parse_next!(iter, u32, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+        );
+
+        return_if_complete!(iter, res, ERR_MSG)
+    }
+}
+
+// for: autolevels
+impl ParseInputsFromIter for (f32, f32) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (f32, f32). v2";
+
+        let res: (f32, f32) = (
+            parse_next!(iter, f32, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+        );
+
+        return_if_complete!(iter, res, ERR_MSG)
+    }
+}
+
+// for: levels
+impl ParseInputsFromIter for (u8, u8, f32, u8, u8) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u8, u8, f32, u8, u8). v2";
+
+        let res: (u8, u8, f32, u8, u8) = (
+            parse_next!(iter, u8, ERR_MSG),
+            parse_next!(iter, u8, ERR_MSG),
+            parse_next!(iter, f32, ERR_MSG),
+            parse_next!(iter, u8, ERR_MSG),
+            parse_next!(iter, u8, ERR_MSG),
+        );
+
+        return_if_complete!(iter, res, ERR_MSG)
+    }
+}
+
+// for: curves
+// The first value is the channel, each following value contains one or more control points of
+// the form `x:y`, separated by whitespace or commas.
+impl ParseInputsFromIter for (Channel, Vec<(u8, u8)>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (channel, control points). v2";
+
+        let channel: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let channel = Channel::try_from_str(channel.0).map_err(|err| err.to_string())?;
+
+        let mut points = Vec::new();
+
+        for value in iter {
+            let value: Describable<'a> = value.into();
+
+            for point in value.0.split(|c: char| c.is_whitespace() || c == ',') {
+                if point.is_empty() {
+                    continue;
+                }
+
+                let mut coordinates = point.split(':');
+                let x = parse_next!(coordinates, u8, ERR_MSG);
+                let y = parse_next!(coordinates, u8, ERR_MSG);
+
+                if coordinates.next().is_some() {
+                    return Err(ERR_MSG.to_string());
+                }
+
+                points.push((x, y));
+            }
+        }
+
+        if points.is_empty() {
+            return Err(ERR_MSG.to_string());
+        }
+
+        Ok((channel, points))
+    }
+}
+
+// for: pixelate-region
+impl ParseInputsFromIter for (u32, Region) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, region). v2";
+
+        let res: (u32, Region) = (
+            parse_next!(iter, u32, ERR_MSG),
+            Region::new(
+                parse_next!(iter, u32, ERR_MSG),
+                parse_next!(iter, u32, ERR_MSG),
+                parse_next!(iter, u32, ERR_MSG),
+                parse_next!(iter, u32, ERR_MSG),
+            ),
+        );
+
+        return_if_complete!(iter, res, ERR_MSG)
+    }
+}
+
+// for: blur-region
+impl ParseInputsFromIter for (f32, Region) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (f32, region). v2";
+
+        let res: (f32, Region) = (
+            parse_next!(iter, f32, ERR_MSG),
+            Region::new(
+                parse_next!(iter, u32, ERR_MSG),
+                parse_next!(iter, u32, ERR_MSG),
+                parse_next!(iter, u32, ERR_MSG),
+                parse_next!(iter, u32, ERR_MSG),
+            ),
+        );
+
+        return_if_complete!(iter, res, ERR_MSG)
+    }
+}
+
+// for: pixelate-regions
+impl ParseInputsFromIter for (u32, String) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, String). v2";
+
+        let value = parse_next!(iter, u32, ERR_MSG);
+        let text: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+
+        return_if_complete!(iter, (value, String::from(text.0)), ERR_MSG)
+    }
+}
+
+// for: blur-regions
+impl ParseInputsFromIter for (f32, String) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (f32, String). v2";
+
+        let value = parse_next!(iter, f32, ERR_MSG);
+        let text: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+
+        return_if_complete!(iter, (value, String::from(text.0)), ERR_MSG)
+    }
+}
+
+// for: shadow
+impl ParseInputsFromIter for (i32, i32, f32, Rgba<u8>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (i32, i32, f32, color). v2";
+
+        let offset_x = parse_next!(iter, i32, ERR_MSG);
+        let offset_y = parse_next!(iter, i32, ERR_MSG);
+        let blur = parse_next!(iter, f32, ERR_MSG);
+
+        let color: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let color = parse_color(color.0)?;
+
+        return_if_complete!(iter, (offset_x, offset_y, blur, color), ERR_MSG)
+    }
+}
+
+// for: draw-ellipse, draw-rect
+impl ParseInputsFromIter for (i32, i32, u32, u32, Rgba<u8>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (i32, i32, u32, u32, color). v2";
+
+        let x = parse_next!(iter, i32, ERR_MSG);
+        let y = parse_next!(iter, i32, ERR_MSG);
+        let width = parse_next!(iter, u32, ERR_MSG);
+        let height = parse_next!(iter, u32, ERR_MSG);
+
+        let color: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let color = parse_color(color.0)?;
+
+        return_if_complete!(iter, (x, y, width, height, color), ERR_MSG)
+    }
+}
+
+// for: draw-line
+impl ParseInputsFromIter for (i32, i32, i32, i32, Rgba<u8>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (i32, i32, i32, i32, color). v2";
+
+        let x1 = parse_next!(iter, i32, ERR_MSG);
+        let y1 = parse_next!(iter, i32, ERR_MSG);
+        let x2 = parse_next!(iter, i32, ERR_MSG);
+        let y2 = parse_next!(iter, i32, ERR_MSG);
+
+        let color: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let color = parse_color(color.0)?;
+
+        return_if_complete!(iter, (x1, y1, x2, y2, color), ERR_MSG)
+    }
+}
+
+// for: draw-polygon
+// The first value is the color, each following value contains one or more points of the form
+// `x:y`, separated by whitespace or commas.
+impl ParseInputsFromIter for (Rgba<u8>, Vec<(i32, i32)>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (color, points). v2";
+
+        let color: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let color = parse_color(color.0)?;
+
+        let mut points = Vec::new();
+
+        for value in iter {
+            let value: Describable<'a> = value.into();
+
+            for point in value.0.split(|c: char| c.is_whitespace() || c == ',') {
+                if point.is_empty() {
+                    continue;
+                }
+
+                let mut coordinates = point.split(':');
+                let x = parse_next!(coordinates, i32, ERR_MSG);
+                let y = parse_next!(coordinates, i32, ERR_MSG);
+
+                if coordinates.next().is_some() {
+                    return Err(ERR_MSG.to_string());
+                }
+
+                points.push((x, y));
+            }
+        }
+
+        if points.is_empty() {
+            return Err(ERR_MSG.to_string());
+        }
+
+        Ok((color, points))
+    }
+}
+
+// for: text
+impl ParseInputsFromIter for (String, i32, i32) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (String, i32, i32). v2";
+
+        let text: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let x = parse_next!(iter, i32, ERR_MSG);
+        let y = parse_next!(iter, i32, ERR_MSG);
+
+        return_if_complete!(iter, (String::from(text.0), x, y), ERR_MSG)
+    }
+}
+
+// for: append
+impl ParseInputsFromIter for (Axis, Vec<String>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (axis, paths). v2";
+
+        let axis: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let axis = Axis::try_from_str(axis.0).map_err(|err| err.to_string())?;
+
+        let paths = iter
+            .map(|path| {
+                let path: Describable<'a> = path.into();
+                String::from(path.0)
+            })
+            .collect::<Vec<_>>();
+
+        if paths.is_empty() {
+            return Err(ERR_MSG.to_string());
+        }
+
+        Ok((axis, paths))
+    }
+}
+
+// for: canvas (generator)
+impl ParseInputsFromIter for (u32, u32, Rgba<u8>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, u32, color). v2";
+
+        let width = parse_next!(iter, u32, ERR_MSG);
+        let height = parse_next!(iter, u32, ERR_MSG);
+
+        let color: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let color = parse_color(color.0)?;
+
+        return_if_complete!(iter, (width, height, color), ERR_MSG)
+    }
+}
+
+// for: checkerboard (generator)
+impl ParseInputsFromIter for (u32, u32, u32, Rgba<u8>, Rgba<u8>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, u32, u32, color, color). v2";
+
+        let width = parse_next!(iter, u32, ERR_MSG);
+        let height = parse_next!(iter, u32, ERR_MSG);
+        let cell = parse_next!(iter, u32, ERR_MSG);
+
+        let first: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let first = parse_color(first.0)?;
+        let second: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let second = parse_color(second.0)?;
+
+        return_if_complete!(iter, (width, height, cell, first, second), ERR_MSG)
+    }
+}
+
+// for: radial gradient (generator)
+impl ParseInputsFromIter for (u32, u32, Rgba<u8>, Rgba<u8>) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, u32, color, color). v2";
+
+        let width = parse_next!(iter, u32, ERR_MSG);
+        let height = parse_next!(iter, u32, ERR_MSG);
+
+        let from: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let from = parse_color(from.0)?;
+        let to: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let to = parse_color(to.0)?;
+
+        return_if_complete!(iter, (width, height, from, to), ERR_MSG)
+    }
+}
+
+// for: linear gradient (generator)
+impl ParseInputsFromIter for (u32, u32, Rgba<u8>, Rgba<u8>, f32) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (u32, u32, color, color, f32). v2";
+
+        let width = parse_next!(iter, u32, ERR_MSG);
+        let height = parse_next!(iter, u32, ERR_MSG);
+
+        let from: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let from = parse_color(from.0)?;
+        let to: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let to = parse_color(to.0)?;
+
+        let angle = parse_next!(iter, f32, ERR_MSG);
+
+        return_if_complete!(iter, (width, height, from, to, angle), ERR_MSG)
+    }
+}
+
+// for: noise (generator)
+impl ParseInputsFromIter for (NoiseKind, u32, u32, f32, u64) {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (noise kind, u32, u32, f32, u64). v2";
+
+        let kind: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let kind = NoiseKind::try_from_str(kind.0).map_err(|err| err.to_string())?;
+
+        let width = parse_next!(iter, u32, ERR_MSG);
+        let height = parse_next!(iter, u32, ERR_MSG);
+        let scale = parse_next!(iter, f32, ERR_MSG);
+        let seed = parse_next!(iter, u64, ERR_MSG);
+
+        return_if_complete!(iter, (kind, width, height, scale, seed), ERR_MSG)
+    }
+}
+
+impl ParseInputsFromIter for Rgba<u8> {
+    type Error = String;
+
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to color. v2";
+
+        let color: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        let color = parse_color(color.0)?;
+
+        return_if_complete!(iter, color, ERR_MSG)
+    }
+}
+
+/// Parses a color, given either as hexadecimal `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` value,
+/// or by one of a few common names. Colors without an alpha component are opaque.
+fn parse_color(value: &str) -> Result<Rgba<u8>, String> {
+    let err = || format!("Unable to parse color: {}", value);
+
+    if value.starts_with('#') {
+        let hex = &value[1..];
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(err());
+        }
+
+        let digits = match hex.len() {
+            // each digit is repeated, i.e. #f80 equals #ff8800
+            3 | 4 => hex.chars().map(|c| c.to_string().repeat(2)).collect(),
+            6 | 8 => hex
+                .as_bytes()
+                .chunks(2)
+                .map(|pair| String::from_utf8_lossy(pair).into_owned())
+                .collect::<Vec<_>>(),
+            _ => return Err(err()),
+        };
+
+        let mut channels = [255u8; 4];
+        for (channel, digit) in channels.iter_mut().zip(digits) {
+            *channel = u8::from_str_radix(&digit, 16).map_err(|_| err())?;
+        }
+
+        return Ok(Rgba(channels));
+    }
+
+    let channels = match value.to_lowercase().as_str() {
+        "black" => [0, 0, 0, 255],
+        "white" => [255, 255, 255, 255],
+        "gray" | "grey" => [128, 128, 128, 255],
+        "red" => [255, 0, 0, 255],
+        "green" => [0, 128, 0, 255],
+        "blue" => [0, 0, 255, 255],
+        "yellow" => [255, 255, 0, 255],
+        "cyan" => [0, 255, 255, 255],
+        "magenta" => [255, 0, 255, 255],
+        "transparent" => [0, 0, 0, 0],
+        _ => return Err(err()),
+    };
+
+    Ok(Rgba(channels))
+}
+
 impl ParseInputsFromIter for String {
     type Error = String;
 
-    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
-    where
-        T: IntoIterator,
-        T::Item: Into<Describable<'a>> + std::fmt::Debug,
-        Self: std::marker::Sized,
-    {
-        let mut iter = iterable.into_iter();
-        const ERR_MSG: &str = "Unable to map a value to (f32, i32). v2";
+    fn parse<'a, T>(iterable: T) -> Result<Self, Self::Error>
+    where
+        T: IntoIterator,
+        T::Item: Into<Describable<'a>> + std::fmt::Debug,
+        Self: std::marker::Sized,
+    {
+        let mut iter = iterable.into_iter();
+        const ERR_MSG: &str = "Unable to map a value to (f32, i32). v2";
+
+        let res: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+
+        return_if_complete!(iter, String::from(res.0), ERR_MSG)
+    }
+}
+
+#[cfg(test)]
+mod tests_parse_from_iter {
+    use super::*;
+
+    #[test]
+    fn a_f32() {
+        let some: f32 = ParseInputsFromIter::parse(&["-1.03"]).unwrap();
+        assert_eq!(some, -1.03f32)
+    }
+
+    mod tuple_u32_u32_u32_u32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32_u32_u32() {
+            let some: (u32, u32, u32, u32) =
+                ParseInputsFromIter::parse(&["03579", "0", "1", "1"]).unwrap();
+            assert_eq!(some, (3579u32, 0u32, 1u32, 1u32))
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_u32_u32_fail_on_neg() {
+            let _some: (u32, u32, u32, u32) =
+                ParseInputsFromIter::parse(&["03579", "-0", "1", "1"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_u32_u32_fail_on_length_too_short() {
+            let _some: (u32, u32, u32, u32) =
+                ParseInputsFromIter::parse(&["03579", "-0", "1"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_u32_u32_fail_on_length_too_long() {
+            let _some: (u32, u32, u32, u32) =
+                ParseInputsFromIter::parse(&["03579", "0", "1", "1", "1"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_u32_u32_fail_not_u32() {
+            let _some: (u32, u32, u32, u32) =
+                ParseInputsFromIter::parse(&["03579", "0", "1", "o"]).unwrap();
+        }
+    }
+
+    mod array_f32x9 {
+        use super::*;
+
+        #[test]
+        fn array_of_f32() {
+            let some: [f32; 9] = ParseInputsFromIter::parse(&[
+                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999",
+            ])
+            .unwrap();
+            let expected: [f32; 9] = [
+                1f32, 2f32, 3f32, 4f32, 5.5f32, -6.0f32, 7f32, 8f32, -9.9999f32,
+            ];
+            assert_eq!(some, expected);
+        }
+
+        #[test]
+        #[should_panic]
+        fn array_of_f32_oof_too_short() {
+            let _some: [f32; 9] =
+                ParseInputsFromIter::parse(&["1", "2", "3", "4", "5.5", "-6.0", "7", "8"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn array_of_f32_oof_too_long() {
+            let _some: [f32; 9] = ParseInputsFromIter::parse(&[
+                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999", "1",
+            ])
+            .unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn array_of_f32_oof_not_a_f32() {
+            let _some: [f32; 9] = ParseInputsFromIter::parse(&[
+                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "lalala",
+            ])
+            .unwrap();
+        }
+    }
+
+    mod tuple_u32_u32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32() {
+            let some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "0"]).unwrap();
+            assert_eq!(some, (3579u32, 0u32))
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_fail_on_neg() {
+            let _some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "-0"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_fail_on_empty() {
+            let empty: &[&str; 0] = &[];
+            let _some: (u32, u32) = ParseInputsFromIter::parse(empty).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_fail_on_too_short() {
+            let _some: (u32, u32) = ParseInputsFromIter::parse(&["03579"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_u32_fail_on_too_long() {
+            let _some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "1", "1"]).unwrap();
+        }
+    }
+
+    mod tuple_f32_i32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_f32_i32() {
+            let some: (f32, i32) = ParseInputsFromIter::parse(&["-03579.1", "-0"]).unwrap();
+            assert_eq!(some, (-3579.1f32, 0i32))
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_i32_fail_on_not_f32() {
+            let _some: (f32, i32) = ParseInputsFromIter::parse(&["f", "-1"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_i32_fail_on_not_i32() {
+            let _some: (f32, i32) = ParseInputsFromIter::parse(&["-1.0", "f"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_i32_fail_on_empty() {
+            let empty: &[&str; 0] = &[];
+            let _some: (f32, i32) = ParseInputsFromIter::parse(empty).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_i32_fail_on_too_short() {
+            let _some: (f32, i32) = ParseInputsFromIter::parse(&["03579"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_i32_fail_on_too_long() {
+            let _some: (f32, i32) = ParseInputsFromIter::parse(&["03579", "1", "1"]).unwrap();
+        }
+    }
+
+    mod tuple_u32_f32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_f32() {
+            let some: (u32, f32) = ParseInputsFromIter::parse(&["8", "2.5"]).unwrap();
+            assert_eq!(some, (8u32, 2.5f32))
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_f32_fail_on_neg() {
+            let _some: (u32, f32) = ParseInputsFromIter::parse(&["-8", "2.5"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_f32_fail_on_too_short() {
+            let _some: (u32, f32) = ParseInputsFromIter::parse(&["8"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u32_f32_fail_on_too_long() {
+            let _some: (u32, f32) = ParseInputsFromIter::parse(&["8", "2.5", "1"]).unwrap();
+        }
+    }
+
+    mod tuple_f32_f32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_f32_f32() {
+            let some: (f32, f32) = ParseInputsFromIter::parse(&["0.5", "-1"]).unwrap();
+            assert_eq!(some, (0.5f32, -1f32))
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_f32_fail_on_not_f32() {
+            let _some: (f32, f32) = ParseInputsFromIter::parse(&["0.5", "f"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_f32_fail_on_empty() {
+            let empty: &[&str; 0] = &[];
+            let _some: (f32, f32) = ParseInputsFromIter::parse(empty).unwrap();
+        }
 
-        let res: Describable<'a> = iter.next().ok_or_else(|| ERR_MSG.to_string())?.into();
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_f32_f32_fail_on_too_long() {
+            let _some: (f32, f32) = ParseInputsFromIter::parse(&["1", "1", "1"]).unwrap();
+        }
+    }
 
-        return_if_complete!(iter, String::from(res.0), ERR_MSG)
+    mod tuple_u8_u8_f32_u8_u8 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u8_u8_f32_u8_u8() {
+            let some: (u8, u8, f32, u8, u8) =
+                ParseInputsFromIter::parse(&["10", "240", "1.2", "0", "255"]).unwrap();
+            assert_eq!(some, (10u8, 240u8, 1.2f32, 0u8, 255u8))
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u8_u8_f32_u8_u8_fail_on_out_of_range() {
+            let _some: (u8, u8, f32, u8, u8) =
+                ParseInputsFromIter::parse(&["10", "256", "1.2", "0", "255"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn a_tuple_of_u8_u8_f32_u8_u8_fail_on_too_short() {
+            let _some: (u8, u8, f32, u8, u8) =
+                ParseInputsFromIter::parse(&["10", "240", "1.2", "0"]).unwrap();
+        }
     }
-}
 
-#[cfg(test)]
-mod tests_parse_from_iter {
-    use super::*;
+    mod tuple_channel_points {
+        use super::*;
 
-    #[test]
-    fn a_f32() {
-        let some: f32 = ParseInputsFromIter::parse(&["-1.03"]).unwrap();
-        assert_eq!(some, -1.03f32)
+        #[test]
+        fn separate_points() {
+            let some: (Channel, Vec<(u8, u8)>) =
+                ParseInputsFromIter::parse(&["rgb", "0:0", "128:150", "255:255"]).unwrap();
+            assert_eq!(some, (Channel::Rgb, vec![(0, 0), (128, 150), (255, 255)]))
+        }
+
+        #[test]
+        fn combined_points() {
+            let some: (Channel, Vec<(u8, u8)>) =
+                ParseInputsFromIter::parse(&["red", "0:10,128:150 255:240"]).unwrap();
+            assert_eq!(some, (Channel::Red, vec![(0, 10), (128, 150), (255, 240)]))
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_unknown_channel() {
+            let _some: (Channel, Vec<(u8, u8)>) =
+                ParseInputsFromIter::parse(&["alpha", "0:0"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_no_points() {
+            let _some: (Channel, Vec<(u8, u8)>) = ParseInputsFromIter::parse(&["rgb"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_incomplete_point() {
+            let _some: (Channel, Vec<(u8, u8)>) =
+                ParseInputsFromIter::parse(&["rgb", "0:0", "12"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_out_of_range() {
+            let _some: (Channel, Vec<(u8, u8)>) =
+                ParseInputsFromIter::parse(&["rgb", "0:0", "12:300"]).unwrap();
+        }
     }
 
-    mod tuple_u32_u32_u32_u32 {
+    mod tuple_u32_region {
         use super::*;
 
         #[test]
-        fn a_tuple_of_u32_u32_u32_u32() {
-            let some: (u32, u32, u32, u32) =
-                ParseInputsFromIter::parse(&["03579", "0", "1", "1"]).unwrap();
-            assert_eq!(some, (3579u32, 0u32, 1u32, 1u32))
+        fn a_tuple_of_u32_region() {
+            let some: (u32, Region) =
+                ParseInputsFromIter::parse(&["8", "1", "2", "3", "4"]).unwrap();
+            assert_eq!(some, (8, Region::new(1, 2, 3, 4)))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_u32_u32_fail_on_neg() {
-            let _some: (u32, u32, u32, u32) =
-                ParseInputsFromIter::parse(&["03579", "-0", "1", "1"]).unwrap();
+        fn fail_on_length_too_short() {
+            let _some: (u32, Region) = ParseInputsFromIter::parse(&["8", "1", "2", "3"]).unwrap();
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_u32_u32_fail_on_length_too_short() {
-            let _some: (u32, u32, u32, u32) =
-                ParseInputsFromIter::parse(&["03579", "-0", "1"]).unwrap();
+        fn fail_on_neg() {
+            let _some: (u32, Region) =
+                ParseInputsFromIter::parse(&["8", "-1", "2", "3", "4"]).unwrap();
+        }
+    }
+
+    mod tuple_f32_region {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_f32_region() {
+            let some: (f32, Region) =
+                ParseInputsFromIter::parse(&["2.5", "1", "2", "3", "4"]).unwrap();
+            assert_eq!(some, (2.5, Region::new(1, 2, 3, 4)))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_u32_u32_fail_on_length_too_long() {
-            let _some: (u32, u32, u32, u32) =
-                ParseInputsFromIter::parse(&["03579", "0", "1", "1", "1"]).unwrap();
+        fn fail_on_length_too_long() {
+            let _some: (f32, Region) =
+                ParseInputsFromIter::parse(&["2.5", "1", "2", "3", "4", "5"]).unwrap();
+        }
+    }
+
+    mod tuple_u32_string {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_string() {
+            let some: (u32, String) = ParseInputsFromIter::parse(&["8", "faces.json"]).unwrap();
+            assert_eq!(some, (8, String::from("faces.json")))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_u32_u32_fail_not_u32() {
-            let _some: (u32, u32, u32, u32) =
-                ParseInputsFromIter::parse(&["03579", "0", "1", "o"]).unwrap();
+        fn fail_on_missing_string() {
+            let _some: (u32, String) = ParseInputsFromIter::parse(&["8"]).unwrap();
         }
     }
 
-    mod array_f32x9 {
+    mod tuple_f32_string {
         use super::*;
 
         #[test]
-        fn array_of_f32() {
-            let some: [f32; 9] = ParseInputsFromIter::parse(&[
-                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999",
-            ])
-            .unwrap();
-            let expected: [f32; 9] = [
-                1f32, 2f32, 3f32, 4f32, 5.5f32, -6.0f32, 7f32, 8f32, -9.9999f32,
-            ];
-            assert_eq!(some, expected);
+        fn a_tuple_of_f32_string() {
+            let some: (f32, String) = ParseInputsFromIter::parse(&["2.5", "faces.csv"]).unwrap();
+            assert_eq!(some, (2.5, String::from("faces.csv")))
         }
 
         #[test]
         #[should_panic]
-        fn array_of_f32_oof_too_short() {
-            let _some: [f32; 9] =
-                ParseInputsFromIter::parse(&["1", "2", "3", "4", "5.5", "-6.0", "7", "8"]).unwrap();
+        fn fail_on_not_f32() {
+            let _some: (f32, String) = ParseInputsFromIter::parse(&["x", "faces.csv"]).unwrap();
+        }
+    }
+
+    mod tuple_i32_i32_f32_color {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_i32_i32_f32_color() {
+            let some: (i32, i32, f32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["-4", "8", "2.5", "#00000080"]).unwrap();
+            assert_eq!(some, (-4, 8, 2.5, Rgba([0, 0, 0, 128])))
         }
 
         #[test]
         #[should_panic]
-        fn array_of_f32_oof_too_long() {
-            let _some: [f32; 9] = ParseInputsFromIter::parse(&[
-                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "-9.9999", "1",
-            ])
-            .unwrap();
+        fn fail_on_invalid_color() {
+            let _some: (i32, i32, f32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "2.5", "#00000"]).unwrap();
         }
 
         #[test]
         #[should_panic]
-        fn array_of_f32_oof_not_a_f32() {
-            let _some: [f32; 9] = ParseInputsFromIter::parse(&[
-                "1", "2", "3", "4", "5.5", "-6.0", "7", "8", "lalala",
-            ])
-            .unwrap();
+        fn fail_on_length_too_short() {
+            let _some: (i32, i32, f32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "2.5"]).unwrap();
         }
     }
 
-    mod tuple_u32_u32 {
+    mod tuple_i32_i32_u32_u32_color {
         use super::*;
 
         #[test]
-        fn a_tuple_of_u32_u32() {
-            let some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "0"]).unwrap();
-            assert_eq!(some, (3579u32, 0u32))
+        fn a_tuple_of_i32_i32_u32_u32_color() {
+            let some: (i32, i32, u32, u32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["-4", "8", "20", "10", "red"]).unwrap();
+            assert_eq!(some, (-4, 8, 20, 10, Rgba([255, 0, 0, 255])))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_fail_on_neg() {
-            let _some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "-0"]).unwrap();
+        fn fail_on_negative_size() {
+            let _some: (i32, i32, u32, u32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "-20", "10", "red"]).unwrap();
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_fail_on_empty() {
-            let empty: &[&str; 0] = &[];
-            let _some: (u32, u32) = ParseInputsFromIter::parse(empty).unwrap();
+        fn fail_on_length_too_long() {
+            let _some: (i32, i32, u32, u32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "20", "10", "red", "1"]).unwrap();
+        }
+    }
+
+    mod tuple_i32_i32_i32_i32_color {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_i32_i32_i32_i32_color() {
+            let some: (i32, i32, i32, i32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["-4", "8", "20", "-10", "#0f0"]).unwrap();
+            assert_eq!(some, (-4, 8, 20, -10, Rgba([0, 255, 0, 255])))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_fail_on_too_short() {
-            let _some: (u32, u32) = ParseInputsFromIter::parse(&["03579"]).unwrap();
+        fn fail_on_missing_color() {
+            let _some: (i32, i32, i32, i32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "20", "10"]).unwrap();
+        }
+    }
+
+    mod tuple_color_points {
+        use super::*;
+
+        #[test]
+        fn separate_values() {
+            let some: (Rgba<u8>, Vec<(i32, i32)>) =
+                ParseInputsFromIter::parse(&["blue", "0:0", "-10:20", "30:5"]).unwrap();
+            assert_eq!(
+                some,
+                (Rgba([0, 0, 255, 255]), vec![(0, 0), (-10, 20), (30, 5)])
+            )
+        }
+
+        #[test]
+        fn comma_separated() {
+            let some: (Rgba<u8>, Vec<(i32, i32)>) =
+                ParseInputsFromIter::parse(&["#ff000080", "0:0,-10:20,30:5"]).unwrap();
+            assert_eq!(
+                some,
+                (Rgba([255, 0, 0, 128]), vec![(0, 0), (-10, 20), (30, 5)])
+            )
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_u32_u32_fail_on_too_long() {
-            let _some: (u32, u32) = ParseInputsFromIter::parse(&["03579", "1", "1"]).unwrap();
+        fn fail_on_no_points() {
+            let _some: (Rgba<u8>, Vec<(i32, i32)>) = ParseInputsFromIter::parse(&["blue"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_invalid_point() {
+            let _some: (Rgba<u8>, Vec<(i32, i32)>) =
+                ParseInputsFromIter::parse(&["blue", "0:0:0"]).unwrap();
         }
     }
 
-    mod tuple_f32_i32 {
+    mod tuple_string_i32_i32 {
         use super::*;
 
         #[test]
-        fn a_tuple_of_f32_i32() {
-            let some: (f32, i32) = ParseInputsFromIter::parse(&["-03579.1", "-0"]).unwrap();
-            assert_eq!(some, (-3579.1f32, 0i32))
+        fn a_tuple_of_string_i32_i32() {
+            let some: (String, i32, i32) =
+                ParseInputsFromIter::parse(&["Hello, world!", "-4", "8"]).unwrap();
+            assert_eq!(some, ("Hello, world!".to_string(), -4, 8))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_f32_i32_fail_on_not_f32() {
-            let _some: (f32, i32) = ParseInputsFromIter::parse(&["f", "-1"]).unwrap();
+        fn fail_on_missing_position() {
+            let _some: (String, i32, i32) = ParseInputsFromIter::parse(&["Hello", "4"]).unwrap();
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_f32_i32_fail_on_not_i32() {
-            let _some: (f32, i32) = ParseInputsFromIter::parse(&["-1.0", "f"]).unwrap();
+        fn fail_on_too_many_values() {
+            let _some: (String, i32, i32) =
+                ParseInputsFromIter::parse(&["Hello", "4", "8", "16"]).unwrap();
+        }
+    }
+
+    mod tuple_axis_paths {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_axis_paths() {
+            let some: (Axis, Vec<String>) =
+                ParseInputsFromIter::parse(&["y", "a.png", "my dir/b.png"]).unwrap();
+            assert_eq!(
+                some,
+                (
+                    Axis::Y,
+                    vec!["a.png".to_string(), "my dir/b.png".to_string()]
+                )
+            )
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_f32_i32_fail_on_empty() {
-            let empty: &[&str; 0] = &[];
-            let _some: (f32, i32) = ParseInputsFromIter::parse(empty).unwrap();
+        fn fail_on_missing_paths() {
+            let _some: (Axis, Vec<String>) = ParseInputsFromIter::parse(&["x"]).unwrap();
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_f32_i32_fail_on_too_short() {
-            let _some: (f32, i32) = ParseInputsFromIter::parse(&["03579"]).unwrap();
+        fn fail_on_unknown_axis() {
+            let _some: (Axis, Vec<String>) = ParseInputsFromIter::parse(&["z", "a.png"]).unwrap();
+        }
+    }
+
+    mod tuple_u32_u32_rgba {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32_rgba() {
+            let some: (u32, u32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "#ff0000"]).unwrap();
+            assert_eq!(some, (4, 8, Rgba([255, 0, 0, 255])))
         }
 
         #[test]
         #[should_panic]
-        fn a_tuple_of_f32_i32_fail_on_too_long() {
-            let _some: (f32, i32) = ParseInputsFromIter::parse(&["03579", "1", "1"]).unwrap();
+        fn fail_on_negative_size() {
+            let _some: (u32, u32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["-4", "8", "red"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_too_many_values() {
+            let _some: (u32, u32, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "red", "blue"]).unwrap();
+        }
+    }
+
+    mod tuple_u32_u32_u32_rgba_rgba {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32_u32_rgba_rgba() {
+            let some: (u32, u32, u32, Rgba<u8>, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "2", "white", "#000"]).unwrap();
+            assert_eq!(
+                some,
+                (4, 8, 2, Rgba([255, 255, 255, 255]), Rgba([0, 0, 0, 255]))
+            )
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_missing_color() {
+            let _some: (u32, u32, u32, Rgba<u8>, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "2", "white"]).unwrap();
+        }
+    }
+
+    mod tuple_u32_u32_rgba_rgba {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32_rgba_rgba() {
+            let some: (u32, u32, Rgba<u8>, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "white", "transparent"]).unwrap();
+            assert_eq!(some, (4, 8, Rgba([255, 255, 255, 255]), Rgba([0, 0, 0, 0])))
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_invalid_color() {
+            let _some: (u32, u32, Rgba<u8>, Rgba<u8>) =
+                ParseInputsFromIter::parse(&["4", "8", "white", "#12"]).unwrap();
+        }
+    }
+
+    mod tuple_u32_u32_rgba_rgba_f32 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_u32_u32_rgba_rgba_f32() {
+            let some: (u32, u32, Rgba<u8>, Rgba<u8>, f32) =
+                ParseInputsFromIter::parse(&["4", "8", "red", "blue", "-45.5"]).unwrap();
+            assert_eq!(
+                some,
+                (4, 8, Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255]), -45.5)
+            )
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_missing_angle() {
+            let _some: (u32, u32, Rgba<u8>, Rgba<u8>, f32) =
+                ParseInputsFromIter::parse(&["4", "8", "red", "blue"]).unwrap();
+        }
+    }
+
+    mod tuple_noise_kind_u32_u32_f32_u64 {
+        use super::*;
+
+        #[test]
+        fn a_tuple_of_noise_kind_u32_u32_f32_u64() {
+            let some: (NoiseKind, u32, u32, f32, u64) =
+                ParseInputsFromIter::parse(&["perlin", "4", "8", "2.5", "18446744073709551615"])
+                    .unwrap();
+            assert_eq!(some, (NoiseKind::Perlin, 4, 8, 2.5, std::u64::MAX))
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_unknown_kind() {
+            let _some: (NoiseKind, u32, u32, f32, u64) =
+                ParseInputsFromIter::parse(&["simplex", "4", "8", "2.5", "1"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_negative_seed() {
+            let _some: (NoiseKind, u32, u32, f32, u64) =
+                ParseInputsFromIter::parse(&["value", "4", "8", "2.5", "-1"]).unwrap();
+        }
+    }
+
+    mod color {
+        use super::*;
+
+        #[test]
+        fn hex_rrggbb() {
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["#ff8001"]).unwrap();
+            assert_eq!(some, Rgba([255, 128, 1, 255]))
+        }
+
+        #[test]
+        fn hex_rrggbbaa() {
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["#FF800140"]).unwrap();
+            assert_eq!(some, Rgba([255, 128, 1, 64]))
+        }
+
+        #[test]
+        fn hex_rgb() {
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["#f80"]).unwrap();
+            assert_eq!(some, Rgba([255, 136, 0, 255]))
+        }
+
+        #[test]
+        fn hex_rgba() {
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["#f808"]).unwrap();
+            assert_eq!(some, Rgba([255, 136, 0, 136]))
+        }
+
+        #[test]
+        fn named() {
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["White"]).unwrap();
+            assert_eq!(some, Rgba([255, 255, 255, 255]));
+
+            let some: Rgba<u8> = ParseInputsFromIter::parse(&["transparent"]).unwrap();
+            assert_eq!(some, Rgba([0, 0, 0, 0]));
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_unknown_name() {
+            let _some: Rgba<u8> = ParseInputsFromIter::parse(&["purplish"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_invalid_hex_digit() {
+            let _some: Rgba<u8> = ParseInputsFromIter::parse(&["#ff80zz"]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn fail_on_invalid_hex_length() {
+            let _some: Rgba<u8> = ParseInputsFromIter::parse(&["#ff80f"]).unwrap();
         }
     }
 }
@@ -2,7 +2,12 @@ use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches};
+use sic_image_engine::atlas::descriptor::Descriptor;
+use sic_image_engine::atlas::{Atlas, Packer};
 use sic_image_engine::engine::Instruction;
+use sic_image_engine::montage::Montage;
+use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+use sic_parser::value_parser::ParseInputsFromIter;
 
 use crate::app::config::{validate_jpeg_quality, Config, ConfigBuilder, SelectedLicenses};
 use crate::app::operations::{
@@ -11,7 +16,19 @@ use crate::app::operations::{
 use crate::get_tool_name;
 use crate::{op_valueless, op_with_values};
 use arg_names::*;
-use sic_io::load::FrameIndex;
+use sic_io::auto::ChoiceReport;
+use sic_io::encoders::ico::IcoSettings;
+use sic_io::encoders::jpeg::ChromaSubsampling;
+use sic_io::encoders::png::{PngCompression, PngFilter};
+use sic_io::encoders::tga::TgaEncoding;
+use sic_io::encoders::tiff::TiffCompression;
+use sic_io::load::{FrameIndex, InputFormat};
+use sic_io::pyramid::{Pyramid, PyramidLayout};
+use sic_io::raw::{ByteOrder, RawColorType, RawFormat, SampleDepth};
+use sic_io::responsive::ResponsiveSet;
+use sic_io::size_limit::SizeLimit;
+use sic_io::split::{EdgePolicy, Split, SplitMode};
+use sic_io::text::TextEncoding;
 
 const ABOUT: &str = include_str!("../../resources/help-pages/about.txt");
 const HELP_OPERATIONS_AVAILABLE: &str =
@@ -24,50 +41,130 @@ pub(crate) mod arg_names {
     // organisational:
     pub(crate) const ARG_LICENSE: &str = "license";
     pub(crate) const ARG_DEP_LICENSES: &str = "dep_licenses";
+    pub(crate) const ARG_LIST_FORMATS: &str = "list_formats";
 
     // io(input):
+    pub(crate) const ARG_ATLAS: &str = "atlas";
+    pub(crate) const ARG_ATLAS_DESCRIPTOR: &str = "atlas_descriptor";
+    pub(crate) const ARG_ATLAS_MAX_SIZE: &str = "atlas_max_size";
+    pub(crate) const ARG_ATLAS_PACKER: &str = "atlas_packer";
+    pub(crate) const ARG_ATLAS_PADDING: &str = "atlas_padding";
+    pub(crate) const ARG_ATLAS_TRIM: &str = "atlas_trim";
+    pub(crate) const ARG_GENERATE: &str = "generate";
     pub(crate) const ARG_INPUT: &str = "input";
+    pub(crate) const ARG_MONTAGE: &str = "montage";
+    pub(crate) const ARG_MONTAGE_BACKGROUND: &str = "montage_background";
+    pub(crate) const ARG_MONTAGE_CELL: &str = "montage_cell";
+    pub(crate) const ARG_MONTAGE_COLUMNS: &str = "montage_columns";
+    pub(crate) const ARG_MONTAGE_LABELS: &str = "montage_labels";
+    pub(crate) const ARG_MONTAGE_SPACING: &str = "montage_spacing";
     pub(crate) const ARG_OUTPUT: &str = "output";
 
     // io(output):
+    pub(crate) const ARG_FAVICON: &str = "favicon";
     pub(crate) const ARG_INPUT_FILE: &str = "input_file";
     pub(crate) const ARG_OUTPUT_FILE: &str = "output_file";
+    pub(crate) const ARG_PYRAMID: &str = "pyramid";
+    pub(crate) const ARG_PYRAMID_FILTER: &str = "pyramid_filter";
+    pub(crate) const ARG_PYRAMID_OVERLAP: &str = "pyramid_overlap";
+    pub(crate) const ARG_PYRAMID_TILE_SIZE: &str = "pyramid_tile_size";
+    pub(crate) const ARG_RESPONSIVE: &str = "responsive";
+    pub(crate) const ARG_RESPONSIVE_FORMATS: &str = "responsive_formats";
+    pub(crate) const ARG_RESPONSIVE_MANIFEST: &str = "responsive_manifest";
+    pub(crate) const GROUP_SPLIT: &str = "group_split";
+    pub(crate) const ARG_SPLIT_EDGE: &str = "split_edge";
+    pub(crate) const ARG_SPLIT_GRID: &str = "split_grid";
+    pub(crate) const ARG_SPLIT_OVERLAP: &str = "split_overlap";
+    pub(crate) const ARG_SPLIT_TILE_SIZE: &str = "split_tile_size";
 
     // config(in):
+    pub(crate) const ARG_INPUT_FORMAT: &str = "input_format";
+    pub(crate) const ARG_RAW_BYTE_ORDER: &str = "raw_byte_order";
+    pub(crate) const ARG_RAW_COLOR_TYPE: &str = "raw_color_type";
+    pub(crate) const ARG_RAW_SAMPLE_DEPTH: &str = "raw_sample_depth";
+    pub(crate) const ARG_RAW_SIZE: &str = "raw_size";
     pub(crate) const ARG_SELECT_FRAME: &str = "select_frame";
 
     // config(out):
     pub(crate) const ARG_DISABLE_AUTOMATIC_COLOR_TYPE_ADJUSTMENT: &str =
         "disable_automatic_color_type_adjustment";
+    pub(crate) const ARG_AUTO_FORMAT_LOSSLESS: &str = "auto_format_lossless";
+    pub(crate) const ARG_AUTO_FORMAT_REPORT: &str = "auto_format_report";
     pub(crate) const ARG_FORCED_OUTPUT_FORMAT: &str = "forced_output_format";
+    pub(crate) const ARG_ICO_SIZES: &str = "ico_sizes";
     pub(crate) const ARG_JPEG_ENCODING_QUALITY: &str = "jpeg_encoding_quality";
-
+    pub(crate) const ARG_JPEG_CHROMA_SUBSAMPLING: &str = "jpeg_chroma_subsampling";
+    pub(crate) const ARG_JPEG_GRAYSCALE: &str = "jpeg_grayscale";
+    pub(crate) const ARG_JPEG_OPTIMIZE_HUFFMAN: &str = "jpeg_optimize_huffman";
+    pub(crate) const ARG_JPEG_PROGRESSIVE: &str = "jpeg_progressive";
+    pub(crate) const ARG_JPEG_RESTART_INTERVAL: &str = "jpeg_restart_interval";
+    pub(crate) const ARG_MAX_OUTPUT_SIZE: &str = "max_output_size";
+    pub(crate) const ARG_MAX_OUTPUT_SIZE_DOWNSCALE: &str = "max_output_size_downscale";
+    pub(crate) const ARG_OUTPUT_TEXT: &str = "output_text";
+
+    pub(crate) const ARG_PNG_COMPRESSION: &str = "png_compression";
+    pub(crate) const ARG_PNG_FILTER: &str = "png_filter";
+    pub(crate) const ARG_PNG_PALETTE: &str = "png_palette";
+    pub(crate) const ARG_PNG_SIXTEEN_BIT: &str = "png_sixteen_bit";
     pub(crate) const ARG_PNM_ENCODING_ASCII: &str = "pnm_encoding_ascii";
+    pub(crate) const ARG_TGA_ENCODING_RLE: &str = "tga_encoding_rle";
+    pub(crate) const ARG_TIFF_COMPRESSION: &str = "tiff_compression";
 
     // image-operations(script):
     pub(crate) const ARG_APPLY_OPERATIONS: &str = "script";
 
     // image-operations(cli-arguments):
     pub(crate) const GROUP_IMAGE_OPERATIONS: &str = "group";
+    pub(crate) const OP_APPEND: &str = "op_append";
+    pub(crate) const OP_AUTO_LEVELS: &str = "op_autolevels";
     pub(crate) const OP_BLUR: &str = "op_blur";
+    pub(crate) const OP_BLUR_REGION: &str = "op_blur_region";
+    pub(crate) const OP_BLUR_REGIONS: &str = "op_blur_regions";
     pub(crate) const OP_BRIGHTEN: &str = "op_brighten";
+    pub(crate) const OP_CIRCLE_CROP: &str = "op_circlecrop";
+    pub(crate) const OP_CLAHE: &str = "op_clahe";
     pub(crate) const OP_CONTRAST: &str = "op_contrast";
     pub(crate) const OP_CROP: &str = "op_crop";
+    pub(crate) const OP_CURVES: &str = "op_curves";
+    pub(crate) const OP_DRAW_ELLIPSE: &str = "op_draw_ellipse";
+    pub(crate) const OP_DRAW_LINE: &str = "op_draw_line";
+    pub(crate) const OP_DRAW_POLYGON: &str = "op_draw_polygon";
+    pub(crate) const OP_DRAW_RECT: &str = "op_draw_rect";
+    pub(crate) const OP_EQUALIZE: &str = "op_equalize";
     pub(crate) const OP_FILTER3X3: &str = "op_filter3x3";
     pub(crate) const OP_FLIP_HORIZONTAL: &str = "op_fliph";
     pub(crate) const OP_FLIP_VERTICAL: &str = "op_flipv";
     pub(crate) const OP_GRAYSCALE: &str = "op_grayscale";
     pub(crate) const OP_HUE_ROTATE: &str = "op_huerotate";
     pub(crate) const OP_INVERT: &str = "op_invert";
+    pub(crate) const OP_LEVELS: &str = "op_levels";
+    pub(crate) const OP_MATCH_HISTOGRAM: &str = "op_matchhistogram";
+    pub(crate) const OP_PIXELATE: &str = "op_pixelate";
+    pub(crate) const OP_PIXELATE_REGION: &str = "op_pixelate_region";
+    pub(crate) const OP_PIXELATE_REGIONS: &str = "op_pixelate_regions";
     pub(crate) const OP_RESIZE: &str = "op_resize";
     pub(crate) const OP_ROTATE90: &str = "op_rot90";
     pub(crate) const OP_ROTATE180: &str = "op_rot180";
     pub(crate) const OP_ROTATE270: &str = "op_rot270";
+    pub(crate) const OP_ROUND_CORNERS: &str = "op_roundcorners";
+    pub(crate) const OP_SHADOW: &str = "op_shadow";
+    pub(crate) const OP_TEXT: &str = "op_text";
     pub(crate) const OP_UNSHARPEN: &str = "op_unsharpen";
+    pub(crate) const OP_VIGNETTE: &str = "op_vignette";
 
     // image-operations(cli-arguments/modifiers):
+    pub(crate) const OPMOD_APPEND_ALIGNMENT: &str = "opmod_append_alignment";
+    pub(crate) const OPMOD_APPEND_BACKGROUND: &str = "opmod_append_background";
+    pub(crate) const OPMOD_DRAW_ANTIALIAS: &str = "opmod_draw_antialias";
+    pub(crate) const OPMOD_DRAW_FILL: &str = "opmod_draw_fill";
+    pub(crate) const OPMOD_DRAW_STROKE_WIDTH: &str = "opmod_draw_stroke_width";
+    pub(crate) const OPMOD_MATCH_HISTOGRAM_MODE: &str = "opmod_matchhistogram_mode";
     pub(crate) const OPMOD_RESIZE_PRESERVE_ASPECT_RATIO: &str = "opmod_resize_par";
     pub(crate) const OPMOD_RESIZE_SAMPLING_FILTER: &str = "opmod_resize_sampling_filter";
+    pub(crate) const OPMOD_TEXT_COLOR: &str = "opmod_text_color";
+    pub(crate) const OPMOD_TEXT_FONT: &str = "opmod_text_font";
+    pub(crate) const OPMOD_TEXT_GRAVITY: &str = "opmod_text_gravity";
+    pub(crate) const OPMOD_TEXT_SIZE: &str = "opmod_text_size";
 }
 
 pub fn cli() -> App<'static, 'static> {
@@ -98,6 +195,11 @@ pub fn cli() -> App<'static, 'static> {
             .help("Displays the licenses of the dependencies on which this software relies.")
             .takes_value(false)
             .conflicts_with_all(&[ARG_LICENSE, ARG_INPUT_FILE, ARG_OUTPUT_FILE, ARG_INPUT, ARG_OUTPUT]))
+        .arg(Arg::with_name(ARG_LIST_FORMATS)
+            .long("list-formats")
+            .help("Displays the supported image formats, and whether each can be read (as input) and written (as output).")
+            .takes_value(false)
+            .conflicts_with_all(&[ARG_LICENSE, ARG_DEP_LICENSES, ARG_INPUT_FILE, ARG_OUTPUT_FILE, ARG_INPUT, ARG_OUTPUT]))
 
         // io(input):
         .arg(Arg::with_name(ARG_INPUT)
@@ -106,12 +208,107 @@ pub fn cli() -> App<'static, 'static> {
             .value_name("INPUT_PATH")
             .takes_value(true)
             .help("Input image path. When using this option, input piped from stdin will be ignored.")
-            .conflicts_with_all(&[ARG_INPUT_FILE, ARG_OUTPUT_FILE, ARG_LICENSE, ARG_DEP_LICENSES]))
+            .conflicts_with_all(&[ARG_ATLAS, ARG_GENERATE, ARG_MONTAGE, ARG_INPUT_FILE, ARG_OUTPUT_FILE, ARG_LICENSE, ARG_DEP_LICENSES]))
+        .arg(Arg::with_name(ARG_GENERATE)
+            .long("generate")
+            .short("g")
+            .value_name("GENERATOR")
+            .takes_value(true)
+            .help("Generates the starting image instead of reading an input image. \
+            Choices are: 'canvas <width> <height> <color>', 'checkerboard <width> <height> <cell_size> <color> <color>', \
+            'gradient linear <width> <height> <color> <color> [<angle>]', 'gradient radial <width> <height> <color> <color>' \
+            and 'noise <perlin|value> <width> <height> <scale> <seed>'.")
+            .conflicts_with_all(&[ARG_INPUT, ARG_ATLAS, ARG_MONTAGE, ARG_INPUT_FILE, ARG_OUTPUT_FILE, ARG_SELECT_FRAME, ARG_INPUT_FORMAT, ARG_LICENSE, ARG_DEP_LICENSES]))
+        .arg(Arg::with_name(ARG_MONTAGE)
+            .long("montage")
+            .value_name("INPUT_PATHS")
+            .takes_value(true)
+            .multiple(true)
+            .min_values(1)
+            .help("Arranges the input images at the given paths in a grid, instead of reading a single input image. \
+            Each image is scaled to fit a cell. The montage is used as starting image.")
+            .conflicts_with_all(&[ARG_INPUT, ARG_ATLAS, ARG_GENERATE, ARG_INPUT_FILE, ARG_OUTPUT_FILE, ARG_LICENSE, ARG_DEP_LICENSES]))
+        .arg(Arg::with_name(ARG_MONTAGE_BACKGROUND)
+            .long("montage-background")
+            .value_name("COLOR")
+            .takes_value(true)
+            .help("The color of the space between and around the cells of the montage. Defaults to white.")
+            .requires(ARG_MONTAGE))
+        .arg(Arg::with_name(ARG_MONTAGE_CELL)
+            .long("montage-cell")
+            .value_name("WIDTH HEIGHT")
+            .takes_value(true)
+            .number_of_values(2)
+            .help("The size of a cell of the montage. Each image is scaled to fit its cell, while preserving its aspect ratio. \
+            Defaults to 128 by 128 pixels.")
+            .requires(ARG_MONTAGE))
+        .arg(Arg::with_name(ARG_MONTAGE_COLUMNS)
+            .long("montage-columns")
+            .value_name("COLUMNS")
+            .takes_value(true)
+            .help("The number of columns of the montage. By default, the grid is about as wide as it is high.")
+            .requires(ARG_MONTAGE))
+        .arg(Arg::with_name(ARG_MONTAGE_LABELS)
+            .long("montage-labels")
+            .help("Labels each cell of the montage with the file name of its image.")
+            .requires(ARG_MONTAGE))
+        .arg(Arg::with_name(ARG_MONTAGE_SPACING)
+            .long("montage-spacing")
+            .value_name("PIXELS")
+            .takes_value(true)
+            .help("The space between the cells of the montage, and between the cells and the edges of the montage. \
+            Defaults to 4 pixels.")
+            .requires(ARG_MONTAGE))
+        .arg(Arg::with_name(ARG_ATLAS)
+            .long("atlas")
+            .value_name("INPUT_PATHS")
+            .takes_value(true)
+            .multiple(true)
+            .min_values(1)
+            .help("Packs the input images at the given paths into the sheets of an atlas (sprite sheet). \
+            Image operations are applied to each image before it is packed. Each sheet is written to the output path, \
+            next to a descriptor with the same name. If the images don't fit on a single sheet, the output path \
+            should contain the '{sheet}' placeholder, which is replaced by the index of each sheet.")
+            .requires(ARG_OUTPUT)
+            .conflicts_with_all(&[ARG_INPUT, ARG_GENERATE, ARG_MONTAGE, ARG_INPUT_FILE, ARG_OUTPUT_FILE, GROUP_SPLIT, ARG_PYRAMID, ARG_RESPONSIVE, ARG_LICENSE, ARG_DEP_LICENSES]))
+        .arg(Arg::with_name(ARG_ATLAS_DESCRIPTOR)
+            .long("atlas-descriptor")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .possible_values(&["json", "xml"])
+            .help("The format of the descriptors of the sheets: 'json' for the JSON (hash) format of TexturePacker (default), \
+            or 'xml' for the Sparrow/Starling format.")
+            .requires(ARG_ATLAS))
+        .arg(Arg::with_name(ARG_ATLAS_MAX_SIZE)
+            .long("atlas-max-size")
+            .value_name("PIXELS")
+            .takes_value(true)
+            .help("The maximum width and height of a sheet, which should be a power of two. The width and height of \
+            each sheet are the smallest powers of two which fit its images. Defaults to 2048.")
+            .requires(ARG_ATLAS))
+        .arg(Arg::with_name(ARG_ATLAS_PACKER)
+            .long("atlas-packer")
+            .value_name("PACKER")
+            .takes_value(true)
+            .possible_values(&["maxrects", "skyline"])
+            .help("The algorithm which places the images on the sheets. Defaults to 'maxrects'.")
+            .requires(ARG_ATLAS))
+        .arg(Arg::with_name(ARG_ATLAS_PADDING)
+            .long("atlas-padding")
+            .value_name("PIXELS")
+            .takes_value(true)
+            .help("The number of transparent pixels between the images on a sheet. Defaults to 2.")
+            .requires(ARG_ATLAS))
+        .arg(Arg::with_name(ARG_ATLAS_TRIM)
+            .long("atlas-trim")
+            .help("Trims the transparent borders of the images before they are packed. The descriptor records \
+            the trimmed borders.")
+            .requires(ARG_ATLAS))
         .arg(Arg::with_name(ARG_INPUT_FILE)
             .help("DEPRECATED. Use '--input' instead. (Sets the input file. Can only be used in combination with OUTPUT_FILE.)")
             .value_name("INPUT_FILE")
             .requires(ARG_OUTPUT_FILE)
-            .conflicts_with_all(&[ARG_INPUT, ARG_OUTPUT, ARG_LICENSE, ARG_DEP_LICENSES])
+            .conflicts_with_all(&[ARG_INPUT, ARG_OUTPUT, ARG_ATLAS, ARG_GENERATE, ARG_MONTAGE, ARG_LICENSE, ARG_DEP_LICENSES])
             .index(1))
 
         // io(output):
@@ -128,8 +325,143 @@ pub fn cli() -> App<'static, 'static> {
             .requires(ARG_INPUT_FILE)
             .conflicts_with_all(&[ARG_INPUT, ARG_OUTPUT, ARG_LICENSE, ARG_DEP_LICENSES])
             .index(2))
+        .group(ArgGroup::with_name(GROUP_SPLIT)
+            .args(&[ARG_SPLIT_TILE_SIZE, ARG_SPLIT_GRID])
+            .requires(ARG_OUTPUT))
+        .arg(Arg::with_name(ARG_SPLIT_TILE_SIZE)
+            .long("split-tile-size")
+            .value_name("WIDTH HEIGHT")
+            .takes_value(true)
+            .number_of_values(2)
+            .help("Splits the image into tiles of the given size, which are written to separate files. \
+            The output path should contain the '{row}' and '{col}' placeholders, which are replaced by the \
+            (zero-indexed) row and column of each tile, for example: 'tiles/{row}_{col}.png'."))
+        .arg(Arg::with_name(ARG_SPLIT_GRID)
+            .long("split-grid")
+            .value_name("COLUMNS ROWS")
+            .takes_value(true)
+            .number_of_values(2)
+            .help("Splits the image into a grid of tiles of (about) equal size, which are written to separate files. \
+            The output path should contain the '{row}' and '{col}' placeholders, like with --split-tile-size."))
+        .arg(Arg::with_name(ARG_SPLIT_OVERLAP)
+            .long("split-overlap")
+            .value_name("PIXELS")
+            .takes_value(true)
+            .help("The number of pixels by which each tile extends into its neighbouring tiles. Defaults to 0.")
+            .requires(GROUP_SPLIT))
+        .arg(Arg::with_name(ARG_SPLIT_EDGE)
+            .long("split-edge")
+            .value_name("POLICY")
+            .takes_value(true)
+            .possible_values(&["keep", "pad", "discard"])
+            .help("What to do with the tiles at the right and bottom edges, when the image can't be divided into whole tiles \
+            of the size given by --split-tile-size: 'keep' the smaller tiles (default), 'pad' them with transparent pixels \
+            to the full tile size, or 'discard' them.")
+            .requires(ARG_SPLIT_TILE_SIZE))
+        .arg(Arg::with_name(ARG_PYRAMID)
+            .long("pyramid")
+            .value_name("LAYOUT")
+            .takes_value(true)
+            .possible_values(&["dzi", "xyz"])
+            .help("Builds a pyramid of tiles from the image, for deep zoom viewers. With 'dzi', the output path is the \
+            DeepZoom descriptor, and the tiles are written to the '<name>_files' directory next to it. With 'xyz', the \
+            tiles are written to '<z>/<x>/<y>' within the output path. The tiles are JPEG images for 'dzi' and PNG images \
+            for 'xyz', unless another format is given with --output-format.")
+            .requires(ARG_OUTPUT)
+            .conflicts_with(GROUP_SPLIT))
+        .arg(Arg::with_name(ARG_PYRAMID_FILTER)
+            .long("pyramid-filter")
+            .value_name("FILTER")
+            .takes_value(true)
+            .help("The sampling filter used to scale the image down to each level of the pyramid. \
+            Choices are: catmullrom, gaussian, lanczos3, nearest and triangle. Defaults to gaussian.")
+            .requires(ARG_PYRAMID))
+        .arg(Arg::with_name(ARG_PYRAMID_OVERLAP)
+            .long("pyramid-overlap")
+            .value_name("PIXELS")
+            .takes_value(true)
+            .help("The number of pixels by which each tile extends into its neighbouring tiles. \
+            Defaults to 1 for 'dzi' and 0 for 'xyz'.")
+            .requires(ARG_PYRAMID))
+        .arg(Arg::with_name(ARG_PYRAMID_TILE_SIZE)
+            .long("pyramid-tile-size")
+            .value_name("PIXELS")
+            .takes_value(true)
+            .help("The width and height of the tiles, without their overlap. Defaults to 254 for 'dzi' and 256 for 'xyz'.")
+            .requires(ARG_PYRAMID))
+        .arg(Arg::with_name(ARG_RESPONSIVE)
+            .long("responsive")
+            .value_name("WIDTHS")
+            .takes_value(true)
+            .multiple(true)
+            .min_values(1)
+            .help("Scales the image to each of the given widths, keeping its aspect ratio, for use in the srcset of an \
+            HTML image. Widths larger than the image are skipped. The output path should contain the '{width}' placeholder, \
+            which is replaced by the width of each image. The written images are described in a manifest.")
+            .requires(ARG_OUTPUT)
+            .conflicts_with_all(&[GROUP_SPLIT, ARG_PYRAMID]))
+        .arg(Arg::with_name(ARG_RESPONSIVE_FORMATS)
+            .long("responsive-formats")
+            .value_name("FORMATS")
+            .takes_value(true)
+            .multiple(true)
+            .min_values(1)
+            .help("The formats in which each image is written, for example 'png jpg'. The format is used as the \
+            extension of each image. Defaults to the format of the output path.")
+            .requires(ARG_RESPONSIVE)
+            .conflicts_with(ARG_FORCED_OUTPUT_FORMAT))
+        .arg(Arg::with_name(ARG_RESPONSIVE_MANIFEST)
+            .long("responsive-manifest")
+            .value_name("PATH")
+            .takes_value(true)
+            .help("The path of the manifest: a '.json' file with the sizes and byte counts of the images and the srcset \
+            of each format, or an '.html' file with a <picture> element. Defaults to the output path with '{width}' \
+            replaced by 'srcset' and the 'json' extension.")
+            .requires(ARG_RESPONSIVE))
+        .arg(Arg::with_name(ARG_FAVICON)
+            .long("favicon")
+            .help("Writes a favicon pack to the output path, which is used as a directory: 'favicon.ico' with the image at \
+            each of the ICO sizes (see --ico-sizes), 'apple-touch-icon.png' (180x180), 'android-chrome-192x192.png' and \
+            'android-chrome-512x512.png', and a 'site.webmanifest' snippet which refers to the Android icons. Images which \
+            aren't square are centered on a transparent background, or a white background for the Apple touch icon.")
+            .requires(ARG_OUTPUT)
+            .conflicts_with_all(&[GROUP_SPLIT, ARG_PYRAMID, ARG_RESPONSIVE, ARG_ATLAS, ARG_FORCED_OUTPUT_FORMAT]))
 
         // config(in):
+        .arg(Arg::with_name(ARG_INPUT_FORMAT)
+            .long("input-format")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .help("The format of the input image(s), instead of recognizing the format by the contents of the image. \
+            Input formats (FORMAT values) supported: BMP, DDS, GIF, HDR, ICO, JPEG, PNG, PBM, PGM, PPM, PAM, TGA, TIFF, WebP \
+            and 'raw'. Raw input is pixel data without a header, of which the size is given by --raw-size, and the layout by \
+            --raw-color-type, --raw-sample-depth and --raw-byte-order."))
+        .arg(Arg::with_name(ARG_RAW_SIZE)
+            .long("raw-size")
+            .value_name("WIDTH HEIGHT")
+            .takes_value(true)
+            .number_of_values(2)
+            .help("The width and height of the image of raw input pixel data.")
+            .requires(ARG_INPUT_FORMAT))
+        .arg(Arg::with_name(ARG_RAW_COLOR_TYPE)
+            .long("raw-color-type")
+            .value_name("COLOR_TYPE")
+            .takes_value(true)
+            .possible_values(&["gray", "graya", "rgb", "rgba", "bgr", "bgra"])
+            .help("The channels of each pixel of raw input or output pixel data, in order. Defaults to 'rgba'."))
+        .arg(Arg::with_name(ARG_RAW_SAMPLE_DEPTH)
+            .long("raw-sample-depth")
+            .value_name("BITS")
+            .takes_value(true)
+            .possible_values(&["8", "16"])
+            .help("The number of bits of each sample (channel of a pixel) of raw input or output pixel data. 16 bit \
+            samples are scaled to 8 bits when read, and scaled up from 8 bits when written. Defaults to 8."))
+        .arg(Arg::with_name(ARG_RAW_BYTE_ORDER)
+            .long("raw-byte-order")
+            .value_name("ORDER")
+            .takes_value(true)
+            .possible_values(&["little", "big"])
+            .help("The byte order of 16 bit samples of raw input or output pixel data. Defaults to 'little' (endian)."))
         .arg(Arg::with_name(ARG_SELECT_FRAME)
             .long("select-frame")
             .value_name("#FRAME")
@@ -150,16 +482,119 @@ pub fn cli() -> App<'static, 'static> {
             .long("output-format")
             .value_name("FORMAT")
             .help("Force the output image format to use FORMAT, regardless of the (if any) extension of the given output file path. \
-                Output formats (FORMAT values) supported: BMP, GIF, ICO, JPEG, PNG, PBM, PGM, PPM and PAM.")
+                Output formats (FORMAT values) supported: BMP, GIF, ICO, JPEG, PNG, PBM, PGM, PPM, PAM, TIFF, TGA, farbfeld (FF) and 'raw'. \
+                Use 'same' to keep the format of the input image, for example when writing to the stdout. \
+                Use 'auto' to write the image in the format in which it's smallest, out of PNG, JPEG, GIF, TIFF, TGA and BMP \
+                (JPEG and GIF are skipped if the image has transparent pixels); \
+                the extension of the output path is corrected if it doesn't match the chosen format. \
+                Raw output is pixel data without a header, of which the layout is given by --raw-color-type, --raw-sample-depth \
+                and --raw-byte-order.")
             .takes_value(true))
+        .arg(Arg::with_name(ARG_AUTO_FORMAT_LOSSLESS)
+            .long("auto-format-lossless")
+            .help("Only choose from the lossless formats (PNG, TIFF, TGA and BMP) when using the 'auto' output format."))
+        .arg(Arg::with_name(ARG_AUTO_FORMAT_REPORT)
+            .long("auto-format-report")
+            .value_name("REPORT")
+            .takes_value(true)
+            .possible_values(&["text", "json"])
+            .help("The format of the report of the choice, which is written to the stderr, when using the 'auto' \
+            output format. The report lists the size of the image in each candidate format. Defaults to 'text'."))
         .arg(Arg::with_name(ARG_JPEG_ENCODING_QUALITY)
             .long("jpeg-encoding-quality")
             .help("Set the jpeg quality to QUALITY. Valid values are positive numbers from 1 up to and including 100. Will only be used when the output format is determined to be jpeg.")
             .value_name("QUALITY")
             .takes_value(true))
+        .arg(Arg::with_name(ARG_JPEG_PROGRESSIVE)
+            .long("jpeg-progressive")
+            .help("Write a progressive image when using the JPEG image output format. A progressive image is shown \
+            at a low quality first, and refined while it is loading."))
+        .arg(Arg::with_name(ARG_JPEG_CHROMA_SUBSAMPLING)
+            .long("jpeg-chroma-subsampling")
+            .value_name("RATIO")
+            .takes_value(true)
+            .possible_values(&["4:4:4", "4:2:2", "4:2:0"])
+            .help("The resolution of the color information relative to the brightness when using the JPEG image \
+            output format: '4:2:2' halves the horizontal resolution and '4:2:0' both the horizontal and vertical \
+            resolution. Defaults to '4:4:4', i.e. no subsampling."))
+        .arg(Arg::with_name(ARG_JPEG_OPTIMIZE_HUFFMAN)
+            .long("jpeg-optimize-huffman")
+            .help("Use Huffman tables fitted to the image instead of the standard tables when using the JPEG image \
+            output format, which makes the image smaller but takes longer to encode."))
+        .arg(Arg::with_name(ARG_JPEG_RESTART_INTERVAL)
+            .long("jpeg-restart-interval")
+            .value_name("MCUS")
+            .takes_value(true)
+            .help("Write a restart marker after every MCUS blocks of pixels (minimum coded units) when using the JPEG \
+            image output format, which lets a decoder recover from corrupted data. Defaults to 0, i.e. no restart markers."))
+        .arg(Arg::with_name(ARG_JPEG_GRAYSCALE)
+            .long("jpeg-grayscale")
+            .help("Write a grayscale image when using the JPEG image output format."))
+        .arg(Arg::with_name(ARG_OUTPUT_TEXT)
+            .long("output-text")
+            .value_name("ENCODING")
+            .takes_value(true)
+            .possible_values(&["base64", "data-uri"])
+            .help("Write the encoded image as text instead of as bytes: 'base64' writes the bytes as a base64 string, \
+            and 'data-uri' as a data URI with the media type of the output format, which can be embedded in HTML or CSS.")
+            .conflicts_with_all(&[GROUP_SPLIT, ARG_PYRAMID, ARG_RESPONSIVE, ARG_ATLAS, ARG_FAVICON]))
+        .arg(Arg::with_name(ARG_MAX_OUTPUT_SIZE)
+            .long("max-output-size")
+            .value_name("SIZE")
+            .takes_value(true)
+            .help("Limit the size of the output image to SIZE, for example '150KB'. Supported units are B, KB, KiB, MB and MiB. \
+            Lossy formats (JPEG) are written at the highest quality, up to the JPEG quality, at which the image fits. \
+            If it doesn't fit at all, the program exits with an error, unless --max-output-size-downscale is provided.")
+            .conflicts_with_all(&[GROUP_SPLIT, ARG_PYRAMID, ARG_RESPONSIVE, ARG_ATLAS, ARG_FAVICON]))
+        .arg(Arg::with_name(ARG_MAX_OUTPUT_SIZE_DOWNSCALE)
+            .long("max-output-size-downscale")
+            .help("Scale the image down, as a last resort, if it doesn't fit within the size given by --max-output-size \
+            at the lowest quality.")
+            .requires(ARG_MAX_OUTPUT_SIZE))
         .arg(Arg::with_name(ARG_PNM_ENCODING_ASCII)
             .long("pnm-encoding-ascii")
             .help("Use ascii based encoding when using a PNM image output format (pbm, pgm or ppm). Doesn't apply to 'pam' (PNM Arbitrary Map)."))
+        .arg(Arg::with_name(ARG_PNG_COMPRESSION)
+            .long("png-compression")
+            .value_name("LEVEL")
+            .takes_value(true)
+            .possible_values(&["fast", "default", "best"])
+            .help("The compression level used when using the PNG image output format. Defaults to 'default'."))
+        .arg(Arg::with_name(ARG_PNG_FILTER)
+            .long("png-filter")
+            .value_name("FILTER")
+            .takes_value(true)
+            .possible_values(&["adaptive", "none", "sub", "up", "average", "paeth"])
+            .help("The filter applied to the scan lines of an image when using the PNG image output format. 'adaptive' \
+            picks the filter for each scan line which is likely to compress best. Defaults to 'adaptive'."))
+        .arg(Arg::with_name(ARG_PNG_SIXTEEN_BIT)
+            .long("png-sixteen-bit")
+            .help("Write 16 bits per sample when using the PNG image output format.")
+            .conflicts_with(ARG_PNG_PALETTE))
+        .arg(Arg::with_name(ARG_PNG_PALETTE)
+            .long("png-palette")
+            .help("Write an indexed (palette) image when using the PNG image output format, if the image has at most \
+            256 colors."))
+        .arg(Arg::with_name(ARG_ICO_SIZES)
+            .long("ico-sizes")
+            .value_name("SIZES")
+            .takes_value(true)
+            .multiple(true)
+            .use_delimiter(true)
+            .require_delimiter(true)
+            .help("The comma separated sizes (in pixels, at most 256) at which the image is embedded when using the ICO \
+            image output format, e.g. '16,32,48'. Images which aren't square are centered on a transparent background. \
+            Defaults to 16, 32, 48 and 256, without the sizes which are larger than the image. The image is scaled with \
+            the resize sampling filter (see --set-resize-sampling-filter)."))
+        .arg(Arg::with_name(ARG_TIFF_COMPRESSION)
+            .long("tiff-compression")
+            .value_name("COMPRESSION")
+            .takes_value(true)
+            .possible_values(&["none", "lzw", "deflate"])
+            .help("The compression used when using the TIFF image output format. Defaults to 'none'."))
+        .arg(Arg::with_name(ARG_TGA_ENCODING_RLE)
+            .long("tga-encoding-rle")
+            .help("Use run-length encoding when using the TGA image output format."))
 
         // image-operations(script):
         .arg(Arg::with_name(ARG_APPLY_OPERATIONS)
@@ -173,27 +608,72 @@ pub fn cli() -> App<'static, 'static> {
         // image-operations(cli-arguments):
         .group(ArgGroup::with_name(GROUP_IMAGE_OPERATIONS)
             .args(&[
+                OP_APPEND,
+                OP_AUTO_LEVELS,
                 OP_BLUR,
+                OP_BLUR_REGION,
+                OP_BLUR_REGIONS,
                 OP_BRIGHTEN,
+                OP_CIRCLE_CROP,
+                OP_CLAHE,
                 OP_CONTRAST,
                 OP_CROP,
+                OP_CURVES,
+                OP_DRAW_ELLIPSE,
+                OP_DRAW_LINE,
+                OP_DRAW_POLYGON,
+                OP_DRAW_RECT,
+                OP_EQUALIZE,
                 OP_FILTER3X3,
                 OP_FLIP_HORIZONTAL,
                 OP_FLIP_VERTICAL,
                 OP_GRAYSCALE,
                 OP_HUE_ROTATE,
                 OP_INVERT,
+                OP_LEVELS,
+                OP_MATCH_HISTOGRAM,
+                OP_PIXELATE,
+                OP_PIXELATE_REGION,
+                OP_PIXELATE_REGIONS,
                 OP_RESIZE,
                 OP_ROTATE90,
                 OP_ROTATE180,
                 OP_ROTATE270,
+                OP_ROUND_CORNERS,
+                OP_SHADOW,
+                OP_TEXT,
                 OP_UNSHARPEN,
-
+                OP_VIGNETTE,
+
+                OPMOD_APPEND_ALIGNMENT,
+                OPMOD_APPEND_BACKGROUND,
+                OPMOD_DRAW_ANTIALIAS,
+                OPMOD_DRAW_FILL,
+                OPMOD_DRAW_STROKE_WIDTH,
+                OPMOD_MATCH_HISTOGRAM_MODE,
                 OPMOD_RESIZE_PRESERVE_ASPECT_RATIO,
                 OPMOD_RESIZE_SAMPLING_FILTER,
+                OPMOD_TEXT_COLOR,
+                OPMOD_TEXT_FONT,
+                OPMOD_TEXT_GRAVITY,
+                OPMOD_TEXT_SIZE,
             ])
             .conflicts_with(ARG_APPLY_OPERATIONS)
             .multiple(true))
+        .arg(Arg::with_name(OP_APPEND)
+            .help("Operation: append.")
+            .long("--append")
+            .takes_value(true)
+            .value_name("axis path")
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OP_AUTO_LEVELS)
+            .help("Operation: auto levels.")
+            .long("--auto-levels")
+            .takes_value(true)
+            .value_name("fp fp")
+            .number_of_values(2)
+            .multiple(true))
         .arg(Arg::with_name(OP_BLUR)
             .help("Operation: blur.")
             .long("--blur")
@@ -202,6 +682,20 @@ pub fn cli() -> App<'static, 'static> {
             .number_of_values(1)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_BLUR_REGION)
+            .help("Operation: blur, restricted to a region.")
+            .long("--blur-region")
+            .takes_value(true)
+            .value_name("fp uint uint uint uint")
+            .number_of_values(5)
+            .multiple(true))
+        .arg(Arg::with_name(OP_BLUR_REGIONS)
+            .help("Operation: blur, restricted to the regions listed in a JSON or CSV file.")
+            .long("--blur-regions")
+            .takes_value(true)
+            .value_name("fp path")
+            .number_of_values(2)
+            .multiple(true))
         .arg(Arg::with_name(OP_BRIGHTEN)
             .help("Operation: brighten.")
             .long("--brighten")
@@ -210,6 +704,17 @@ pub fn cli() -> App<'static, 'static> {
             .number_of_values(1)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_CIRCLE_CROP)
+            .help("Operation: circle crop.")
+            .long("--circle-crop")
+            .multiple(true))
+        .arg(Arg::with_name(OP_CLAHE)
+            .help("Operation: clahe.")
+            .long("--clahe")
+            .takes_value(true)
+            .value_name("uint fp")
+            .number_of_values(2)
+            .multiple(true))
         .arg(Arg::with_name(OP_CONTRAST)
             .help("Operation: contrast.")
             .long("--contrast")
@@ -225,6 +730,49 @@ pub fn cli() -> App<'static, 'static> {
             .value_name("uint uint uint uint")
             .number_of_values(4)
             .multiple(true))
+        .arg(Arg::with_name(OP_CURVES)
+            .help("Operation: curves.")
+            .long("--curves")
+            .takes_value(true)
+            .value_name("channel points")
+            .number_of_values(2)
+            .multiple(true))
+        .arg(Arg::with_name(OP_DRAW_ELLIPSE)
+            .help("Operation: draw ellipse.")
+            .long("--draw-ellipse")
+            .takes_value(true)
+            .value_name("int int uint uint color")
+            .number_of_values(5)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_DRAW_LINE)
+            .help("Operation: draw line.")
+            .long("--draw-line")
+            .takes_value(true)
+            .value_name("int int int int color")
+            .number_of_values(5)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_DRAW_POLYGON)
+            .help("Operation: draw polygon.")
+            .long("--draw-polygon")
+            .takes_value(true)
+            .value_name("color points")
+            .number_of_values(2)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_DRAW_RECT)
+            .help("Operation: draw rect.")
+            .long("--draw-rect")
+            .takes_value(true)
+            .value_name("int int uint uint color")
+            .number_of_values(5)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_EQUALIZE)
+            .help("Operation: equalize.")
+            .long("--equalize")
+            .multiple(true))
         .arg(Arg::with_name(OP_FILTER3X3)
             .help("Operation: filter3x3.")
             .long("--filter3x3")
@@ -257,6 +805,41 @@ pub fn cli() -> App<'static, 'static> {
             .number_of_values(1)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_LEVELS)
+            .help("Operation: levels.")
+            .long("--levels")
+            .takes_value(true)
+            .value_name("uint uint fp uint uint")
+            .number_of_values(5)
+            .multiple(true))
+        .arg(Arg::with_name(OP_MATCH_HISTOGRAM)
+            .help("Operation: match histogram.")
+            .long("--match-histogram")
+            .takes_value(true)
+            .value_name("path")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OP_PIXELATE)
+            .help("Operation: pixelate.")
+            .long("--pixelate")
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OP_PIXELATE_REGION)
+            .help("Operation: pixelate, restricted to a region.")
+            .long("--pixelate-region")
+            .takes_value(true)
+            .value_name("uint uint uint uint uint")
+            .number_of_values(5)
+            .multiple(true))
+        .arg(Arg::with_name(OP_PIXELATE_REGIONS)
+            .help("Operation: pixelate, restricted to the regions listed in a JSON or CSV file.")
+            .long("--pixelate-regions")
+            .takes_value(true)
+            .value_name("uint path")
+            .number_of_values(2)
+            .multiple(true))
         .arg(Arg::with_name(OP_RESIZE)
             .help("Operation: resize.")
             .long("--resize")
@@ -276,6 +859,29 @@ pub fn cli() -> App<'static, 'static> {
             .help("Operation: rotate 270 degree.")
             .long("--rotate270")
             .multiple(true))
+        .arg(Arg::with_name(OP_ROUND_CORNERS)
+            .help("Operation: round corners.")
+            .long("--round-corners")
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true))
+        .arg(Arg::with_name(OP_SHADOW)
+            .help("Operation: shadow.")
+            .long("--shadow")
+            .takes_value(true)
+            .value_name("int int fp color")
+            .number_of_values(4)
+            .multiple(true)
+            .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_TEXT)
+            .help("Operation: text.")
+            .long("--text")
+            .takes_value(true)
+            .value_name("str int int")
+            .number_of_values(3)
+            .multiple(true)
+            .allow_hyphen_values(true))
         .arg(Arg::with_name(OP_UNSHARPEN)
             .help("Operation: unsharpen.")
             .long("--unsharpen")
@@ -284,8 +890,66 @@ pub fn cli() -> App<'static, 'static> {
             .number_of_values(2)
             .multiple(true)
             .allow_hyphen_values(true))
+        .arg(Arg::with_name(OP_VIGNETTE)
+            .help("Operation: vignette.")
+            .long("--vignette")
+            .takes_value(true)
+            .value_name("fp fp")
+            .number_of_values(2)
+            .multiple(true))
 
         // image-operations(cli-arguments/modifiers):
+        .arg(Arg::with_name(OPMOD_APPEND_ALIGNMENT)
+            .help("Operation modifier for: append")
+            .long("--set-append-alignment")
+            .takes_value(true)
+            .value_name("str")
+            .number_of_values(1)
+            .multiple(true)
+            .possible_values(&["start", "center", "end"])
+        )
+        .arg(Arg::with_name(OPMOD_APPEND_BACKGROUND)
+            .help("Operation modifier for: append")
+            .long("--set-append-background")
+            .takes_value(true)
+            .value_name("color")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OPMOD_DRAW_ANTIALIAS)
+            .help("Operation modifier for: draw")
+            .long("--set-draw-antialias")
+            .takes_value(true)
+            .value_name("bool")
+            .number_of_values(1)
+            .multiple(true)
+            .possible_values(&["true", "false"])
+        )
+        .arg(Arg::with_name(OPMOD_DRAW_FILL)
+            .help("Operation modifier for: draw")
+            .long("--set-draw-fill")
+            .takes_value(true)
+            .value_name("color")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OPMOD_DRAW_STROKE_WIDTH)
+            .help("Operation modifier for: draw")
+            .long("--set-draw-stroke-width")
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OPMOD_MATCH_HISTOGRAM_MODE)
+            .help("Operation modifier for: match histogram")
+            .long("--set-match-histogram-mode")
+            .takes_value(true)
+            .value_name("str")
+            .number_of_values(1)
+            .multiple(true)
+            .possible_values(&["histogram", "reinhard"])
+        )
         .arg(Arg::with_name(OPMOD_RESIZE_PRESERVE_ASPECT_RATIO)
             .help("Operation modifier for: resize")
             .long("--set-resize-preserve-aspect-ratio")
@@ -304,6 +968,42 @@ pub fn cli() -> App<'static, 'static> {
             .multiple(true)
             .possible_values(&["catmullrom", "gaussian", "lanczos3", "nearest", "triangle"])
         )
+        .arg(Arg::with_name(OPMOD_TEXT_COLOR)
+            .help("Operation modifier for: text")
+            .long("--set-text-color")
+            .takes_value(true)
+            .value_name("color")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OPMOD_TEXT_FONT)
+            .help("Operation modifier for: text")
+            .long("--set-text-font")
+            .takes_value(true)
+            .value_name("path")
+            .number_of_values(1)
+            .multiple(true)
+        )
+        .arg(Arg::with_name(OPMOD_TEXT_GRAVITY)
+            .help("Operation modifier for: text")
+            .long("--set-text-gravity")
+            .takes_value(true)
+            .value_name("str")
+            .number_of_values(1)
+            .multiple(true)
+            .possible_values(&[
+                "northwest", "north", "northeast", "west", "center", "east", "southwest", "south",
+                "southeast",
+            ])
+        )
+        .arg(Arg::with_name(OPMOD_TEXT_SIZE)
+            .help("Operation modifier for: text")
+            .long("--set-text-size")
+            .takes_value(true)
+            .value_name("uint")
+            .number_of_values(1)
+            .multiple(true)
+        )
 }
 
 // Here any argument should not panic when invalid.
@@ -330,6 +1030,27 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> Result<Config<'a>, Strin
         (false, false) => (),
     };
 
+    // organisational/list-formats:
+    if matches.is_present(ARG_LIST_FORMATS) {
+        builder = builder.show_formats(true);
+    }
+
+    // io(input)/generate:
+    if let Some(spec) = matches.value_of(ARG_GENERATE) {
+        builder = builder.generator(sic_parser::parse_generator_spec(spec)?);
+    }
+
+    // io(input)/montage:
+    if let Some(inputs) = matches.values_of(ARG_MONTAGE) {
+        builder = builder.montage(inputs.collect(), build_montage_layout(matches)?);
+    }
+
+    // io(input)/atlas:
+    if let Some(inputs) = matches.values_of(ARG_ATLAS) {
+        let (layout, descriptor) = build_atlas_layout(matches)?;
+        builder = builder.atlas(inputs.collect(), layout, descriptor);
+    }
+
     // io(output):
     if let Some(path) = matches
         .value_of(ARG_OUTPUT)
@@ -338,6 +1059,40 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> Result<Config<'a>, Strin
         builder = builder.output_path(path);
     }
 
+    // io(output)/split:
+    if matches.is_present(GROUP_SPLIT) {
+        builder = builder.split(build_split(matches)?);
+    }
+
+    // io(output)/pyramid:
+    if let Some(layout) = matches.value_of(ARG_PYRAMID) {
+        builder = builder.pyramid(build_pyramid(layout, matches)?);
+    }
+
+    // io(output)/responsive:
+    if let Some(widths) = matches.values_of(ARG_RESPONSIVE) {
+        let widths = widths
+            .map(u32::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                "Provided arguments for --responsive should be positive widths.".to_string()
+            })?;
+
+        builder = builder.responsive(
+            ResponsiveSet { widths },
+            matches
+                .values_of(ARG_RESPONSIVE_FORMATS)
+                .map(|formats| formats.collect())
+                .unwrap_or_default(),
+            matches.value_of(ARG_RESPONSIVE_MANIFEST),
+        );
+    }
+
+    // io(output)/favicon:
+    if matches.is_present(ARG_FAVICON) {
+        builder = builder.favicon(true);
+    }
+
     // config(in)/gif-select-frame:
     if let Some(frame_in) = matches.value_of(ARG_SELECT_FRAME) {
         let frame_out = match frame_in {
@@ -366,6 +1121,17 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> Result<Config<'a>, Strin
         builder = builder.select_frame(frame_out);
     }
 
+    // config(in)/input-format:
+    if let Some(identifier) = matches.value_of(ARG_INPUT_FORMAT) {
+        let format = if identifier.eq_ignore_ascii_case("raw") {
+            build_raw_input_format(matches)?
+        } else {
+            InputFormat::by_identifier(identifier)?
+        };
+
+        builder = builder.input_format(format);
+    }
+
     // config(out)/disable-automatic-color-type-adjustment:
     if matches.is_present(ARG_DISABLE_AUTOMATIC_COLOR_TYPE_ADJUSTMENT) {
         builder = builder.disable_automatic_color_type_adjustment(true);
@@ -376,6 +1142,17 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> Result<Config<'a>, Strin
         builder = builder.forced_output_format(format);
     }
 
+    // config(out)/auto-format-lossless:
+    if matches.is_present(ARG_AUTO_FORMAT_LOSSLESS) {
+        builder = builder.auto_format_lossless(true);
+    }
+
+    // config(out)/auto-format-report:
+    if let Some(report) = matches.value_of(ARG_AUTO_FORMAT_REPORT) {
+        builder = builder
+            .auto_format_report(ChoiceReport::try_from_str(report).map_err(|err| err.to_string())?);
+    }
+
     // config(out)/jpeg-encoding-quality:
     if let Some(value) = matches.value_of(ARG_JPEG_ENCODING_QUALITY) {
         let requested_jpeg_quality = u8::from_str(value)
@@ -386,11 +1163,103 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> Result<Config<'a>, Strin
         builder = builder.jpeg_quality(requested_jpeg_quality);
     }
 
+    // config(out)/jpeg-progressive:
+    if matches.is_present(ARG_JPEG_PROGRESSIVE) {
+        builder = builder.jpeg_progressive(true);
+    }
+
+    // config(out)/jpeg-chroma-subsampling:
+    if let Some(subsampling) = matches.value_of(ARG_JPEG_CHROMA_SUBSAMPLING) {
+        builder = builder.jpeg_chroma_subsampling(
+            ChromaSubsampling::try_from_str(subsampling).map_err(|err| err.to_string())?,
+        );
+    }
+
+    // config(out)/jpeg-optimize-huffman:
+    if matches.is_present(ARG_JPEG_OPTIMIZE_HUFFMAN) {
+        builder = builder.jpeg_optimize_huffman_tables(true);
+    }
+
+    // config(out)/jpeg-restart-interval:
+    if let Some(value) = matches.value_of(ARG_JPEG_RESTART_INTERVAL) {
+        let interval = u16::from_str(value).map_err(|_| {
+            "The JPEG restart interval should be a value between 0 and 65535 (inclusive)."
+                .to_string()
+        })?;
+        builder = builder.jpeg_restart_interval(interval);
+    }
+
+    // config(out)/jpeg-grayscale:
+    if matches.is_present(ARG_JPEG_GRAYSCALE) {
+        builder = builder.jpeg_grayscale(true);
+    }
+
     // config(out)/pnm-encoding-type:
     if matches.is_present(ARG_PNM_ENCODING_ASCII) {
         builder = builder.pnm_format_type(true);
     }
 
+    // config(out)/png-compression:
+    if let Some(compression) = matches.value_of(ARG_PNG_COMPRESSION) {
+        builder = builder.png_compression(
+            PngCompression::try_from_str(compression).map_err(|err| err.to_string())?,
+        );
+    }
+
+    // config(out)/png-filter:
+    if let Some(filter) = matches.value_of(ARG_PNG_FILTER) {
+        builder =
+            builder.png_filter(PngFilter::try_from_str(filter).map_err(|err| err.to_string())?);
+    }
+
+    // config(out)/png-sixteen-bit:
+    if matches.is_present(ARG_PNG_SIXTEEN_BIT) {
+        builder = builder.png_sixteen_bit(true);
+    }
+
+    // config(out)/png-palette:
+    if matches.is_present(ARG_PNG_PALETTE) {
+        builder = builder.png_palette(true);
+    }
+
+    // config(out)/ico-sizes:
+    if let Some(sizes) = matches.values_of(ARG_ICO_SIZES) {
+        let sizes = sizes
+            .map(IcoSettings::parse_size)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+        builder = builder.ico_sizes(sizes);
+    }
+
+    // config(out)/tiff-compression:
+    if let Some(compression) = matches.value_of(ARG_TIFF_COMPRESSION) {
+        builder = builder.tiff_compression(
+            TiffCompression::try_from_str(compression).map_err(|err| err.to_string())?,
+        );
+    }
+
+    // config(out)/tga-encoding-rle:
+    if matches.is_present(ARG_TGA_ENCODING_RLE) {
+        builder = builder.tga_encoding(TgaEncoding::RunLength);
+    }
+
+    // config(out)/raw-*:
+    builder = builder.raw_format(build_raw_format(matches)?);
+
+    // config(out)/output-text:
+    if let Some(encoding) = matches.value_of(ARG_OUTPUT_TEXT) {
+        builder = builder
+            .text_encoding(TextEncoding::try_from_str(encoding).map_err(|err| err.to_string())?);
+    }
+
+    // config(out)/max-output-size:
+    if let Some(size) = matches.value_of(ARG_MAX_OUTPUT_SIZE) {
+        builder = builder.size_limit(SizeLimit {
+            max_bytes: SizeLimit::parse_size(size).map_err(|err| err.to_string())?,
+            downscale: matches.is_present(ARG_MAX_OUTPUT_SIZE_DOWNSCALE),
+        });
+    }
+
     // image-operations:
     //
     // Image operations are a bit more involved.
@@ -424,30 +1293,235 @@ pub fn build_app_config<'a>(matches: &'a ArgMatches) -> Result<Config<'a>, Strin
     Ok(builder.build())
 }
 
+fn build_raw_input_format(matches: &ArgMatches) -> Result<InputFormat, String> {
+    let (width, height) = match matches.values_of(ARG_RAW_SIZE) {
+        Some(size) => ParseInputsFromIter::parse(size.collect::<Vec<_>>()).map_err(|_| {
+            "Provided arguments for --raw-size should be a positive width and height.".to_string()
+        })?,
+        None => {
+            return Err(
+                "Raw input requires the width and height of the image, given by --raw-size."
+                    .to_string(),
+            )
+        }
+    };
+
+    Ok(InputFormat::Raw {
+        format: build_raw_format(matches)?,
+        width,
+        height,
+    })
+}
+
+/// The layout of raw input and output pixel data.
+fn build_raw_format(matches: &ArgMatches) -> Result<RawFormat, String> {
+    let mut format = RawFormat::default();
+
+    if let Some(color_type) = matches.value_of(ARG_RAW_COLOR_TYPE) {
+        format.color_type =
+            RawColorType::try_from_str(color_type).map_err(|err| err.to_string())?;
+    }
+
+    if let Some(depth) = matches.value_of(ARG_RAW_SAMPLE_DEPTH) {
+        format.sample_depth = SampleDepth::try_from_str(depth).map_err(|err| err.to_string())?;
+    }
+
+    if let Some(order) = matches.value_of(ARG_RAW_BYTE_ORDER) {
+        format.byte_order = ByteOrder::try_from_str(order).map_err(|err| err.to_string())?;
+    }
+
+    Ok(format)
+}
+
+fn build_montage_layout(matches: &ArgMatches) -> Result<Montage, String> {
+    let mut layout = Montage::default();
+
+    if let Some(color) = matches.value_of(ARG_MONTAGE_BACKGROUND) {
+        layout.background = ParseInputsFromIter::parse(&[color]).map_err(|err| {
+            format!(
+                "Provided argument for --montage-background is not a valid color: {}",
+                err
+            )
+        })?;
+    }
+
+    if let Some(cell) = matches.values_of(ARG_MONTAGE_CELL) {
+        layout.cell = ParseInputsFromIter::parse(cell.collect::<Vec<_>>()).map_err(|_| {
+            "Provided arguments for --montage-cell should be a positive width and height."
+                .to_string()
+        })?;
+    }
+
+    if let Some(columns) = matches.value_of(ARG_MONTAGE_COLUMNS) {
+        let columns = u32::from_str(columns).map_err(|_| {
+            "Provided argument for --montage-columns should be a positive number.".to_string()
+        })?;
+        layout.columns = Some(columns);
+    }
+
+    if let Some(spacing) = matches.value_of(ARG_MONTAGE_SPACING) {
+        layout.spacing = u32::from_str(spacing).map_err(|_| {
+            "Provided argument for --montage-spacing should be a positive number or 0.".to_string()
+        })?;
+    }
+
+    layout.labels = matches.is_present(ARG_MONTAGE_LABELS);
+
+    Ok(layout)
+}
+
+fn build_atlas_layout(matches: &ArgMatches) -> Result<(Atlas, Descriptor), String> {
+    let mut layout = Atlas::default();
+
+    if let Some(size) = matches.value_of(ARG_ATLAS_MAX_SIZE) {
+        layout.max_size = u32::from_str(size)
+            .ok()
+            .filter(|size| size.is_power_of_two())
+            .ok_or_else(|| {
+                "Provided argument for --atlas-max-size should be a power of two.".to_string()
+            })?;
+    }
+
+    if let Some(padding) = matches.value_of(ARG_ATLAS_PADDING) {
+        layout.padding = u32::from_str(padding).map_err(|_| {
+            "Provided argument for --atlas-padding should be a positive number or 0.".to_string()
+        })?;
+    }
+
+    if let Some(packer) = matches.value_of(ARG_ATLAS_PACKER) {
+        layout.packer = Packer::try_from_str(packer).map_err(|err| err.to_string())?;
+    }
+
+    layout.trim = matches.is_present(ARG_ATLAS_TRIM);
+
+    let descriptor = match matches.value_of(ARG_ATLAS_DESCRIPTOR) {
+        Some(format) => Descriptor::try_from_str(format).map_err(|err| err.to_string())?,
+        None => Descriptor::default(),
+    };
+
+    Ok((layout, descriptor))
+}
+
+fn build_split(matches: &ArgMatches) -> Result<Split, String> {
+    let mode = if let Some(size) = matches.values_of(ARG_SPLIT_TILE_SIZE) {
+        let (width, height) =
+            ParseInputsFromIter::parse(size.collect::<Vec<_>>()).map_err(|_| {
+                "Provided arguments for --split-tile-size should be a positive width and height."
+                    .to_string()
+            })?;
+        SplitMode::TileSize(width, height)
+    } else {
+        let grid = matches
+            .values_of(ARG_SPLIT_GRID)
+            .ok_or_else(|| "Unable to determine how to split the image.".to_string())?;
+        let (columns, rows) = ParseInputsFromIter::parse(grid.collect::<Vec<_>>()).map_err(|_| {
+            "Provided arguments for --split-grid should be a positive number of columns and rows."
+                .to_string()
+        })?;
+        SplitMode::Grid(columns, rows)
+    };
+
+    let overlap = match matches.value_of(ARG_SPLIT_OVERLAP) {
+        Some(overlap) => u32::from_str(overlap).map_err(|_| {
+            "Provided argument for --split-overlap should be a positive number or 0.".to_string()
+        })?,
+        None => 0,
+    };
+
+    let edge = match matches.value_of(ARG_SPLIT_EDGE) {
+        Some(edge) => EdgePolicy::try_from_str(edge)?,
+        None => EdgePolicy::default(),
+    };
+
+    Ok(Split {
+        mode,
+        overlap,
+        edge,
+    })
+}
+
+fn build_pyramid(layout: &str, matches: &ArgMatches) -> Result<Pyramid, String> {
+    let mut pyramid = Pyramid::new(PyramidLayout::try_from_str(layout)?);
+
+    if let Some(size) = matches.value_of(ARG_PYRAMID_TILE_SIZE) {
+        pyramid.tile_size = u32::from_str(size)
+            .ok()
+            .filter(|&size| size > 0)
+            .ok_or_else(|| {
+                "Provided argument for --pyramid-tile-size should be a positive number.".to_string()
+            })?;
+    }
+
+    if let Some(overlap) = matches.value_of(ARG_PYRAMID_OVERLAP) {
+        pyramid.overlap = u32::from_str(overlap).map_err(|_| {
+            "Provided argument for --pyramid-overlap should be a positive number or 0.".to_string()
+        })?;
+    }
+
+    if let Some(filter) = matches.value_of(ARG_PYRAMID_FILTER) {
+        pyramid.filter = FilterTypeWrap::try_from_str(filter)
+            .map_err(|err| err.to_string())?
+            .into();
+    }
+
+    Ok(pyramid)
+}
+
 fn build_ast_from_matches(
     matches: &ArgMatches,
     tree: &mut IndexTree,
 ) -> Result<Vec<Instruction>, String> {
     let operations = vec![
         // operations
+        OperationId::Append,
+        OperationId::AutoLevels,
         OperationId::Blur,
+        OperationId::BlurRegion,
+        OperationId::BlurRegions,
         OperationId::Brighten,
+        OperationId::CircleCrop,
+        OperationId::Clahe,
         OperationId::Contrast,
         OperationId::Crop,
+        OperationId::Curves,
+        OperationId::DrawEllipse,
+        OperationId::DrawLine,
+        OperationId::DrawPolygon,
+        OperationId::DrawRect,
+        OperationId::Equalize,
         OperationId::Filter3x3,
         OperationId::FlipH,
         OperationId::FlipV,
         OperationId::Grayscale,
         OperationId::HueRotate,
         OperationId::Invert,
+        OperationId::Levels,
+        OperationId::MatchHistogram,
+        OperationId::Pixelate,
+        OperationId::PixelateRegion,
+        OperationId::PixelateRegions,
         OperationId::Resize,
         OperationId::Rotate90,
         OperationId::Rotate180,
         OperationId::Rotate270,
+        OperationId::RoundCorners,
+        OperationId::Shadow,
+        OperationId::Text,
         OperationId::Unsharpen,
+        OperationId::Vignette,
         // modifiers
+        OperationId::ModAppendAlignment,
+        OperationId::ModAppendBackground,
+        OperationId::ModDrawAntialias,
+        OperationId::ModDrawFill,
+        OperationId::ModDrawStrokeWidth,
+        OperationId::ModMatchHistogramMode,
         OperationId::ModResizeSamplingFilter,
         OperationId::ModResizePreserveAspectRatio,
+        OperationId::ModTextColor,
+        OperationId::ModTextFont,
+        OperationId::ModTextGravity,
+        OperationId::ModTextSize,
     ];
     ast_extend_with_operation(tree, matches, operations)?;
 
@@ -492,7 +1566,13 @@ fn ast_from_index_tree(tree: &mut IndexTree) -> Result<Vec<Instruction>, String>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sic_image_engine::engine::Instruction;
+    use sic_core::image::Rgba;
+    use sic_image_engine::engine::{EnvironmentItem, EnvironmentKind, Instruction};
+    use sic_image_engine::wrapper::alignment::Alignment;
+    use sic_image_engine::wrapper::axis::Axis;
+    use sic_image_engine::wrapper::channel::Channel;
+    use sic_image_engine::wrapper::gravity::Gravity;
+    use sic_image_engine::wrapper::region::Region;
     use sic_image_engine::ImgOp;
     use std::collections::BTreeMap;
 
@@ -512,21 +1592,52 @@ mod tests {
     #[test]
     fn build_from_args_all() {
         let input = "sic -i in -o out \
+                     --set-append-alignment center \
+                     --set-append-background white \
+                     --append y other.png \
+                     --auto-levels 1 2 \
                      --blur 1 \
+                     --blur-region 2 1 2 3 4 \
+                     --blur-regions 2 resources/regions.csv \
                      --brighten 2 \
+                     --circle-crop \
+                     --clahe 8 2.5 \
                      --contrast 3 \
                      --crop 0 0 2 2 \
+                     --curves rgb 0:0,64:90,255:255 \
+                     --set-draw-antialias true \
+                     --set-draw-fill #ff000080 \
+                     --set-draw-stroke-width 3 \
+                     --draw-ellipse 5 5 4 2 blue \
+                     --draw-line -1 0 5 5 red \
+                     --draw-polygon #0f0 0:0,4:0,2:-3 \
+                     --draw-rect 1 1 5 5 black \
+                     --set-draw-antialias false \
+                     --equalize \
                      --filter3x3 0 1 2 3 4 5 6 7 8 \
                      --flip-horizontal \
                      --flip-vertical \
                      --grayscale \
                      --hue-rotate -90 \
                      --invert \
+                     --levels 10 240 1.2 0 255 \
+                     --match-histogram ref.png \
+                     --pixelate 4 \
+                     --pixelate-region 4 1 2 3 4 \
+                     --pixelate-regions 4 resources/regions.json \
                      --resize 10 10 \
                      --rotate90 \
                      --rotate180 \
                      --rotate270 \
-                     --unsharpen 1.5 1";
+                     --round-corners 12 \
+                     --shadow -4 6 2.5 #00000080 \
+                     --set-text-color white \
+                     --set-text-font font.ttf \
+                     --set-text-gravity south \
+                     --set-text-size 24 \
+                     --text sic 4 -2 \
+                     --unsharpen 1.5 1 \
+                     --vignette 0.8 0.25";
 
         let input = input.split_ascii_whitespace();
         let matches = cli().get_matches_from(input);
@@ -535,18 +1646,65 @@ mod tests {
         let ast = ast.unwrap();
         let mut iter = ast.iter();
 
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::AppendAlignment(n)),
+            assert_eq!(*n, Alignment::Center)
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::AppendBackground(n)),
+            assert_eq!(*n, Rgba([255, 255, 255, 255]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Append(n)),
+            assert_eq!(*n, (Axis::Y, vec!["other.png".to_string()]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::AutoLevels(n)),
+            assert_eq!(*n, (1f32, 2f32))
+        );
+
         assert_match!(
             iter,
             Instruction::Operation(ImgOp::Blur(n)),
             assert_eq!(*n, 1f32)
         );
 
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::BlurRegions(n)),
+            assert_eq!(*n, (2f32, vec![Region::new(1, 2, 3, 4)]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::BlurRegions(n)),
+            assert_eq!(
+                *n,
+                (2f32, vec![Region::new(1, 1, 3, 2), Region::new(4, 3, 4, 3)])
+            )
+        );
+
         assert_match!(
             iter,
             Instruction::Operation(ImgOp::Brighten(n)),
             assert_eq!(*n, 2i32)
         );
 
+        assert_match!(iter, Instruction::Operation(ImgOp::CircleCrop), ());
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Clahe(n)),
+            assert_eq!(*n, (8u32, 2.5f32))
+        );
+
         assert_match!(
             iter,
             Instruction::Operation(ImgOp::Contrast(n)),
@@ -559,6 +1717,62 @@ mod tests {
             assert_eq!(*n, (0u32, 0u32, 2u32, 2u32))
         );
 
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Curves(n)),
+            assert_eq!(*n, (Channel::Rgb, vec![(0u8, 0u8), (64, 90), (255, 255)]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::DrawAntialias),
+            ()
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::DrawFill(n)),
+            assert_eq!(*n, Rgba([255, 0, 0, 128]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::DrawStrokeWidth(n)),
+            assert_eq!(*n, 3)
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::DrawEllipse(n)),
+            assert_eq!(*n, (5, 5, 4, 2, Rgba([0, 0, 255, 255])))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::DrawLine(n)),
+            assert_eq!(*n, (-1, 0, 5, 5, Rgba([255, 0, 0, 255])))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::DrawPolygon(n)),
+            assert_eq!(*n, (Rgba([0, 255, 0, 255]), vec![(0, 0), (4, 0), (2, -3)]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::DrawRect(n)),
+            assert_eq!(*n, (1, 1, 5, 5, Rgba([0, 0, 0, 255])))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::RemoveFromEnv(EnvironmentKind::DrawAntialias),
+            ()
+        );
+
+        assert_match!(iter, Instruction::Operation(ImgOp::Equalize), ());
+
         assert_match!(
             iter,
             Instruction::Operation(ImgOp::Filter3x3(n)),
@@ -579,6 +1793,39 @@ mod tests {
 
         assert_match!(iter, Instruction::Operation(ImgOp::Invert), ());
 
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Levels(n)),
+            assert_eq!(*n, (10u8, 240u8, 1.2f32, 0u8, 255u8))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::MatchHistogram(n)),
+            assert_eq!(n, "ref.png")
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Pixelate(n)),
+            assert_eq!(*n, 4u32)
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::PixelateRegions(n)),
+            assert_eq!(*n, (4u32, vec![Region::new(1, 2, 3, 4)]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::PixelateRegions(n)),
+            assert_eq!(
+                *n,
+                (4u32, vec![Region::new(1, 1, 3, 2), Region::new(4, 3, 4, 3)])
+            )
+        );
+
         assert_match!(
             iter,
             Instruction::Operation(ImgOp::Resize(n)),
@@ -591,12 +1838,60 @@ mod tests {
 
         assert_match!(iter, Instruction::Operation(ImgOp::Rotate270), ());
 
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::RoundCorners(n)),
+            assert_eq!(*n, 12u32)
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Shadow(n)),
+            assert_eq!(*n, (-4i32, 6i32, 2.5f32, Rgba([0u8, 0, 0, 128])))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::TextColor(n)),
+            assert_eq!(*n, Rgba([255, 255, 255, 255]))
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::TextFont(n)),
+            assert_eq!(n, "font.ttf")
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::TextGravity(n)),
+            assert_eq!(*n, Gravity::South)
+        );
+
+        assert_match!(
+            iter,
+            Instruction::AddToEnv(EnvironmentItem::TextSize(n)),
+            assert_eq!(*n, 24)
+        );
+
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Text(n)),
+            assert_eq!(*n, ("sic".to_string(), 4, -2))
+        );
+
         assert_match!(
             iter,
             Instruction::Operation(ImgOp::Unsharpen(n)),
             assert_eq!(*n, (1.5f32, 1i32))
         );
 
+        assert_match!(
+            iter,
+            Instruction::Operation(ImgOp::Vignette(n)),
+            assert_eq!(*n, (0.8f32, 0.25f32))
+        );
+
         assert_eq!(iter.next(), None);
     }
 
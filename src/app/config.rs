@@ -1,5 +1,20 @@
+use sic_image_engine::atlas::descriptor::Descriptor;
+use sic_image_engine::atlas::Atlas;
 use sic_image_engine::engine::Instruction;
-use sic_io::load::FrameIndex;
+use sic_image_engine::generator::Generator;
+use sic_image_engine::montage::Montage;
+use sic_io::auto::ChoiceReport;
+use sic_io::encoders::jpeg::ChromaSubsampling;
+use sic_io::encoders::png::{PngCompression, PngFilter};
+use sic_io::encoders::tga::TgaEncoding;
+use sic_io::encoders::tiff::TiffCompression;
+use sic_io::load::{FrameIndex, InputFormat};
+use sic_io::pyramid::Pyramid;
+use sic_io::raw::RawFormat;
+use sic_io::responsive::ResponsiveSet;
+use sic_io::size_limit::SizeLimit;
+use sic_io::split::Split;
+use sic_io::text::TextEncoding;
 
 #[derive(Debug)]
 pub struct Config<'a> {
@@ -9,13 +24,57 @@ pub struct Config<'a> {
     /// Display license of this software or its dependencies.
     pub show_license_text_of: Option<SelectedLicenses>,
 
+    // organisational
+    /// Display the supported image formats, and whether they can be read and written.
+    pub show_formats: bool,
+
+    // io(input)
+    /// Generates the starting image, instead of loading it from an input path or the stdin.
+    pub generator: Option<Generator>,
+
+    // io(input)
+    /// Arranges the images at the input paths in a montage, which is used as the starting image,
+    /// instead of loading a single image.
+    pub montage: Option<MontageSettings<'a>>,
+
+    // io(input)
+    /// Packs the images at the input paths into the sheets of an atlas, which are written to the
+    /// output path, each with a descriptor.
+    pub atlas: Option<AtlasSettings<'a>>,
+
     // io(output)
     /// The image output path.
     pub output: Option<&'a str>,
 
+    // io(output)
+    /// Splits the image into tiles, which are written to separate files. The output path is then
+    /// used as a template for the paths of the tiles.
+    pub split: Option<Split>,
+
+    // io(output)
+    /// Builds a pyramid of tiles from the image, which is written to the output path (a DeepZoom
+    /// descriptor or a directory).
+    pub pyramid: Option<Pyramid>,
+
+    // io(output)
+    /// Scales the image to several widths, each of which is written in one or more formats, and
+    /// describes the written images in a manifest. The output path is then used as a template for
+    /// the paths of the images.
+    pub responsive: Option<ResponsiveSettings<'a>>,
+
+    // io(output)
+    /// Writes a favicon pack to the output path, which is then used as a directory: an ICO file,
+    /// the Apple touch and Android icons, and a web app manifest snippet.
+    pub favicon: bool,
+
     // config(in)
     pub selected_frame: FrameIndex,
 
+    // config(in)
+    /// The format of the input image(s); if none is given, the format is recognized by the
+    /// contents of each image.
+    pub input_format: Option<InputFormat>,
+
     // config(out)
     /// Disable color type adjustments on save.
     pub disable_automatic_color_type_adjustment: bool,
@@ -24,10 +83,24 @@ pub struct Config<'a> {
     /// Format to which an image will be converted (enforced).
     pub forced_output_format: Option<&'a str>,
 
+    // config(out)
+    /// Settings of the automatic choice of the output format, when the output format is `auto`.
+    pub auto_format: AutoFormatSettings,
+
     // config(out)
     /// Encoding settings for specific output formats.
     pub encoding_settings: FormatEncodingSettings,
 
+    // config(out)
+    /// Writes the encoded image as text, such as a base64 string or a `data:` URI, instead of
+    /// as bytes.
+    pub text_encoding: Option<TextEncoding>,
+
+    // config(out)
+    /// Limits the size of the written image; lossy formats are encoded at the highest quality
+    /// which fits.
+    pub size_limit: Option<SizeLimit>,
+
     // image-operations
     /// If a user wants to perform image operations on input image, they will need to provide
     /// the image operation commands.
@@ -45,28 +118,104 @@ impl Default for Config<'_> {
             /// Defaults to no displayed license text.
             show_license_text_of: None,
 
+            /// Defaults to not displaying the supported image formats.
+            show_formats: false,
+
+            /// By default the starting image is loaded, not generated.
+            generator: None,
+
+            /// By default no montage is made.
+            montage: None,
+
+            /// By default no atlas is packed.
+            atlas: None,
+
             /// Default output path is None. The program may require an output to be set
             /// for most of its program behaviour.
             output: None,
 
+            /// By default the image is not split into tiles.
+            split: None,
+
+            /// By default no pyramid is built.
+            pyramid: None,
+
+            /// By default no responsive image set is created.
+            responsive: None,
+
+            /// By default no favicon pack is written.
+            favicon: false,
+
             /// By default the first frame of a gif is used.
             selected_frame: FrameIndex::First,
 
+            /// By default the format of an input image is recognized by its contents.
+            input_format: None,
+
             /// Defaults to using automatic color type adjustment where appropriate.
             disable_automatic_color_type_adjustment: false,
 
             /// Defaults to not forcing a specific image output format.
             forced_output_format: None,
 
+            /// By default all candidate formats are tried, and the choice is reported as text.
+            auto_format: AutoFormatSettings::default(),
+
             /// Default format encoding settings.
             encoding_settings: FormatEncodingSettings {
                 /// Default JPEG quality is set to 80.
                 jpeg_quality: 80,
 
+                /// Default JPEG files are baseline (sequential) images.
+                jpeg_progressive: false,
+
+                /// Default chroma of JPEG files is not subsampled (4:4:4).
+                jpeg_chroma_subsampling: ChromaSubsampling::default(),
+
+                /// Default JPEG files use the standard Huffman tables.
+                jpeg_optimize_huffman_tables: false,
+
+                /// Default JPEG files have no restart markers.
+                jpeg_restart_interval: 0,
+
+                /// Default JPEG files are only grayscale if the image is.
+                jpeg_grayscale: false,
+
                 /// Default encoding type of PNM files (excluding PAM) is set to binary.
                 pnm_use_ascii_format: false,
+
+                /// Default compression level of PNG files is the default of the encoder.
+                png_compression: PngCompression::default(),
+
+                /// Default filter of PNG files is chosen adaptively for each scan line.
+                png_filter: PngFilter::default(),
+
+                /// Default bit depth of PNG files is 8 bits per sample.
+                png_sixteen_bit: false,
+
+                /// Default PNG files are not written as indexed (palette) images.
+                png_palette: false,
+
+                /// Default ICO files contain the image at 16, 32, 48 and 256 pixels, except for the
+                /// sizes which are larger than the image.
+                ico_sizes: None,
+
+                /// Default compression of TIFF files is none.
+                tiff_compression: TiffCompression::default(),
+
+                /// Default encoding of TGA files is raw, i.e. not run-length encoded.
+                tga_encoding: TgaEncoding::default(),
+
+                /// Default layout of raw pixel data is 8 bit RGBA.
+                raw_format: RawFormat::default(),
             },
 
+            /// By default the encoded image is written as bytes.
+            text_encoding: None,
+
+            /// By default the size of the written image isn't limited.
+            size_limit: None,
+
             /// Defaults to no provided image operations script.
             image_operations_program: Vec::new(),
         }
@@ -95,18 +244,69 @@ impl<'a> ConfigBuilder<'a> {
         self
     }
 
+    // organisational
+    pub fn show_formats(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.show_formats = toggle;
+        self
+    }
+
+    // io(input)
+    pub fn generator(mut self, generator: Generator) -> ConfigBuilder<'a> {
+        self.settings.generator = Some(generator);
+        self
+    }
+
+    // io(input)
+    pub fn montage(mut self, inputs: Vec<&'a str>, layout: Montage) -> ConfigBuilder<'a> {
+        self.settings.montage = Some(MontageSettings { inputs, layout });
+        self
+    }
+
+    // io(input)
+    pub fn atlas(
+        mut self,
+        inputs: Vec<&'a str>,
+        layout: Atlas,
+        descriptor: Descriptor,
+    ) -> ConfigBuilder<'a> {
+        self.settings.atlas = Some(AtlasSettings {
+            inputs,
+            layout,
+            descriptor,
+        });
+        self
+    }
+
     // config(in)
     pub fn select_frame(mut self, frame: FrameIndex) -> ConfigBuilder<'a> {
         self.settings.selected_frame = frame;
         self
     }
 
+    // config(in)
+    pub fn input_format(mut self, format: InputFormat) -> ConfigBuilder<'a> {
+        self.settings.input_format = Some(format);
+        self
+    }
+
     // config(out)
     pub fn forced_output_format(mut self, format: &'a str) -> ConfigBuilder<'a> {
         self.settings.forced_output_format = Some(format);
         self
     }
 
+    // config(out)
+    pub fn auto_format_lossless(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.auto_format.lossless_only = toggle;
+        self
+    }
+
+    // config(out)
+    pub fn auto_format_report(mut self, report: ChoiceReport) -> ConfigBuilder<'a> {
+        self.settings.auto_format.report = report;
+        self
+    }
+
     // config(out)
     pub fn disable_automatic_color_type_adjustment(mut self, toggle: bool) -> ConfigBuilder<'a> {
         self.settings.disable_automatic_color_type_adjustment = toggle;
@@ -119,18 +319,141 @@ impl<'a> ConfigBuilder<'a> {
         self
     }
 
+    // config(out)
+    pub fn jpeg_progressive(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.jpeg_progressive = toggle;
+        self
+    }
+
+    // config(out)
+    pub fn jpeg_chroma_subsampling(mut self, subsampling: ChromaSubsampling) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.jpeg_chroma_subsampling = subsampling;
+        self
+    }
+
+    // config(out)
+    pub fn jpeg_optimize_huffman_tables(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.jpeg_optimize_huffman_tables = toggle;
+        self
+    }
+
+    // config(out)
+    pub fn jpeg_restart_interval(mut self, interval: u16) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.jpeg_restart_interval = interval;
+        self
+    }
+
+    // config(out)
+    pub fn jpeg_grayscale(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.jpeg_grayscale = toggle;
+        self
+    }
+
     // config(out)
     pub fn pnm_format_type(mut self, use_ascii: bool) -> ConfigBuilder<'a> {
         self.settings.encoding_settings.pnm_use_ascii_format = use_ascii;
         self
     }
 
+    // config(out)
+    pub fn png_compression(mut self, compression: PngCompression) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.png_compression = compression;
+        self
+    }
+
+    // config(out)
+    pub fn png_filter(mut self, filter: PngFilter) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.png_filter = filter;
+        self
+    }
+
+    // config(out)
+    pub fn png_sixteen_bit(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.png_sixteen_bit = toggle;
+        self
+    }
+
+    // config(out)
+    pub fn png_palette(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.png_palette = toggle;
+        self
+    }
+
+    // config(out)
+    pub fn ico_sizes(mut self, sizes: Vec<u32>) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.ico_sizes = Some(sizes);
+        self
+    }
+
+    // config(out)
+    pub fn tiff_compression(mut self, compression: TiffCompression) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.tiff_compression = compression;
+        self
+    }
+
+    // config(out)
+    pub fn tga_encoding(mut self, encoding: TgaEncoding) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.tga_encoding = encoding;
+        self
+    }
+
+    // config(out)
+    pub fn raw_format(mut self, format: RawFormat) -> ConfigBuilder<'a> {
+        self.settings.encoding_settings.raw_format = format;
+        self
+    }
+
+    // config(out)
+    pub fn text_encoding(mut self, encoding: TextEncoding) -> ConfigBuilder<'a> {
+        self.settings.text_encoding = Some(encoding);
+        self
+    }
+
+    // config(out)
+    pub fn size_limit(mut self, limit: SizeLimit) -> ConfigBuilder<'a> {
+        self.settings.size_limit = Some(limit);
+        self
+    }
+
     // config(out)
     pub fn output_path(mut self, path: &'a str) -> ConfigBuilder<'a> {
         self.settings.output = Some(path);
         self
     }
 
+    // io(output)
+    pub fn split(mut self, split: Split) -> ConfigBuilder<'a> {
+        self.settings.split = Some(split);
+        self
+    }
+
+    // io(output)
+    pub fn pyramid(mut self, pyramid: Pyramid) -> ConfigBuilder<'a> {
+        self.settings.pyramid = Some(pyramid);
+        self
+    }
+
+    // io(output)
+    pub fn responsive(
+        mut self,
+        set: ResponsiveSet,
+        formats: Vec<&'a str>,
+        manifest: Option<&'a str>,
+    ) -> ConfigBuilder<'a> {
+        self.settings.responsive = Some(ResponsiveSettings {
+            set,
+            formats,
+            manifest,
+        });
+        self
+    }
+
+    // io(output)
+    pub fn favicon(mut self, toggle: bool) -> ConfigBuilder<'a> {
+        self.settings.favicon = toggle;
+        self
+    }
+
     // image-operations
     pub fn image_operations_program(mut self, program: Vec<Instruction>) -> ConfigBuilder<'a> {
         self.settings.image_operations_program = program;
@@ -150,10 +473,53 @@ pub enum SelectedLicenses {
     ThisSoftwarePlusDependencies,
 }
 
+#[derive(Debug, Clone)]
+pub struct MontageSettings<'a> {
+    pub inputs: Vec<&'a str>,
+    pub layout: Montage,
+}
+
+#[derive(Debug, Clone)]
+pub struct AtlasSettings<'a> {
+    pub inputs: Vec<&'a str>,
+    pub layout: Atlas,
+    pub descriptor: Descriptor,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponsiveSettings<'a> {
+    pub set: ResponsiveSet,
+    /// The identifiers of the formats in which each image is written. If empty, the format of the
+    /// output path is used.
+    pub formats: Vec<&'a str>,
+    /// The path of the manifest, if it differs from the default path.
+    pub manifest: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AutoFormatSettings {
+    /// Only try the lossless candidate formats.
+    pub lossless_only: bool,
+    pub report: ChoiceReport,
+}
+
 #[derive(Debug, Clone)]
 pub struct FormatEncodingSettings {
     pub jpeg_quality: u8,
+    pub jpeg_progressive: bool,
+    pub jpeg_chroma_subsampling: ChromaSubsampling,
+    pub jpeg_optimize_huffman_tables: bool,
+    pub jpeg_restart_interval: u16,
+    pub jpeg_grayscale: bool,
     pub pnm_use_ascii_format: bool,
+    pub png_compression: PngCompression,
+    pub png_filter: PngFilter,
+    pub png_sixteen_bit: bool,
+    pub png_palette: bool,
+    pub ico_sizes: Option<Vec<u32>>,
+    pub tiff_compression: TiffCompression,
+    pub tga_encoding: TgaEncoding,
+    pub raw_format: RawFormat,
 }
 
 /// Strictly speaking not necessary here since the responsible owners will validate the quality as well.
@@ -1,57 +1,133 @@
 use crate::app::cli::arg_names::{
-    OPMOD_RESIZE_PRESERVE_ASPECT_RATIO, OPMOD_RESIZE_SAMPLING_FILTER, OP_BLUR, OP_BRIGHTEN,
-    OP_CONTRAST, OP_CROP, OP_FILTER3X3, OP_FLIP_HORIZONTAL, OP_FLIP_VERTICAL, OP_GRAYSCALE,
-    OP_HUE_ROTATE, OP_INVERT, OP_RESIZE, OP_ROTATE180, OP_ROTATE270, OP_ROTATE90, OP_UNSHARPEN,
+    OPMOD_APPEND_ALIGNMENT, OPMOD_APPEND_BACKGROUND, OPMOD_DRAW_ANTIALIAS, OPMOD_DRAW_FILL,
+    OPMOD_DRAW_STROKE_WIDTH, OPMOD_MATCH_HISTOGRAM_MODE, OPMOD_RESIZE_PRESERVE_ASPECT_RATIO,
+    OPMOD_RESIZE_SAMPLING_FILTER, OPMOD_TEXT_COLOR, OPMOD_TEXT_FONT, OPMOD_TEXT_GRAVITY,
+    OPMOD_TEXT_SIZE, OP_APPEND, OP_AUTO_LEVELS, OP_BLUR, OP_BLUR_REGION, OP_BLUR_REGIONS,
+    OP_BRIGHTEN, OP_CIRCLE_CROP, OP_CLAHE, OP_CONTRAST, OP_CROP, OP_CURVES, OP_DRAW_ELLIPSE,
+    OP_DRAW_LINE, OP_DRAW_POLYGON, OP_DRAW_RECT, OP_EQUALIZE, OP_FILTER3X3, OP_FLIP_HORIZONTAL,
+    OP_FLIP_VERTICAL, OP_GRAYSCALE, OP_HUE_ROTATE, OP_INVERT, OP_LEVELS, OP_MATCH_HISTOGRAM,
+    OP_PIXELATE, OP_PIXELATE_REGION, OP_PIXELATE_REGIONS, OP_RESIZE, OP_ROTATE180, OP_ROTATE270,
+    OP_ROTATE90, OP_ROUND_CORNERS, OP_SHADOW, OP_TEXT, OP_UNSHARPEN, OP_VIGNETTE,
 };
+use sic_core::image::Rgba;
 use sic_image_engine::engine::{EnvironmentItem, EnvironmentKind, Instruction};
+use sic_image_engine::wrapper::alignment::Alignment;
+use sic_image_engine::wrapper::axis::Axis;
+use sic_image_engine::wrapper::channel::Channel;
 use sic_image_engine::wrapper::filter_type::FilterTypeWrap;
+use sic_image_engine::wrapper::gravity::Gravity;
+use sic_image_engine::wrapper::match_mode::MatchMode;
+use sic_image_engine::wrapper::region::Region;
 use sic_image_engine::ImgOp;
+use sic_parser::region_file::load_regions;
 use sic_parser::value_parser::{Describable, ParseInputsFromIter};
 use std::collections::BTreeMap;
 
 /// The enumeration of all supported operations.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub(crate) enum OperationId {
+    Append,
+    AutoLevels,
     Blur,
+    BlurRegion,
+    BlurRegions,
     Brighten,
+    CircleCrop,
+    Clahe,
     Contrast,
     Crop,
+    Curves,
+    DrawEllipse,
+    DrawLine,
+    DrawPolygon,
+    DrawRect,
+    Equalize,
     Filter3x3,
     FlipH,
     FlipV,
     Grayscale,
     HueRotate,
     Invert,
+    Levels,
+    MatchHistogram,
+    Pixelate,
+    PixelateRegion,
+    PixelateRegions,
     Resize,
     Rotate90,
     Rotate180,
     Rotate270,
+    RoundCorners,
+    Shadow,
+    Text,
     Unsharpen,
+    Vignette,
+    ModAppendAlignment,
+    ModAppendBackground,
+    ModDrawAntialias,
+    ModDrawFill,
+    ModDrawStrokeWidth,
+    ModMatchHistogramMode,
     ModResizePreserveAspectRatio,
     ModResizeSamplingFilter,
+    ModTextColor,
+    ModTextFont,
+    ModTextGravity,
+    ModTextSize,
 }
 
 impl OperationId {
     /// A string representation for each operation.
     pub fn as_str(&self) -> &str {
         match self {
+            OperationId::Append => OP_APPEND,
+            OperationId::AutoLevels => OP_AUTO_LEVELS,
             OperationId::Blur => OP_BLUR,
+            OperationId::BlurRegion => OP_BLUR_REGION,
+            OperationId::BlurRegions => OP_BLUR_REGIONS,
             OperationId::Brighten => OP_BRIGHTEN,
+            OperationId::CircleCrop => OP_CIRCLE_CROP,
+            OperationId::Clahe => OP_CLAHE,
             OperationId::Contrast => OP_CONTRAST,
             OperationId::Crop => OP_CROP,
+            OperationId::Curves => OP_CURVES,
+            OperationId::DrawEllipse => OP_DRAW_ELLIPSE,
+            OperationId::DrawLine => OP_DRAW_LINE,
+            OperationId::DrawPolygon => OP_DRAW_POLYGON,
+            OperationId::DrawRect => OP_DRAW_RECT,
+            OperationId::Equalize => OP_EQUALIZE,
             OperationId::Filter3x3 => OP_FILTER3X3,
             OperationId::FlipH => OP_FLIP_HORIZONTAL,
             OperationId::FlipV => OP_FLIP_VERTICAL,
             OperationId::Grayscale => OP_GRAYSCALE,
             OperationId::HueRotate => OP_HUE_ROTATE,
             OperationId::Invert => OP_INVERT,
+            OperationId::Levels => OP_LEVELS,
+            OperationId::MatchHistogram => OP_MATCH_HISTOGRAM,
+            OperationId::Pixelate => OP_PIXELATE,
+            OperationId::PixelateRegion => OP_PIXELATE_REGION,
+            OperationId::PixelateRegions => OP_PIXELATE_REGIONS,
             OperationId::Resize => OP_RESIZE,
             OperationId::Rotate90 => OP_ROTATE90,
             OperationId::Rotate180 => OP_ROTATE180,
             OperationId::Rotate270 => OP_ROTATE270,
+            OperationId::RoundCorners => OP_ROUND_CORNERS,
+            OperationId::Shadow => OP_SHADOW,
+            OperationId::Text => OP_TEXT,
             OperationId::Unsharpen => OP_UNSHARPEN,
+            OperationId::Vignette => OP_VIGNETTE,
+            OperationId::ModAppendAlignment => OPMOD_APPEND_ALIGNMENT,
+            OperationId::ModAppendBackground => OPMOD_APPEND_BACKGROUND,
+            OperationId::ModDrawAntialias => OPMOD_DRAW_ANTIALIAS,
+            OperationId::ModDrawFill => OPMOD_DRAW_FILL,
+            OperationId::ModDrawStrokeWidth => OPMOD_DRAW_STROKE_WIDTH,
+            OperationId::ModMatchHistogramMode => OPMOD_MATCH_HISTOGRAM_MODE,
             OperationId::ModResizePreserveAspectRatio => OPMOD_RESIZE_PRESERVE_ASPECT_RATIO,
             OperationId::ModResizeSamplingFilter => OPMOD_RESIZE_SAMPLING_FILTER,
+            OperationId::ModTextColor => OPMOD_TEXT_COLOR,
+            OperationId::ModTextFont => OPMOD_TEXT_FONT,
+            OperationId::ModTextGravity => OPMOD_TEXT_GRAVITY,
+            OperationId::ModTextSize => OPMOD_TEXT_SIZE,
         }
     }
 
@@ -64,23 +140,54 @@ impl OperationId {
     ///     conditions into account, but they are not relevant for this particular method =).
     pub fn takes_number_of_arguments(self) -> usize {
         match self {
+            OperationId::Append => 2,
+            OperationId::AutoLevels => 2,
             OperationId::Blur => 1,
+            OperationId::BlurRegion => 5,
+            OperationId::BlurRegions => 2,
             OperationId::Brighten => 1,
+            OperationId::CircleCrop => 0,
+            OperationId::Clahe => 2,
             OperationId::Contrast => 1,
             OperationId::Crop => 4,
+            OperationId::Curves => 2,
+            OperationId::DrawEllipse => 5,
+            OperationId::DrawLine => 5,
+            OperationId::DrawPolygon => 2,
+            OperationId::DrawRect => 5,
+            OperationId::Equalize => 0,
             OperationId::Filter3x3 => 9,
             OperationId::FlipH => 0,
             OperationId::FlipV => 0,
             OperationId::Grayscale => 0,
             OperationId::HueRotate => 1,
             OperationId::Invert => 0,
+            OperationId::Levels => 5,
+            OperationId::MatchHistogram => 1,
+            OperationId::Pixelate => 1,
+            OperationId::PixelateRegion => 5,
+            OperationId::PixelateRegions => 2,
             OperationId::Resize => 2,
             OperationId::Rotate90 => 0,
             OperationId::Rotate180 => 0,
             OperationId::Rotate270 => 0,
+            OperationId::RoundCorners => 1,
+            OperationId::Shadow => 4,
+            OperationId::Text => 3,
             OperationId::Unsharpen => 2,
+            OperationId::Vignette => 2,
+            OperationId::ModAppendAlignment => 1,
+            OperationId::ModAppendBackground => 1,
+            OperationId::ModDrawAntialias => 1,
+            OperationId::ModDrawFill => 1,
+            OperationId::ModDrawStrokeWidth => 1,
+            OperationId::ModMatchHistogramMode => 1,
             OperationId::ModResizePreserveAspectRatio => 1,
             OperationId::ModResizeSamplingFilter => 1,
+            OperationId::ModTextColor => 1,
+            OperationId::ModTextFont => 1,
+            OperationId::ModTextGravity => 1,
+            OperationId::ModTextSize => 1,
         }
     }
 }
@@ -100,12 +207,31 @@ impl OperationId {
         T::Item: Into<Describable<'a>> + std::fmt::Debug,
     {
         let stmt = match self {
+            OperationId::Append => {
+                let append = parse_inputs_by_type!(inputs, (Axis, Vec<String>))?;
+                Instruction::Operation(ImgOp::Append(append))
+            }
+            OperationId::AutoLevels => Instruction::Operation(ImgOp::AutoLevels(
+                parse_inputs_by_type!(inputs, (f32, f32))?,
+            )),
             OperationId::Blur => {
                 Instruction::Operation(ImgOp::Blur(parse_inputs_by_type!(inputs, f32)?))
             }
+            OperationId::BlurRegion => {
+                let (sigma, region) = parse_inputs_by_type!(inputs, (f32, Region))?;
+                Instruction::Operation(ImgOp::BlurRegions((sigma, vec![region])))
+            }
+            OperationId::BlurRegions => {
+                let (sigma, path) = parse_inputs_by_type!(inputs, (f32, String))?;
+                Instruction::Operation(ImgOp::BlurRegions((sigma, load_regions(path)?)))
+            }
             OperationId::Brighten => {
                 Instruction::Operation(ImgOp::Brighten(parse_inputs_by_type!(inputs, i32)?))
             }
+            OperationId::CircleCrop => Instruction::Operation(ImgOp::CircleCrop),
+            OperationId::Clahe => {
+                Instruction::Operation(ImgOp::Clahe(parse_inputs_by_type!(inputs, (u32, f32))?))
+            }
             OperationId::Contrast => {
                 Instruction::Operation(ImgOp::Contrast(parse_inputs_by_type!(inputs, f32)?))
             }
@@ -113,6 +239,27 @@ impl OperationId {
                 inputs,
                 (u32, u32, u32, u32)
             )?)),
+            OperationId::Curves => {
+                let curve = parse_inputs_by_type!(inputs, (Channel, Vec<(u8, u8)>))?;
+                Instruction::Operation(ImgOp::Curves(curve))
+            }
+            OperationId::DrawEllipse => {
+                let ellipse = parse_inputs_by_type!(inputs, (i32, i32, u32, u32, Rgba<u8>))?;
+                Instruction::Operation(ImgOp::DrawEllipse(ellipse))
+            }
+            OperationId::DrawLine => {
+                let line = parse_inputs_by_type!(inputs, (i32, i32, i32, i32, Rgba<u8>))?;
+                Instruction::Operation(ImgOp::DrawLine(line))
+            }
+            OperationId::DrawPolygon => {
+                let polygon = parse_inputs_by_type!(inputs, (Rgba<u8>, Vec<(i32, i32)>))?;
+                Instruction::Operation(ImgOp::DrawPolygon(polygon))
+            }
+            OperationId::DrawRect => {
+                let rect = parse_inputs_by_type!(inputs, (i32, i32, u32, u32, Rgba<u8>))?;
+                Instruction::Operation(ImgOp::DrawRect(rect))
+            }
+            OperationId::Equalize => Instruction::Operation(ImgOp::Equalize),
             OperationId::Filter3x3 => {
                 Instruction::Operation(ImgOp::Filter3x3(parse_inputs_by_type!(inputs, [f32; 9])?))
             }
@@ -123,16 +270,78 @@ impl OperationId {
                 Instruction::Operation(ImgOp::HueRotate(parse_inputs_by_type!(inputs, i32)?))
             }
             OperationId::Invert => Instruction::Operation(ImgOp::Invert),
+            OperationId::Levels => Instruction::Operation(ImgOp::Levels(parse_inputs_by_type!(
+                inputs,
+                (u8, u8, f32, u8, u8)
+            )?)),
+            OperationId::MatchHistogram => Instruction::Operation(ImgOp::MatchHistogram(
+                parse_inputs_by_type!(inputs, String)?,
+            )),
+            OperationId::Pixelate => {
+                Instruction::Operation(ImgOp::Pixelate(parse_inputs_by_type!(inputs, u32)?))
+            }
+            OperationId::PixelateRegion => {
+                let (block, region) = parse_inputs_by_type!(inputs, (u32, Region))?;
+                Instruction::Operation(ImgOp::PixelateRegions((block, vec![region])))
+            }
+            OperationId::PixelateRegions => {
+                let (block, path) = parse_inputs_by_type!(inputs, (u32, String))?;
+                Instruction::Operation(ImgOp::PixelateRegions((block, load_regions(path)?)))
+            }
             OperationId::Resize => {
                 Instruction::Operation(ImgOp::Resize(parse_inputs_by_type!(inputs, (u32, u32))?))
             }
             OperationId::Rotate90 => Instruction::Operation(ImgOp::Rotate90),
             OperationId::Rotate180 => Instruction::Operation(ImgOp::Rotate180),
             OperationId::Rotate270 => Instruction::Operation(ImgOp::Rotate270),
+            OperationId::RoundCorners => {
+                Instruction::Operation(ImgOp::RoundCorners(parse_inputs_by_type!(inputs, u32)?))
+            }
+            OperationId::Shadow => {
+                let shadow = parse_inputs_by_type!(inputs, (i32, i32, f32, Rgba<u8>))?;
+                Instruction::Operation(ImgOp::Shadow(shadow))
+            }
+            OperationId::Text => {
+                let text = parse_inputs_by_type!(inputs, (String, i32, i32))?;
+                Instruction::Operation(ImgOp::Text(text))
+            }
             OperationId::Unsharpen => {
                 Instruction::Operation(ImgOp::Unsharpen(parse_inputs_by_type!(inputs, (f32, i32))?))
             }
+            OperationId::Vignette => {
+                Instruction::Operation(ImgOp::Vignette(parse_inputs_by_type!(inputs, (f32, f32))?))
+            }
 
+            OperationId::ModAppendAlignment => {
+                let input = parse_inputs_by_type!(inputs, String)?;
+                let alignment = Alignment::try_from_str(&input)
+                    .map_err(|_| "Error: append alignment not found.".to_string())?;
+                Instruction::AddToEnv(EnvironmentItem::AppendAlignment(alignment))
+            }
+            OperationId::ModAppendBackground => Instruction::AddToEnv(
+                EnvironmentItem::AppendBackground(parse_inputs_by_type!(inputs, Rgba<u8>)?),
+            ),
+            OperationId::ModDrawAntialias => {
+                let toggle = parse_inputs_by_type!(inputs, bool)?;
+                if toggle {
+                    Instruction::AddToEnv(EnvironmentItem::DrawAntialias)
+                } else {
+                    Instruction::RemoveFromEnv(EnvironmentKind::DrawAntialias)
+                }
+            }
+            OperationId::ModDrawFill => Instruction::AddToEnv(EnvironmentItem::DrawFill(
+                parse_inputs_by_type!(inputs, Rgba<u8>)?,
+            )),
+            OperationId::ModDrawStrokeWidth => {
+                let width = parse_inputs_by_type!(inputs, u32)?;
+                Instruction::AddToEnv(EnvironmentItem::DrawStrokeWidth(width))
+            }
+            OperationId::ModMatchHistogramMode => {
+                let input = parse_inputs_by_type!(inputs, String)?;
+                let mode = MatchMode::try_from_str(&input)
+                    .map_err(|_| "Error: match histogram mode not found.".to_string())?;
+                Instruction::AddToEnv(EnvironmentItem::MatchHistogramMode(mode))
+            }
             OperationId::ModResizePreserveAspectRatio => {
                 let toggle = parse_inputs_by_type!(inputs, bool)?;
                 if toggle {
@@ -147,6 +356,22 @@ impl OperationId {
                     .map_err(|_| "Error: resize sampling filter not found.".to_string())?;
                 Instruction::AddToEnv(EnvironmentItem::CustomSamplingFilter(filter))
             }
+            OperationId::ModTextColor => Instruction::AddToEnv(EnvironmentItem::TextColor(
+                parse_inputs_by_type!(inputs, Rgba<u8>)?,
+            )),
+            OperationId::ModTextFont => Instruction::AddToEnv(EnvironmentItem::TextFont(
+                parse_inputs_by_type!(inputs, String)?,
+            )),
+            OperationId::ModTextGravity => {
+                let input = parse_inputs_by_type!(inputs, String)?;
+                let gravity = Gravity::try_from_str(&input)
+                    .map_err(|_| "Error: text gravity not found.".to_string())?;
+                Instruction::AddToEnv(EnvironmentItem::TextGravity(gravity))
+            }
+            OperationId::ModTextSize => {
+                let size = parse_inputs_by_type!(inputs, u32)?;
+                Instruction::AddToEnv(EnvironmentItem::TextSize(size))
+            }
         };
 
         Ok(stmt)
@@ -525,6 +750,64 @@ mod test_tree_extend {
     // 1) Individual uses of: op_with_values! and op_valueless!
     // FIXME: Quite a bit duplication currently.
 
+    mod case_append {
+        use super::*;
+
+        #[test]
+        fn append_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--append x other.png");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Append);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Append);
+                    assert_eq!(*values, vec!["x".to_string(), "other.png".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn append_x1_too_few_values() {
+            setup("--append x");
+        }
+    }
+
+    mod case_auto_levels {
+        use super::*;
+
+        #[test]
+        fn auto_levels_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--auto-levels 0.5 1");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::AutoLevels);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::AutoLevels);
+                    assert_eq!(*values, vec!["0.5".to_string(), "1".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn auto_levels_x1_too_few_values() {
+            setup("--auto-levels 0.5");
+        }
+    }
+
     mod case_blur {
         use super::*;
 
@@ -565,6 +848,58 @@ mod test_tree_extend {
         }
     }
 
+    mod case_blur_region {
+        use super::*;
+
+        #[test]
+        fn blur_region_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--blur-region 1.5 0 1 2 3");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::BlurRegion);
+            extend_index_tree_with_unification(&mut tree, op, 5).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::BlurRegion);
+                    assert_eq!(
+                        *values,
+                        ["1.5", "0", "1", "2", "3"]
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_blur_regions {
+        use super::*;
+
+        #[test]
+        fn blur_regions_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--blur-regions 1.5 faces.json");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::BlurRegions);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::BlurRegions);
+                    assert_eq!(*values, vec!["1.5".to_string(), "faces.json".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
     mod case_brighten {
         use super::*;
 
@@ -653,6 +988,60 @@ mod test_tree_extend {
         }
     }
 
+    mod case_circle_crop {
+        use super::*;
+
+        #[test]
+        fn circle_crop_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let op_id = OperationId::CircleCrop;
+            let setup = setup("--circle-crop");
+            let matches = setup.0;
+            let op = op_valueless!(matches, op_id);
+            extend_index_tree_with_unification(&mut tree, op, 0).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let id = match out {
+                (_, Op::Bare(id)) => *id,
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(id, op_id);
+        }
+    }
+
+    mod case_clahe {
+        use super::*;
+
+        #[test]
+        fn clahe_x2() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--clahe 8 2 --clahe 4 3.5");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Clahe);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let mut iter = tree.iter();
+
+            match iter.next().unwrap().1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Clahe);
+                    assert_eq!(*values, vec!["8".to_string(), "2".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+
+            match iter.next().unwrap().1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Clahe);
+                    assert_eq!(*values, vec!["4".to_string(), "3.5".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
     mod case_crop {
         use super::*;
 
@@ -688,6 +1077,135 @@ mod test_tree_extend {
         }
     }
 
+    mod case_draw {
+        use super::*;
+
+        fn test(setup: (ArgMatches, String), id: OperationId, amount: usize, expect: &[&str]) {
+            let mut tree: IndexTree = BTreeMap::new();
+            let matches = setup.0;
+            let op = op_with_values!(matches, id);
+            extend_index_tree_with_unification(&mut tree, op, amount).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(out_id, values) => {
+                    assert_eq!(*out_id, id);
+                    assert_eq!(
+                        *values,
+                        expect.iter().map(ToString::to_string).collect::<Vec<_>>()
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        fn draw_ellipse_x1() {
+            let setup = setup("--draw-ellipse -5 10 20 15 red");
+            test(
+                setup,
+                OperationId::DrawEllipse,
+                5,
+                &["-5", "10", "20", "15", "red"],
+            );
+        }
+
+        #[test]
+        fn draw_line_x1() {
+            let setup = setup("--draw-line 0 -1 20 15 #00ff00");
+            test(
+                setup,
+                OperationId::DrawLine,
+                5,
+                &["0", "-1", "20", "15", "#00ff00"],
+            );
+        }
+
+        #[test]
+        fn draw_polygon_x1() {
+            let setup = setup("--draw-polygon blue 0:0,10:-5,20:30");
+            test(
+                setup,
+                OperationId::DrawPolygon,
+                2,
+                &["blue", "0:0,10:-5,20:30"],
+            );
+        }
+
+        #[test]
+        fn draw_rect_x1() {
+            let setup = setup("--draw-rect 1 2 3 4 black");
+            test(
+                setup,
+                OperationId::DrawRect,
+                5,
+                &["1", "2", "3", "4", "black"],
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn draw_rect_x1_too_few_values() {
+            setup("--draw-rect 1 2 3 black");
+        }
+    }
+
+    mod case_curves {
+        use super::*;
+
+        #[test]
+        fn curves_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--curves red 0:0,128:160,255:255");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Curves);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Curves);
+                    assert_eq!(
+                        *values,
+                        vec!["red".to_string(), "0:0,128:160,255:255".to_string()]
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn curves_x1_no_points() {
+            setup("--curves red");
+        }
+    }
+
+    mod case_equalize {
+        use super::*;
+
+        #[test]
+        fn equalize_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let op_id = OperationId::Equalize;
+            let setup = setup("--equalize");
+            let matches = setup.0;
+            let op = op_valueless!(matches, op_id);
+            extend_index_tree_with_unification(&mut tree, op, 0).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let id = match out {
+                (_, Op::Bare(id)) => *id,
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(id, op_id);
+        }
+    }
+
     mod case_filter3x3 {
         use super::*;
 
@@ -891,32 +1409,165 @@ mod test_tree_extend {
         }
     }
 
-    mod case_resize {
+    mod case_levels {
         use super::*;
 
         #[test]
-        fn resize_x1_pos() {
+        fn levels_x1() {
             let mut tree: IndexTree = BTreeMap::new();
-            let setup = setup("--resize 1 2");
+            let setup = setup("--levels 10 240 1.2 0 255");
             let matches = setup.0;
-            let resize = op_with_values!(matches, OperationId::Resize);
-            extend_index_tree_with_unification(&mut tree, resize, 2).unwrap();
+            let op = op_with_values!(matches, OperationId::Levels);
+            extend_index_tree_with_unification(&mut tree, op, 5).unwrap();
 
             let out = tree.iter().next().unwrap();
 
-            let (id, values) = match out {
-                (_, Op::WithValues(id, values)) => (id, values),
-                _ => panic!("unexpected test error"),
-            };
-
-            assert_eq!(*id, OperationId::Resize);
-            assert_eq!(
-                *values,
-                vec!["1", "2"]
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-            );
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Levels);
+                    assert_eq!(
+                        *values,
+                        ["10", "240", "1.2", "0", "255"]
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_match_histogram {
+        use super::*;
+
+        #[test]
+        fn match_histogram_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--match-histogram reference.png");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::MatchHistogram);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::MatchHistogram);
+                    assert_eq!(*values, vec!["reference.png".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_pixelate {
+        use super::*;
+
+        #[test]
+        fn pixelate_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--pixelate 8");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Pixelate);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Pixelate);
+                    assert_eq!(*values, vec!["8".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_pixelate_region {
+        use super::*;
+
+        #[test]
+        fn pixelate_region_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--pixelate-region 8 0 1 2 3");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::PixelateRegion);
+            extend_index_tree_with_unification(&mut tree, op, 5).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::PixelateRegion);
+                    assert_eq!(
+                        *values,
+                        ["8", "0", "1", "2", "3"]
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn pixelate_region_x1_too_few_values() {
+            setup("--pixelate-region 8 0 1 2");
+        }
+    }
+
+    mod case_pixelate_regions {
+        use super::*;
+
+        #[test]
+        fn pixelate_regions_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--pixelate-regions 8 plates.csv");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::PixelateRegions);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::PixelateRegions);
+                    assert_eq!(*values, vec!["8".to_string(), "plates.csv".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_resize {
+        use super::*;
+
+        #[test]
+        fn resize_x1_pos() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--resize 1 2");
+            let matches = setup.0;
+            let resize = op_with_values!(matches, OperationId::Resize);
+            extend_index_tree_with_unification(&mut tree, resize, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let (id, values) = match out {
+                (_, Op::WithValues(id, values)) => (id, values),
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(*id, OperationId::Resize);
+            assert_eq!(
+                *values,
+                vec!["1", "2"]
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            );
         }
 
         #[test]
@@ -1007,6 +1658,99 @@ mod test_tree_extend {
         }
     }
 
+    mod case_round_corners {
+        use super::*;
+
+        #[test]
+        fn round_corners_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--round-corners 12");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::RoundCorners);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::RoundCorners);
+                    assert_eq!(*values, vec!["12".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_shadow {
+        use super::*;
+
+        #[test]
+        fn shadow_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--shadow -4 6 2.5 #00000080");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Shadow);
+            extend_index_tree_with_unification(&mut tree, op, 4).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Shadow);
+                    assert_eq!(
+                        *values,
+                        ["-4", "6", "2.5", "#00000080"]
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn shadow_x1_too_few_values() {
+            setup("--shadow 4 6 2.5");
+        }
+    }
+
+    mod case_text {
+        use super::*;
+
+        #[test]
+        fn text_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--text hello -4 6");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Text);
+            extend_index_tree_with_unification(&mut tree, op, 3).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Text);
+                    assert_eq!(
+                        *values,
+                        ["hello", "-4", "6"]
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                    );
+                }
+                _ => panic!("test err"),
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn text_x1_too_few_values() {
+            setup("--text hello 4");
+        }
+    }
+
     mod case_unsharpen {
         use super::*;
 
@@ -1111,6 +1855,203 @@ mod test_tree_extend {
         }
     }
 
+    mod case_vignette {
+        use super::*;
+
+        #[test]
+        fn vignette_x1() {
+            let mut tree: IndexTree = BTreeMap::new();
+            let setup = setup("--vignette 0.8 0.25");
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::Vignette);
+            extend_index_tree_with_unification(&mut tree, op, 2).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            match out.1 {
+                Op::WithValues(id, values) => {
+                    assert_eq!(*id, OperationId::Vignette);
+                    assert_eq!(*values, vec!["0.8".to_string(), "0.25".to_string()]);
+                }
+                _ => panic!("test err"),
+            }
+        }
+    }
+
+    mod case_opmod_append {
+        use super::*;
+
+        fn test(setup: (ArgMatches, String), id: OperationId, expect: &str) {
+            let mut tree: IndexTree = BTreeMap::new();
+            let matches = setup.0;
+            let op = op_with_values!(matches, id);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let (a, b) = match out {
+                (_, Op::WithValues(id, values)) => (id, values),
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(*a, id);
+            assert_eq!(*b, vec![expect.to_string()]);
+        }
+
+        #[test]
+        fn set_alignment() {
+            let setup = setup("--set-append-alignment center");
+            test(setup, OperationId::ModAppendAlignment, "center");
+        }
+
+        #[test]
+        fn set_background() {
+            let setup = setup("--set-append-background #ffffff");
+            test(setup, OperationId::ModAppendBackground, "#ffffff");
+        }
+
+        #[test]
+        #[should_panic]
+        fn alignment_not_allowed_value() {
+            setup("--set-append-alignment middle");
+        }
+    }
+
+    mod case_opmod_draw {
+        use super::*;
+
+        fn test(setup: (ArgMatches, String), id: OperationId, expect: &str) {
+            let mut tree: IndexTree = BTreeMap::new();
+            let matches = setup.0;
+            let op = op_with_values!(matches, id);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let (a, b) = match out {
+                (_, Op::WithValues(id, values)) => (id, values),
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(*a, id);
+            assert_eq!(*b, vec![expect.to_string()]);
+        }
+
+        #[test]
+        fn set_antialias() {
+            let setup = setup("--set-draw-antialias true");
+            test(setup, OperationId::ModDrawAntialias, "true");
+        }
+
+        #[test]
+        fn set_fill() {
+            let setup = setup("--set-draw-fill #ff000080");
+            test(setup, OperationId::ModDrawFill, "#ff000080");
+        }
+
+        #[test]
+        fn set_stroke_width() {
+            let setup = setup("--set-draw-stroke-width 4");
+            test(setup, OperationId::ModDrawStrokeWidth, "4");
+        }
+
+        #[test]
+        #[should_panic]
+        fn antialias_not_allowed_value() {
+            setup("--set-draw-antialias yes");
+        }
+    }
+
+    mod case_opmod_match_histogram_mode {
+        use super::*;
+
+        fn test(setup: (ArgMatches, String), expect: &str) {
+            let mut tree: IndexTree = BTreeMap::new();
+            let matches = setup.0;
+            let op = op_with_values!(matches, OperationId::ModMatchHistogramMode);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let (a, b) = match out {
+                (_, Op::WithValues(id, values)) => (id, values),
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(*a, OperationId::ModMatchHistogramMode);
+            assert_eq!(*b, vec![expect.to_string()]);
+        }
+
+        #[test]
+        fn set_histogram() {
+            let setup = setup("--set-match-histogram-mode histogram");
+            test(setup, "histogram");
+        }
+
+        #[test]
+        fn set_reinhard() {
+            let setup = setup("--set-match-histogram-mode reinhard");
+            test(setup, "reinhard");
+        }
+
+        #[test]
+        #[should_panic]
+        fn not_allowed_value() {
+            setup("--set-match-histogram-mode lab");
+        }
+    }
+
+    mod case_opmod_text {
+        use super::*;
+
+        fn test(setup: (ArgMatches, String), id: OperationId, expect: &str) {
+            let mut tree: IndexTree = BTreeMap::new();
+            let matches = setup.0;
+            let op = op_with_values!(matches, id);
+            extend_index_tree_with_unification(&mut tree, op, 1).unwrap();
+
+            let out = tree.iter().next().unwrap();
+
+            let (a, b) = match out {
+                (_, Op::WithValues(id, values)) => (id, values),
+                _ => panic!("unexpected test error"),
+            };
+
+            assert_eq!(*a, id);
+            assert_eq!(*b, vec![expect.to_string()]);
+        }
+
+        #[test]
+        fn set_color() {
+            let setup = setup("--set-text-color #ffffff80");
+            test(setup, OperationId::ModTextColor, "#ffffff80");
+        }
+
+        #[test]
+        fn set_font() {
+            let setup = setup("--set-text-font fonts/font.ttf");
+            test(setup, OperationId::ModTextFont, "fonts/font.ttf");
+        }
+
+        #[test]
+        fn set_gravity() {
+            let setup = setup("--set-text-gravity southeast");
+            test(setup, OperationId::ModTextGravity, "southeast");
+        }
+
+        #[test]
+        fn set_size() {
+            let setup = setup("--set-text-size 32");
+            test(setup, OperationId::ModTextSize, "32");
+        }
+
+        #[test]
+        #[should_panic]
+        fn gravity_not_allowed_value() {
+            setup("--set-text-gravity middle");
+        }
+    }
+
     mod case_opmod_resize_keep_aspect_ratio {
         use super::*;
 
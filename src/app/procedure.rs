@@ -1,52 +1,430 @@
 use std::error::Error;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use clap::ArgMatches;
 use sic_core::image;
-use sic_image_engine::engine::ImageEngine;
+use sic_core::image::{DynamicImage, FilterType};
+use sic_image_engine::engine::{
+    EnvironmentItem, EnvironmentKind, ImageEngine, Instruction, DEFAULT_RESIZE_SAMPLING_FILTER,
+};
+use sic_io::auto;
 use sic_io::conversion::AutomaticColorTypeAdjustment;
+use sic_io::encoders::ico::IcoSettings;
+use sic_io::encoders::jpeg::JpegSettings;
+use sic_io::encoders::png::PngSettings;
 use sic_io::format::{
-    DetermineEncodingFormat, EncodingFormatByIdentifier, EncodingFormatByMethod, JPEGQuality,
+    DetermineEncodingFormat, EncodingFormatByIdentifier, EncodingFormatByMethod,
+    EncodingFormatICOSettings, EncodingFormatPNGSettings, JPEGQuality, OutputFormat,
+};
+use sic_io::load::{load_image, load_image_with_format, ImportConfig, InputFormat};
+use sic_io::pyramid::Pyramid;
+use sic_io::responsive::Manifest;
+use sic_io::save::{
+    export, export_favicon, export_pyramid, export_responsive, export_smallest, export_tiles,
+    ExportMethod, ExportSettings,
 };
-use sic_io::load::{load_image, ImportConfig};
-use sic_io::save::{export, ExportMethod, ExportSettings};
+use sic_io::support::formats_report;
 
 use crate::app::cli::arg_names::{ARG_INPUT, ARG_INPUT_FILE};
-use crate::app::config::Config;
+use crate::app::config::{AtlasSettings, Config, ResponsiveSettings};
 use crate::app::license::PrintTextFor;
 
 const NO_INPUT_PATH_MSG: &str = "Input path was expected but could not be found.";
+const SHEET_PLACEHOLDER: &str = "{sheet}";
+const WIDTH_PLACEHOLDER: &str = "{width}";
+/// The output format which stands for the format of the input image.
+const SAME_FORMAT: &str = "same";
+/// The output format which stands for the format in which the image is smallest.
+const AUTO_FORMAT: &str = "auto";
 
 /// The run function runs the sic application, taking the matches found by Clap.
 /// This function is separated from the main() function so that it can be used more easily in test cases.
 /// This function consumes the matches provided.
 pub fn run(matches: &ArgMatches, options: &Config) -> Result<(), String> {
-    if options.output.is_none() {
+    if options.output.is_none() && options.forced_output_format.is_none() {
         eprintln!(
             "The default output format is BMP. Use --output-format <FORMAT> to specify \
-             a different output format."
+             a different output format, or --output-format same to keep the format of the \
+             input image."
         );
     }
 
-    let mut reader = mk_reader(matches)?;
+    if let Some(atlas) = &options.atlas {
+        return run_atlas(atlas, options);
+    }
 
-    let img = load_image(
-        &mut reader,
-        &ImportConfig {
-            selected_frame: options.selected_frame,
-        },
-    )?;
+    let (img, input_format) = load_starting_image(matches, options)?;
+    let forced_format = forced_output_format(options, input_format)?;
 
     let mut image_engine = ImageEngine::new(img);
     let buffer = image_engine
         .ignite(&options.image_operations_program)
         .map_err(|err| err.to_string())?;
 
+    if let Some(pyramid) = &options.pyramid {
+        return run_pyramid(buffer, pyramid, forced_format, options);
+    }
+
+    if let Some(responsive) = &options.responsive {
+        return run_responsive(buffer, responsive, forced_format, options);
+    }
+
+    if options.favicon {
+        return run_favicon(buffer, options);
+    }
+
     let export_method =
         determine_export_method(options.output.as_ref()).map_err(|err| err.to_string())?;
 
-    let encoding_format_determiner = DetermineEncodingFormat {
+    let export_settings = ExportSettings {
+        adjust_color_type: AutomaticColorTypeAdjustment::default(),
+        text_encoding: options.text_encoding,
+        size_limit: options
+            .size_limit
+            .map(|limit| (limit, resize_sampling_filter(options))),
+    };
+
+    if forced_format == Some(AUTO_FORMAT) {
+        return run_auto(buffer, export_method, export_settings, options);
+    }
+
+    let encoding_format = determine_encoding_format(options, forced_format, &export_method)?;
+
+    match (&options.split, export_method) {
+        (Some(split), ExportMethod::File(path_template)) => export_tiles(
+            buffer,
+            split,
+            path_template,
+            encoding_format,
+            export_settings,
+        ),
+        (Some(_), ExportMethod::StdoutBytes) => {
+            Err("Splitting an image into tiles requires an output path.".to_string())
+        }
+        (None, export_method) => export(buffer, export_method, encoding_format, export_settings),
+    }
+}
+
+/// Writes the image in the candidate format in which it's smallest, and reports the choice on the
+/// stderr.
+fn run_auto<P: AsRef<Path>>(
+    image: &DynamicImage,
+    export_method: ExportMethod<P>,
+    export_settings: ExportSettings,
+    options: &Config,
+) -> Result<(), String> {
+    let encoding_format_determiner = encoding_format_determiner(options)?;
+
+    // formats which can't store transparency would make the image opaque
+    let transparent = auto::has_transparency(image);
+    let candidates = auto::candidates(options.auto_format.lossless_only, transparent)
+        .into_iter()
+        .map(|identifier| {
+            encoding_format_determiner
+                .by_identifier(identifier)
+                .map(|format| (identifier, format))
+                .map_err(|err| err.to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let choice = export_smallest(image, export_method, &candidates, export_settings)?;
+    eprint!("{}", choice.report(options.auto_format.report));
+
+    Ok(())
+}
+
+/// Packs the input images into the sheets of an atlas. Each sheet is written to the output path,
+/// in which `{sheet}` is replaced by the index of the sheet, next to its descriptor.
+fn run_atlas(atlas: &AtlasSettings, options: &Config) -> Result<(), String> {
+    let path_template = options
+        .output
+        .ok_or_else(|| "Packing an atlas requires an output path.".to_string())?;
+
+    let names = frame_names(&atlas.inputs)?;
+
+    // image operations are applied to each image separately, before it is packed
+    let images = load_images(&atlas.inputs, options, Ok)?
+        .into_iter()
+        .zip(names)
+        .map(|((_, image), name)| {
+            let mut image_engine = ImageEngine::new(image);
+            image_engine
+                .ignite(&options.image_operations_program)
+                .map(|buffer| (name, buffer.clone()))
+                .map_err(|err| err.to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let forced_format = forced_output_format(options, None)?;
+    let sheets = atlas.layout.pack(&images).map_err(|err| err.to_string())?;
+
+    if sheets.len() > 1 && !path_template.contains(SHEET_PLACEHOLDER) {
+        return Err(format!(
+            "The images don't fit on a single sheet; the output path '{}' should contain the '{}' \
+             placeholder, which is replaced by the index of each sheet.",
+            path_template, SHEET_PLACEHOLDER
+        ));
+    }
+
+    for (index, sheet) in sheets.iter().enumerate() {
+        let path = PathBuf::from(path_template.replace(SHEET_PLACEHOLDER, &index.to_string()));
+        let export_method = ExportMethod::File(&path);
+        let encoding_format = determine_encoding_format(options, forced_format, &export_method)?;
+
+        export(
+            &sheet.image,
+            export_method,
+            encoding_format,
+            ExportSettings {
+                adjust_color_type: AutomaticColorTypeAdjustment::default(),
+                text_encoding: None,
+                size_limit: None,
+            },
+        )?;
+
+        let descriptor_path = path.with_extension(atlas.descriptor.extension());
+        let description = atlas
+            .descriptor
+            .describe(sheet, &file_name(&path.to_string_lossy()));
+
+        std::fs::write(&descriptor_path, description).map_err(|err| {
+            format!(
+                "Unable to write the descriptor '{}': {}",
+                descriptor_path.display(),
+                err
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The names of the images in the descriptor of an atlas: their paths relative to the directory
+/// which contains all of them, so the descriptor doesn't depend on where the images are stored.
+/// Components of the names are separated by a `/`.
+fn frame_names(paths: &[&str]) -> Result<Vec<String>, String> {
+    let current_dir = std::env::current_dir()
+        .map_err(|err| format!("Unable to determine the current directory: {}", err))?;
+    let absolute_paths = paths
+        .iter()
+        .map(|path| {
+            current_dir
+                .join(path)
+                .components()
+                .filter(|component| *component != Component::CurDir)
+                .collect::<PathBuf>()
+        })
+        .collect::<Vec<_>>();
+
+    let first_directory = absolute_paths
+        .first()
+        .and_then(|path| path.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let common_directory = absolute_paths
+        .iter()
+        .skip(1)
+        .fold(first_directory, |common, path| {
+            common
+                .components()
+                .zip(path.components())
+                .take_while(|(left, right)| left == right)
+                .map(|(component, _)| component)
+                .collect()
+        });
+
+    let names = absolute_paths
+        .iter()
+        .map(|path| {
+            path.strip_prefix(&common_directory)
+                .unwrap_or(path)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<Vec<_>>();
+
+    for (index, name) in names.iter().enumerate() {
+        if let Some(other) = names[..index].iter().position(|other| other == name) {
+            return Err(format!(
+                "The images '{}' and '{}' would both be named '{}' in the descriptor of the atlas.",
+                paths[other], paths[index], name
+            ));
+        }
+    }
+
+    Ok(names)
+}
+
+/// Writes the tiles of the pyramid of the image, and its descriptor, to the output path. The tiles
+/// are encoded in the forced output format, or otherwise in the default format of the layout.
+fn run_pyramid(
+    image: &DynamicImage,
+    pyramid: &Pyramid,
+    forced_format: Option<&str>,
+    options: &Config,
+) -> Result<(), String> {
+    let output = options
+        .output
+        .ok_or_else(|| "Building a pyramid requires an output path.".to_string())?;
+
+    let extension = forced_format.unwrap_or_else(|| pyramid.layout.default_format());
+    let encoding_format = encoding_format_determiner(options)?
+        .by_identifier(extension)
+        .map_err(|err| err.to_string())?;
+
+    export_pyramid(
+        image,
+        pyramid,
+        Path::new(output),
+        encoding_format,
+        extension,
+        ExportSettings {
+            adjust_color_type: AutomaticColorTypeAdjustment::default(),
+            text_encoding: None,
+            size_limit: None,
+        },
+    )
+}
+
+/// Writes the image at each width of the responsive set, in each of its formats, and describes the
+/// written images in the manifest.
+fn run_responsive(
+    image: &DynamicImage,
+    responsive: &ResponsiveSettings,
+    forced_format: Option<&str>,
+    options: &Config,
+) -> Result<(), String> {
+    let path_template = options
+        .output
+        .ok_or_else(|| "Creating a responsive image set requires an output path.".to_string())?;
+
+    let identifiers = if responsive.formats.is_empty() {
+        let identifier = forced_format
+            .or_else(|| {
+                Path::new(path_template)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+            })
+            .ok_or_else(|| "Unable to determine output format from extension.".to_string())?;
+        vec![identifier]
+    } else {
+        responsive.formats.clone()
+    };
+
+    let encoding_format_determiner = encoding_format_determiner(options)?;
+    let formats = identifiers
+        .into_iter()
+        .map(|identifier| {
+            encoding_format_determiner
+                .by_identifier(identifier)
+                .map(|format| (identifier, format))
+                .map_err(|err| err.to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let manifest_path = match responsive.manifest {
+        Some(path) => PathBuf::from(path),
+        None => {
+            PathBuf::from(path_template.replace(WIDTH_PLACEHOLDER, "srcset")).with_extension("json")
+        }
+    };
+    let manifest = Manifest::by_extension(&manifest_path)?;
+
+    let variants = export_responsive(
+        image,
+        &responsive.set,
+        resize_sampling_filter(options),
+        path_template,
+        &formats,
+        ExportSettings {
+            adjust_color_type: AutomaticColorTypeAdjustment::default(),
+            text_encoding: None,
+            size_limit: None,
+        },
+    )?;
+
+    std::fs::write(&manifest_path, manifest.describe(&variants, &manifest_path)).map_err(|err| {
+        format!(
+            "Unable to write the manifest '{}': {}",
+            manifest_path.display(),
+            err
+        )
+    })
+}
+
+/// Writes a favicon pack of the image to the output path, which is used as a directory.
+fn run_favicon(image: &DynamicImage, options: &Config) -> Result<(), String> {
+    let directory = options
+        .output
+        .ok_or_else(|| "Writing a favicon pack requires an output path.".to_string())?;
+
+    let encoding_format_determiner = encoding_format_determiner(options)?;
+
+    export_favicon(
+        image,
+        &encoding_format_determiner
+            .ico_settings()
+            .map_err(|err| err.to_string())?,
+        encoding_format_determiner
+            .png_settings()
+            .map_err(|err| err.to_string())?,
+        Path::new(directory),
+        ExportSettings {
+            adjust_color_type: AutomaticColorTypeAdjustment::default(),
+            text_encoding: None,
+            size_limit: None,
+        },
+    )
+}
+
+/// The forced output format, if any. The `same` format is replaced by the format of the input
+/// image, which is only known if a single image was read.
+fn forced_output_format<'a>(
+    options: &Config<'a>,
+    input_format: Option<InputFormat>,
+) -> Result<Option<&'a str>, String> {
+    match options.forced_output_format {
+        Some(SAME_FORMAT) => input_format.map(|format| Some(format.identifier())).ok_or_else(|| {
+            "The output format can only be the same as the input format if a single image is read \
+             from an input path or the stdin."
+                .to_string()
+        }),
+        Some(AUTO_FORMAT)
+            if options.split.is_some()
+                || options.pyramid.is_some()
+                || options.responsive.is_some()
+                || options.favicon
+                || options.atlas.is_some() =>
+        {
+            Err("The output format can only be chosen automatically if a single image is written."
+                .to_string())
+        }
+        format => Ok(format),
+    }
+}
+
+/// Determines the format in which the image is encoded: the forced output format if any, or
+/// otherwise the format belonging to the export method.
+fn determine_encoding_format<P: AsRef<Path>>(
+    options: &Config,
+    forced_format: Option<&str>,
+    export_method: &ExportMethod<P>,
+) -> Result<OutputFormat, String> {
+    let encoding_format_determiner = encoding_format_determiner(options)?;
+
+    match forced_format {
+        Some(format) => encoding_format_determiner.by_identifier(format),
+        None => encoding_format_determiner.by_method(export_method),
+    }
+    .map_err(|err| err.to_string())
+}
+
+/// Sets up the encoding settings of the output formats, such as the JPEG quality.
+fn encoding_format_determiner(options: &Config) -> Result<DetermineEncodingFormat, String> {
+    Ok(DetermineEncodingFormat {
         pnm_sample_encoding: if options.encoding_settings.pnm_use_ascii_format {
             Some(image::pnm::SampleEncoding::Ascii)
         } else {
@@ -58,22 +436,126 @@ pub fn run(matches: &ArgMatches, options: &Config) -> Result<(), String> {
 
             Some(quality?)
         },
+        jpeg_settings: Some(JpegSettings {
+            progressive: options.encoding_settings.jpeg_progressive,
+            subsampling: options.encoding_settings.jpeg_chroma_subsampling,
+            optimize_huffman_tables: options.encoding_settings.jpeg_optimize_huffman_tables,
+            restart_interval: options.encoding_settings.jpeg_restart_interval,
+            grayscale: options.encoding_settings.jpeg_grayscale,
+        }),
+        png_settings: Some(PngSettings {
+            compression: options.encoding_settings.png_compression,
+            filter: options.encoding_settings.png_filter,
+            sixteen_bit: options.encoding_settings.png_sixteen_bit,
+            palette: options.encoding_settings.png_palette,
+        }),
+        ico_settings: Some(match &options.encoding_settings.ico_sizes {
+            // explicitly given sizes are used as is
+            Some(sizes) => IcoSettings {
+                sizes: sizes.clone(),
+                filter: resize_sampling_filter(options),
+                upscale: true,
+            },
+            None => IcoSettings {
+                filter: resize_sampling_filter(options),
+                ..IcoSettings::default()
+            },
+        }),
+        tiff_compression: Some(options.encoding_settings.tiff_compression),
+        tga_encoding: Some(options.encoding_settings.tga_encoding),
+        raw_format: Some(options.encoding_settings.raw_format),
+    })
+}
+
+/// The resize sampling filter which is set by the image operations program, once all of its
+/// instructions have been applied. Changes made within a block don't affect the program after it.
+fn resize_sampling_filter(options: &Config) -> FilterType {
+    options.image_operations_program.iter().fold(
+        DEFAULT_RESIZE_SAMPLING_FILTER,
+        |filter, instruction| match instruction {
+            Instruction::AddToEnv(EnvironmentItem::CustomSamplingFilter(custom)) => {
+                FilterType::from(*custom)
+            }
+            Instruction::RemoveFromEnv(EnvironmentKind::CustomSamplingFilter) => {
+                DEFAULT_RESIZE_SAMPLING_FILTER
+            }
+            _ => filter,
+        },
+    )
+}
+
+/// Creates the image on which the image operations will be applied: a generated image, a montage
+/// of input images, or a single image read from a file or the stdin. The format of the image is
+/// returned as well, if it was read.
+fn load_starting_image(
+    matches: &ArgMatches,
+    options: &Config,
+) -> Result<(DynamicImage, Option<InputFormat>), String> {
+    let import_config = ImportConfig {
+        selected_frame: options.selected_frame,
+        input_format: options.input_format,
     };
 
-    let encoding_format = match &options.forced_output_format {
-        Some(format) => encoding_format_determiner.by_identifier(format),
-        None => encoding_format_determiner.by_method(&export_method),
+    if let Some(generator) = &options.generator {
+        return generator
+            .generate()
+            .map(|image| (image, None))
+            .map_err(|err| err.to_string());
     }
-    .map_err(|err| err.to_string())?;
 
-    export(
-        buffer,
-        export_method,
-        encoding_format,
-        ExportSettings {
-            adjust_color_type: AutomaticColorTypeAdjustment::default(),
-        },
-    )
+    if let Some(montage) = &options.montage {
+        // only the thumbnails are kept in memory, instead of the images at full size
+        let thumbnail = |image: DynamicImage| Ok(montage.layout.thumbnail(&image));
+        let images = load_images(&montage.inputs, options, thumbnail)?
+            .into_iter()
+            .map(|(path, image)| (file_name(path), image))
+            .collect::<Vec<_>>();
+
+        return montage
+            .layout
+            .compose(&images)
+            .map(|image| (image, None))
+            .map_err(|err| err.to_string());
+    }
+
+    let mut reader = mk_reader(matches)?;
+    let (image, format) = load_image_with_format(&mut reader, &import_config)?;
+    Ok((image, Some(format)))
+}
+
+/// Loads the images at the given paths, each accompanied by its path. Each image is prepared as
+/// soon as it has been loaded, so only the prepared images are kept.
+fn load_images<'a, F>(
+    paths: &[&'a str],
+    options: &Config,
+    prepare: F,
+) -> Result<Vec<(&'a str, DynamicImage)>, String>
+where
+    F: Fn(DynamicImage) -> Result<DynamicImage, String>,
+{
+    let import_config = ImportConfig {
+        selected_frame: options.selected_frame,
+        input_format: options.input_format,
+    };
+
+    paths
+        .iter()
+        .map(|&path| {
+            sic_io::load::file_reader(path)
+                .and_then(|mut reader| load_image(&mut reader, &import_config))
+                .map_err(|err| format!("Unable to load '{}': {}", path, String::from(err)))
+                .and_then(&prepare)
+                .map(|image| (path, image))
+        })
+        .collect()
+}
+
+/// The file name of a path, used to label an image; falls back to the complete path.
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
 }
 
 /// Create a reader which will be used to load the image.
@@ -95,8 +577,9 @@ fn mk_reader(matches: &ArgMatches) -> Result<Box<dyn Read>, String> {
     } else {
         if atty::is(atty::Stream::Stdin) {
             return Err(
-                "An input image should be given by providing a path using the input argument or by \
-                piping an image to the stdin.".to_string(),
+                "An input image should be given by providing a path using the input argument, by \
+                piping an image to the stdin or by generating one using the generate argument."
+                    .to_string(),
             );
         }
         sic_io::load::stdin_reader()?
@@ -127,3 +610,8 @@ pub fn run_display_licenses(config: &Config) -> Result<(), String> {
         .ok_or_else(|| "Unable to display license texts".to_string())
         .and_then(|license_text| license_text.print())
 }
+
+pub fn run_display_formats() -> Result<(), String> {
+    print!("{}", formats_report());
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use sic_lib::app::cli::build_app_config;
-use sic_lib::app::procedure::{run, run_display_licenses};
+use sic_lib::app::procedure::{run, run_display_formats, run_display_licenses};
 
 fn main() -> Result<(), String> {
     let app = sic_lib::app::cli::cli();
@@ -11,6 +11,8 @@ fn main() -> Result<(), String> {
 
     if license_display {
         run_display_licenses(&configuration)
+    } else if configuration.show_formats {
+        run_display_formats()
     } else {
         run(&matches, &configuration)
     }
@@ -0,0 +1,206 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_dir(test_output_dir: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_dir);
+
+    std::fs::create_dir_all(&dir).expect("Unable to create the output directory.");
+    dir
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+// The inputs are 4x4, 8x6 and 2x2 pixels large.
+fn atlas(output: &Path, args: &[&str]) -> Result<(), String> {
+    let inputs = ["palette_4x4.png", "rainbow_8x6.bmp", "blackwhite_2x2.bmp"]
+        .iter()
+        .map(|input| setup_input_path(input))
+        .collect::<Vec<_>>();
+
+    let mut arguments = vec!["sic", "-o", path_buf_str(output), "--atlas"];
+    arguments.extend(inputs.iter().map(|input| path_buf_str(input)));
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+/// The sorted names of the files in the output directory; the directory is removed afterwards.
+fn clean_up(output_dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(output_dir)
+        .expect("Unable to read the output directory.")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    std::fs::remove_dir_all(output_dir).expect("Unable to remove directory after test.");
+
+    names.sort();
+    names
+}
+
+#[test]
+fn atlas_json() {
+    let dir = setup_output_dir("atlas_json");
+    let sheet = dir.join("sheet.png");
+
+    assert_eq!(Ok(()), atlas(&sheet, &[]));
+
+    let (width, height) = image::open(&sheet).unwrap().dimensions();
+    assert!(width.is_power_of_two() && height.is_power_of_two());
+
+    // the images are named relative to the directory which contains them
+    let descriptor = std::fs::read_to_string(dir.join("sheet.json")).unwrap();
+    assert!(descriptor.contains("\"palette_4x4.png\":"));
+    assert!(descriptor.contains("\"rainbow_8x6.bmp\":"));
+    assert!(descriptor.contains("\"blackwhite_2x2.bmp\":"));
+    assert!(descriptor.contains("\"image\": \"sheet.png\","));
+    assert!(!descriptor.contains("resources"));
+
+    assert_eq!(vec!["sheet.json", "sheet.png"], clean_up(&dir));
+}
+
+#[test]
+fn atlas_xml_with_options() {
+    let dir = setup_output_dir("atlas_xml");
+    let sheet = dir.join("sheet.png");
+
+    assert_eq!(
+        Ok(()),
+        atlas(
+            &sheet,
+            &[
+                "--atlas-descriptor",
+                "xml",
+                "--atlas-packer",
+                "skyline",
+                "--atlas-padding",
+                "0",
+                "--atlas-trim",
+                "--atlas-max-size",
+                "16",
+            ]
+        )
+    );
+
+    let descriptor = std::fs::read_to_string(dir.join("sheet.xml")).unwrap();
+    assert!(descriptor.contains("<TextureAtlas imagePath=\"sheet.png\">"));
+    assert_eq!(3, descriptor.matches("<SubTexture ").count());
+
+    assert_eq!(vec!["sheet.png", "sheet.xml"], clean_up(&dir));
+}
+
+#[test]
+fn atlas_multiple_sheets() {
+    let dir = setup_output_dir("atlas_multiple_sheets");
+    let sheet = dir.join("sheet_{sheet}.png");
+
+    // the 8x6 image fills the first sheet, when padded by 2 pixels
+    assert_eq!(Ok(()), atlas(&sheet, &["--atlas-max-size", "8"]));
+    assert_eq!(
+        vec!["sheet_0.json", "sheet_0.png", "sheet_1.json", "sheet_1.png",],
+        clean_up(&dir)
+    );
+}
+
+#[test]
+fn atlas_multiple_sheets_without_placeholder() {
+    let dir = setup_output_dir("atlas_multiple_sheets_without_placeholder");
+    let sheet = dir.join("sheet.png");
+
+    assert!(atlas(&sheet, &["--atlas-max-size", "8"]).is_err());
+    assert!(clean_up(&dir).is_empty());
+}
+
+#[test]
+fn atlas_with_image_operations() {
+    let dir = setup_output_dir("atlas_with_image_operations");
+    let sheet = dir.join("sheet.png");
+
+    assert_eq!(
+        Ok(()),
+        atlas(&sheet, &["--atlas-padding", "0", "--resize", "2", "2"])
+    );
+    // three images of 2x2 pixels, side by side
+    assert_eq!((8, 2), image::open(&sheet).unwrap().dimensions());
+
+    clean_up(&dir);
+}
+
+#[test]
+fn atlas_invalid_max_size() {
+    let dir = setup_output_dir("atlas_invalid_max_size");
+    let sheet = dir.join("sheet.png");
+
+    assert!(atlas(&sheet, &["--atlas-max-size", "100"]).is_err());
+
+    clean_up(&dir);
+}
+
+#[test]
+fn atlas_names_relative_to_common_directory() {
+    let dir = setup_output_dir("atlas_names_relative_to_common_directory");
+    let sprites = dir.join("sprites");
+    let (hero, coin) = (sprites.join("hero"), sprites.join("coin"));
+
+    std::fs::create_dir_all(&hero).unwrap();
+    std::fs::create_dir_all(&coin).unwrap();
+    std::fs::copy(setup_input_path("palette_4x4.png"), hero.join("idle.png")).unwrap();
+    std::fs::copy(setup_input_path("bwlines.png"), coin.join("idle.png")).unwrap();
+
+    let sheet = dir.join("sheet.png");
+    let matches = get_app().get_matches_from(vec![
+        "sic",
+        "-o",
+        path_buf_str(&sheet),
+        "--atlas",
+        path_buf_str(&hero.join("idle.png")),
+        path_buf_str(&coin.join("idle.png")),
+    ]);
+    let config = build_app_config(&matches).unwrap();
+
+    assert_eq!(Ok(()), run(&matches, &config));
+
+    let descriptor = std::fs::read_to_string(dir.join("sheet.json")).unwrap();
+    assert!(descriptor.contains("{\"frames\": {\n\"hero/idle.png\":\n"));
+    assert!(descriptor.contains("\n\"coin/idle.png\":\n"));
+    assert!(!descriptor.contains("sprites"));
+
+    clean_up(&dir);
+}
+
+#[test]
+fn atlas_duplicate_names() {
+    let dir = setup_output_dir("atlas_duplicate_names");
+    let sheet = dir.join("sheet.png");
+    let input = setup_input_path("palette_4x4.png");
+
+    let matches = get_app().get_matches_from(vec![
+        "sic",
+        "-o",
+        path_buf_str(&sheet),
+        "--atlas",
+        path_buf_str(&input),
+        path_buf_str(&input),
+    ]);
+    let config = build_app_config(&matches).unwrap();
+
+    assert!(run(&matches, &config).is_err());
+    assert!(clean_up(&dir).is_empty());
+}
@@ -743,3 +743,707 @@ fn convert_jpeg_quality_different() {
     clean_up_output_path(path_buf_str(&out1));
     clean_up_output_path(path_buf_str(&out2));
 }
+
+// TIFF, TGA and farbfeld are encoded by sic itself
+
+#[test]
+fn convert_to_tiff_by_extension() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_03.tif");
+
+    let args = vec!["sic", path_buf_str(&our_input), path_buf_str(&our_output)];
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+    assert!(our_output.exists());
+    assert!(is_image_format(
+        path_buf_str(&our_output),
+        image::ImageFormat::TIFF
+    ));
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_tiff_compression_different() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let out1 = setup_output_path("out_03_tiff_none.tiff");
+    let out2 = setup_output_path("out_03_tiff_lzw.tiff");
+
+    let args1 = vec!["sic", path_buf_str(&our_input), path_buf_str(&out1)];
+    let args2 = vec![
+        "sic",
+        "--tiff-compression",
+        "lzw",
+        path_buf_str(&our_input),
+        path_buf_str(&out2),
+    ];
+
+    let matches1 = get_app().get_matches_from(args1);
+    let complete1 = run(&matches1, &build_app_config(&matches1).unwrap());
+
+    let matches2 = get_app().get_matches_from(args2);
+    let complete2 = run(&matches2, &build_app_config(&matches2).unwrap());
+
+    assert_eq!((Ok(()), Ok(())), (complete1, complete2));
+
+    let contents1 = read_file_to_bytes(path_buf_str(&out1));
+    let contents2 = read_file_to_bytes(path_buf_str(&out2));
+    assert_ne!(contents1, contents2);
+
+    // both decode to the same pixels
+    let decoded1 = image::load_from_memory_with_format(&contents1, image::ImageFormat::TIFF);
+    let decoded2 = image::load_from_memory_with_format(&contents2, image::ImageFormat::TIFF);
+    assert_eq!(
+        decoded1.unwrap().raw_pixels(),
+        decoded2.unwrap().raw_pixels()
+    );
+
+    clean_up_output_path(path_buf_str(&out1));
+    clean_up_output_path(path_buf_str(&out2));
+}
+
+#[test]
+fn convert_to_tga_by_ff_rle() {
+    let which = "tga";
+
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let our_output = setup_output_path(&format!("out_03_{}", which));
+
+    let args = vec![
+        "sic",
+        "--tga-encoding-rle",
+        "--output-format",
+        which,
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    // run-length encoded true color image
+    assert_eq!(10, contents[2]);
+
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::TGA);
+    let expected = image::open(&our_input).unwrap();
+    assert_eq!(
+        expected.to_rgb().into_raw(),
+        decoded.unwrap().to_rgb().into_raw()
+    );
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_farbfeld_by_extension() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_03.ff");
+
+    let args = vec!["sic", path_buf_str(&our_input), path_buf_str(&our_output)];
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert_eq!(b"farbfeld", &contents[..8]);
+    // a 4x4 image, with 8 bytes per pixel
+    assert_eq!(16 + 4 * 4 * 8, contents.len());
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_read_only_format() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_04.webp");
+
+    let args = vec!["sic", path_buf_str(&our_input), path_buf_str(&our_output)];
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert!(complete.unwrap_err().contains("can only be read"));
+    assert!(!our_output.exists());
+}
+
+#[test]
+fn convert_to_raw_by_ff() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let our_output = setup_output_path("out_05_raw");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "raw",
+        "--raw-color-type",
+        "rgb",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // only the pixels, without a header
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    let expected = image::open(&our_input).unwrap();
+    assert_eq!(8 * 6 * 3, contents.len());
+    assert_eq!(expected.to_rgb().into_raw(), contents);
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_raw_sixteen_bits() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_05.raw");
+
+    let args = vec![
+        "sic",
+        "--raw-color-type",
+        "gray",
+        "--raw-sample-depth",
+        "16",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert_eq!(4 * 4 * 2, contents.len());
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_data_uri() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_06_data_uri.txt");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "png",
+        "--output-text",
+        "data-uri",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // the PNG signature, base64 encoded
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    let text = std::str::from_utf8(&contents).unwrap();
+    assert!(text.starts_with("data:image/png;base64,iVBORw0KGgo"));
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_base64() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_06_base64.bmp");
+
+    let args = vec![
+        "sic",
+        "--output-text",
+        "base64",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // 'BM', base64 encoded
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert!(contents.starts_with(b"Qk"));
+    assert_eq!(0, contents.len() % 4);
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_same_format() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_07_same.bmp");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "same",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+    // the extension of the output path is ignored
+    assert!(is_image_format(
+        path_buf_str(&our_output),
+        image::ImageFormat::PNG
+    ));
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_to_same_format_read_only() {
+    let our_input = setup_input_path("gray_20x12.webp");
+    let our_output = setup_output_path("out_07_same.webp");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "same",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert!(complete.unwrap_err().contains("can only be read"));
+}
+
+#[test]
+fn convert_to_same_format_generated() {
+    let our_output = setup_output_path("out_07_same_generated.png");
+
+    let args = vec![
+        "sic",
+        "--generate",
+        "canvas 2 2 red",
+        "--output-format",
+        "same",
+        "--output",
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert!(complete.unwrap_err().contains("same as the input format"));
+    assert!(!our_output.exists());
+}
+
+// PNG is encoded by sic itself as well
+
+#[test]
+fn convert_png_filter_and_compression_different() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let out1 = setup_output_path("out_08_png_default.png");
+    let out2 = setup_output_path("out_08_png_paeth.png");
+
+    let args1 = vec!["sic", path_buf_str(&our_input), path_buf_str(&out1)];
+    let args2 = vec![
+        "sic",
+        "--png-filter",
+        "paeth",
+        "--png-compression",
+        "fast",
+        path_buf_str(&our_input),
+        path_buf_str(&out2),
+    ];
+
+    let matches1 = get_app().get_matches_from(args1);
+    let complete1 = run(&matches1, &build_app_config(&matches1).unwrap());
+
+    let matches2 = get_app().get_matches_from(args2);
+    let complete2 = run(&matches2, &build_app_config(&matches2).unwrap());
+
+    assert_eq!((Ok(()), Ok(())), (complete1, complete2));
+
+    let contents1 = read_file_to_bytes(path_buf_str(&out1));
+    let contents2 = read_file_to_bytes(path_buf_str(&out2));
+    assert_ne!(contents1, contents2);
+
+    // both decode to the same pixels
+    let decoded1 = image::load_from_memory_with_format(&contents1, image::ImageFormat::PNG);
+    let decoded2 = image::load_from_memory_with_format(&contents2, image::ImageFormat::PNG);
+    assert_eq!(
+        decoded1.unwrap().raw_pixels(),
+        decoded2.unwrap().raw_pixels()
+    );
+
+    clean_up_output_path(path_buf_str(&out1));
+    clean_up_output_path(path_buf_str(&out2));
+}
+
+#[test]
+fn convert_png_palette() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_08_png_palette.png");
+
+    let args = vec![
+        "sic",
+        "--png-palette",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // an indexed image
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert_eq!(3, contents[25]);
+
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::PNG);
+    let expected = image::open(&our_input).unwrap();
+    assert_eq!(
+        expected.to_rgba().into_raw(),
+        decoded.unwrap().to_rgba().into_raw()
+    );
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_png_sixteen_bit() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_08_png_sixteen_bit.png");
+
+    let args = vec![
+        "sic",
+        "--png-sixteen-bit",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert_eq!(16, contents[24]);
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_jpeg_progressive() {
+    let our_input = setup_input_path("unsplash_763569_cropped.jpg");
+    let our_output = setup_output_path("out_08_jpeg_progressive.jpg");
+
+    let args = vec![
+        "sic",
+        "--jpeg-progressive",
+        "--jpeg-chroma-subsampling",
+        "4:2:0",
+        "--jpeg-optimize-huffman",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // a progressive frame header
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert!(contents.windows(2).any(|marker| marker == [0xff, 0xc2]));
+
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::JPEG);
+    let expected = image::open(&our_input).unwrap();
+    assert_eq!(
+        expected.to_rgb().dimensions(),
+        decoded.unwrap().to_rgb().dimensions()
+    );
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_jpeg_grayscale_with_restart_interval() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let our_output = setup_output_path("out_08_jpeg_grayscale.jpg");
+
+    let args = vec![
+        "sic",
+        "--jpeg-grayscale",
+        "--jpeg-restart-interval",
+        "1",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::JPEG);
+    match decoded.unwrap() {
+        image::DynamicImage::ImageLuma8(_) => {}
+        _ => panic!("Expected a grayscale image."),
+    }
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_jpeg_invalid_restart_interval() {
+    let args = vec![
+        "sic",
+        "--jpeg-restart-interval",
+        "many",
+        "in.png",
+        "out.jpg",
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    assert!(build_app_config(&matches).is_err());
+}
+
+#[test]
+fn convert_max_output_size() {
+    let our_input = setup_input_path("unsplash_763569_cropped.jpg");
+    let our_output = setup_output_path("out_08_max_output_size.jpg");
+
+    let args = vec![
+        "sic",
+        "--max-output-size",
+        "10KB",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert!(contents.len() <= 10_000);
+
+    // the image isn't scaled down
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::JPEG);
+    let expected = image::open(&our_input).unwrap();
+    assert_eq!(
+        expected.to_rgb().dimensions(),
+        decoded.unwrap().to_rgb().dimensions()
+    );
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_max_output_size_downscale() {
+    let our_input = setup_input_path("unsplash_763569_cropped.jpg");
+    let our_output = setup_output_path("out_08_max_output_size_downscale.png");
+
+    let args = vec![
+        "sic",
+        "--max-output-size",
+        "20KiB",
+        "--max-output-size-downscale",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert!(contents.len() <= 20 * 1024);
+
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::PNG);
+    let expected = image::open(&our_input).unwrap();
+    assert!(decoded.unwrap().to_rgb().width() < expected.to_rgb().width());
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_max_output_size_does_not_fit() {
+    let our_input = setup_input_path("unsplash_763569_cropped.jpg");
+    let our_output = setup_output_path("out_08_max_output_size_does_not_fit.png");
+
+    let args = vec![
+        "sic",
+        "--max-output-size",
+        "1KB",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert!(complete
+        .unwrap_err()
+        .starts_with("Unable to encode the image"));
+    assert!(!our_output.exists());
+}
+
+#[test]
+fn convert_auto_format_lossless() {
+    let our_input = setup_input_path("palette_4x4.png");
+    let our_output = setup_output_path("out_08_auto_format.jpg");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "auto",
+        "--auto-format-lossless",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // the extension is replaced by the extension of the chosen (lossless) format
+    assert!(!our_output.exists());
+    let written = ["png", "tiff", "tga", "bmp"]
+        .iter()
+        .map(|ext| our_output.with_extension(ext))
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    assert_eq!(1, written.len());
+
+    let decoded = image::open(&written[0]).unwrap();
+    let expected = image::open(&our_input).unwrap();
+    assert_eq!(expected.to_rgba().into_raw(), decoded.to_rgba().into_raw());
+
+    clean_up_output_path(path_buf_str(&written[0]));
+}
+
+#[test]
+fn convert_auto_format_photo() {
+    let our_input = setup_input_path("unsplash_763569_cropped.jpg");
+    let our_output = setup_output_path("out_08_auto_format_photo.jpg");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "auto",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // a photo is smallest as JPEG, so the output path is kept
+    assert!(is_image_format(
+        path_buf_str(&our_output),
+        image::ImageFormat::JPEG
+    ));
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_auto_format_transparent_photo() {
+    let our_input = setup_input_path("unsplash_763569_cropped.jpg");
+    let our_output = setup_output_path("out_08_auto_format_transparent.jpg");
+
+    let args = vec![
+        "sic",
+        "--output-format",
+        "auto",
+        "--circle-crop",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // JPEG can't store the transparent corners, so another format is chosen
+    assert!(!our_output.exists());
+    let written = ["png", "tiff", "tga", "bmp"]
+        .iter()
+        .map(|ext| our_output.with_extension(ext))
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    assert_eq!(1, written.len());
+
+    let decoded = image::open(&written[0]).unwrap().to_rgba();
+    assert_eq!(0, decoded.get_pixel(0, 0)[3]);
+
+    clean_up_output_path(path_buf_str(&written[0]));
+}
+
+#[test]
+fn convert_ico_sizes() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let our_output = setup_output_path("out_08_ico_sizes.ico");
+
+    let args = vec![
+        "sic",
+        "--ico-sizes",
+        "16,32",
+        "--set-resize-sampling-filter",
+        "nearest",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let complete = run(&matches, &build_app_config(&matches).unwrap());
+
+    assert_eq!(Ok(()), complete);
+
+    // the directory has an entry for each size; the largest one is decoded
+    let contents = read_file_to_bytes(path_buf_str(&our_output));
+    assert_eq!(&[0, 0, 1, 0, 2, 0], &contents[..6]);
+    assert_eq!((16, 16), (contents[6], contents[7]));
+    assert_eq!((32, 32), (contents[22], contents[23]));
+
+    let decoded = image::load_from_memory_with_format(&contents, image::ImageFormat::ICO);
+    assert_eq!((32, 32), decoded.unwrap().to_rgba().dimensions());
+
+    clean_up_output_path(path_buf_str(&our_output));
+}
+
+#[test]
+fn convert_ico_invalid_size() {
+    let our_input = setup_input_path("rainbow_8x6.bmp");
+    let our_output = setup_output_path("out_08_ico_invalid_size.ico");
+
+    let args = vec![
+        "sic",
+        "--ico-sizes",
+        "512",
+        path_buf_str(&our_input),
+        path_buf_str(&our_output),
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    assert!(build_app_config(&matches).is_err());
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_dir(test_output_dir: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_dir)
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+// The input is 8x6 pixels large.
+fn favicon(output: &Path, args: &[&str]) -> Result<(), String> {
+    let input = setup_input_path("rainbow_8x6.bmp");
+
+    let mut arguments = vec![
+        "sic",
+        "-i",
+        path_buf_str(&input),
+        "-o",
+        path_buf_str(output),
+        "--favicon",
+    ];
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+/// The sorted names of the files in the output directory; the directory is removed afterwards.
+fn clean_up(output_dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(output_dir)
+        .expect("Unable to read the output directory.")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    std::fs::remove_dir_all(output_dir).expect("Unable to remove directory after test.");
+
+    names.sort();
+    names
+}
+
+#[test]
+fn favicon_pack() {
+    let dir = setup_output_dir("favicon_pack");
+
+    assert_eq!(Ok(()), favicon(&dir, &[]));
+
+    // the ICO file contains the image at its own size, since the default sizes are larger
+    assert_eq!(
+        (8, 8),
+        image::open(dir.join("favicon.ico")).unwrap().dimensions()
+    );
+    assert_eq!(
+        (180, 180),
+        image::open(dir.join("apple-touch-icon.png"))
+            .unwrap()
+            .dimensions()
+    );
+    assert_eq!(
+        (512, 512),
+        image::open(dir.join("android-chrome-512x512.png"))
+            .unwrap()
+            .dimensions()
+    );
+
+    let manifest = std::fs::read_to_string(dir.join("site.webmanifest")).unwrap();
+    assert!(manifest.contains(
+        "{\"src\": \"android-chrome-192x192.png\", \"sizes\": \"192x192\", \"type\": \"image/png\"}"
+    ));
+
+    assert_eq!(
+        vec![
+            "android-chrome-192x192.png",
+            "android-chrome-512x512.png",
+            "apple-touch-icon.png",
+            "favicon.ico",
+            "site.webmanifest"
+        ],
+        clean_up(&dir)
+    );
+}
+
+#[test]
+fn favicon_ico_sizes() {
+    let dir = setup_output_dir("favicon_ico_sizes");
+
+    assert_eq!(
+        Ok(()),
+        favicon(
+            &dir,
+            &[
+                "--ico-sizes",
+                "16,48",
+                "--set-resize-sampling-filter",
+                "lanczos3"
+            ]
+        )
+    );
+
+    let ico = std::fs::read(dir.join("favicon.ico")).unwrap();
+    assert_eq!(2, ico[4]);
+    assert_eq!(
+        (48, 48),
+        image::load_from_memory(&ico).unwrap().dimensions()
+    );
+
+    // the input is wider than it is high, so the Apple touch icon has a white border
+    let apple_touch_icon = image::open(dir.join("apple-touch-icon.png")).unwrap();
+    assert_eq!(
+        image::Rgba([255, 255, 255, 255]),
+        apple_touch_icon.get_pixel(90, 0)
+    );
+
+    assert_eq!(5, clean_up(&dir).len());
+}
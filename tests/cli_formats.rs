@@ -0,0 +1,21 @@
+use std::process::{Command, Output};
+
+fn run_list_formats_command() -> Output {
+    Command::new("cargo")
+        .args(&["run", "--", "--list-formats"])
+        .output()
+        .expect("Running test failed")
+}
+
+#[test]
+fn cli_list_formats() {
+    let res = run_list_formats_command();
+
+    assert!(res.status.success());
+
+    let report = std::str::from_utf8(&res.stdout).unwrap();
+    assert!(report.starts_with("format"));
+    assert!(report
+        .lines()
+        .any(|line| line.starts_with("WebP") && line.ends_with("yes   no")));
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_output_path(test_output_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_path)
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+fn clean_up_output_path(test_output_path: &str) {
+    std::fs::remove_file(setup_output_path(test_output_path))
+        .expect("Unable to remove file after test.");
+}
+
+fn generate(spec: &str, output: &str, extra_args: &[&str]) -> Result<(), String> {
+    let our_output = setup_output_path(output);
+
+    let mut args = vec!["sic", "--generate", spec, "-o", path_buf_str(&our_output)];
+    args.extend(extra_args);
+
+    let matches = get_app().get_matches_from(args);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+fn generated_dimensions(output: &str) -> (u32, u32) {
+    let our_output = setup_output_path(output);
+    let dimensions = image::open(&our_output)
+        .expect("Failed to open the generated image.")
+        .dimensions();
+
+    clean_up_output_path(path_buf_str(&our_output));
+
+    dimensions
+}
+
+#[test]
+fn generate_canvas() {
+    let output = "gen_canvas.png";
+
+    assert_eq!(Ok(()), generate("canvas 12 7 #ff000080", output, &[]));
+    assert_eq!((12, 7), generated_dimensions(output));
+}
+
+#[test]
+fn generate_checkerboard() {
+    let output = "gen_checkerboard.png";
+
+    assert_eq!(
+        Ok(()),
+        generate("checkerboard 12 7 3 white black", output, &[])
+    );
+    assert_eq!((12, 7), generated_dimensions(output));
+}
+
+#[test]
+fn generate_gradient_linear() {
+    let output = "gen_gradient_linear.png";
+
+    assert_eq!(
+        Ok(()),
+        generate("gradient linear 12 7 red blue 45", output, &[])
+    );
+    assert_eq!((12, 7), generated_dimensions(output));
+}
+
+#[test]
+fn generate_gradient_radial() {
+    let output = "gen_gradient_radial.png";
+
+    assert_eq!(
+        Ok(()),
+        generate("gradient radial 12 7 white black", output, &[])
+    );
+    assert_eq!((12, 7), generated_dimensions(output));
+}
+
+#[test]
+fn generate_noise() {
+    let output = "gen_noise.png";
+
+    assert_eq!(Ok(()), generate("noise value 12 7 4 42", output, &[]));
+    assert_eq!((12, 7), generated_dimensions(output));
+}
+
+#[test]
+fn generate_with_image_operations() {
+    let output = "gen_with_ops.png";
+
+    assert_eq!(
+        Ok(()),
+        generate("canvas 12 7 white", output, &["--resize", "6", "3"])
+    );
+    assert_eq!((6, 3), generated_dimensions(output));
+}
+
+#[test]
+fn generate_invalid_spec() {
+    let matches = get_app().get_matches_from(vec!["sic", "--generate", "canvas 12 red"]);
+
+    assert!(build_app_config(&matches).is_err());
+}
+
+#[test]
+fn generate_empty_canvas() {
+    let output = "gen_empty.png";
+
+    assert!(generate("canvas 0 7 red", output, &[]).is_err());
+    assert!(!setup_output_path(output).exists());
+}
@@ -4,6 +4,73 @@ pub mod common;
 // The following integration test modules currently only check whether the processes they start exit
 // successfully.
 
+#[cfg(test)]
+mod append {
+    use crate::common::*;
+
+    #[test]
+    fn append() {
+        let other = setup_input_path("palette_4x4.png");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_append1.png",
+            &format!("--append x {}", other.to_str().unwrap()),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn append_with_modifiers() {
+        let other = setup_input_path("palette_4x4.png");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_append2.png",
+            &format!(
+                "--set-append-alignment end --set-append-background #ffffff --append y {}",
+                other.to_str().unwrap()
+            ),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn append_not() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_append3.png",
+            "--append x does_not_exist.png",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod auto_levels {
+    use crate::common::*;
+
+    #[test]
+    fn auto_levels() {
+        let mut process = command(DEFAULT_IN, "cio_auto_levels1.png", "--auto-levels 0.5 1");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn auto_levels_not() {
+        let mut process = command(DEFAULT_IN, "cio_auto_levels2.png", "--auto-levels 60 60");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
 #[cfg(test)]
 mod blur {
     use crate::common::*;
@@ -40,6 +107,89 @@ mod blur {
         assert!(result.is_ok());
         assert!(result.unwrap().success());
     }
+
+    #[test]
+    fn blur_region() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_blur_region1.png",
+            "--blur-region 1 2 1 4 4",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn blur_region_zero_sigma() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_blur_region2.png",
+            "--blur-region 0 2 1 4 4",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+
+    #[test]
+    fn blur_regions() {
+        let regions = setup_input_path("regions.json");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_blur_regions1.png",
+            &format!("--blur-regions 1 {}", regions.to_str().unwrap()),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn blur_regions_not() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_blur_regions2.png",
+            "--blur-regions 1 does_not_exist.json",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod circle_crop {
+    use crate::common::*;
+
+    #[test]
+    fn circle_crop() {
+        let mut process = command(DEFAULT_IN, "cio_circle_crop.png", "--circle-crop");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod clahe {
+    use crate::common::*;
+
+    #[test]
+    fn clahe() {
+        let mut process = command(DEFAULT_IN, "cio_clahe1.png", "--clahe 2 2.0");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn clahe_not() {
+        let mut process = command(DEFAULT_IN, "cio_clahe2.png", "--clahe 0 2.0");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +280,134 @@ mod crop {
     }
 }
 
+#[cfg(test)]
+mod curves {
+    use crate::common::*;
+
+    #[test]
+    fn curves() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_curves1.png",
+            "--curves rgb 0:0,64:90,255:255 --curves blue 0:10,255:240",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn curves_not() {
+        let mut process = command(DEFAULT_IN, "cio_curves2.png", "--curves rgb 0:0");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod draw {
+    use crate::common::*;
+
+    #[test]
+    fn draw_ellipse() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_ellipse1.png",
+            "--draw-ellipse 4 3 3 2 red",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn draw_ellipse_zero_radius() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_ellipse2.png",
+            "--draw-ellipse 4 3 0 2 red",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+
+    #[test]
+    fn draw_line() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_line1.png",
+            "--draw-line -2 0 7 5 #ffff00",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn draw_polygon() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_polygon1.png",
+            "--draw-polygon white 0:0,7:0,3:5",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn draw_polygon_too_few_points() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_polygon2.png",
+            "--draw-polygon white 0:0,7:0",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+
+    #[test]
+    fn draw_rect_with_modifiers() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_rect1.png",
+            "--set-draw-antialias true --set-draw-fill #0000ff80 --set-draw-stroke-width 2 \
+             --draw-rect 1 1 5 4 black",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn draw_rect_invalid_color() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_draw_rect2.png",
+            "--draw-rect 1 1 5 4 purplish",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod equalize {
+    use crate::common::*;
+
+    #[test]
+    fn equalize() {
+        let mut process = command(DEFAULT_IN, "cio_equalize.png", "--equalize");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
 #[cfg(test)]
 mod filter3x3 {
     use crate::common::*;
@@ -232,6 +510,119 @@ mod invert {
     }
 }
 
+#[cfg(test)]
+mod levels {
+    use crate::common::*;
+
+    #[test]
+    fn levels() {
+        let mut process = command(DEFAULT_IN, "cio_levels1.png", "--levels 10 240 1.2 0 255");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn levels_not() {
+        let mut process = command(DEFAULT_IN, "cio_levels2.png", "--levels 10 256 1.2 0 255");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod match_histogram {
+    use crate::common::*;
+
+    #[test]
+    fn match_histogram() {
+        let reference = setup_input_path("palette_4x4.png");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_match_histogram1.png",
+            &format!("--match-histogram {}", reference.to_str().unwrap()),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn match_histogram_reinhard() {
+        let reference = setup_input_path("palette_4x4.png");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_match_histogram2.png",
+            &format!(
+                "--set-match-histogram-mode reinhard --match-histogram {}",
+                reference.to_str().unwrap()
+            ),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn match_histogram_not() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_match_histogram3.png",
+            "--match-histogram does_not_exist.png",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod pixelate {
+    use crate::common::*;
+
+    #[test]
+    fn pixelate() {
+        let mut process = command(DEFAULT_IN, "cio_pixelate1.png", "--pixelate 3");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn pixelate_zero() {
+        let mut process = command(DEFAULT_IN, "cio_pixelate2.png", "--pixelate 0");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+
+    #[test]
+    fn pixelate_region() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_pixelate_region1.png",
+            "--pixelate-region 2 1 1 6 4",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn pixelate_regions() {
+        let regions = setup_input_path("regions.csv");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_pixelate_regions1.png",
+            &format!("--pixelate-regions 2 {}", regions.to_str().unwrap()),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+}
+
 #[cfg(test)]
 mod resize {
     use crate::common::*;
@@ -292,6 +683,98 @@ mod rotate270 {
     }
 }
 
+#[cfg(test)]
+mod round_corners {
+    use crate::common::*;
+
+    #[test]
+    fn round_corners() {
+        let mut process = command(DEFAULT_IN, "cio_round_corners1.png", "--round-corners 3");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn round_corners_not() {
+        let mut process = command(DEFAULT_IN, "cio_round_corners2.png", "--round-corners -3");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod shadow {
+    use crate::common::*;
+
+    #[test]
+    fn shadow() {
+        let mut process = command(DEFAULT_IN, "cio_shadow1.png", "--shadow -2 3 1.5 #00000080");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn shadow_named_color() {
+        let mut process = command(DEFAULT_IN, "cio_shadow2.png", "--shadow 2 2 0 black");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn shadow_not() {
+        let mut process = command(DEFAULT_IN, "cio_shadow3.png", "--shadow 2 2 1 #0000000");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
+#[cfg(test)]
+mod text {
+    use crate::common::*;
+
+    #[test]
+    fn text() {
+        let mut process = command(DEFAULT_IN, "cio_text1.png", "--text sic -1 -2");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn text_with_modifiers() {
+        let font = setup_input_path("square_font.ttf");
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_text2.png",
+            &format!(
+                "--set-text-font {} --set-text-size 4 --set-text-color white \
+                 --set-text-gravity center --text AB 0 0",
+                font.to_str().unwrap()
+            ),
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn text_font_not_found() {
+        let mut process = command(
+            DEFAULT_IN,
+            "cio_text3.png",
+            "--set-text-font does_not_exist.ttf --text sic 0 0",
+        );
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
 #[cfg(test)]
 mod unsharpen {
     use crate::common::*;
@@ -313,6 +796,27 @@ mod unsharpen {
     }
 }
 
+#[cfg(test)]
+mod vignette {
+    use crate::common::*;
+
+    #[test]
+    fn vignette() {
+        let mut process = command(DEFAULT_IN, "cio_vignette1.png", "--vignette 0.8 0.25");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert!(result.unwrap().success());
+    }
+
+    #[test]
+    fn vignette_not() {
+        let mut process = command(DEFAULT_IN, "cio_vignette2.png", "--vignette 1.5 0.25");
+        let result = process.wait();
+        assert!(result.is_ok());
+        assert_not!(result.unwrap().success());
+    }
+}
+
 #[cfg(test)]
 mod mixed {
     use crate::common::*;
@@ -322,21 +826,35 @@ mod mixed {
         let mut process = command(
             DEFAULT_IN,
             "img_op_arg_mixed_all.png",
-            "--blur 1 \
+            "--auto-levels 0.5 0.5 \
+             --blur 1 \
+             --blur-region 1 0 0 4 4 \
              --brighten 2 \
+             --circle-crop \
+             --clahe 2 2.0 \
              --contrast 3 \
              --crop 0 0 2 2 \
+             --curves rgb 0:0,64:90,255:255 \
+             --draw-line 0 0 7 5 red \
+             --equalize \
              --filter3x3 0 1 2 3 4 5 6 7 8 \
              --flip-horizontal \
              --flip-vertical \
              --grayscale \
              --hue-rotate -90 \
              --invert \
+             --levels 10 240 1.2 0 255 \
+             --pixelate 2 \
+             --pixelate-region 2 1 1 4 4 \
              --resize 10 10 \
              --rotate90 \
              --rotate180 \
              --rotate270 \
-             --unsharpen 1.5 1",
+             --round-corners 2 \
+             --shadow 1 1 0.5 #00000080 \
+             --text sic 0 0 \
+             --unsharpen 1.5 1 \
+             --vignette 0.5 0.5",
         );
         let result = process.wait();
         assert!(result.is_ok());
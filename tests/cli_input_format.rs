@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_path(test_output_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_path)
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+fn convert(input: &Path, output: &Path, args: &[&str]) -> Result<(), String> {
+    let mut arguments = vec!["sic", "-i", path_buf_str(input), "-o", path_buf_str(output)];
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+/// Writes the raw pixel data to a file, which is removed by `clean_up`.
+fn setup_raw_input(name: &str, pixels: &[u8]) -> PathBuf {
+    let path = setup_output_path(name);
+    std::fs::write(&path, pixels).expect("Unable to write the raw input.");
+    path
+}
+
+fn clean_up(paths: &[&Path]) {
+    for path in paths {
+        std::fs::remove_file(path).expect("Unable to remove file after test.");
+    }
+}
+
+#[test]
+fn raw_rgba_input() {
+    let input = setup_raw_input(
+        "input_format_rgba.raw",
+        &[255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 128, 9, 9, 9, 0],
+    );
+    let output = setup_output_path("input_format_rgba.png");
+
+    let args = ["--input-format", "raw", "--raw-size", "2", "2"];
+    assert_eq!(Ok(()), convert(&input, &output, &args));
+
+    let image = image::open(&output).unwrap();
+    assert_eq!((2, 2), image.dimensions());
+    assert_eq!([0, 0, 255, 128], image.get_pixel(0, 1).0);
+
+    clean_up(&[&input, &output]);
+}
+
+#[test]
+fn raw_gray_sixteen_bits_big_endian_input() {
+    let input = setup_raw_input(
+        "input_format_gray16.raw",
+        &[0xff, 0xff, 0x00, 0x00, 0x80, 0x80],
+    );
+    let output = setup_output_path("input_format_gray16.png");
+
+    let args = [
+        "--input-format",
+        "raw",
+        "--raw-size",
+        "3",
+        "1",
+        "--raw-color-type",
+        "gray",
+        "--raw-sample-depth",
+        "16",
+        "--raw-byte-order",
+        "big",
+    ];
+    assert_eq!(Ok(()), convert(&input, &output, &args));
+
+    let image = image::open(&output).unwrap();
+    assert_eq!(vec![255, 0, 128], image.to_luma().into_raw());
+
+    clean_up(&[&input, &output]);
+}
+
+#[test]
+fn raw_input_size_mismatch() {
+    let input = setup_raw_input("input_format_mismatch.raw", &[0; 15]);
+    let output = setup_output_path("input_format_mismatch.png");
+
+    let args = ["--input-format", "raw", "--raw-size", "2", "2"];
+    let result = convert(&input, &output, &args);
+
+    assert!(result.unwrap_err().contains("Expected 16 bytes"));
+    assert!(!output.exists());
+
+    clean_up(&[&input]);
+}
+
+#[test]
+fn raw_input_requires_size() {
+    let input = setup_input_path("rainbow_8x6.bmp");
+    let output = setup_output_path("input_format_no_size.png");
+
+    assert!(convert(&input, &output, &["--input-format", "raw"]).is_err());
+}
+
+#[test]
+fn explicit_input_format() {
+    let input = setup_input_path("rainbow_8x6.tga");
+    let output = setup_output_path("input_format_tga.png");
+
+    assert_eq!(Ok(()), convert(&input, &output, &["--input-format", "tga"]));
+    assert_eq!((8, 6), image::open(&output).unwrap().dimensions());
+
+    clean_up(&[&output]);
+}
+
+#[test]
+fn explicit_input_format_is_not_guessed() {
+    let input = setup_input_path("rainbow_8x6.bmp");
+    let output = setup_output_path("input_format_wrong.png");
+
+    assert!(convert(&input, &output, &["--input-format", "png"]).is_err());
+    assert!(!output.exists());
+}
@@ -95,3 +95,27 @@ fn only_o() {
     // expect a non zero exit status
     assert_not!(result.unwrap().success());
 }
+
+#[test]
+fn stdout_same_format() {
+    let input = String::from(setup_input_path("palette_4x4.png").to_str().unwrap());
+    let result = Command::new("cargo")
+        .args(&["run", "--", "-i", &input, "--output-format", "same"])
+        .output()
+        .expect("process");
+
+    assert!(result.status.success());
+    assert!(result.stdout.starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn stdout_default_format() {
+    let input = String::from(setup_input_path("palette_4x4.png").to_str().unwrap());
+    let result = Command::new("cargo")
+        .args(&["run", "--", "-i", &input])
+        .output()
+        .expect("process");
+
+    assert!(result.status.success());
+    assert!(result.stdout.starts_with(b"BM"));
+}
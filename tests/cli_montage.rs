@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_path(test_output_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_path)
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+fn montage(output: &str, args: &[&str]) -> Result<(), String> {
+    let inputs = ["palette_4x4.png", "rainbow_8x6.bmp", "loop.gif"]
+        .iter()
+        .map(|input| setup_input_path(input))
+        .collect::<Vec<_>>();
+    let our_output = setup_output_path(output);
+
+    let mut arguments = vec!["sic", "-o", path_buf_str(&our_output), "--montage"];
+    arguments.extend(inputs.iter().map(|input| path_buf_str(input)));
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+fn montage_dimensions(output: &str) -> (u32, u32) {
+    let our_output = setup_output_path(output);
+    let dimensions = image::open(&our_output)
+        .expect("Failed to open the montage.")
+        .dimensions();
+
+    std::fs::remove_file(&our_output).expect("Unable to remove file after test.");
+
+    dimensions
+}
+
+#[test]
+fn montage_defaults() {
+    let output = "montage_defaults.png";
+
+    assert_eq!(Ok(()), montage(output, &[]));
+    // two columns and rows of 128x128 cells, 4 pixels apart
+    assert_eq!(
+        (2 * 128 + 3 * 4, 2 * 128 + 3 * 4),
+        montage_dimensions(output)
+    );
+}
+
+#[test]
+fn montage_layout() {
+    let output = "montage_layout.png";
+
+    assert_eq!(
+        Ok(()),
+        montage(
+            output,
+            &[
+                "--montage-cell",
+                "16",
+                "12",
+                "--montage-columns",
+                "3",
+                "--montage-spacing",
+                "0",
+                "--montage-background",
+                "#00000000",
+            ]
+        )
+    );
+    assert_eq!((3 * 16, 12), montage_dimensions(output));
+}
+
+#[test]
+fn montage_labels() {
+    let output = "montage_labels.png";
+
+    assert_eq!(
+        Ok(()),
+        montage(
+            output,
+            &[
+                "--montage-cell",
+                "64",
+                "32",
+                "--montage-labels",
+                "--grayscale"
+            ]
+        )
+    );
+    assert_eq!(
+        (2 * 64 + 3 * 4, 2 * (32 + 8 + 4) + 3 * 4),
+        montage_dimensions(output)
+    );
+}
+
+#[test]
+fn montage_invalid_columns() {
+    let output = "montage_invalid_columns.png";
+
+    assert!(montage(output, &["--montage-columns", "0"]).is_err());
+}
+
+#[test]
+fn montage_invalid_background() {
+    let output = "montage_invalid_background.png";
+
+    assert!(montage(output, &["--montage-background", "#12"]).is_err());
+}
+
+#[test]
+fn montage_missing_input() {
+    let our_output = setup_output_path("montage_missing_input.png");
+    let args = vec![
+        "sic",
+        "-o",
+        path_buf_str(&our_output),
+        "--montage",
+        "does_not_exist.png",
+    ];
+
+    let matches = get_app().get_matches_from(args);
+    let config = build_app_config(&matches).unwrap();
+
+    assert!(run(&matches, &config).is_err());
+}
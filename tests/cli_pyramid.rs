@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_dir(test_output_dir: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_dir);
+
+    std::fs::create_dir_all(&dir).expect("Unable to create the output directory.");
+    dir
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+// The input is 8x6 pixels large.
+fn pyramid(output: &Path, args: &[&str]) -> Result<(), String> {
+    let input = setup_input_path("rainbow_8x6.bmp");
+
+    let mut arguments = vec![
+        "sic",
+        "-i",
+        path_buf_str(&input),
+        "-o",
+        path_buf_str(output),
+    ];
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+fn dimensions(path: &Path) -> (u32, u32) {
+    image::open(path).unwrap().dimensions()
+}
+
+#[test]
+fn pyramid_dzi() {
+    let dir = setup_output_dir("pyramid_dzi");
+    let descriptor = dir.join("scan.dzi");
+
+    assert_eq!(
+        Ok(()),
+        pyramid(
+            &descriptor,
+            &["--pyramid", "dzi", "--pyramid-tile-size", "4"]
+        )
+    );
+
+    let xml = std::fs::read_to_string(&descriptor).unwrap();
+    assert!(xml.contains("Format=\"jpg\" Overlap=\"1\" TileSize=\"4\">"));
+    assert!(xml.contains("<Size Width=\"8\" Height=\"6\"/>"));
+
+    // the levels are 1x1, 2x2, 4x3 and 8x6 pixels large
+    let tiles = dir.join("scan_files");
+    assert_eq!((1, 1), dimensions(&tiles.join("0/0_0.jpg")));
+    assert_eq!((4, 3), dimensions(&tiles.join("2/0_0.jpg")));
+    assert_eq!((5, 5), dimensions(&tiles.join("3/0_0.jpg")));
+    assert_eq!((5, 3), dimensions(&tiles.join("3/1_1.jpg")));
+    assert!(!tiles.join("3/2_0.jpg").exists());
+
+    std::fs::remove_dir_all(dir).expect("Unable to remove directory after test.");
+}
+
+#[test]
+fn pyramid_xyz() {
+    let dir = setup_output_dir("pyramid_xyz");
+
+    assert_eq!(
+        Ok(()),
+        pyramid(
+            &dir,
+            &[
+                "--pyramid",
+                "xyz",
+                "--pyramid-tile-size",
+                "4",
+                "--pyramid-filter",
+                "nearest"
+            ]
+        )
+    );
+
+    // the edge tiles are padded to the full tile size
+    assert_eq!((4, 4), dimensions(&dir.join("0/0/0.png")));
+    assert_eq!((4, 4), dimensions(&dir.join("1/1/1.png")));
+    assert!(!dir.join("2").exists());
+
+    std::fs::remove_dir_all(dir).expect("Unable to remove directory after test.");
+}
+
+#[test]
+fn pyramid_with_output_format() {
+    let dir = setup_output_dir("pyramid_with_output_format");
+    let descriptor = dir.join("scan.dzi");
+
+    assert_eq!(
+        Ok(()),
+        pyramid(&descriptor, &["--pyramid", "dzi", "--output-format", "png"])
+    );
+
+    let xml = std::fs::read_to_string(&descriptor).unwrap();
+    assert!(xml.contains("Format=\"png\""));
+    assert_eq!((8, 6), dimensions(&dir.join("scan_files/3/0_0.png")));
+
+    std::fs::remove_dir_all(dir).expect("Unable to remove directory after test.");
+}
+
+#[test]
+fn pyramid_invalid_tile_size() {
+    let dir = setup_output_dir("pyramid_invalid_tile_size");
+
+    assert!(pyramid(&dir, &["--pyramid", "xyz", "--pyramid-tile-size", "0"]).is_err());
+
+    std::fs::remove_dir_all(dir).expect("Unable to remove directory after test.");
+}
+
+#[test]
+fn pyramid_auto_format() {
+    let dir = setup_output_dir("pyramid_auto_format");
+    let descriptor = dir.join("scan.dzi");
+
+    let result = pyramid(
+        &descriptor,
+        &["--pyramid", "dzi", "--output-format", "auto"],
+    );
+
+    assert!(result
+        .unwrap_err()
+        .contains("can only be chosen automatically if a single image is written"));
+}
@@ -0,0 +1,172 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_dir(test_output_dir: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_dir);
+
+    std::fs::create_dir_all(&dir).expect("Unable to create the output directory.");
+    dir
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+// The input is 8x6 pixels large.
+fn responsive(output: &Path, args: &[&str]) -> Result<(), String> {
+    let input = setup_input_path("rainbow_8x6.bmp");
+
+    let mut arguments = vec![
+        "sic",
+        "-i",
+        path_buf_str(&input),
+        "-o",
+        path_buf_str(output),
+    ];
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+/// The sorted names of the files in the output directory; the directory is removed afterwards.
+fn clean_up(output_dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(output_dir)
+        .expect("Unable to read the output directory.")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    std::fs::remove_dir_all(output_dir).expect("Unable to remove directory after test.");
+
+    names.sort();
+    names
+}
+
+#[test]
+fn responsive_formats_with_json_manifest() {
+    let dir = setup_output_dir("responsive_json");
+    let template = dir.join("hero-{width}.png");
+
+    assert_eq!(
+        Ok(()),
+        responsive(
+            &template,
+            &[
+                "--responsive",
+                "4",
+                "8",
+                "16",
+                "--responsive-formats",
+                "png",
+                "jpg"
+            ]
+        )
+    );
+
+    assert_eq!(
+        (4, 3),
+        image::open(dir.join("hero-4.jpg")).unwrap().dimensions()
+    );
+    assert_eq!(
+        (8, 6),
+        image::open(dir.join("hero-8.png")).unwrap().dimensions()
+    );
+
+    let bytes = std::fs::metadata(dir.join("hero-4.png")).unwrap().len();
+    let manifest = std::fs::read_to_string(dir.join("hero-srcset.json")).unwrap();
+    assert!(manifest.contains(&format!(
+        "{{\"path\": \"hero-4.png\", \"format\": \"png\", \"width\": 4, \"height\": 3, \"bytes\": {}}}",
+        bytes
+    )));
+    assert!(manifest.contains("\"jpg\": \"hero-4.jpg 4w, hero-8.jpg 8w\""));
+
+    // the 16 pixels wide image is skipped, since the input is only 8 pixels wide
+    assert_eq!(
+        vec![
+            "hero-4.jpg",
+            "hero-4.png",
+            "hero-8.jpg",
+            "hero-8.png",
+            "hero-srcset.json"
+        ],
+        clean_up(&dir)
+    );
+}
+
+#[test]
+fn responsive_html_manifest() {
+    let dir = setup_output_dir("responsive_html");
+    let template = dir.join("hero_{width}w.jpg");
+    let manifest = dir.join("hero.html");
+
+    assert_eq!(
+        Ok(()),
+        responsive(
+            &template,
+            &[
+                "--responsive",
+                "2",
+                "8",
+                "--responsive-manifest",
+                path_buf_str(&manifest)
+            ]
+        )
+    );
+
+    let html = std::fs::read_to_string(&manifest).unwrap();
+    assert!(html.contains("<img src=\"hero_8w.jpg\" srcset=\"hero_2w.jpg 2w, hero_8w.jpg 8w\""));
+
+    assert_eq!(
+        vec!["hero.html", "hero_2w.jpg", "hero_8w.jpg"],
+        clean_up(&dir)
+    );
+}
+
+#[test]
+fn responsive_without_placeholder() {
+    let dir = setup_output_dir("responsive_without_placeholder");
+
+    assert!(responsive(&dir.join("hero.png"), &["--responsive", "4"]).is_err());
+    assert!(clean_up(&dir).is_empty());
+}
+
+#[test]
+fn responsive_all_widths_too_large() {
+    let dir = setup_output_dir("responsive_all_widths_too_large");
+
+    assert!(responsive(&dir.join("hero-{width}.png"), &["--responsive", "100"]).is_err());
+    assert!(clean_up(&dir).is_empty());
+}
+
+#[test]
+fn responsive_unknown_manifest_format() {
+    let dir = setup_output_dir("responsive_unknown_manifest_format");
+    let manifest = dir.join("hero.txt");
+
+    assert!(responsive(
+        &dir.join("hero-{width}.png"),
+        &[
+            "--responsive",
+            "4",
+            "--responsive-manifest",
+            path_buf_str(&manifest)
+        ]
+    )
+    .is_err());
+    assert!(clean_up(&dir).is_empty());
+}
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use sic_core::image;
+use sic_core::image::GenericImageView;
+
+use sic_lib::app::cli::{build_app_config, cli as get_app};
+use sic_lib::app::procedure::run;
+
+fn setup_input_path(test_image_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(test_image_path)
+}
+
+fn setup_output_dir(test_output_dir: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join(test_output_dir);
+
+    std::fs::create_dir_all(&dir).expect("Unable to create the output directory.");
+    dir
+}
+
+fn path_buf_str(pb: &Path) -> &str {
+    pb.to_str().unwrap()
+}
+
+// The rainbow test image is 8 pixels wide and 6 pixels high.
+fn split(output_dir: &Path, template: &str, args: &[&str]) -> Result<(), String> {
+    let input = setup_input_path("rainbow_8x6.bmp");
+    let output = output_dir.join(template);
+
+    let mut arguments = vec![
+        "sic",
+        "-i",
+        path_buf_str(&input),
+        "-o",
+        path_buf_str(&output),
+    ];
+    arguments.extend(args);
+
+    let matches = get_app().get_matches_from(arguments);
+    let config = build_app_config(&matches)?;
+
+    run(&matches, &config)
+}
+
+/// The dimensions of the tiles in the output directory, by file name; the directory is removed
+/// afterwards.
+fn tiles(output_dir: &Path) -> Vec<(String, (u32, u32))> {
+    let mut tiles = std::fs::read_dir(output_dir)
+        .expect("Unable to read the output directory.")
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let dimensions = image::open(&path)
+                .expect("Failed to open a tile.")
+                .dimensions();
+
+            (name, dimensions)
+        })
+        .collect::<Vec<_>>();
+
+    std::fs::remove_dir_all(output_dir).expect("Unable to remove directory after test.");
+
+    tiles.sort();
+    tiles
+}
+
+#[test]
+fn split_tile_size() {
+    let dir = setup_output_dir("split_tile_size");
+
+    assert_eq!(
+        Ok(()),
+        split(&dir, "{row}_{col}.png", &["--split-tile-size", "4", "3"])
+    );
+    assert_eq!(
+        vec![
+            ("0_0.png".to_string(), (4, 3)),
+            ("0_1.png".to_string(), (4, 3)),
+            ("1_0.png".to_string(), (4, 3)),
+            ("1_1.png".to_string(), (4, 3)),
+        ],
+        tiles(&dir)
+    );
+}
+
+#[test]
+fn split_tile_size_edges() {
+    let keep = setup_output_dir("split_tile_size_keep");
+    let pad = setup_output_dir("split_tile_size_pad");
+    let discard = setup_output_dir("split_tile_size_discard");
+
+    let args = |edge| vec!["--split-tile-size", "5", "4", "--split-edge", edge];
+
+    assert_eq!(Ok(()), split(&keep, "tile_{row}_{col}.png", &args("keep")));
+    assert_eq!(Ok(()), split(&pad, "tile_{row}_{col}.png", &args("pad")));
+    assert_eq!(
+        Ok(()),
+        split(&discard, "tile_{row}_{col}.png", &args("discard"))
+    );
+
+    assert_eq!(
+        vec![(5, 4), (3, 4), (5, 2), (3, 2)],
+        tiles(&keep)
+            .into_iter()
+            .map(|(_, dimensions)| dimensions)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![(5, 4); 4],
+        tiles(&pad)
+            .into_iter()
+            .map(|(_, dimensions)| dimensions)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![("tile_0_0.png".to_string(), (5, 4))], tiles(&discard));
+}
+
+#[test]
+fn split_grid_with_overlap() {
+    let dir = setup_output_dir("split_grid_overlap");
+
+    assert_eq!(
+        Ok(()),
+        split(
+            &dir,
+            "{row}-{col}.bmp",
+            &["--split-grid", "2", "1", "--split-overlap", "1"]
+        )
+    );
+    assert_eq!(
+        vec![
+            ("0-0.bmp".to_string(), (5, 6)),
+            ("0-1.bmp".to_string(), (5, 6)),
+        ],
+        tiles(&dir)
+    );
+}
+
+#[test]
+fn split_template_without_placeholders() {
+    let dir = setup_output_dir("split_template_without_placeholders");
+
+    assert!(split(&dir, "tile.png", &["--split-grid", "2", "2"]).is_err());
+    assert!(tiles(&dir).is_empty());
+}
+
+#[test]
+fn split_grid_too_large() {
+    let dir = setup_output_dir("split_grid_too_large");
+
+    assert!(split(&dir, "{row}_{col}.png", &["--split-grid", "9", "1"]).is_err());
+    assert!(tiles(&dir).is_empty());
+}
+
+#[test]
+fn split_requires_output() {
+    let input = setup_input_path("rainbow_8x6.bmp");
+    let matches = get_app().get_matches_from_safe(vec![
+        "sic",
+        "-i",
+        path_buf_str(&input),
+        "--split-grid",
+        "2",
+        "2",
+    ]);
+
+    assert!(matches.is_err());
+}
+
+#[test]
+fn split_edge_requires_tile_size() {
+    let matches = get_app().get_matches_from_safe(vec![
+        "sic",
+        "-i",
+        "in.png",
+        "-o",
+        "{row}_{col}.png",
+        "--split-grid",
+        "2",
+        "2",
+        "--split-edge",
+        "pad",
+    ]);
+
+    assert!(matches.is_err());
+}